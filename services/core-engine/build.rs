@@ -0,0 +1,22 @@
+fn main() {
+    // Commit reported by /health: `GIT_SHA` when set (container builds have no .git), else HEAD
+    let sha = std::env::var("GIT_SHA").ok().filter(|s| !s.is_empty()).or_else(|| {
+        let out = std::process::Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok().filter(|o| o.status.success())?;
+        Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+    });
+    if let Some(sha) = sha { println!("cargo:rustc-env=KINEMATICS_GIT_SHA={sha}"); }
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto");
+    if let Some(out) = std::process::Command::new("git").args(["rev-parse", "--git-dir"]).output().ok().filter(|o| o.status.success()) {
+        let log = std::path::Path::new(String::from_utf8_lossy(&out.stdout).trim()).join("logs/HEAD");
+        if log.exists() { println!("cargo:rerun-if-changed={}", log.display()); }
+    }
+    // Protobuf messages (and the gRPC server stubs) are generated on demand; protoc comes vendored
+    #[cfg(feature = "protobuf")]
+    {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().expect("vendored protoc"));
+        tonic_build::configure().build_client(false).build_server(cfg!(feature = "grpc"))
+            .compile_protos(&["proto/kinematics.proto"], &["proto"]).expect("compile protos");
+    }
+}
@@ -0,0 +1,225 @@
+// ── kinematics-cli ──────────────────────────────────────────
+//
+// Solves from the shell, against a running engine (`--url`, the default) or in
+// process with `--local`, which runs the same solver crate the engine does on the
+// built-in chains plus any `--chain-file`. Results print as `key value` lines for
+// grep/awk, or as the API's response body with `--json`.
+//
+// Exit status: 0 on success, 1 on any error, 2 when IK ran but did not converge.
+
+use clap::{Args, Parser, Subcommand};
+use kinematics_client::{ChainDef, ChainInfo, ChainListQuery, Client, FkRequest, FkResponse, IkConstraints, IkRequest, IkResponse, Joints};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+#[derive(Parser)]
+#[command(name = "kinematics-cli", version, about = "Solve IK/FK against an ALICE Kinematics Cloud engine or locally")]
+struct Cli {
+    /// Engine base URL.
+    #[arg(long, env = "KINEMATICS_URL", default_value = "http://localhost:8081", global = true)]
+    url: String,
+    #[arg(long, env = "KINEMATICS_API_KEY", hide_env_values = true, global = true)]
+    api_key: Option<String>,
+    /// Solve in process instead of calling the engine.
+    #[arg(long, global = true)]
+    local: bool,
+    /// With `--local`: extra chains, as one chain JSON object or an array (e.g. a chain export).
+    #[arg(long, global = true)]
+    chain_file: Vec<PathBuf>,
+    /// Print the full response body as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Joint angles reaching a target position (and orientation).
+    SolveIk(IkArgs),
+    /// End-effector pose of a set of joint angles.
+    SolveFk {
+        #[arg(long)] chain: String,
+        /// Comma-separated joint angles (rad, or m for prismatic joints).
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true, required = true)] angles: Vec<f64>,
+    },
+    /// List the available chains.
+    Chains,
+    /// Print one chain's definition.
+    Chain { id: String },
+}
+
+#[derive(Args)]
+struct IkArgs {
+    #[arg(long)] chain: String,
+    /// Target position `x,y,z` (m).
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true, required = true)] target: Vec<f64>,
+    /// Target orientation quaternion `x,y,z,w`.
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true)] orientation: Option<Vec<f64>>,
+    /// Starting angles `a,b,...` or the name of one of the chain's configurations.
+    #[arg(long, allow_hyphen_values = true)] seed: Option<String>,
+    #[arg(long)] seed_policy: Option<String>,
+    #[arg(long)] solver: Option<String>,
+    #[arg(long)] max_iterations: Option<u32>,
+    #[arg(long)] tolerance: Option<f64>,
+    #[arg(long)] damping: Option<f64>,
+    /// `f64` (default) or `f32`.
+    #[arg(long)] precision: Option<String>,
+    /// `none` (default), `wrap_to_pi` or `shortest_from_seed`.
+    #[arg(long)] wrap_policy: Option<String>,
+}
+
+fn vector(s: &str) -> Result<Vec<f64>, String> {
+    s.split(',').map(|v| v.trim().parse::<f64>().map_err(|_| format!("'{v}' is not a number"))).collect()
+}
+
+fn fixed<const N: usize>(v: &[f64], what: &str) -> Result<[f64; N], String> {
+    v.try_into().map_err(|_| format!("{what} takes {N} comma-separated numbers, got {}", v.len()))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(2),
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Where solves run.
+enum Backend { Remote(Client), Local(Vec<ChainDef>) }
+
+/// `Ok(false)` when an IK solve did not converge.
+async fn run(cli: &Cli) -> Result<bool, String> {
+    let backend = if cli.local {
+        Backend::Local(local_chains(&cli.chain_file)?)
+    } else {
+        let c = Client::new(&cli.url).map_err(|e| e.to_string())?;
+        Backend::Remote(match &cli.api_key { Some(k) => c.with_api_key(k), None => c })
+    };
+    match &cli.command {
+        Command::SolveIk(args) => {
+            let req = ik_request(args)?;
+            let r = match &backend {
+                Backend::Remote(c) => c.solve_ik(&req).await.map_err(|e| e.to_string())?,
+                Backend::Local(chains) => local_ik(find(chains, &args.chain)?, &req)?,
+            };
+            let converged = r.converged;
+            print(cli, &r, |o, r| {
+                _ = writeln!(o, "angles {}", join(&r.joint_angles));
+                _ = writeln!(o, "converged {}", r.converged);
+                _ = writeln!(o, "iterations {}", r.iterations);
+                _ = writeln!(o, "error {:.3e}", r.error_distance);
+                if let Some(e) = r.orientation_error { _ = writeln!(o, "orientation_error {e:.3e}"); }
+                if let Some(c) = &r.chain { _ = writeln!(o, "chain {c}"); }
+            })?;
+            Ok(converged)
+        }
+        Command::SolveFk { chain, angles } => {
+            let req = FkRequest { chain_id: Some(chain.clone()), joint_angles: angles.clone(), link_lengths: None, pad_link_length: None };
+            let r = match &backend {
+                Backend::Remote(c) => c.solve_fk(&req).await.map_err(|e| e.to_string())?,
+                Backend::Local(chains) => local_fk(find(chains, chain)?, &req.joint_angles)?,
+            };
+            print(cli, &r, |o, r| {
+                _ = writeln!(o, "position {}", join(&r.end_effector_position));
+                _ = writeln!(o, "orientation {}", join(&r.end_effector_orientation));
+                if !r.limit_violations.is_empty() {
+                    _ = writeln!(o, "limit_violations {}", r.limit_violations.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(" "));
+                }
+                if let Some(c) = &r.chain { _ = writeln!(o, "chain {c}"); }
+            })?;
+            Ok(true)
+        }
+        Command::Chains => {
+            let list: Vec<ChainInfo> = match &backend {
+                Backend::Remote(c) => c.list_chains(&ChainListQuery { limit: Some(1000), ..Default::default() }).await.map_err(|e| e.to_string())?.items,
+                Backend::Local(chains) => chains.iter().map(|c| ChainInfo {
+                    id: c.id.clone(), version: c.version, name: c.name.clone(), description: c.description.clone(), dof: c.dof() as u32, joint_type: c.joint_type(),
+                }).collect(),
+            };
+            print(cli, &list, |o, list| list.iter().for_each(|c| _ = writeln!(o, "{}@v{} {} {}", c.id, c.version, c.dof, c.name)))?;
+            Ok(true)
+        }
+        Command::Chain { id } => {
+            let body = match &backend {
+                Backend::Remote(c) => serde_json::to_value(c.get_chain(id).await.map_err(|e| e.to_string())?),
+                Backend::Local(chains) => serde_json::to_value(find(chains, id)?),
+            };
+            print(cli, &body.map_err(|e| e.to_string())?, |o, body| _ = writeln!(o, "{body:#}"))?;
+            Ok(true)
+        }
+    }
+}
+
+/// Write `value` as JSON with `--json`, else as `text`. A closed stdout (`| head`) is not an error.
+fn print<T: Serialize>(cli: &Cli, value: &T, text: impl Fn(&mut String, &T)) -> Result<(), String> {
+    let mut out = String::new();
+    if cli.json {
+        out = serde_json::to_string_pretty(value).map_err(|e| e.to_string())? + "\n";
+    } else {
+        text(&mut out, value);
+    }
+    match std::io::stdout().lock().write_all(out.as_bytes()) {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn join(v: &[f64]) -> String { v.iter().map(|x| format!("{x:.6}")).collect::<Vec<_>>().join(" ") }
+
+fn ik_request(a: &IkArgs) -> Result<IkRequest, String> {
+    let seed = a.seed.as_ref().map(|s| vector(s).map(Joints::Angles).unwrap_or_else(|_| Joints::Named(s.clone())));
+    Ok(IkRequest {
+        chain_id: Some(a.chain.clone()),
+        target_position: fixed(&a.target, "--target")?,
+        target_orientation: a.orientation.as_deref().map(|o| fixed(o, "--orientation")).transpose()?,
+        constraints: Some(IkConstraints { max_iterations: a.max_iterations, tolerance: a.tolerance, damping: a.damping }),
+        solver: a.solver.clone(), seed_policy: a.seed_policy.clone(), seed, joint_count: None, deadline_ms: None, deadline_us: None,
+        precision: a.precision.clone(), wrap_policy: a.wrap_policy.clone(), session_id: None,
+    })
+}
+
+// ── Local solves ────────────────────────────────────────────
+
+/// The built-in chains, then those in `files`; a file's chain replaces a built-in of the same id.
+fn local_chains(files: &[PathBuf]) -> Result<Vec<ChainDef>, String> {
+    let mut chains = kinematics_solver::presets();
+    for path in files {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let loaded = if text.trim_start().starts_with('[') {
+            serde_json::from_str::<Vec<ChainDef>>(&text)
+        } else {
+            serde_json::from_str::<ChainDef>(&text).map(|c| vec![c])
+        }.map_err(|e| format!("{}: {e}", path.display()))?;
+        for chain in loaded {
+            chain.validate().map_err(|e| format!("{}: chain '{}': {e}", path.display(), chain.id))?;
+            chains.retain(|c| c.id != chain.id);
+            chains.push(chain);
+        }
+    }
+    Ok(chains)
+}
+
+/// By id or pinned `id@vN`.
+fn find<'a>(chains: &'a [ChainDef], id: &str) -> Result<&'a ChainDef, String> {
+    chains.iter().find(|c| c.id == id || c.pinned_id() == id).ok_or_else(|| format!("chain not found: {id} (local chains: {})", chains.iter().map(|c| c.id.as_str()).collect::<Vec<_>>().join(", ")))
+}
+
+fn local_ik(chain: &ChainDef, req: &IkRequest) -> Result<IkResponse, String> {
+    let t = Instant::now();
+    kinematics_solver::ik_response(chain, req).map(|r| IkResponse { elapsed_us: t.elapsed().as_micros(), ..r })
+}
+
+fn local_fk(chain: &ChainDef, q: &[f64]) -> Result<FkResponse, String> {
+    let t = Instant::now();
+    kinematics_solver::fk_response(chain, q).map(|r| FkResponse { elapsed_us: t.elapsed().as_micros(), ..r })
+}
@@ -0,0 +1,398 @@
+// ── ALICE Kinematics Cloud client ───────────────────────────
+//
+// Async client for the engine's REST API, one method per endpoint, typed with the
+// same `kinematics-types` structs the engine serves (re-exported here). Failures
+// come back as `Error`: the API's own envelope when it sent one, otherwise the
+// transport, status or decoding problem.
+//
+// Requests that hit a transport error, 429, 502, 503 or 504 are retried with
+// exponential backoff, waiting `Retry-After` when the engine sends it. Every POST
+// carries a fresh `Idempotency-Key` that stays the same across its retries, so a
+// retried registration or job submission still runs once. Every call also sends an
+// `X-Request-Id`, the same across retries, which the engine logs; errors report it.
+//
+// Not covered: the WebSocket IK stream, the job events SSE stream, the FlatBuffers
+// and protobuf bodies and GraphQL.
+
+pub use kinematics_types::*;
+
+use reqwest::{header, Method, StatusCode, Url};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Statuses worth another attempt: rate limited, or the engine (or a proxy) unavailable.
+const RETRY_STATUSES: [StatusCode; 4] = [StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY, StatusCode::SERVICE_UNAVAILABLE, StatusCode::GATEWAY_TIMEOUT];
+
+// ── Errors ──────────────────────────────────────────────────
+#[derive(Debug)]
+pub enum Error {
+    /// The engine rejected the request with its error envelope.
+    Api { status: u16, error: Box<ApiError>, request_id: Option<String> },
+    /// A non-success status without an error envelope, e.g. from a proxy in between.
+    Status { status: u16, body: String, request_id: Option<String> },
+    /// The engine could not be reached, or the response could not be read.
+    Transport(reqwest::Error),
+    /// A request body that would not serialize, or a success response whose body is
+    /// not the expected type.
+    Json(serde_json::Error),
+    /// The base URL cannot take API paths.
+    Url(String),
+}
+
+impl Error {
+    /// The API's error code, when the engine sent one.
+    pub fn code(&self) -> Option<ErrorCode> { match self { Error::Api { error, .. } => Some(error.code), _ => None } }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api { status, .. } | Error::Status { status, .. } => Some(*status),
+            Error::Transport(e) => e.status().map(|s| s.as_u16()),
+            Error::Json(_) | Error::Url(_) => None,
+        }
+    }
+
+    /// The `X-Request-Id` of the failed call, to find it in the engine's logs.
+    pub fn request_id(&self) -> Option<&str> {
+        match self { Error::Api { request_id, .. } | Error::Status { request_id, .. } => request_id.as_deref(), _ => None }
+    }
+
+    async fn from_response(res: reqwest::Response) -> Error {
+        let status = res.status().as_u16();
+        let request_id = res.headers().get("x-request-id").and_then(|v| v.to_str().ok()).map(String::from);
+        match res.bytes().await {
+            Ok(body) => match serde_json::from_slice::<ApiError>(&body) {
+                Ok(error) => Error::Api { status, error: Box::new(error), request_id },
+                Err(_) => Error::Status { status, body: String::from_utf8_lossy(&body).into_owned(), request_id },
+            },
+            Err(e) => Error::Transport(e),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Api { status, error, .. } => write!(f, "HTTP {status} {:?}: {}", error.code, error.summary())?,
+            Error::Status { status, body, .. } => write!(f, "HTTP {status}: {}", body.trim())?,
+            Error::Transport(e) => write!(f, "transport: {e}")?,
+            Error::Json(e) => write!(f, "JSON: {e}")?,
+            Error::Url(e) => write!(f, "base URL: {e}")?,
+        }
+        match self.request_id() { Some(id) => write!(f, " (request {id})"), None => Ok(()) }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Api { error, .. } => Some(error.as_ref()),
+            Error::Transport(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Status { .. } | Error::Url(_) => None,
+        }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+// ── Retries ─────────────────────────────────────────────────
+/// How often and how patiently failed requests are retried.
+#[derive(Clone, Copy, Debug)]
+pub struct Retry {
+    /// Total attempts per request, the first included; 1 disables retries.
+    pub max_attempts: u32,
+    /// Wait before the second attempt, doubling for each one after.
+    pub initial_backoff: Duration, pub max_backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self { Self { max_attempts: 3, initial_backoff: Duration::from_millis(200), max_backoff: Duration::from_secs(5) } }
+}
+
+impl Retry {
+    pub fn none() -> Self { Self { max_attempts: 1, ..Self::default() } }
+
+    /// Wait after failed attempt `attempt` (1-based).
+    fn backoff(&self, attempt: u32) -> Duration { self.initial_backoff.saturating_mul(1u32 << (attempt - 1).min(16)).min(self.max_backoff) }
+}
+
+fn retry_after(res: &reqwest::Response) -> Option<Duration> {
+    res.headers().get(header::RETRY_AFTER)?.to_str().ok()?.trim().parse().ok().map(Duration::from_secs)
+}
+
+// ── Client ──────────────────────────────────────────────────
+/// One page of a listing plus the number of matches across all pages.
+#[derive(Clone, Debug)]
+pub struct Page<T> { pub items: Vec<T>, pub total: usize }
+
+#[derive(Clone, Debug)]
+pub struct Client { http: reqwest::Client, base: Url, credential: Option<(&'static str, String)>, retry: Retry, timeout: Option<Duration> }
+
+impl Client {
+    /// A client for the engine at `base_url`, e.g. `http://localhost:8081`.
+    pub fn new(base_url: &str) -> Result<Self> {
+        let base = Url::parse(base_url).map_err(|e| Error::Url(format!("{base_url}: {e}")))?;
+        if base.cannot_be_a_base() { return Err(Error::Url(format!("{base_url} cannot take paths"))); }
+        Ok(Self { http: reqwest::Client::new(), base, credential: None, retry: Retry::default(), timeout: Some(Duration::from_secs(30)) })
+    }
+
+    /// Authenticate with an API key (`X-API-Key`).
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self { self.credential = Some(("x-api-key", key.into())); self }
+
+    /// Authenticate with an OIDC access token (`Authorization: Bearer`).
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credential = Some(("authorization", format!("Bearer {}", token.into())));
+        self
+    }
+
+    pub fn with_retry(mut self, retry: Retry) -> Self { self.retry = retry; self }
+
+    /// Per-attempt timeout (30 s by default); `None` waits indefinitely.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self { self.timeout = timeout; self }
+
+    /// Send through a preconfigured `reqwest::Client` (proxies, TLS roots, pooling).
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self { self.http = http; self }
+
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base.clone();
+        url.path_segments_mut().expect("checked in new").pop_if_empty().extend(segments);
+        url
+    }
+
+    fn api(&self, segments: &[&str]) -> Url { self.url(&[&["api", "v1", "kinematics"], segments].concat()) }
+
+    fn with_query(&self, url: Url, query: &impl Serialize) -> Result<Url> {
+        self.http.get(url).query(query).build().map(|r| r.url().clone()).map_err(Error::Transport)
+    }
+
+    /// Send with retries; a success response is returned, anything else is an `Error`.
+    async fn execute(&self, method: Method, url: Url, body: Option<(&'static str, Vec<u8>)>) -> Result<reqwest::Response> {
+        let idempotency_key = (method == Method::POST).then(|| uuid::Uuid::new_v4().to_string());
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let mut attempt = 1;
+        loop {
+            let mut req = self.http.request(method.clone(), url.clone()).header(header::ACCEPT, "application/json").header("x-request-id", &request_id);
+            if let Some((name, value)) = &self.credential { req = req.header(*name, value); }
+            if let Some(key) = &idempotency_key { req = req.header("idempotency-key", key); }
+            if let Some((content_type, bytes)) = &body { req = req.header(header::CONTENT_TYPE, *content_type).body(bytes.clone()); }
+            if let Some(t) = self.timeout { req = req.timeout(t); }
+            let outcome = req.send().await;
+            let wait = match &outcome {
+                Ok(res) if RETRY_STATUSES.contains(&res.status()) => Some(retry_after(res)),
+                Ok(_) => None,
+                Err(e) if e.is_builder() => None,
+                Err(_) => Some(None),
+            };
+            match wait {
+                Some(after) if attempt < self.retry.max_attempts => {
+                    tokio::time::sleep(after.unwrap_or_else(|| self.retry.backoff(attempt))).await;
+                    attempt += 1;
+                }
+                _ => return match outcome {
+                    Ok(res) if res.status().is_success() => Ok(res),
+                    Ok(res) => Err(Error::from_response(res).await),
+                    Err(e) => Err(Error::Transport(e)),
+                },
+            }
+        }
+    }
+
+    async fn decode<T: DeserializeOwned>(res: reqwest::Response) -> Result<T> {
+        let body = res.bytes().await.map_err(Error::Transport)?;
+        serde_json::from_slice(&body).map_err(Error::Json)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T> { Self::decode(self.execute(Method::GET, url, None).await?).await }
+
+    async fn send_json<T: DeserializeOwned>(&self, method: Method, url: Url, body: &impl Serialize) -> Result<T> {
+        let body = serde_json::to_vec(body).map_err(Error::Json)?;
+        Self::decode(self.execute(method, url, Some(("application/json", body))).await?).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, url: Url, body: &impl Serialize) -> Result<T> { self.send_json(Method::POST, url, body).await }
+
+    /// POST without a body, for actions named by the path alone.
+    async fn post_empty<T: DeserializeOwned>(&self, url: Url) -> Result<T> { Self::decode(self.execute(Method::POST, url, None).await?).await }
+
+    async fn delete(&self, url: Url) -> Result<()> { self.execute(Method::DELETE, url, None).await.map(drop) }
+
+    // ── Service ─────────────────────────────────────────────
+    pub async fn health(&self) -> Result<Health> { self.get(self.url(&["healthz"])).await }
+
+    /// The engine's OpenAPI 3.1 document.
+    pub async fn openapi(&self) -> Result<serde_json::Value> { self.get(self.url(&["api", "v1", "openapi.json"])).await }
+
+    /// Totals, plus per-key and (for admins) per-tenant usage.
+    pub async fn stats(&self) -> Result<StatsResponse> { self.get(self.api(&["stats"])).await }
+
+    /// Audited solves of the caller's tenant, oldest first.
+    pub async fn audit(&self, query: &AuditQuery) -> Result<AuditResponse> { self.get(self.with_query(self.api(&["audit"]), query)?).await }
+
+    // ── Solving ─────────────────────────────────────────────
+    pub async fn solve_ik(&self, req: &IkRequest) -> Result<IkResponse> { self.post(self.api(&["solve-ik"]), req).await }
+
+    /// Many position targets on one chain; large batches may run on the engine's GPU.
+    pub async fn solve_ik_batch(&self, req: &IkBatchRequest) -> Result<IkBatchResponse> { self.post(self.api(&["solve-ik", "batch"]), req).await }
+
+    /// Joint velocities for a tool twist (resolved-rate control).
+    pub async fn solve_ik_velocity(&self, req: &VelocityIkRequest) -> Result<VelocityIkResponse> { self.post(self.api(&["solve-ik", "velocity"]), req).await }
+    pub async fn solve_ik_acceleration(&self, req: &AccelerationIkRequest) -> Result<AccelerationIkResponse> { self.post(self.api(&["solve-ik", "acceleration"]), req).await }
+    pub async fn reachable(&self, req: &ReachableRequest) -> Result<ReachableResponse> { self.post(self.api(&["reachable"]), req).await }
+    pub async fn self_motion(&self, req: &SelfMotionRequest) -> Result<SelfMotionResponse> { self.post(self.api(&["self-motion"]), req).await }
+    pub async fn joint_compliance(&self, req: &ComplianceRequest) -> Result<ComplianceResponse> { self.post(self.api(&["compliance"]), req).await }
+
+    pub async fn solve_fk(&self, req: &FkRequest) -> Result<FkResponse> { self.post(self.api(&["solve-fk"]), req).await }
+
+    /// IK for a pose, then FK of the solution: the closed-loop error, for checking a chain and its solver settings.
+    pub async fn verify(&self, req: &IkRequest) -> Result<VerifyResponse> { self.post(self.api(&["verify"]), req).await }
+
+    /// Carry a motion on one chain over to another by its end-effector path.
+    pub async fn retarget(&self, req: &RetargetRequest) -> Result<RetargetResponse> { self.post(self.api(&["retarget"]), req).await }
+
+    /// Solve a BVH or C3D take on a human chain.
+    pub async fn import_mocap(&self, query: &MocapQuery, file: Vec<u8>) -> Result<MocapResponse> {
+        let url = self.with_query(self.api(&["mocap", "import"]), query)?;
+        Self::decode(self.execute(Method::POST, url, Some(("application/octet-stream", file))).await?).await
+    }
+
+    /// The camera on the flange from paired flange and target poses.
+    pub async fn calibrate_hand_eye(&self, req: &HandEyeRequest) -> Result<HandEyeResponse> { self.post(self.api(&["calibrate", "hand-eye"]), req).await }
+
+    /// A stored solve's response: `IkResponse`, `HandIkResponse` or `MocapResponse`, as the solve returned it.
+    pub async fn get_solution<T: DeserializeOwned>(&self, solution_id: &str) -> Result<T> { self.get(self.api(&["solutions", solution_id])).await }
+
+    /// A stored joint trajectory as a BVH animation.
+    pub async fn export_bvh(&self, solution_id: &str, query: &BvhQuery) -> Result<String> {
+        let res = self.execute(Method::GET, self.with_query(self.api(&["solutions", solution_id, "bvh"]), query)?, None).await?;
+        res.text().await.map_err(Error::Transport)
+    }
+
+    pub async fn optimize_trajectory(&self, req: &TrajectoryRequest) -> Result<TrajectoryResponse> { self.post(self.api(&["optimize-trajectory"]), req).await }
+    pub async fn simulate_tracking(&self, req: &TrackingRequest) -> Result<TrackingResponse> { self.post(self.api(&["simulate-tracking"]), req).await }
+
+    // ── Intent ──────────────────────────────────────────────
+    pub async fn compress_intent(&self, req: &IntentRequest) -> Result<IntentResponse> { self.post(self.api(&["compress-intent"]), req).await }
+
+    pub async fn compress_intent_channels(&self, req: &MultiIntentRequest) -> Result<MultiIntentResponse> {
+        self.post(self.api(&["compress-intent", "channels"]), req).await
+    }
+
+    pub async fn list_intents(&self, query: &IntentQuery) -> Result<IntentHistoryResponse> { self.get(self.with_query(self.api(&["intents"]), query)?).await }
+
+    pub async fn get_intent(&self, intent_id: &str) -> Result<IntentRecord> { self.get(self.api(&["intents", intent_id])).await }
+
+    pub async fn codec_encode(&self, req: &EncodeRequest) -> Result<EncodeResponse> { self.post(self.api(&["codec", "encode"]), req).await }
+
+    pub async fn codec_decode(&self, req: &DecodeRequest) -> Result<DecodeResponse> { self.post(self.api(&["codec", "decode"]), req).await }
+
+    // ── Chains ──────────────────────────────────────────────
+    pub async fn list_chains(&self, query: &ChainListQuery) -> Result<Page<ChainInfo>> {
+        let res = self.execute(Method::GET, self.with_query(self.api(&["chains"]), query)?, None).await?;
+        let total = res.headers().get("x-total-count").and_then(|v| v.to_str().ok()?.parse().ok());
+        let items: Vec<ChainInfo> = Self::decode(res).await?;
+        Ok(Page { total: total.unwrap_or(items.len()), items })
+    }
+
+    /// Latest version, or a pinned one with `id@vN`.
+    pub async fn get_chain(&self, chain_id: &str) -> Result<ChainDetail> { self.get(self.api(&["chains", chain_id])).await }
+
+    pub async fn register_chain(&self, chain: &ChainDef) -> Result<ChainDef> { self.post(self.api(&["chains"]), chain).await }
+
+    /// Store `chain` as the next version of `chain_id`.
+    pub async fn update_chain(&self, chain_id: &str, chain: &ChainDef) -> Result<ChainDef> { self.send_json(Method::PUT, self.api(&["chains", chain_id]), chain).await }
+
+    pub async fn delete_chain(&self, chain_id: &str) -> Result<()> { self.delete(self.api(&["chains", chain_id])).await }
+
+    pub async fn generate_chain(&self, spec: &TemplateSpec) -> Result<ChainDef> { self.post(self.api(&["chains", "generate"]), spec).await }
+
+    pub async fn compose_chain(&self, spec: &ComposeSpec) -> Result<ChainDef> { self.post(self.api(&["chains", "compose"]), spec).await }
+
+    /// Register a subject-scaled copy of the `human_arm` or `human_leg` preset.
+    pub async fn scale_chain(&self, chain_id: &str, spec: &SubjectSpec) -> Result<ChainDef> { self.post(self.api(&["chains", chain_id, "scale"]), spec).await }
+
+    /// Register a chain from a URDF (or other `query.format`) document.
+    pub async fn import_chain(&self, query: &ImportQuery, document: impl Into<String>) -> Result<ChainDef> {
+        let url = self.with_query(self.api(&["chains", "import"]), query)?;
+        Self::decode(self.execute(Method::POST, url, Some(("application/xml", document.into().into_bytes()))).await?).await
+    }
+
+    pub async fn import_moveit(&self, req: &MoveItImport) -> Result<MoveItImportResponse> { self.post(self.api(&["chains", "import", "moveit"]), req).await }
+
+    /// The chain as a URDF (or other `query.format`) document.
+    pub async fn export_chain(&self, chain_id: &str, query: &ExportQuery) -> Result<String> {
+        let res = self.execute(Method::GET, self.with_query(self.api(&["chains", chain_id, "export"]), query)?, None).await?;
+        res.text().await.map_err(Error::Transport)
+    }
+
+    /// Every custom chain version, in the chain store's format.
+    pub async fn export_chains(&self) -> Result<Vec<ChainDef>> { self.get(self.api(&["chains", "export"])).await }
+
+    pub async fn flush_chains(&self) -> Result<ChainFlushResponse> { self.post_empty(self.api(&["chains", "flush"])).await }
+
+    pub async fn list_configurations(&self, chain_id: &str) -> Result<BTreeMap<String, Vec<f64>>> { self.get(self.api(&["chains", chain_id, "configurations"])).await }
+
+    pub async fn get_configuration(&self, chain_id: &str, name: &str) -> Result<Configuration> { self.get(self.api(&["chains", chain_id, "configurations", name])).await }
+
+    pub async fn put_configuration(&self, chain_id: &str, name: &str, joint_angles: &[f64]) -> Result<Configuration> {
+        self.send_json(Method::PUT, self.api(&["chains", chain_id, "configurations", name]), &joint_angles).await
+    }
+
+    // ── Hands ───────────────────────────────────────────────
+    pub async fn list_hands(&self) -> Result<Vec<HandInfo>> { self.get(self.api(&["hands"])).await }
+
+    pub async fn get_hand(&self, hand_id: &str) -> Result<HandDef> { self.get(self.api(&["hands", hand_id])).await }
+
+    pub async fn register_hand(&self, hand: &HandDef) -> Result<HandDef> { self.post(self.api(&["hands"]), hand).await }
+
+    pub async fn solve_hand_fk(&self, hand_id: &str, req: &HandFkRequest) -> Result<HandFkResponse> { self.post(self.api(&["hands", hand_id, "solve-fk"]), req).await }
+
+    pub async fn solve_hand_ik(&self, hand_id: &str, req: &HandIkRequest) -> Result<HandIkResponse> { self.post(self.api(&["hands", hand_id, "solve-ik"]), req).await }
+
+    // ── Jobs ────────────────────────────────────────────────
+    pub async fn submit_job(&self, req: &JobRequest) -> Result<Job> { self.post(self.api(&["jobs"]), req).await }
+
+    pub async fn list_jobs(&self) -> Result<Vec<Job>> { self.get(self.api(&["jobs"])).await }
+
+    pub async fn get_job(&self, job_id: &str) -> Result<Job> { self.get(self.api(&["jobs", job_id])).await }
+
+    /// A finished job's response body; a failed job returns the error the operation would have.
+    pub async fn job_result<T: DeserializeOwned>(&self, job_id: &str) -> Result<T> { self.get(self.api(&["jobs", job_id, "result"])).await }
+
+    pub async fn cancel_job(&self, job_id: &str) -> Result<Job> { self.post_empty(self.api(&["jobs", job_id, "cancel"])).await }
+
+    pub async fn delete_job(&self, job_id: &str) -> Result<()> { self.delete(self.api(&["jobs", job_id])).await }
+
+    // ── Sessions ────────────────────────────────────────────
+    /// Start tracking a robot's joint state; pass its `session_id` to solves and trajectories.
+    pub async fn create_session(&self, req: &SessionRequest) -> Result<SessionState> { self.post(self.api(&["sessions"]), req).await }
+
+    pub async fn get_session(&self, session_id: &str) -> Result<SessionState> { self.get(self.api(&["sessions", session_id])).await }
+
+    pub async fn report_state(&self, session_id: &str, report: &StateReport) -> Result<SessionState> {
+        self.send_json(Method::PUT, self.api(&["sessions", session_id, "state"]), report).await
+    }
+
+    pub async fn delete_session(&self, session_id: &str) -> Result<()> { self.delete(self.api(&["sessions", session_id])).await }
+
+    // ── Admin ───────────────────────────────────────────────
+    pub async fn list_keys(&self) -> Result<Vec<ApiKey>> { self.get(self.url(&["api", "v1", "admin", "keys"])).await }
+
+    /// Issue a key; the secret is only ever returned here.
+    pub async fn create_key(&self, req: &CreateKeyRequest) -> Result<CreatedKey> { self.post(self.url(&["api", "v1", "admin", "keys"]), req).await }
+
+    pub async fn revoke_key(&self, key_id: &str) -> Result<()> { self.delete(self.url(&["api", "v1", "admin", "keys", key_id])).await }
+
+    /// Zero the engine's counters; returns the emptied stats.
+    pub async fn reset_stats(&self) -> Result<StatsResponse> { self.post_empty(self.url(&["api", "v1", "admin", "stats", "reset"])).await }
+
+    pub async fn maintenance(&self) -> Result<Maintenance> { self.get(self.url(&["api", "v1", "admin", "maintenance"])).await }
+
+    /// Switch maintenance mode, in which solves get `Unavailable` while health stays green.
+    pub async fn set_maintenance(&self, req: &MaintenanceRequest) -> Result<Maintenance> { self.send_json(Method::PUT, self.url(&["api", "v1", "admin", "maintenance"]), req).await }
+
+    pub async fn engine_state(&self) -> Result<EngineState> { self.get(self.url(&["api", "v1", "admin", "state"])).await }
+
+    /// Run a synthetic IK load on the engine and get its throughput and latency back.
+    pub async fn bench(&self, req: &BenchRequest) -> Result<BenchResponse> { self.post(self.url(&["api", "v1", "admin", "bench"]), req).await }
+}
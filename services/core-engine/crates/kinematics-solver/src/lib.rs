@@ -0,0 +1,1046 @@
+// ── Serial-chain kinematics ─────────────────────────────────
+//
+// Spatial forward and inverse kinematics for registry chains. Each joint's frame is
+// the previous joint's frame composed with its `origin`; the joint then rotates about
+// (revolute) or slides along (prismatic) its local axis. The tip is the last joint
+// frame, then the last link along x, then the TCP. FK composes nalgebra isometries
+// over a `Model` built once per chain, so an IK solve does not redo the constant
+// origins every iteration. IK scratch (the model, joint frames, Jacobian and the
+// damped system) lives in a per-thread `Workspace` that is reused across solves, so
+// once it has grown to a chain's size a solve allocates nothing but its result. The
+// engine and `kinematics-cli` both solve through this crate, so a local solve matches
+// a server one.
+
+use kinematics_types::{ChainDef, FkResponse, IkRequest, IkResponse, JointDef, JointType, Joints, SolverDefaults, SolverSettings};
+use nalgebra::{DMatrix, DVector, Isometry3, Quaternion, SMatrix, SVector, Translation3, UnitQuaternion, Vector3, Vector6};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+pub type Mat = [[f64; 3]; 3];
+
+/// Rigid transform: rotation `r`, translation `p`.
+#[derive(Clone, Copy)]
+pub struct Frame { pub r: Mat, pub p: [f64; 3] }
+
+impl Frame {
+    pub const IDENTITY: Frame = Frame { r: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]], p: [0.0; 3] };
+
+    /// URDF convention: fixed-axis roll, pitch, yaw, i.e. `Rz(yaw) · Ry(pitch) · Rx(roll)`.
+    pub fn from_xyz_rpy(xyz: [f64; 3], rpy: [f64; 3]) -> Frame {
+        Frame { r: matmul(&matmul(&rot(2, rpy[2]), &rot(1, rpy[1])), &rot(0, rpy[0])), p: xyz }
+    }
+
+    /// Rotation from a quaternion given as `[w, x, y, z]`.
+    pub fn from_quat_wxyz(p: [f64; 3], q: [f64; 4]) -> Frame {
+        let n = q.iter().map(|c| c * c).sum::<f64>().sqrt().max(1e-12);
+        let [w, x, y, z] = q.map(|c| c / n);
+        Frame { r: [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w)],
+            [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w)],
+            [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y)],
+        ], p }
+    }
+
+    pub fn mul(&self, o: &Frame) -> Frame {
+        let mut p = self.rotate(o.p);
+        for (pi, si) in p.iter_mut().zip(self.p) { *pi += si; }
+        Frame { r: matmul(&self.r, &o.r), p }
+    }
+
+    pub fn inverse(&self) -> Frame {
+        let r = transpose(&self.r);
+        let p = mat_vec(&r, self.p).map(|v| -v);
+        Frame { r, p }
+    }
+
+    pub fn rotate(&self, v: [f64; 3]) -> [f64; 3] { mat_vec(&self.r, v) }
+
+    pub fn rpy(&self) -> [f64; 3] {
+        let r = &self.r;
+        // `+ 0.0` folds the -0.0 that atan2 yields for exactly aligned frames
+        [r[2][1].atan2(r[2][2]), (-r[2][0]).atan2((r[2][1] * r[2][1] + r[2][2] * r[2][2]).sqrt()), r[1][0].atan2(r[0][0])].map(|a| a + 0.0)
+    }
+
+    /// Rotation as a unit quaternion `[x, y, z, w]` with `w >= 0`, so each rotation
+    /// has exactly one representation.
+    pub fn quat_xyzw(&self) -> [f64; 4] {
+        let q = self.quat_raw();
+        let n = q.iter().map(|c| c * c).sum::<f64>().sqrt().max(1e-12);
+        let sign = if q[3] < 0.0 { -1.0 } else { 1.0 };
+        q.map(|c| sign * c / n + 0.0)
+    }
+
+    fn quat_raw(&self) -> [f64; 4] {
+        let r = &self.r;
+        let tr = r[0][0] + r[1][1] + r[2][2];
+        if tr > 0.0 {
+            let s = (tr + 1.0).sqrt() * 2.0;
+            [(r[2][1] - r[1][2]) / s, (r[0][2] - r[2][0]) / s, (r[1][0] - r[0][1]) / s, 0.25 * s]
+        } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+            let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+            [0.25 * s, (r[0][1] + r[1][0]) / s, (r[0][2] + r[2][0]) / s, (r[2][1] - r[1][2]) / s]
+        } else if r[1][1] > r[2][2] {
+            let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+            [(r[0][1] + r[1][0]) / s, 0.25 * s, (r[1][2] + r[2][1]) / s, (r[0][2] - r[2][0]) / s]
+        } else {
+            let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+            [(r[0][2] + r[2][0]) / s, (r[1][2] + r[2][1]) / s, 0.25 * s, (r[1][0] - r[0][1]) / s]
+        }
+    }
+}
+
+/// Elementary rotation about x (0), y (1) or z (2).
+pub fn rot(axis: usize, a: f64) -> Mat {
+    let (s, c) = a.sin_cos();
+    match axis {
+        0 => [[1.0, 0.0, 0.0], [0.0, c, -s], [0.0, s, c]],
+        1 => [[c, 0.0, s], [0.0, 1.0, 0.0], [-s, 0.0, c]],
+        _ => [[c, -s, 0.0], [s, c, 0.0], [0.0, 0.0, 1.0]],
+    }
+}
+
+pub fn matmul(a: &Mat, b: &Mat) -> Mat {
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() { *v = (0..3).map(|k| a[i][k] * b[k][j]).sum(); }
+    }
+    m
+}
+
+pub fn transpose(a: &Mat) -> Mat { [[a[0][0], a[1][0], a[2][0]], [a[0][1], a[1][1], a[2][1]], [a[0][2], a[1][2], a[2][2]]] }
+
+pub fn mat_vec(a: &Mat, v: [f64; 3]) -> [f64; 3] { a.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2]) }
+
+impl Frame {
+    pub fn translation(p: [f64; 3]) -> Frame { Frame { r: Frame::IDENTITY.r, p } }
+
+    /// Rotation by `angle` about the unit vector `axis` (Rodrigues).
+    pub fn from_axis_angle(axis: [f64; 3], angle: f64) -> Frame {
+        let (s, c) = (angle / 2.0).sin_cos();
+        Frame::from_quat_wxyz([0.0; 3], [c, axis[0] * s, axis[1] * s, axis[2] * s])
+    }
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let n = v.iter().map(|x| x * x).sum::<f64>().sqrt().max(1e-12);
+    v.map(|x| x / n)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 { a[0] * b[0] + a[1] * b[1] + a[2] * b[2] }
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] { [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]] }
+
+fn norm(v: [f64; 3]) -> f64 { v.iter().map(|x| x * x).sum::<f64>().sqrt() }
+
+impl From<Isometry3<f64>> for Frame {
+    fn from(t: Isometry3<f64>) -> Frame {
+        let m = t.rotation.to_rotation_matrix();
+        Frame { r: [0, 1, 2].map(|i| [0, 1, 2].map(|j| m[(i, j)])), p: t.translation.vector.into() }
+    }
+}
+
+/// World frames of every joint (after its motion) and of the tip.
+pub struct Pose { pub joints: Vec<Frame>, pub tip: Frame }
+
+/// A chain's constant geometry as isometries: each joint's origin, unit axis and type,
+/// and the tip offset (last link, then TCP).
+#[derive(Default)]
+pub struct Model { joints: Vec<(Isometry3<f64>, Vector3<f64>, JointType)>, tip: Isometry3<f64> }
+
+impl Model {
+    pub fn new(chain: &ChainDef) -> Model {
+        let mut model = Model::default();
+        model.rebuild(chain);
+        model
+    }
+
+    /// Become `chain`'s model, keeping the joint buffer's capacity.
+    pub fn rebuild(&mut self, chain: &ChainDef) {
+        self.joints.clear();
+        self.joints.extend(chain.joints.iter().enumerate().map(|(i, j)| {
+            let o = chain.origin(i);
+            let origin = Isometry3::from_parts(Translation3::from(o.xyz), UnitQuaternion::from_euler_angles(o.rpy[0], o.rpy[1], o.rpy[2]));
+            (origin, Vector3::from(normalize(j.axis)), j.joint_type)
+        }));
+        let last = chain.joints.last().map_or(0.0, |j| j.link_length);
+        let tcp = chain.tcp.unwrap_or_default();
+        let [x, y, z, w] = tcp.orientation;
+        let orientation = UnitQuaternion::try_new(Quaternion::new(w, x, y, z), 1e-12).unwrap_or_else(UnitQuaternion::identity);
+        self.tip = Translation3::new(last, 0.0, 0.0) * Isometry3::from_parts(Translation3::from(tcp.position), orientation);
+    }
+
+    pub fn forward(&self, q: &[f64]) -> Pose {
+        let mut joints = Vec::with_capacity(q.len());
+        let tip = self.forward_into(q, &mut joints);
+        Pose { joints, tip }
+    }
+
+    /// `forward` writing the joint frames into `joints` (cleared first); returns the tip.
+    pub fn forward_into(&self, q: &[f64], joints: &mut Vec<Frame>) -> Frame {
+        let mut t = Isometry3::identity();
+        joints.clear();
+        for ((origin, axis, joint_type), &qi) in self.joints.iter().zip(q) {
+            let motion = match joint_type {
+                JointType::Revolute => Isometry3::from_parts(Translation3::identity(), UnitQuaternion::from_scaled_axis(axis * qi)),
+                JointType::Prismatic => Isometry3::from_parts(Translation3::from(axis * qi), UnitQuaternion::identity()),
+            };
+            t = t * origin * motion;
+            joints.push(Frame::from(t));
+        }
+        Frame::from(t * self.tip)
+    }
+
+    /// Each joint's origin, unit axis and type, for evaluating the model elsewhere.
+    pub fn joints(&self) -> impl Iterator<Item = (Frame, [f64; 3], JointType)> + '_ {
+        self.joints.iter().map(|(origin, axis, joint_type)| (Frame::from(*origin), [axis.x, axis.y, axis.z], *joint_type))
+    }
+
+    /// The tip offset from the last joint.
+    pub fn tip(&self) -> Frame { Frame::from(self.tip) }
+}
+
+/// One-off FK; build a [`Model`] to evaluate the same chain repeatedly.
+pub fn forward(chain: &ChainDef, q: &[f64]) -> Pose { Model::new(chain).forward(q) }
+
+/// The generic chain used without a `chain_id`: revolute joints about z, each followed
+/// by its link along x.
+pub fn planar(links: &[f64]) -> ChainDef {
+    ChainDef {
+        id: "planar".into(), version: 1, name: "Planar".into(), description: String::new(),
+        joints: links.iter().map(|&link_length| JointDef { name: None, joint_type: JointType::Revolute, axis: [0.0, 0.0, 1.0], limits: None, link_length, origin: None, dh: None, inertial: None }).collect(),
+        tcp: None, solver: None, configurations: BTreeMap::new(), disabled_collisions: Vec::new(),
+    }
+}
+
+/// Clamp each joint into its limits, if it has any.
+pub fn clamp_to_limits(chain: &ChainDef, q: &mut [f64]) {
+    for (qi, j) in q.iter_mut().zip(&chain.joints) {
+        if let Some([lo, hi]) = j.limits { *qi = qi.clamp(lo, hi); }
+    }
+}
+
+/// `wrap_policy` values: `none` leaves solved angles where the solver ended,
+/// `wrap_to_pi` brings each revolute angle into [-π, π], and `shortest_from_seed` to
+/// the turn nearest its seed, so consecutive solves do not jump by 2π.
+pub const WRAP_POLICIES: [&str; 3] = ["none", "wrap_to_pi", "shortest_from_seed"];
+
+/// Apply a `wrap_policy` to solved angles `q` started from `seed`. An angle is left as
+/// it is when its wrapped value would fall outside the joint's limits.
+pub fn wrap_angles(chain: &ChainDef, q: &mut [f64], seed: &[f64], policy: Option<&str>) {
+    for (i, (qi, j)) in q.iter_mut().zip(&chain.joints).enumerate() {
+        let reference = match policy {
+            Some("wrap_to_pi") => 0.0,
+            Some("shortest_from_seed") => seed.get(i).copied().unwrap_or(0.0),
+            _ => return,
+        };
+        if j.joint_type != JointType::Revolute { continue; }
+        let wrapped = *qi - 2.0 * PI * ((*qi - reference) / (2.0 * PI)).round();
+        if j.limits.is_none_or(|[lo, hi]| (lo..=hi).contains(&wrapped)) { *qi = wrapped; }
+    }
+}
+
+/// Angle (rad) between two `[x, y, z, w]` orientations, the measure IK's `orientation_error` uses.
+pub fn orientation_angle(target: [f64; 4], current: [f64; 4]) -> f64 {
+    let r = |[x, y, z, w]: [f64; 4]| Frame::from_quat_wxyz([0.0; 3], [w, x, y, z]).r;
+    norm(rotation_error(&r(target), &r(current)))
+}
+
+/// Rotation vector (axis × angle) taking `current` onto `target`.
+fn rotation_error(target: &Mat, current: &Mat) -> [f64; 3] {
+    let [x, y, z, w] = Frame { r: matmul(target, &transpose(current)), p: [0.0; 3] }.quat_xyzw();
+    let (v, w) = if w < 0.0 { ([-x, -y, -z], -w) } else { ([x, y, z], w) };
+    let s = norm(v);
+    if s < 1e-12 { return [0.0; 3]; }
+    let angle = 2.0 * s.atan2(w);
+    v.map(|c| c / s * angle)
+}
+
+pub struct IkSettings { pub max_iterations: u32, pub tolerance: f64, pub damping: f64 }
+
+/// `stopped`: cut short by the caller's `stop` before converging or running out of iterations.
+pub struct IkResult { pub angles: Vec<f64>, pub iterations: u32, pub converged: bool, pub position_error: f64, pub orientation_error: Option<f64>, pub stopped: bool }
+
+/// Damped least squares on the geometric Jacobian, respecting joint limits. The
+/// orientation (`[x, y, z, w]`) is matched too when given.
+pub fn solve_ik(chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings) -> IkResult {
+    solve_ik_until(chain, target, orientation, seed, s, || false)
+}
+
+/// `solve_ik`, asking `stop` before each iteration after the first whether to give up
+/// and return where it got to, so a caller can bound the time spent.
+pub fn solve_ik_until(chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> IkResult {
+    with_workspace(|ws| ws.solve(chain, target, orientation, seed, s, &stop))
+}
+
+/// IK for a time budget rather than an iteration count: iterates until convergence or
+/// `stop`, ignoring `max_iterations`, adapting the damping (starting from `s.damping`)
+/// as it goes, and returns the best configuration reached with its quality in `[0, 1]`:
+/// 1 when converged, otherwise the fraction of the seed's error removed.
+pub fn solve_ik_within(chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> (IkResult, f64) {
+    with_workspace(|ws| ws.solve_adaptive(chain, target, orientation, seed, s, &stop))
+}
+
+fn with_workspace<T>(f: impl FnOnce(&mut Workspace) -> T) -> T {
+    WORKSPACE.with(|ws| match ws.try_borrow_mut() {
+        Ok(mut ws) => f(&mut ws),
+        // Re-entered from `stop`: solve in a one-off workspace
+        Err(_) => f(&mut Workspace::default()),
+    })
+}
+
+// ── Workspaces ──────────────────────────────────────────────
+
+thread_local! {
+    static WORKSPACE: RefCell<Workspace> = RefCell::new(Workspace::default());
+}
+
+/// Adaptive damping bounds; past `LAMBDA_MAX` no step improves, so the solve is stuck.
+const LAMBDA_MIN: f64 = 1e-4;
+const LAMBDA_MAX: f64 = 1e4;
+/// Backstop for budgeted solves whose `stop` never fires.
+const ADAPTIVE_MAX_ITERATIONS: u32 = 1_000_000;
+
+/// Scratch buffers for IK solves, reused by every solve on a thread.
+#[derive(Default)]
+pub struct Workspace { model: Model, joints: Vec<Frame>, jac: Vec<[f64; 6]>, trial: Vec<f64> }
+
+/// Tip, position error and rotation error (when an orientation is solved for).
+type Residual = (Frame, [f64; 3], Option<[f64; 3]>);
+
+impl Workspace {
+    /// `solve_ik_until` in this workspace; `seed` becomes the result's angles.
+    pub fn solve(&mut self, chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> IkResult {
+        let mut q = seed;
+        clamp_to_limits(chain, &mut q);
+        let target_r = orientation.map(|[x, y, z, w]| Frame::from_quat_wxyz([0.0; 3], [w, x, y, z]).r);
+        self.model.rebuild(chain);
+        let mut iterations = 0u32;
+        loop {
+            iterations += 1;
+            let (tip, ep, eo) = self.residual(&q, target, target_r.as_ref());
+            let (position_error, orientation_error) = (norm(ep), eo.map(norm));
+            let converged = position_error < s.tolerance && orientation_error.is_none_or(|e| e < s.tolerance);
+            let stopped = !converged && iterations < s.max_iterations && iterations > 1 && stop();
+            if converged || iterations >= s.max_iterations || stopped {
+                return IkResult { angles: q, iterations, converged, position_error, orientation_error, stopped };
+            }
+            self.step(chain, &mut q, &tip, ep, eo, s.damping);
+        }
+    }
+
+    /// `solve_ik_within` in this workspace: Levenberg-Marquardt style, a step that lowers
+    /// the error is kept and the damping halved, one that does not is undone and the
+    /// damping quadrupled.
+    pub fn solve_adaptive(&mut self, chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> (IkResult, f64) {
+        let mut q = seed;
+        clamp_to_limits(chain, &mut q);
+        let target_r = orientation.map(|[x, y, z, w]| Frame::from_quat_wxyz([0.0; 3], [w, x, y, z]).r);
+        self.model.rebuild(chain);
+        let error = |ep: [f64; 3], eo: Option<[f64; 3]>| (norm(ep).powi(2) + eo.map_or(0.0, |o| norm(o).powi(2))).sqrt();
+        let (mut tip, mut ep, mut eo) = self.residual(&q, target, target_r.as_ref());
+        let initial = error(ep, eo);
+        let (mut lambda, mut iterations, mut trial) = (s.damping.max(LAMBDA_MIN), 0u32, std::mem::take(&mut self.trial));
+        loop {
+            iterations += 1;
+            let (position_error, orientation_error) = (norm(ep), eo.map(norm));
+            let converged = position_error < s.tolerance && orientation_error.is_none_or(|e| e < s.tolerance);
+            let stopped = !converged && iterations > 1 && stop();
+            if converged || stopped || lambda > LAMBDA_MAX || iterations >= ADAPTIVE_MAX_ITERATIONS {
+                self.trial = trial;
+                let quality = if converged { 1.0 } else if initial > 0.0 { (1.0 - error(ep, eo) / initial).clamp(0.0, 1.0) } else { 0.0 };
+                return (IkResult { angles: q, iterations, converged, position_error, orientation_error, stopped }, quality);
+            }
+            trial.clone_from(&q);
+            self.step(chain, &mut trial, &tip, ep, eo, lambda);
+            let next = self.residual(&trial, target, target_r.as_ref());
+            if error(next.1, next.2) < error(ep, eo) {
+                std::mem::swap(&mut q, &mut trial);
+                (tip, ep, eo) = next;
+                lambda = (lambda * 0.5).max(LAMBDA_MIN);
+            } else {
+                lambda *= 4.0;
+                // Back to the frames at `q` for the next step's Jacobian
+                self.residual(&q, target, target_r.as_ref());
+            }
+        }
+    }
+
+    /// FK at `q` against the target, leaving the joint frames for `step`.
+    fn residual(&mut self, q: &[f64], target: [f64; 3], target_r: Option<&Mat>) -> Residual {
+        let tip = self.model.forward_into(q, &mut self.joints);
+        let ep = [target[0] - tip.p[0], target[1] - tip.p[1], target[2] - tip.p[2]];
+        let eo = target_r.map(|r| rotation_error(r, &tip.r));
+        (tip, ep, eo)
+    }
+
+    /// One damped least squares step from the frames `residual` left at `q`.
+    fn step(&mut self, chain: &ChainDef, q: &mut [f64], tip: &Frame, ep: [f64; 3], eo: Option<[f64; 3]>, damping: f64) {
+        let Workspace { model, joints, jac, .. } = self;
+        let rows = if eo.is_some() { 6 } else { 3 };
+        jac.clear();
+        jac.extend(model.joints.iter().zip(joints.iter()).map(|((_, axis, joint_type), f)| jacobian_column(axis, *joint_type, f, tip)));
+        let e = [ep[0], ep[1], ep[2], eo.map_or(0.0, |o| o[0]), eo.map_or(0.0, |o| o[1]), eo.map_or(0.0, |o| o[2])];
+        // dq = Jᵀ (J Jᵀ + λ² I)⁻¹ e
+        let mut system = [[0.0; 7]; 6];
+        for (r, row) in system.iter_mut().enumerate().take(rows) {
+            for c in 0..rows { row[c] = jac.iter().map(|col| col[r] * col[c]).sum::<f64>(); }
+            row[r] += damping * damping;
+            row[rows] = e[r];
+        }
+        let y = solve_system(&mut system, rows);
+        for (qi, col) in q.iter_mut().zip(jac.iter()) {
+            *qi += (0..rows).map(|r| col[r] * y[r]).sum::<f64>();
+        }
+        clamp_to_limits(chain, q);
+    }
+}
+
+/// Geometric Jacobian column of a joint at world frame `f`: revolute a × (p_tip − p_joint) / a,
+/// prismatic a / 0.
+fn jacobian_column(axis: &Vector3<f64>, joint_type: JointType, f: &Frame, tip: &Frame) -> [f64; 6] {
+    let a = f.rotate([axis.x, axis.y, axis.z]);
+    match joint_type {
+        JointType::Revolute => {
+            let v = cross(a, [tip.p[0] - f.p[0], tip.p[1] - f.p[1], tip.p[2] - f.p[2]]);
+            [v[0], v[1], v[2], a[0], a[1], a[2]]
+        }
+        JointType::Prismatic => [a[0], a[1], a[2], 0.0, 0.0, 0.0],
+    }
+}
+
+/// Conditioning of the Jacobian at `q`, over the position rows, or all six when an
+/// `orientation` is solved for, as in IK: its smallest singular value, and the largest
+/// over the smallest (`None` when exactly singular). A converged solve with a small
+/// singular value sits near a singularity, where small target moves need large joint moves.
+pub fn conditioning(chain: &ChainDef, q: &[f64], orientation: bool) -> (f64, Option<f64>) {
+    let model = Model::new(chain);
+    let pose = model.forward(q);
+    let rows = if orientation { 6 } else { 3 };
+    let cols: Vec<[f64; 6]> = model.joints.iter().zip(&pose.joints).map(|((_, axis, joint_type), f)| jacobian_column(axis, *joint_type, f, &pose.tip)).collect();
+    let sv = DMatrix::from_fn(rows, cols.len(), |r, c| cols[c][r]).singular_values();
+    let (min, max) = sv.iter().fold((f64::INFINITY, 0.0f64), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+    let min = if min.is_finite() { min } else { 0.0 };
+    (min, (min > 1e-12).then(|| max / min))
+}
+
+// ── Reachability ────────────────────────────────────────────
+//
+// Whether a pose is inside a chain's workspace, answered cheaply. A target beyond the
+// chain's reach sphere is out without solving. Otherwise a few short solves run from
+// seeds spread over the joint ranges, stopping at the first that converges; the pose is
+// reachable when one does, and the closest any came is the best achievable error.
+
+/// Short solves tried per pose, the first from the caller's seed.
+pub const REACH_SEEDS: usize = 4;
+/// Iteration cap for each of them.
+pub const REACH_ITERATIONS: u32 = 100;
+
+/// `margin`: the target's clearance inside the reach sphere (negative outside it).
+/// `solved`: false when the reach bound alone decided, so `position_error` is a lower bound.
+pub struct Reach { pub reachable: bool, pub margin: f64, pub position_error: f64, pub orientation_error: Option<f64>, pub solved: bool }
+
+pub fn reachability(chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> Reach {
+    let margin = chain.reach() - norm(target);
+    if margin < -s.tolerance { return Reach { reachable: false, margin, position_error: -margin, orientation_error: None, solved: false }; }
+    let ik = IkSettings { max_iterations: s.max_iterations.min(REACH_ITERATIONS), tolerance: s.tolerance, damping: s.damping };
+    let miss = |r: &IkResult| r.position_error + r.orientation_error.unwrap_or(0.0);
+    let mut best = solve_ik_until(chain, target, orientation, seed, &ik, &stop);
+    for k in 1..REACH_SEEDS {
+        if best.converged || stop() { break; }
+        let r = solve_ik_until(chain, target, orientation, spread_seed(chain, k), &ik, &stop);
+        if r.converged || miss(&r) < miss(&best) { best = r; }
+    }
+    Reach { reachable: best.converged, margin, position_error: best.position_error, orientation_error: best.orientation_error, solved: true }
+}
+
+/// The `k`th of a low-discrepancy sequence of configurations within the joint limits
+/// (±π for unlimited revolute joints, ±1 m for unlimited prismatic ones).
+fn spread_seed(chain: &ChainDef, k: usize) -> Vec<f64> {
+    chain.joints.iter().enumerate().map(|(i, j)| {
+        let [lo, hi] = j.limits.unwrap_or(match j.joint_type { JointType::Revolute => [-PI, PI], JointType::Prismatic => [-1.0, 1.0] });
+        lo + (hi - lo) * (0.5 + k as f64 * 0.618_033_988_75 + i as f64 * 0.414_213_562_37).fract()
+    }).collect()
+}
+
+// ── Differential kinematics ─────────────────────────────────
+//
+// Resolved-rate control: a tool twist mapped to joint velocities through the damped
+// pseudo-inverse of the geometric Jacobian, q̇ = Jᵀ (J Jᵀ + λ² I)⁻¹ v. The damping is
+// zero away from singularities and rises towards its maximum as the smallest singular
+// value drops through `SINGULAR_REGION` (Nakamura & Hanafusa), so the tool follows the
+// twist exactly where it can and joint speeds stay bounded where it cannot. At the
+// acceleration level the same inverse maps ẍ − J̇q̇ to joint accelerations, with J̇q̇
+// taken by central differences along q̇.
+// Cartesian stiffness and damping map the other way, onto the joints, as Jᵀ K J.
+
+/// Smallest singular value below which velocity solves start damping.
+pub const SINGULAR_REGION: f64 = 0.05;
+/// Damping reached at an exact singularity unless a request sets its own.
+pub const DEFAULT_RATE_DAMPING: f64 = 0.05;
+/// Joint-space distance of each side of the J̇q̇ central difference.
+const RATE_STEP: f64 = 1e-6;
+
+/// The tip at `q` and the geometric Jacobian there: one `[vx, vy, vz, ωx, ωy, ωz]`
+/// column per joint, in the base frame, for a point moving with the tip.
+pub struct Jacobian { pub tip: Frame, pub columns: Vec<[f64; 6]> }
+
+pub fn jacobian(chain: &ChainDef, q: &[f64]) -> Jacobian {
+    let model = Model::new(chain);
+    let pose = model.forward(q);
+    let columns = model.joints.iter().zip(&pose.joints).map(|((_, axis, joint_type), f)| jacobian_column(axis, *joint_type, f, &pose.tip)).collect();
+    Jacobian { tip: pose.tip, columns }
+}
+
+/// J̇(q, q̇) · q̇: the tool acceleration the joint velocities `qd` cause on their own.
+pub fn velocity_product(chain: &ChainDef, q: &[f64], qd: &[f64]) -> [f64; 6] {
+    let speed = qd.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if speed == 0.0 { return [0.0; 6]; }
+    let h = RATE_STEP / speed;
+    let at = |sign: f64| jacobian(chain, &q.iter().zip(qd).map(|(q, v)| q + sign * h * v).collect::<Vec<_>>()).twist(qd);
+    let (ahead, behind) = (at(1.0), at(-1.0));
+    std::array::from_fn(|r| (ahead[r] - behind[r]) / (2.0 * h))
+}
+
+impl Jacobian {
+    /// J · `qd`.
+    pub fn twist(&self, qd: &[f64]) -> [f64; 6] {
+        std::array::from_fn(|r| self.columns.iter().zip(qd).map(|(c, v)| c[r] * v).sum())
+    }
+
+    /// Smallest singular value over the first `rows` rows (3: linear only, 6: all).
+    pub fn min_singular_value(&self, rows: usize) -> f64 {
+        if self.columns.len() < rows { return 0.0; }
+        DMatrix::from_fn(rows, self.columns.len(), |r, c| self.columns[c][r]).singular_values().min()
+    }
+
+    /// The same Jacobian with its rows along the tool axes.
+    pub fn in_tool(&self) -> Jacobian {
+        let r = transpose(&self.tip.r);
+        let columns = self.columns.iter().map(|c| { let (v, w) = (mat_vec(&r, [c[0], c[1], c[2]]), mat_vec(&r, [c[3], c[4], c[5]])); [v[0], v[1], v[2], w[0], w[1], w[2]] }).collect();
+        Jacobian { tip: self.tip, columns }
+    }
+
+    /// Jᵀ K J over the first `k.len()` rows: a Cartesian stiffness or damping `k` as the joints feel it.
+    pub fn congruence(&self, k: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let rows = k.len();
+        let kj: Vec<[f64; 6]> = self.columns.iter().map(|c| std::array::from_fn(|r| if r < rows { (0..rows).map(|m| k[r][m] * c[m]).sum() } else { 0.0 })).collect();
+        self.columns.iter().map(|a| kj.iter().map(|b| (0..rows).map(|r| a[r] * b[r]).sum()).collect()).collect()
+    }
+
+    /// Joint velocities for the twist `v` over `rows` rows, leaving `blocked` joints still;
+    /// also returns the damping used.
+    pub fn resolve(&self, v: [f64; 6], rows: usize, max_damping: f64, blocked: &[usize]) -> (Vec<f64>, f64) {
+        let free = |c: usize| !blocked.contains(&c);
+        let sigma = Jacobian { tip: self.tip, columns: self.columns.iter().enumerate().map(|(c, col)| if free(c) { *col } else { [0.0; 6] }).collect() }.min_singular_value(rows);
+        let damping = if sigma < SINGULAR_REGION { max_damping * (1.0 - (sigma / SINGULAR_REGION).powi(2)).sqrt() } else { 0.0 };
+        let mut system = [[0.0; 7]; 6];
+        for (r, row) in system.iter_mut().enumerate().take(rows) {
+            for c in 0..rows { row[c] = self.columns.iter().enumerate().filter(|(i, _)| free(*i)).map(|(_, col)| col[r] * col[c]).sum::<f64>(); }
+            row[r] += damping * damping;
+            row[rows] = v[r];
+        }
+        let y = solve_system(&mut system, rows);
+        let qd = self.columns.iter().enumerate().map(|(c, col)| if free(c) { (0..rows).map(|r| col[r] * y[r]).sum() } else { 0.0 }).collect();
+        (qd, damping)
+    }
+}
+
+// ── Self-motion ─────────────────────────────────────────────
+//
+// A 7-joint chain holding its tool at a fixed pose can still move through a
+// one-parameter family of postures, its self-motion manifold. The family is indexed by
+// the elbow swivel: the angle of the elbow (the fourth joint's origin) about the line
+// from the shoulder (the second's) to the wrist (the sixth's), right-handed about that
+// line and zero with the elbow towards base -z (base +x when the line is vertical).
+// Each sample solves the pose plus the swivel as a seventh task row, its gradient by
+// central differences, and the sweep warm-starts every sample from the last feasible one.
+
+/// Joints a chain needs for a one-parameter self-motion.
+pub const SELF_MOTION_JOINTS: usize = 7;
+/// Iteration cap per swivel; warm-started feasible ones need a handful.
+pub const SELF_MOTION_ITERATIONS: u32 = 50;
+const SWIVEL_STEP: f64 = 1e-6;
+const SWIVEL_STALL: u32 = 5;
+
+/// One posture on the manifold; `converged` is false where joint limits cut the swivel off.
+pub struct SelfMotionSample { pub swivel: f64, pub angles: Vec<f64>, pub converged: bool, pub position_error: f64, pub orientation_error: f64 }
+
+/// The elbow swivel at `q`, or `None` with the elbow on the shoulder–wrist line.
+pub fn swivel(model: &Model, q: &[f64]) -> Option<f64> {
+    let joints = model.forward(q).joints;
+    let [s, e, w] = [1, 3, 5].map(|i| joints[i].p);
+    let u = normalize([w[0] - s[0], w[1] - s[1], w[2] - s[2]]);
+    let off_line = |v: [f64; 3]| { let d = dot(v, u); [v[0] - d * u[0], v[1] - d * u[1], v[2] - d * u[2]] };
+    let elbow = off_line([e[0] - s[0], e[1] - s[1], e[2] - s[2]]);
+    let down = off_line([0.0, 0.0, -1.0]);
+    let reference = normalize(if norm(down) > 1e-6 { down } else { off_line([1.0, 0.0, 0.0]) });
+    (norm(elbow) > 1e-9).then(|| dot(u, cross(reference, elbow)).atan2(dot(reference, elbow)))
+}
+
+/// `samples` postures at evenly spaced swivels from -π, all holding the tool at `target`,
+/// starting from the IK solution nearest `seed`, with that solution's swivel; `None` for
+/// samples `stop` cut off.
+pub fn self_motion(chain: &ChainDef, target: Frame, samples: usize, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> Result<(f64, Vec<Option<SelfMotionSample>>), String> {
+    if chain.dof() != SELF_MOTION_JOINTS { return Err(format!("self-motion sampling needs {SELF_MOTION_JOINTS} joints, one redundant; this chain has {}", chain.dof())); }
+    let model = Model::new(chain);
+    let start = solve_ik_until(chain, target.p, Some(target.quat_xyzw()), seed, s, &stop);
+    if !start.converged { return Err(format!("the pose was not reached from the seed ({:.3e} m, {:.3e} rad off)", start.position_error, start.orientation_error.unwrap_or(0.0))); }
+    let first = swivel(&model, &start.angles).ok_or("the elbow lies on the shoulder–wrist line, so the swivel is undefined")?;
+    let at = |k: usize| -PI + 2.0 * PI * k as f64 / samples as f64;
+    let nearest = (0..samples).min_by(|&a, &b| wrap_pi(at(a) - first).abs().total_cmp(&wrap_pi(at(b) - first).abs())).unwrap_or(0);
+    let (mut out, mut last): (Vec<Option<SelfMotionSample>>, Vec<f64>) = ((0..samples).map(|_| None).collect(), start.angles.clone());
+    for k in (nearest..samples).chain(0..nearest) {
+        if stop() { break; }
+        let mut sample = solve_swivel(&model, chain, &target, at(k), last.clone(), s);
+        if !sample.converged && last != start.angles {
+            let retry = solve_swivel(&model, chain, &target, at(k), start.angles.clone(), s);
+            if retry.converged { sample = retry; }
+        }
+        if sample.converged { last.clone_from(&sample.angles); }
+        out[k] = Some(sample);
+    }
+    Ok((first, out))
+}
+
+/// Damped Newton on the pose and the swivel together, given up once the joint limits
+/// have stopped it improving for `SWIVEL_STALL` iterations.
+fn solve_swivel(model: &Model, chain: &ChainDef, target: &Frame, phi: f64, seed: Vec<f64>, s: &IkSettings) -> SelfMotionSample {
+    let mut q = seed;
+    let (n, mut iterations, mut best, mut stalled) = (q.len(), 0u32, f64::INFINITY, 0);
+    loop {
+        let pose = model.forward(&q);
+        let (ep, eo) = ([0, 1, 2].map(|i| target.p[i] - pose.tip.p[i]), rotation_error(&target.r, &pose.tip.r));
+        let es = swivel(model, &q).map_or(0.0, |v| wrap_pi(phi - v));
+        let (position_error, orientation_error) = (norm(ep), norm(eo));
+        let converged = position_error < s.tolerance && orientation_error < s.tolerance && es.abs() < s.tolerance;
+        let miss = position_error + orientation_error + es.abs();
+        (best, stalled) = if miss < best * (1.0 - 1e-6) { (miss, 0) } else { (best, stalled + 1) };
+        if converged || stalled >= SWIVEL_STALL || iterations >= s.max_iterations.min(SELF_MOTION_ITERATIONS) {
+            return SelfMotionSample { swivel: phi, angles: q, converged, position_error, orientation_error };
+        }
+        let columns: Vec<[f64; 6]> = model.joints.iter().zip(&pose.joints).map(|((_, axis, joint_type), f)| jacobian_column(axis, *joint_type, f, &pose.tip)).collect();
+        let gradient: Vec<f64> = (0..n).map(|c| {
+            let nudged = |d: f64| { let mut q = q.clone(); q[c] += d; swivel(model, &q).unwrap_or(phi) };
+            wrap_pi(nudged(SWIVEL_STEP) - nudged(-SWIVEL_STEP)) / (2.0 * SWIVEL_STEP)
+        }).collect();
+        let a = DMatrix::from_fn(7, n, |r, c| if r < 6 { columns[c][r] } else { gradient[c] });
+        let e = DVector::from_row_slice(&[ep[0], ep[1], ep[2], eo[0], eo[1], eo[2], es]);
+        let system = &a * a.transpose() + DMatrix::identity(7, 7) * s.damping.powi(2);
+        let Some(y) = system.lu().solve(&e) else { return SelfMotionSample { swivel: phi, angles: q, converged, position_error, orientation_error } };
+        let dq = a.transpose() * y;
+        q.iter_mut().zip(dq.iter()).for_each(|(qi, d)| *qi += d);
+        clamp_to_limits(chain, &mut q);
+        iterations += 1;
+    }
+}
+
+fn wrap_pi(a: f64) -> f64 { a - 2.0 * PI * (a / (2.0 * PI)).round() }
+
+// ── Dynamics ────────────────────────────────────────────────
+
+/// Rotor inertia added to every joint (kg·m², or kg for prismatic joints), so the mass
+/// matrix stays invertible when some links carry no mass.
+pub const ARMATURE: f64 = 1e-4;
+/// Standard gravity (m/s²), along -z.
+pub const GRAVITY: [f64; 3] = [0.0, 0.0, -9.81];
+/// Central-difference step for the Coriolis terms.
+const FD_STEP: f64 = 1e-6;
+
+/// A link's mass, world inertia about its centre of mass, and the linear and angular
+/// Jacobians of that centre (3 × n each).
+struct LinkJacobian { mass: f64, inertia: DMatrix<f64>, jv: DMatrix<f64>, jw: DMatrix<f64> }
+
+/// Every link with an `inertial`, at `q`.
+fn link_jacobians(model: &Model, chain: &ChainDef, q: &[f64]) -> Vec<LinkJacobian> {
+    let pose = model.forward(q);
+    chain.joints.iter().enumerate().filter_map(|(i, j)| j.inertial.map(|m| {
+        let f = &pose.joints[i];
+        let com = f.rotate(m.com);
+        let centre = Frame { r: f.r, p: [f.p[0] + com[0], f.p[1] + com[1], f.p[2] + com[2]] };
+        let cols: Vec<[f64; 6]> = model.joints.iter().zip(&pose.joints).take(i + 1).map(|((_, axis, joint_type), f)| jacobian_column(axis, *joint_type, f, &centre)).collect();
+        let jacobian = |rows: std::ops::Range<usize>| DMatrix::from_fn(3, q.len(), |r, c| cols.get(c).map_or(0.0, |col| col[rows.start + r]));
+        let [ixx, ixy, ixz, iyy, iyz, izz] = m.inertia;
+        let r = DMatrix::from_fn(3, 3, |a, b| f.r[a][b]);
+        let inertia = &r * DMatrix::from_row_slice(3, 3, &[ixx, ixy, ixz, ixy, iyy, iyz, ixz, iyz, izz]) * r.transpose();
+        LinkJacobian { mass: m.mass, inertia, jv: jacobian(0..3), jw: jacobian(3..6) }
+    })).collect()
+}
+
+/// Joint-space mass matrix M(q), armature included.
+pub fn mass_matrix(model: &Model, chain: &ChainDef, q: &[f64]) -> DMatrix<f64> {
+    let n = q.len();
+    link_jacobians(model, chain, q).into_iter().fold(DMatrix::identity(n, n) * ARMATURE, |m, l| {
+        m + l.jv.transpose() * &l.jv * l.mass + l.jw.transpose() * l.inertia * &l.jw
+    })
+}
+
+/// Joint torques G(q) that hold the chain still against `gravity`.
+pub fn gravity_torques(model: &Model, chain: &ChainDef, q: &[f64], gravity: [f64; 3]) -> Vec<f64> {
+    let g = DVector::from_column_slice(&gravity);
+    link_jacobians(model, chain, q).into_iter().fold(DVector::zeros(q.len()), |b, l| b - l.jv.transpose() * &g * l.mass).iter().copied().collect()
+}
+
+/// Joint accelerations under `tau` (N·m, or N for prismatic joints), from
+/// M(q) q̈ + C(q, q̇) q̇ + G(q) = τ. The Coriolis and centrifugal terms are central
+/// differences of M: C q̇ = Ṁ q̇ − ½ ∂(q̇ᵀ M q̇)/∂q.
+pub fn forward_dynamics(model: &Model, chain: &ChainDef, q: &[f64], qd: &[f64], tau: &[f64], gravity: [f64; 3]) -> Vec<f64> {
+    let n = q.len();
+    let v = DVector::from_column_slice(qd);
+    let mut bias = DVector::from_vec(gravity_torques(model, chain, q, gravity));
+    if v.iter().any(|&x| x != 0.0) {
+        let shifted = |dq: &dyn Fn(usize) -> f64| mass_matrix(model, chain, &q.iter().enumerate().map(|(k, &qk)| qk + dq(k)).collect::<Vec<_>>());
+        let m_dot = (shifted(&|k| FD_STEP * qd[k]) - shifted(&|k| -FD_STEP * qd[k])) / (2.0 * FD_STEP);
+        bias += m_dot * &v;
+        for k in 0..n {
+            let energy = |sign: f64| v.dot(&(shifted(&|j| if j == k { sign * FD_STEP } else { 0.0 }) * &v));
+            bias[k] -= 0.5 * (energy(1.0) - energy(-1.0)) / (2.0 * FD_STEP);
+        }
+    }
+    let rhs = DVector::from_column_slice(tau) - bias;
+    let m = mass_matrix(model, chain, q);
+    m.clone().cholesky().map_or_else(|| m.lu().solve(&rhs).unwrap_or_else(|| DVector::zeros(n)), |c| c.solve(&rhs)).iter().copied().collect()
+}
+
+/// `solve_augmented` on the leading `m x (m + 1)` block of a fixed array, without allocating.
+fn solve_system(a: &mut [[f64; 7]; 6], m: usize) -> [f64; 6] {
+    for col in 0..m {
+        let pivot = (col..m).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs())).unwrap_or(col);
+        a.swap(col, pivot);
+        let p = a[col][col];
+        if p.abs() < 1e-12 { continue; }
+        let pivot_row = a[col];
+        for (r, row) in a.iter_mut().enumerate().take(m) {
+            if r == col { continue; }
+            let f = row[col] / p;
+            if f == 0.0 { continue; }
+            for (cell, pv) in row.iter_mut().zip(&pivot_row).take(m + 1) { *cell -= f * pv; }
+        }
+    }
+    let mut y = [0.0; 6];
+    for (i, yi) in y.iter_mut().enumerate().take(m) { *yi = if a[i][i].abs() < 1e-12 { 0.0 } else { a[i][m] / a[i][i] }; }
+    y
+}
+
+/// Gauss-Jordan elimination with partial pivoting on an augmented `m x (m + 1)` system.
+pub fn solve_augmented(mut a: Vec<Vec<f64>>) -> Vec<f64> {
+    let m = a.len();
+    for col in 0..m {
+        let pivot = (col..m).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs())).unwrap_or(col);
+        a.swap(col, pivot);
+        let p = a[col][col];
+        if p.abs() < 1e-12 { continue; }
+        let pivot_row = a[col].clone();
+        for (r, row) in a.iter_mut().enumerate() {
+            if r == col { continue; }
+            let f = row[col] / p;
+            if f == 0.0 { continue; }
+            for (cell, pv) in row.iter_mut().zip(&pivot_row) { *cell -= f * pv; }
+        }
+    }
+    (0..m).map(|i| if a[i][i].abs() < 1e-12 { 0.0 } else { a[i][m] / a[i][i] }).collect()
+}
+
+// ── Hand-eye calibration ────────────────────────────────────
+//
+// AX = XB for a camera on the flange (Park & Martin): between any two stations the
+// flange moves by A = A_i⁻¹ A_j and the camera by B = B_i B_j⁻¹, where A_i is the
+// flange in the robot base and B_i the calibration target in the camera. X's rotation
+// carries every camera rotation axis onto the flange's (weighted by angle), found by
+// SVD; its translation then solves the stacked (R_A - I) t = R_X t_B - t_A in least
+// squares. Both need rotations about at least two non-parallel axes.
+
+/// Fewest stations for a unique solution.
+pub const HAND_EYE_MIN_POSES: usize = 3;
+
+/// The camera in the flange frame, and how well it fits.
+pub struct HandEye {
+    pub x: Frame, pub pairs: usize,
+    /// RMS over all station pairs of the rotation (rad) and translation (m) left between AX and XB.
+    pub rotation_rms: f64, pub translation_rms: f64,
+    /// Per station, how far the target position it implies (A_i X B_i) is from the mean of all of them.
+    pub station_errors: Vec<f64>,
+}
+
+pub fn hand_eye(a: &[Frame], b: &[Frame]) -> Result<HandEye, String> {
+    if a.len() != b.len() { return Err(format!("{} flange poses for {} target poses", a.len(), b.len())); }
+    if a.len() < HAND_EYE_MIN_POSES { return Err(format!("{} stations given, at least {HAND_EYE_MIN_POSES} are needed", a.len())); }
+    let motions: Vec<(Frame, Frame)> = (0..a.len()).flat_map(|i| (i + 1..a.len()).map(move |j| (i, j)))
+        .map(|(i, j)| (a[i].inverse().mul(&a[j]), b[i].mul(&b[j].inverse()))).collect();
+    let log = |f: &Frame| {
+        let [x, y, z, w] = f.quat_xyzw();
+        let n = (x * x + y * y + z * z).sqrt();
+        if n < 1e-12 { Vector3::zeros() } else { Vector3::new(x, y, z) * (2.0 * n.atan2(w) / n) }
+    };
+    let h = motions.iter().fold(nalgebra::Matrix3::zeros(), |h, (ma, mb)| h + log(mb) * log(ma).transpose());
+    let svd = h.svd(true, true);
+    let (u, v_t) = (svd.u.expect("computed"), svd.v_t.expect("computed"));
+    let mut sv: Vec<f64> = svd.singular_values.iter().copied().collect();
+    sv.sort_by(|x, y| y.total_cmp(x));
+    if sv[0] < 1e-9 || sv[1] < 1e-6 * sv[0] { return Err("the stations rotate about a single axis; tilt the flange about at least two non-parallel axes".into()); }
+    let d = (v_t.transpose() * u.transpose()).determinant().signum();
+    let r = v_t.transpose() * nalgebra::Matrix3::from_diagonal(&Vector3::new(1.0, 1.0, d)) * u.transpose();
+    let rx: Mat = [0, 1, 2].map(|i| [0, 1, 2].map(|j| r[(i, j)]));
+    let (mut m, mut rhs) = (nalgebra::Matrix3::<f64>::zeros(), Vector3::zeros());
+    for (ma, mb) in &motions {
+        let c = nalgebra::Matrix3::from_fn(|i, j| ma.r[i][j] - if i == j { 1.0 } else { 0.0 });
+        let e = Vector3::from(mat_vec(&rx, mb.p)) - Vector3::from(ma.p);
+        (m, rhs) = (m + c.transpose() * c, rhs + c.transpose() * e);
+    }
+    let t = m.lu().solve(&rhs).ok_or("the stations' rotations do not fix the camera's position")?;
+    let x = Frame { r: rx, p: [t.x, t.y, t.z] };
+    let (mut rot2, mut trans2) = (0.0, 0.0);
+    for (ma, mb) in &motions {
+        let e = ma.mul(&x).inverse().mul(&x.mul(mb));
+        rot2 += log(&e).norm_squared();
+        trans2 += e.p.iter().map(|c| c * c).sum::<f64>();
+    }
+    let targets: Vec<[f64; 3]> = a.iter().zip(b).map(|(ai, bi)| ai.mul(&x).mul(bi).p).collect();
+    let mean = [0, 1, 2].map(|k| targets.iter().map(|p| p[k]).sum::<f64>() / targets.len() as f64);
+    let station_errors = targets.iter().map(|p| (0..3).map(|k| (p[k] - mean[k]).powi(2)).sum::<f64>().sqrt()).collect();
+    let n = motions.len() as f64;
+    Ok(HandEye { x, pairs: motions.len(), rotation_rms: (rot2 / n).sqrt(), translation_rms: (trans2 / n).sqrt(), station_errors })
+}
+
+// ── Single precision ────────────────────────────────────────
+//
+// `precision: "f32"`: the damped least squares of `Workspace::solve` in single
+// precision, for callers that trade the last digits for throughput (animation,
+// previews). FK, the Jacobian and the damped system run on f32 nalgebra types, the
+// system through a fixed-size Cholesky factorization. f32 resolves a metre-scale
+// chain's tip to a few µm, so the tolerance is floored at `F32_TOLERANCE`.
+
+/// The tightest tolerance a single-precision solve honours.
+pub const F32_TOLERANCE: f64 = 1e-4;
+
+/// `solve_ik_until` at a request's `precision`: `f32`, or f64 otherwise.
+pub fn solve_ik_at(precision: Option<&str>, chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> IkResult {
+    match precision {
+        Some("f32") => solve_ik_f32(chain, target, orientation, seed, s, stop),
+        _ => solve_ik_until(chain, target, orientation, seed, s, stop),
+    }
+}
+
+/// `settings` as solved at `precision`: `f32` is recorded, with the tolerance it gets.
+pub fn with_precision(mut settings: SolverSettings, precision: Option<&str>) -> SolverSettings {
+    if precision == Some("f32") {
+        settings.tolerance = settings.tolerance.max(F32_TOLERANCE);
+        settings.precision = Some("f32".into());
+    }
+    settings
+}
+
+/// `solve_ik_until` in single precision; the result is widened back to f64.
+pub fn solve_ik_f32(chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> IkResult {
+    WORKSPACE_F32.with(|ws| match ws.try_borrow_mut() {
+        Ok(mut ws) => ws.solve(chain, target, orientation, seed, s, &stop),
+        Err(_) => WorkspaceF32::default().solve(chain, target, orientation, seed, s, &stop),
+    })
+}
+
+thread_local! {
+    static WORKSPACE_F32: RefCell<WorkspaceF32> = RefCell::new(WorkspaceF32::default());
+}
+
+/// Single-precision scratch: the f64 model it is cast from, the cast model, each
+/// joint's world position and axis, the Jacobian and the joint values.
+#[derive(Default)]
+struct WorkspaceF32 {
+    model: Model, joints: Vec<(Isometry3<f32>, Vector3<f32>, JointType)>, tip: Isometry3<f32>,
+    frames: Vec<(Vector3<f32>, Vector3<f32>)>, jac: Vec<Vector6<f32>>, q: Vec<f32>,
+}
+
+impl WorkspaceF32 {
+    fn solve(&mut self, chain: &ChainDef, target: [f64; 3], orientation: Option<[f64; 4]>, seed: Vec<f64>, s: &IkSettings, stop: impl Fn() -> bool) -> IkResult {
+        let mut angles = seed;
+        clamp_to_limits(chain, &mut angles);
+        self.model.rebuild(chain);
+        self.joints.clear();
+        self.joints.extend(self.model.joints.iter().map(|(origin, axis, joint_type)| (origin.cast(), axis.cast(), *joint_type)));
+        self.tip = self.model.tip.cast();
+        self.q.clear();
+        self.q.extend(angles.iter().map(|&v| v as f32));
+        let target_p = Vector3::from(target.map(|v| v as f32));
+        let target_r = orientation.map(|[x, y, z, w]| UnitQuaternion::new_normalize(Quaternion::new(w as f32, x as f32, y as f32, z as f32)));
+        let (tolerance, damping) = (s.tolerance.max(F32_TOLERANCE) as f32, s.damping as f32);
+        let mut iterations = 0u32;
+        loop {
+            iterations += 1;
+            let tip = self.forward();
+            let ep = target_p - tip.translation.vector;
+            let eo = target_r.map(|r| (r * tip.rotation.inverse()).scaled_axis());
+            let (position_error, orientation_error) = (ep.norm(), eo.map(|o| o.norm()));
+            let converged = position_error < tolerance && orientation_error.is_none_or(|e| e < tolerance);
+            let stopped = !converged && iterations < s.max_iterations && iterations > 1 && stop();
+            if converged || iterations >= s.max_iterations || stopped {
+                angles.clear();
+                angles.extend(self.q.iter().map(|&v| v as f64));
+                return IkResult { angles, iterations, converged, position_error: position_error.into(), orientation_error: orientation_error.map(f64::from), stopped };
+            }
+            self.step(chain, tip.translation.vector, ep, eo, damping);
+        }
+    }
+
+    /// FK at `q`, leaving each joint's world position and axis; returns the tip.
+    fn forward(&mut self) -> Isometry3<f32> {
+        let mut t = Isometry3::identity();
+        self.frames.clear();
+        for ((origin, axis, joint_type), &qi) in self.joints.iter().zip(&self.q) {
+            t *= origin;
+            self.frames.push((t.translation.vector, t.rotation * axis));
+            t *= match joint_type {
+                JointType::Revolute => Isometry3::from_parts(Translation3::identity(), UnitQuaternion::from_scaled_axis(axis * qi)),
+                JointType::Prismatic => Isometry3::from_parts(Translation3::from(axis * qi), UnitQuaternion::identity()),
+            };
+        }
+        t * self.tip
+    }
+
+    fn step(&mut self, chain: &ChainDef, tip: Vector3<f32>, ep: Vector3<f32>, eo: Option<Vector3<f32>>, damping: f32) {
+        self.jac.clear();
+        self.jac.extend(self.joints.iter().zip(&self.frames).map(|((_, _, joint_type), (p, a))| match joint_type {
+            JointType::Revolute => { let v = a.cross(&(tip - p)); Vector6::new(v.x, v.y, v.z, a.x, a.y, a.z) }
+            JointType::Prismatic => Vector6::new(a.x, a.y, a.z, 0.0, 0.0, 0.0),
+        }));
+        match eo {
+            Some(eo) => dls_step(&self.jac, &mut self.q, Vector6::new(ep.x, ep.y, ep.z, eo.x, eo.y, eo.z), damping),
+            None => dls_step(&self.jac, &mut self.q, ep, damping),
+        }
+        for (qi, j) in self.q.iter_mut().zip(&chain.joints) {
+            if let Some([lo, hi]) = j.limits { *qi = qi.clamp(lo as f32, hi as f32); }
+        }
+    }
+}
+
+/// `q += Jᵀ (J Jᵀ + λ² I)⁻¹ e` over the leading `R` rows of the Jacobian; no step
+/// when the system is singular (only possible with zero damping).
+fn dls_step<const R: usize>(jac: &[Vector6<f32>], q: &mut [f32], e: SVector<f32, R>, damping: f32) {
+    let mut a = SMatrix::<f32, R, R>::identity() * (damping * damping);
+    for col in jac { let c = col.fixed_rows::<R>(0); a += c * c.transpose(); }
+    let Some(y) = a.cholesky().map(|a| a.solve(&e)) else { return };
+    for (qi, col) in q.iter_mut().zip(jac) { *qi += col.fixed_rows::<R>(0).dot(&y); }
+}
+
+// ── Settings ────────────────────────────────────────────────
+
+pub const DEFAULT_MAX_ITERATIONS: u32 = 100;
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+pub const DEFAULT_DAMPING: f64 = 0.1;
+
+/// Request overrides, then the chain's defaults, then the engine's.
+pub fn settings(chain: Option<&ChainDef>, overrides: &SolverDefaults) -> SolverSettings {
+    let defaults = chain.and_then(|c| c.solver.clone()).unwrap_or_default();
+    SolverSettings {
+        solver: overrides.solver.clone().or(defaults.solver).unwrap_or_else(|| "dls".into()),
+        max_iterations: overrides.max_iterations.or(defaults.max_iterations).unwrap_or(DEFAULT_MAX_ITERATIONS),
+        tolerance: overrides.tolerance.or(defaults.tolerance).unwrap_or(DEFAULT_TOLERANCE),
+        damping: overrides.damping.or(defaults.damping).unwrap_or(DEFAULT_DAMPING),
+        seed_policy: overrides.seed_policy.clone().or(defaults.seed_policy).unwrap_or_else(|| "zero".into()),
+        precision: None,
+    }
+}
+
+/// Starting configuration under a seed policy: `mid` is the middle of each joint's
+/// limits, anything else all zeros.
+pub fn seed(chain: &ChainDef, policy: &str) -> Vec<f64> {
+    match policy {
+        "mid" => chain.joints.iter().map(|j| j.limits.map_or(0.0, |[lo, hi]| (lo + hi) / 2.0)).collect(),
+        _ => vec![0.0; chain.dof()],
+    }
+}
+
+// ── Requests ────────────────────────────────────────────────
+
+/// A `solve-ik` request on `chain`, solved in process: settings resolve and the seed
+/// is chosen as the engine does. `solution_id` is `local`; `elapsed_us` is left 0
+/// for the caller, which knows what clock it has.
+pub fn ik_response(chain: &ChainDef, req: &IkRequest) -> Result<IkResponse, String> {
+    if req.target_position.iter().chain(req.target_orientation.iter().flatten()).any(|v| !v.is_finite()) {
+        return Err("target values must be finite".into());
+    }
+    let c = req.constraints.clone().unwrap_or_default();
+    let overrides = SolverDefaults { solver: req.solver.clone(), seed_policy: req.seed_policy.clone(), damping: c.damping, tolerance: c.tolerance, max_iterations: c.max_iterations };
+    overrides.validate().map_err(|e| format!("invalid solver settings: {e}"))?;
+    if req.precision.as_deref().is_some_and(|p| !["f64", "f32"].contains(&p)) { return Err("precision must be f64 or f32".into()); }
+    if req.wrap_policy.as_deref().is_some_and(|p| !WRAP_POLICIES.contains(&p)) { return Err(format!("wrap_policy must be one of {}", WRAP_POLICIES.join(", "))); }
+    let effective = with_precision(settings(Some(chain), &overrides), req.precision.as_deref());
+    let start = match &req.seed {
+        Some(Joints::Angles(q)) => q.clone(),
+        Some(Joints::Named(name)) => chain.configurations.get(name).cloned().ok_or_else(|| format!("chain '{}' has no configuration '{name}'", chain.id))?,
+        None => seed(chain, &effective.seed_policy),
+    };
+    if start.len() != chain.dof() { return Err(format!("{} seed angles for {} joints", start.len(), chain.dof())); }
+    let ik = IkSettings { max_iterations: effective.max_iterations, tolerance: effective.tolerance, damping: effective.damping };
+    let mut r = solve_ik_at(req.precision.as_deref(), chain, req.target_position, req.target_orientation, start.clone(), &ik, || false);
+    wrap_angles(chain, &mut r.angles, &start, req.wrap_policy.as_deref());
+    let (min_singular_value, condition_number) = conditioning(chain, &r.angles, req.target_orientation.is_some());
+    Ok(IkResponse {
+        solution_id: "local".into(), joint_angles: r.angles, iterations: r.iterations, converged: r.converged,
+        error_distance: r.position_error, orientation_error: r.orientation_error, solver: effective,
+        chain: Some(chain.pinned_id()), elapsed_us: 0, timed_out: false, warm_started: false, quality: None,
+        min_singular_value: Some(min_singular_value), condition_number,
+    })
+}
+
+/// A `solve-fk` on `chain`, computed in process; `elapsed_us` is left 0 as in [`ik_response`].
+pub fn fk_response(chain: &ChainDef, q: &[f64]) -> Result<FkResponse, String> {
+    if q.len() != chain.dof() { return Err(format!("chain '{}' has {} joints, got {} angles", chain.id, chain.dof(), q.len())); }
+    let pose = forward(chain, q);
+    let limit_violations = chain.joints.iter().zip(q).enumerate()
+        .filter(|(_, (j, &qi))| j.limits.is_some_and(|[lo, hi]| qi < lo || qi > hi)).map(|(i, _)| i).collect();
+    Ok(FkResponse {
+        end_effector_position: pose.tip.p, end_effector_orientation: pose.tip.quat_xyzw(), quaternion_order: kinematics_types::QUATERNION_ORDER.into(),
+        joint_positions: pose.joints.iter().map(|f| f.p).chain([pose.tip.p]).collect(),
+        limit_violations, chain: Some(chain.pinned_id()), elapsed_us: 0,
+    })
+}
+
+// ── Presets ─────────────────────────────────────────────────
+
+pub fn joint(joint_type: JointType, axis: [f64; 3], limits: [f64; 2], link_length: f64, name: &str) -> JointDef {
+    JointDef { name: Some(name.into()), joint_type, axis, limits: Some(limits), link_length, origin: None, dh: None, inertial: None }
+}
+
+/// The built-in chains every registry starts with.
+pub fn presets() -> Vec<ChainDef> {
+    use JointType::{Prismatic as P, Revolute as R};
+    let (x, y, z) = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]);
+    let chain = |id: &str, name: &str, description: &str, joints| ChainDef { id: id.into(), version: 1, name: name.into(), description: description.into(), joints, tcp: None, solver: None, configurations: BTreeMap::new(), disabled_collisions: Vec::new() };
+    vec![
+        chain("human_arm", "Human Arm", "7-DOF human arm: shoulder(3) + elbow(1) + wrist(3)", vec![
+            joint(R, x, [-PI / 2.0, PI], 0.0, "shoulder_abduction"),
+            joint(R, y, [-PI / 3.0, PI], 0.0, "shoulder_flexion"),
+            joint(R, z, [-PI / 2.0, PI / 2.0], 0.30, "shoulder_rotation"),
+            joint(R, y, [0.0, 2.6], 0.25, "elbow_flexion"),
+            joint(R, z, [-PI / 2.0, PI / 2.0], 0.0, "forearm_pronation"),
+            joint(R, y, [-1.2, 1.2], 0.0, "wrist_flexion"),
+            joint(R, x, [-0.4, 0.6], 0.08, "wrist_deviation"),
+        ]),
+        chain("human_leg", "Human Leg", "6-DOF human leg: hip(3) + knee(1) + ankle(2)", vec![
+            joint(R, y, [-0.5, 2.1], 0.0, "hip_flexion"),
+            joint(R, x, [-0.5, 0.8], 0.0, "hip_abduction"),
+            joint(R, z, [-0.7, 0.7], 0.43, "hip_rotation"),
+            joint(R, y, [0.0, 2.4], 0.42, "knee_flexion"),
+            joint(R, y, [-0.9, 0.4], 0.0, "ankle_dorsiflexion"),
+            joint(R, x, [-0.6, 0.4], 0.08, "ankle_inversion"),
+        ]),
+        chain("robotic_arm_6dof", "Robotic Arm (6-DOF)", "Standard industrial 6-DOF manipulator", vec![
+            joint(R, z, [-PI, PI], 0.0, "base"),
+            joint(R, y, [-PI / 2.0, PI / 2.0], 0.40, "shoulder"),
+            joint(R, y, [-2.6, 2.6], 0.35, "elbow"),
+            joint(R, x, [-PI, PI], 0.0, "wrist_roll"),
+            joint(R, y, [-2.0, 2.0], 0.0, "wrist_pitch"),
+            joint(R, x, [-PI, PI], 0.08, "flange"),
+        ]),
+        chain("delta_robot", "Delta Robot", "3-DOF parallel kinematic delta robot for high-speed pick-and-place", vec![
+            joint(P, x, [-0.2, 0.2], 0.0, "x"),
+            joint(P, y, [-0.2, 0.2], 0.0, "y"),
+            joint(P, z, [-0.3, 0.0], 0.0, "z"),
+        ]),
+        chain("scara", "SCARA", "4-DOF selective compliance assembly robot arm", vec![
+            joint(R, z, [-2.5, 2.5], 0.35, "shoulder"),
+            joint(R, z, [-2.5, 2.5], 0.30, "elbow"),
+            joint(P, z, [-0.2, 0.0], 0.0, "quill"),
+            joint(R, z, [-PI, PI], 0.0, "tool_roll"),
+        ]),
+    ]
+}
@@ -0,0 +1,107 @@
+// ── Errors ──────────────────────────────────────────────────
+//
+// The one error envelope every endpoint returns:
+// `{"code": "invalid", "message": "Empty trajectory", "field": "waypoints", "details": ...}`.
+// `code` is stable and machine-readable and fixes the HTTP status; `field` is the
+// JSON path of the offending input when there is one.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Unparseable body or query, or an unknown option value.
+    BadRequest,
+    /// Well-formed input that cannot be used (empty, non-finite, inconsistent).
+    Invalid,
+    Unauthorized, NotFound, Conflict, Forbidden, PayloadTooLarge, UnsupportedMediaType, TooManyRequests, Internal, Unavailable,
+    /// The deadline passed before the work could start.
+    Timeout,
+}
+
+impl ErrorCode {
+    pub fn http_status(self) -> u16 {
+        match self {
+            ErrorCode::BadRequest => 400,
+            ErrorCode::Invalid => 422,
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::NotFound => 404,
+            ErrorCode::Conflict => 409,
+            ErrorCode::Forbidden => 403,
+            ErrorCode::PayloadTooLarge => 413,
+            ErrorCode::UnsupportedMediaType => 415,
+            ErrorCode::TooManyRequests => 429,
+            ErrorCode::Internal => 500,
+            ErrorCode::Unavailable => 503,
+            ErrorCode::Timeout => 504,
+        }
+    }
+}
+
+/// Error envelope returned by every endpoint.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema), schema(as = Error))]
+pub struct ApiError {
+    pub code: ErrorCode, pub message: String,
+    /// Path of the offending input, e.g. `waypoints[2]` or `samples[0].position`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub details: Option<String>,
+    /// Every violation when request validation found several; `field` is the first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub errors: Vec<FieldError>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FieldError { pub field: String, pub message: String }
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>, details: impl Into<String>) -> Self {
+        Self { code, message: message.into(), field: None, details: Some(details.into()), errors: Vec::new() }
+    }
+    pub fn bad_request(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::BadRequest, message, details) }
+    pub fn invalid(field: impl Into<String>, message: impl Into<String>, details: impl Into<String>) -> Self {
+        Self { field: Some(field.into()), ..Self::new(ErrorCode::Invalid, message, details) }
+    }
+    pub fn not_found(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::NotFound, message, details) }
+    pub fn conflict(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::Conflict, message, details) }
+    pub fn forbidden(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::Forbidden, message, details) }
+    pub fn internal(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::Internal, message, details) }
+    pub fn unavailable(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::Unavailable, message, details) }
+    pub fn timeout(message: impl Into<String>, details: impl Into<String>) -> Self { Self::new(ErrorCode::Timeout, message, details) }
+
+    #[cfg(feature = "axum")]
+    pub fn status(&self) -> axum::http::StatusCode {
+        axum::http::StatusCode::from_u16(self.code.http_status()).expect("valid status code")
+    }
+
+    /// Nest the error under `prefix` (`channels[1]` + `samples` → `channels[1].samples`).
+    pub fn within(mut self, prefix: &str) -> Self {
+        self.field = Some(match self.field.take() {
+            Some(f) if f.starts_with('[') => format!("{prefix}{f}"),
+            Some(f) => format!("{prefix}.{f}"),
+            None => prefix.to_string(),
+        });
+        self
+    }
+
+    /// One-line form for transports without a structured error body (gRPC, ROS 2).
+    pub fn summary(&self) -> String {
+        match (&self.field, &self.details) {
+            (Some(f), Some(d)) => format!("{} ({f}): {d}", self.message),
+            (None, Some(d)) => format!("{}: {d}", self.message),
+            (_, None) => self.message.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(&self.summary()) }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response { (self.status(), axum::Json(self)).into_response() }
+}
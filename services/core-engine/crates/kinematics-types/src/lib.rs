@@ -0,0 +1,1240 @@
+// ── ALICE Kinematics Cloud API types ────────────────────────
+//
+// Request, response and error types of the REST API, shared by the engine that
+// serves them and `kinematics-client` that sends them, so both ends of the wire are
+// one definition. Every type serializes and deserializes; with the `schema` feature
+// they also derive the OpenAPI schemas the engine publishes, field docs included.
+
+mod error;
+mod model;
+
+pub use error::{ApiError, ErrorCode, FieldError};
+pub use model::*;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// `elapsed_us` read as u64: serde cannot buffer a u128, which `flatten` needs.
+fn micros<'de, D: Deserializer<'de>>(d: D) -> Result<u128, D::Error> { u64::deserialize(d).map(u128::from) }
+
+fn is_false(b: &bool) -> bool { !b }
+
+/// Component order of every quaternion the engine returns: `w` last.
+pub const QUATERNION_ORDER: &str = "xyzw";
+
+fn quaternion_order() -> String { QUATERNION_ORDER.into() }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Health {
+    pub status: String, pub version: String, pub uptime_secs: u64, pub total_solves: u64,
+    #[serde(default)] pub capabilities: Capabilities,
+}
+/// What this build and deployment offer, for feature detection at runtime.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Capabilities {
+    /// Commit the engine was built from; `unknown` when built outside a git checkout.
+    pub git_sha: String,
+    /// Optional features compiled in, e.g. `grpc`, `graphql`, `mqtt`, `nats`, `ros2`, `onnx`, `otel`, `zstd`.
+    pub features: Vec<String>,
+    /// Backend per store (`chains`, `keys`, `intents`, `stats`, `audit`): `file` or `memory`.
+    pub storage: BTreeMap<String, String>,
+    /// Whether an ONNX intent model is loaded; heuristic classification otherwise.
+    pub intent_model: bool,
+    pub solvers: Vec<String>, pub seed_policies: Vec<String>,
+    /// Chains (presets plus registered, latest versions) in the default tenant's registry; absent while it loads.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub chains: Option<usize>,
+}
+
+/// `/readyz`: `ready` when every check passes, otherwise `not_ready`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Readiness { pub status: String, pub checks: BTreeMap<String, ReadinessCheck> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ReadinessCheck { pub ok: bool, #[serde(default, skip_serializing_if = "Option::is_none")] pub detail: Option<String> }
+/// `GET /selftest`: the built-in golden cases, per solver backend.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfTest { pub passed: bool, pub backends: BTreeMap<String, SelfTestBackend>, #[serde(deserialize_with = "micros")] pub elapsed_us: u128 }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfTestBackend { pub passed: bool, pub cases: Vec<SelfTestCase> }
+/// One golden case on one backend. `error` is the distance (m) from the case's known
+/// end-effector position: of FK of the known solution on `fk`, of FK of the solve elsewhere.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfTestCase {
+    pub name: String, pub passed: bool, pub error: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    #[serde(default)] pub iterations: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub detail: Option<String>,
+}
+
+// ── IK ──────────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkRequest {
+    /// Registered chain whose link lengths and DoF replace `joint_count`.
+    pub chain_id: Option<String>,
+    pub target_position: [f64; 3],
+    pub target_orientation: Option<[f64; 4]>,
+    pub joint_count: Option<u32>,
+    pub constraints: Option<IkConstraints>,
+    /// Overrides the chain's default solver and seed policy.
+    pub solver: Option<String>, pub seed_policy: Option<String>,
+    /// Explicit starting configuration (angles or a configuration name), taking
+    /// precedence over any seed policy.
+    pub seed: Option<Joints>,
+    /// Time budget in ms, capped at the engine's solve timeout; see `timed_out`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub deadline_ms: Option<u64>,
+    /// Time budget in µs to spend instead of `max_iterations`, adapting the damping as it
+    /// goes; the best solution by then comes back with its `quality`. Needs `chain_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub deadline_us: Option<u64>,
+    /// `f64` (the default) or `f32`: single precision with a relaxed tolerance, for
+    /// throughput over the last digits (animation, previews). Needs `chain_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub precision: Option<String>,
+    /// `none` (the default), `wrap_to_pi` or `shortest_from_seed`: how solved revolute
+    /// angles are brought to one turn; `shortest_from_seed` keeps them continuous with the seed.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub wrap_policy: Option<String>,
+    /// Robot session standing in for `chain_id` (its pinned chain) and, without a `seed`,
+    /// for the starting configuration (its current joint state).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub session_id: Option<String>,
+}
+/// Joint angles given inline or as the name of one of the chain's configurations.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum Joints { Angles(Vec<f64>), Named(String) }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkConstraints { pub max_iterations: Option<u32>, pub tolerance: Option<f64>, pub damping: Option<f64> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkResponse {
+    pub solution_id: String, pub joint_angles: Vec<f64>, pub iterations: u32,
+    pub converged: bool, pub error_distance: f64,
+    /// Residual rotation (rad) when `target_orientation` was solved for.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    /// Effective settings after applying request overrides to the chain defaults.
+    pub solver: SolverSettings,
+    /// Pinned `id@vN` of the chain used, for reproducing the solve.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub chain: Option<String>,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The deadline passed before convergence; the angles are those reached by then.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+    /// Seeded from the solution for a nearby recent target rather than the seed policy.
+    #[serde(default, skip_serializing_if = "is_false")] pub warm_started: bool,
+    /// With `deadline_us`: 1 when converged, otherwise the fraction of the seed's error removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub quality: Option<f64>,
+    /// Jacobian conditioning at a chain solve's returned angles: its smallest singular
+    /// value, and the largest over the smallest (absent when exactly singular). A large
+    /// condition number on a converged solution means it sits near a singularity.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub min_singular_value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub condition_number: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SolverSettings {
+    pub solver: String, pub max_iterations: u32, pub tolerance: f64, pub damping: f64, pub seed_policy: String,
+    /// `f32` when solved in single precision; `tolerance` is then the relaxed one applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub precision: Option<String>,
+}
+
+/// Many position targets on one chain, each solved independently from the same seed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkBatchRequest {
+    pub chain_id: String,
+    pub targets: Vec<[f64; 3]>,
+    pub constraints: Option<IkConstraints>,
+    pub seed_policy: Option<String>, pub seed: Option<Joints>,
+    /// `auto` (the default: the GPU from `KINEMATICS_GPU_BATCH_THRESHOLD` targets, when there is one), `cpu` or `gpu`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub backend: Option<String>,
+    /// Time budget in ms for the whole batch, as in `IkRequest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub deadline_ms: Option<u64>,
+    /// `f64` or `f32`, as in `IkRequest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub precision: Option<String>,
+    /// As in `IkRequest`, relative to the batch's seed.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub wrap_policy: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkBatchResponse {
+    /// One per target, in order.
+    pub solutions: Vec<BatchSolution>,
+    /// How many solutions converged.
+    pub converged: usize,
+    pub solver: SolverSettings, pub chain: String,
+    /// `cpu` or `gpu`: where the batch was solved.
+    pub backend: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The deadline passed before every target converged.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BatchSolution { pub joint_angles: Vec<f64>, pub iterations: u32, pub converged: bool, pub error_distance: f64 }
+
+/// A pose to test against a chain's workspace.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ReachableRequest {
+    pub chain_id: String, pub target_position: [f64; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub target_orientation: Option<[f64; 4]>,
+    /// Position error (m) still counted as reached; the chain's solver tolerance by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tolerance: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ReachableResponse {
+    pub reachable: bool,
+    /// Clearance (m) of the target inside the chain's reach sphere; negative outside it.
+    pub margin: f64,
+    /// Closest approach found (m, and rad when an orientation was given); 0 within tolerance when reachable.
+    pub position_error: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    /// `reach`: the target is beyond the reach sphere and `position_error` is a lower bound;
+    /// `search`: decided by a short search over the joint space.
+    pub decided_by: String,
+    pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+/// A fixed tool pose on a 7-joint chain, to sweep through its elbow swivel postures.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfMotionRequest {
+    pub chain_id: String, pub target_position: [f64; 3], pub target_orientation: [f64; 4],
+    /// Swivel angles sampled over the full turn (default 36, every 10°).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub samples: Option<usize>,
+    /// Where the first IK solve starts; the chain's seed policy by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub seed: Option<Joints>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfMotionResponse {
+    /// One per swivel angle, ascending from -π.
+    pub samples: Vec<SelfMotionSample>,
+    /// How many samples hold the pose within the joint limits.
+    pub feasible: usize,
+    /// Swivel of the IK solution the sweep started from.
+    pub seed_swivel: f64,
+    pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The solve timeout passed before every swivel was tried; the rest are missing.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SelfMotionSample {
+    /// Elbow angle (rad) about the shoulder–wrist line.
+    pub swivel: f64, pub joint_angles: Vec<f64>,
+    /// False where the joint limits rule this swivel out; the angles are then the closest found.
+    pub feasible: bool, pub position_error: f64, pub orientation_error: f64,
+    /// Smallest distance of any limited joint from its nearer limit (rad or m).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub limit_margin: Option<f64>,
+}
+
+// IK stream: session settings in the upgrade query, one target per message
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct IkStreamQuery {
+    pub chain_id: Option<String>, pub joint_count: Option<u32>, pub solver: Option<String>, pub seed_policy: Option<String>,
+    /// Configuration name seeding the first solve.
+    pub seed: Option<String>,
+    pub max_iterations: Option<u32>, pub tolerance: Option<f64>, pub damping: Option<f64>,
+    /// Time budget per target in ms, or in µs to spend adaptively as in `IkRequest`.
+    pub deadline_ms: Option<u64>, pub deadline_us: Option<u64>,
+    /// `f64` or `f32`, and the `wrap_policy`, as in `IkRequest`; `shortest_from_seed`
+    /// keeps each solution continuous with the previous one.
+    pub precision: Option<String>, pub wrap_policy: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IkStreamTarget {
+    pub target_position: [f64; 3], pub target_orientation: Option<[f64; 4]>,
+    /// Echoed back so clients can match solutions to targets.
+    pub seq: Option<u64>,
+    /// Overrides the carried-over seed for this solve.
+    pub seed: Option<Joints>,
+}
+
+// ── FK ──────────────────────────────────────────────────────
+/// `verify`: an IK solve of the request's pose, and FK of its solution measured against that pose.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct VerifyResponse {
+    pub ik: IkResponse, pub fk: FkResponse,
+    /// Distance (m) from the FK end effector to `target_position`.
+    pub position_error: f64,
+    /// Angle (rad) from the FK end-effector orientation to `target_orientation`, when one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    /// The closed-loop errors agree with those IK reported, within its tolerance.
+    pub consistent: bool,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FkRequest {
+    pub chain_id: Option<String>, pub joint_angles: Vec<f64>, pub link_lengths: Option<Vec<f64>>,
+    /// Length (m) of the links a short `link_lengths` leaves out; without it, `link_lengths`
+    /// must give one per joint.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub pad_link_length: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FkResponse {
+    pub end_effector_position: [f64; 3],
+    /// Unit quaternion with `w >= 0`, in `quaternion_order`.
+    pub end_effector_orientation: [f64; 4],
+    /// Always [`QUATERNION_ORDER`] (`"xyzw"`).
+    #[serde(default = "quaternion_order")] pub quaternion_order: String,
+    pub joint_positions: Vec<[f64; 3]>,
+    /// Indices of joints whose angle lies outside the chain's limits.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub limit_violations: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub chain: Option<String>,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+// ── Differential kinematics ─────────────────────────────────
+/// A tool twist to map onto joint velocities at `joint_angles` (resolved-rate control).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct VelocityIkRequest {
+    pub chain_id: String, pub joint_angles: Vec<f64>,
+    /// Tool velocity (m/s).
+    pub linear_velocity: [f64; 3],
+    /// Tool angular velocity (rad/s); without it only the tool position is controlled.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub angular_velocity: Option<[f64; 3]>,
+    /// `base` (the default) or `tool`: the frame the twist is given in.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub frame: Option<String>,
+    /// Damping reached at a singularity (default 0.05); none is applied away from one.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub damping: Option<f64>,
+    /// Joint speed limits (rad/s or m/s): one for every joint, or one per joint. When a
+    /// joint would exceed its limit, all velocities are scaled down together.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub max_joint_velocity: Option<Vec<f64>>,
+    /// Control period (s); when given, the response includes the angles one period ahead.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub dt: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct VelocityIkResponse {
+    pub joint_velocities: Vec<f64>,
+    /// Factor (≤ 1) applied to keep every joint within `max_joint_velocity`, and the joint that set it.
+    pub scale: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub limiting_joint: Option<usize>,
+    /// Joints held still because they sit at a position limit and the twist pushes them past it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub blocked_joints: Vec<usize>,
+    /// The tool twist the joint velocities produce, in the request's `frame`.
+    pub linear_velocity: [f64; 3], pub angular_velocity: [f64; 3],
+    /// Magnitude of the requested twist the solution misses before scaling, from damping or blocked joints.
+    pub twist_error: f64,
+    /// Smallest singular value of the Jacobian, and the damping it called for.
+    pub min_singular_value: f64, pub damping: f64,
+    /// `joint_angles + joint_velocities · dt`, clamped to the limits, when `dt` was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub next_joint_angles: Option<Vec<f64>>,
+    pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+/// A tool acceleration to map onto joint accelerations at `joint_angles` moving at
+/// `joint_velocities`, for operational-space controllers.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct AccelerationIkRequest {
+    pub chain_id: String, pub joint_angles: Vec<f64>, pub joint_velocities: Vec<f64>,
+    /// Tool acceleration (m/s²).
+    pub linear_acceleration: [f64; 3],
+    /// Tool angular acceleration (rad/s²); without it only the tool position is controlled.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub angular_acceleration: Option<[f64; 3]>,
+    /// `base` (the default) or `tool`: the axes the accelerations are given along.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub frame: Option<String>,
+    /// Damping reached at a singularity (default 0.05); none is applied away from one.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub damping: Option<f64>,
+    /// Joint acceleration limits (rad/s² or m/s²): one for every joint, or one per joint.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub max_joint_acceleration: Option<Vec<f64>>,
+    /// Control period (s); when given, the response includes the state one period ahead.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub dt: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct AccelerationIkResponse {
+    pub joint_accelerations: Vec<f64>,
+    /// Factor (≤ 1) applied to keep every joint within `max_joint_acceleration`, and the joint that set it.
+    pub scale: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub limiting_joint: Option<usize>,
+    /// Joints held still because they sit at a position limit and would be driven past it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub blocked_joints: Vec<usize>,
+    /// The tool acceleration the solution produces, in the request's `frame`.
+    pub linear_acceleration: [f64; 3], pub angular_acceleration: [f64; 3],
+    /// J̇q̇: the tool acceleration the joint velocities cause with no joint acceleration.
+    pub bias_linear_acceleration: [f64; 3], pub bias_angular_acceleration: [f64; 3],
+    /// The tool's current velocity, J q̇.
+    pub linear_velocity: [f64; 3], pub angular_velocity: [f64; 3],
+    /// Magnitude of the requested acceleration the solution misses before scaling.
+    pub acceleration_error: f64,
+    pub min_singular_value: f64, pub damping: f64,
+    /// The joint velocities and angles (clamped to the limits) one `dt` ahead, when `dt` was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub next_joint_velocities: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub next_joint_angles: Option<Vec<f64>>,
+    pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+/// Cartesian stiffness and damping at the tool, to express in joint space at `joint_angles`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ComplianceRequest {
+    pub chain_id: String, pub joint_angles: Vec<f64>,
+    /// Symmetric 3 × 3 (translation, N/m) or 6 × 6 (then rotation, N·m/rad) matrix, or a single row giving its diagonal.
+    pub stiffness: Vec<Vec<f64>>,
+    /// Cartesian damping (N·s/m, N·m·s/rad), in the same form and size as `stiffness`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub damping: Option<Vec<Vec<f64>>>,
+    /// `base` (the default) or `tool`: the axes the matrices are given along.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub frame: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ComplianceResponse {
+    /// Jᵀ K J (n × n), in N·m/rad or N/m per joint.
+    pub joint_stiffness: Vec<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub joint_damping: Option<Vec<Vec<f64>>>,
+    /// Smallest singular value of the Jacobian rows used; near zero, some tool directions get no joint stiffness.
+    pub min_singular_value: f64,
+    pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+// ── Intent compression ──────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IntentRequest {
+    pub samples: Vec<MotionSample>, pub sample_rate_hz: Option<u32>,
+    /// `"pca"` additionally returns the principal-component decomposition of the window.
+    pub mode: Option<String>, pub pca_components: Option<usize>,
+    pub filter: Option<FilterSpec>,
+    /// `"cartesian"` (default) analyses `position`; `"joint"` analyses `joint_angles`.
+    pub space: Option<String>,
+    /// Also pack the raw stream with the delta+quantization codec.
+    pub codec: Option<CodecSpec>,
+    /// Groups results in the intent history for later querying.
+    pub session_id: Option<String>,
+    /// `"heuristic"` (default) or `"model"` to use the ONNX classifier loaded at startup.
+    pub classifier: Option<String>,
+    /// Extrapolate the movement's endpoint and ETA (Cartesian space only).
+    pub predict: Option<bool>, pub prediction_horizon_ms: Option<u64>,
+    /// Interpolate irregular samples onto a uniform `sample_rate_hz` grid before analysis.
+    pub resample: Option<bool>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct CodecSpec { pub step: Option<f64>, pub entropy: Option<String> }
+/// Smoothing applied to sample positions before analysis: `kalman`, `savgol` or `butterworth`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FilterSpec {
+    pub kind: String, pub cutoff_hz: Option<f64>, pub window: Option<usize>, pub order: Option<usize>,
+    pub process_noise: Option<f64>, pub measurement_noise: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MotionSample {
+    pub timestamp_ms: u64, #[serde(default)] pub position: [f64; 3], pub velocity: Option<[f64; 3]>,
+    pub joint_angles: Option<Vec<f64>>,
+    /// Optional `[x, y, z, w]` orientation; enables the rotational intents.
+    pub orientation: Option<[f64; 4]>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IntentResponse {
+    pub intent_id: String, pub compressed_bytes: u64, pub original_samples: usize,
+    pub compression_ratio: f64, pub intent_type: String, pub confidence: f64,
+    pub alternatives: Vec<IntentAlternative>, pub direction: [f64; 3],
+    pub magnitude: f64, pub avg_speed: f64, pub estimated_velocities: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub timing: Option<TimingSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub rotation: Option<RotationSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub prediction: Option<PredictionSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub joint_space: Option<JointSpaceSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub pca: Option<PcaSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub filter: Option<FilterSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub encoded: Option<EncodedStream>,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FilterSummary { pub kind: String, pub raw: PathStatsSummary, pub filtered: PathStatsSummary }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct PathStatsSummary { pub displacement: f64, pub path_length: f64, pub avg_speed: f64, pub jitter: f64 }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RotationSummary { pub axis: [f64; 3], pub angle_rad: f64, pub path_angle_rad: f64, pub angular_speed: f64 }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TimingSummary {
+    pub nominal_rate_hz: f64, pub effective_rate_hz: f64, pub duration_ms: f64,
+    pub gaps: Vec<SampleGap>, pub resampled_samples: Option<usize>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SampleGap { pub start_ms: u64, pub end_ms: u64, pub missing_samples: u64 }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct PredictionSummary { pub model: String, pub endpoint: [f64; 3], pub eta_ms: f64, pub reaches_rest: bool }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JointSpaceSummary { pub displacement: Vec<f64>, pub dominant_joint: usize, pub segments: Vec<MotionSegment> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MotionSegment { pub start_ms: u64, pub end_ms: u64, pub moving: bool, pub dominant_joint: usize }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IntentAlternative { pub intent_type: String, pub score: f64 }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct PcaSummary {
+    pub mean: Vec<f64>, pub basis: Vec<Vec<f64>>, pub explained_variance: Vec<f64>,
+    pub explained_variance_ratio: Vec<f64>, pub coefficients: Vec<Vec<f64>>,
+}
+
+// Multi-channel intent
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MultiIntentRequest { pub channels: Vec<IntentChannel> }
+/// One labelled stream (`left_hand`, `right_hand`, `head`, ...) with its own analysis options.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IntentChannel { pub label: String, #[serde(flatten)] pub request: IntentRequest }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MultiIntentResponse { pub coordination: String, pub channels: Vec<ChannelIntent>, #[serde(deserialize_with = "micros")] pub elapsed_us: u128 }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChannelIntent { pub label: String, #[serde(flatten)] pub intent: IntentResponse }
+
+// Intent history
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct IntentQuery { pub session: Option<String>, pub from: Option<u64>, pub to: Option<u64>, pub limit: Option<usize>, pub offset: Option<usize> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct IntentHistoryResponse { pub total: usize, pub intents: Vec<IntentRecord> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject), graphql(name = "Intent"))]
+pub struct IntentRecord {
+    pub intent_id: String,
+    pub session_id: Option<String>,
+    pub recorded_at_ms: u64,
+    pub space: String,
+    pub intent_type: String,
+    pub confidence: f64,
+    pub direction: [f64; 3],
+    pub magnitude: f64,
+    pub original_samples: usize,
+    /// Owning tenant; absent for the default tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub tenant: Option<String>,
+}
+
+// ── Codec ───────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct EncodeRequest { pub frames: Vec<Vec<f64>>, pub timestamps_ms: Option<Vec<u64>>, #[serde(flatten)] pub spec: CodecSpec }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct EncodedStream {
+    /// Base64 of the AKQ1 byte stream.
+    pub data: String, pub raw_bytes: u64, pub encoded_bytes: u64, pub compression_ratio: f64, pub max_error: f64,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct EncodeResponse { #[serde(flatten)] pub stream: EncodedStream, #[serde(deserialize_with = "micros")] pub elapsed_us: u128 }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct DecodeRequest { pub data: String }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct DecodeResponse { pub frames: Vec<Vec<f64>>, pub timestamps_ms: Option<Vec<u64>>, pub step: f64, #[serde(deserialize_with = "micros")] pub elapsed_us: u128 }
+
+// ── Trajectory ──────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrajectoryRequest {
+    /// Cartesian points, or configuration names resolved to the chain's tip position.
+    pub waypoints: Vec<Waypoint>, pub max_velocity: Option<f64>,
+    /// Registered chain; waypoints beyond its reach are rejected.
+    pub chain_id: Option<String>,
+    pub max_acceleration: Option<f64>, pub smoothness: Option<f64>,
+    /// Robot session to plan from and execute on: its pinned chain stands in for
+    /// `chain_id`, the path starts at its current end effector, and its joint state moves
+    /// to the last waypoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub session_id: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum Waypoint { Point(Vec<f64>), Named(String) }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrajectoryResponse {
+    pub trajectory_id: String, pub optimized_waypoints: Vec<TrajectoryPoint>,
+    pub total_distance: f64, pub total_time: f64, pub max_velocity_reached: f64, #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrajectoryPoint { pub position: [f64; 3], pub velocity: [f64; 3], pub time: f64 }
+/// Last line of a streamed (`application/x-ndjson`) trajectory, after one `TrajectoryPoint` line per point.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrajectorySummary {
+    pub trajectory_id: String, pub points: usize,
+    pub total_distance: f64, pub total_time: f64, pub max_velocity_reached: f64, #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+// ── Retargeting ─────────────────────────────────────────────
+/// A motion on one chain (by default the human arm) carried over to another: the
+/// source end-effector path, scaled and placed in the target's base frame, solved
+/// frame by frame on the target.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RetargetRequest {
+    /// Chain the motion was recorded on (default `human_arm`).
+    pub source_chain_id: Option<String>,
+    pub target_chain_id: String,
+    /// The motion as source joint angles, one row per frame...
+    pub joint_angles: Option<Vec<Vec<f64>>>,
+    /// ...or as source end-effector (wrist) positions in its base frame, with
+    /// `orientations` (`[x, y, z, w]`, one per position) to follow them too.
+    pub positions: Option<Vec<[f64; 3]>>, pub orientations: Option<Vec<[f64; 4]>>,
+    /// Factor on source positions; the target's reach over the source's by default.
+    pub scale: Option<f64>,
+    /// Rotation (`[x, y, z, w]`) from the source base frame into the target's, then
+    /// `offset` (m) in the target base frame; identity and zero by default.
+    pub rotation: Option<[f64; 4]>, pub offset: Option<[f64; 3]>,
+    /// Turn the target end effector as the source's turns, from its orientation at the seed.
+    #[serde(default)] pub follow_orientation: bool,
+    /// Target configuration the first frame is solved from (angles or a configuration
+    /// name); each later frame starts from the one before.
+    pub seed: Option<Joints>,
+    pub constraints: Option<IkConstraints>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RetargetResponse {
+    pub retarget_id: String,
+    /// Pinned `id@vN` of both chains.
+    pub source_chain: String, pub target_chain: String,
+    /// Scale applied to source positions.
+    pub scale: f64,
+    /// One per source frame, in order.
+    pub frames: Vec<RetargetFrame>,
+    /// Frames that converged, and frames whose target was pulled within reach.
+    pub converged: usize, pub clamped: usize,
+    /// Largest `error_distance` over the frames.
+    pub max_error: f64,
+    pub solver: SolverSettings,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The deadline passed before every frame was solved; later frames are missing.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RetargetFrame {
+    pub joint_angles: Vec<f64>,
+    /// Where the target end effector was sent, in its base frame.
+    pub target_position: [f64; 3],
+    pub iterations: u32, pub converged: bool, pub error_distance: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    /// The scaled position lay beyond the target's reach and was pulled onto it.
+    #[serde(default, skip_serializing_if = "is_false")] pub clamped: bool,
+    /// Joints held at a limit in this frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub limit_hits: Vec<usize>,
+}
+
+// ── Motion capture ──────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct MocapQuery {
+    /// `bvh` or `c3d`; detected from the file when absent.
+    pub format: Option<String>,
+    /// Human chain to solve on (default `human_arm`).
+    pub chain_id: Option<String>,
+    /// `right` (the default) or `left`; a left limb is mirrored onto the chain.
+    pub side: Option<String>,
+    /// `arm` or `leg`; `leg` by default when `chain_id` contains "leg".
+    pub limb: Option<String>,
+    /// BVH joints or C3D markers at the root and end of the limb and, for C3D, at the
+    /// same point on the other side; comma-separated markers are averaged. Defaults
+    /// follow the side and limb.
+    pub base: Option<String>, pub tip: Option<String>, pub reference: Option<String>,
+    /// The file's up axis, `x`, `y` or `z`: `y` for BVH, `z` for C3D by default.
+    pub up: Option<String>,
+    /// Chain metres per file unit; by default BVH limbs are scaled to the chain's
+    /// reach, and C3D points are converted from their units.
+    pub scale: Option<f64>,
+    /// Solve every `stride`-th frame (default 1).
+    pub stride: Option<usize>,
+    /// Configuration name the first frame is solved from.
+    pub seed: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct BvhQuery {
+    /// `right` (the default) or `left`; names the joints and mirrors the limb.
+    pub side: Option<String>,
+    /// Offsets in `cm` (the default), `mm` or `m`.
+    pub units: Option<String>,
+    /// Seconds per frame; by default the stored motion's own frame spacing, else 1/30.
+    pub frame_time: Option<f64>,
+}
+/// A motion capture take solved frame by frame on a human chain.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MocapResponse {
+    pub solution_id: String, pub chain: String,
+    /// `bvh` or `c3d`.
+    pub format: String,
+    /// Frames in the file, and seconds between them.
+    pub source_frames: usize, pub frame_time: f64,
+    /// Chain metres per file unit applied.
+    pub scale: f64,
+    /// One per solved frame, and its time (s) from the start of the take.
+    pub frames: Vec<RetargetFrame>, pub times: Vec<f64>,
+    /// File frames skipped because a marker was missing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub gaps: Vec<usize>,
+    pub converged: usize, pub clamped: usize, pub max_error: f64,
+    pub solver: SolverSettings,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The solve timeout passed; later frames are missing.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+
+// ── Calibration ─────────────────────────────────────────────
+/// A rigid pose: `position` (m) and `orientation` `[x, y, z, w]`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Transform { pub position: [f64; 3], pub orientation: [f64; 4] }
+/// Stations of a camera on the flange looking at a fixed calibration target.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandEyeRequest {
+    /// The flange in the robot base at each station.
+    pub flange_poses: Vec<Transform>,
+    /// The target in the camera at each station, in the same order.
+    pub target_poses: Vec<Transform>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandEyeResponse {
+    /// The camera in the flange frame, in the shape of a chain's `tcp`.
+    pub camera_to_flange: Transform,
+    /// Always [`QUATERNION_ORDER`] (`"xyzw"`).
+    #[serde(default = "quaternion_order")] pub quaternion_order: String,
+    /// Stations, and the station pairs (motions) fitted.
+    pub stations: usize, pub pairs: usize,
+    /// RMS rotation (rad) and translation (m) left between AX and XB over all pairs.
+    pub rotation_rms: f64, pub translation_rms: f64,
+    /// Per station, the distance (m) of the target position it implies from the mean;
+    /// a station far above the rest is likely a bad detection.
+    pub station_errors: Vec<f64>,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+// ── Chains ──────────────────────────────────────────────────
+/// Full model of one chain version, as returned by `GET /chains/{id}`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChainDetail {
+    pub id: String, pub version: u32, pub versions: Vec<u32>, pub name: String, pub description: String,
+    pub dof: u32, pub joint_type: String, pub reach: f64, pub joints: Vec<JointDetail>,
+    pub tcp: Tcp, pub total_mass: Option<f64>, pub solver: SolverSettings,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub configurations: BTreeMap<String, Vec<f64>>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Configuration { pub name: String, pub joint_angles: Vec<f64>, pub chain: String }
+/// A joint with its `origin` always filled in (URDF-style, in the parent joint frame).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JointDetail { pub index: usize, #[serde(flatten)] pub joint: JointDef }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct ChainListQuery {
+    pub dof: Option<u32>, pub joint_type: Option<String>,
+    /// Case-insensitive match against id and name.
+    pub q: Option<String>,
+    pub limit: Option<usize>, #[serde(default)] pub offset: usize,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct ExportQuery { pub format: Option<String> }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct ImportQuery { pub format: Option<String>, pub id: Option<String>, pub name: Option<String>, pub tip: Option<String> }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MoveItImport {
+    pub urdf: String, pub srdf: String,
+    /// Planning groups to import; all supported groups when absent.
+    pub groups: Option<Vec<String>>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MoveItImportResponse { pub chains: Vec<ChainDef>, pub skipped: Vec<SkippedGroup> }
+/// One SRDF planning group that could not be reduced to a serial chain.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SkippedGroup { pub group: String, pub reason: String }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChainFlushResponse { pub path: Option<String>, pub chains: usize }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChainInfo { pub id: String, pub version: u32, pub name: String, pub description: String, pub dof: u32, pub joint_type: String }
+
+// ── Hands ───────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandInfo { pub id: String, pub name: String, pub description: String, pub dof: usize, pub fingers: Vec<String> }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandFkRequest {
+    /// Angles per finger; fingers left out are at zero.
+    #[serde(default)] pub joint_angles: FingerAngles,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandFkResponse {
+    pub fingertips: BTreeMap<String, Fingertip>,
+    /// Component order of each fingertip `orientation`: always [`QUATERNION_ORDER`] (`"xyzw"`).
+    #[serde(default = "quaternion_order")] pub quaternion_order: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Fingertip { pub position: [f64; 3], pub orientation: [f64; 4] }
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandIkRequest {
+    /// Fingertip targets by finger; untargeted fingers keep their seed.
+    pub targets: BTreeMap<String, FingerTarget>,
+    /// Starting angles per finger (targeted fingers default to mid-range, others to zero).
+    #[serde(default)] pub seed: FingerAngles,
+    pub constraints: Option<IkConstraints>,
+    /// Time budget in ms for all fingers, as in `IkRequest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub deadline_ms: Option<u64>,
+    /// As in `IkRequest`, relative to each finger's seed.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub wrap_policy: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FingerTarget { pub position: [f64; 3], pub orientation: Option<[f64; 4]> }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandIkResponse {
+    pub solution_id: String,
+    /// Every finger's angles, and the same concatenated in the hand's finger order.
+    pub posture: FingerAngles, pub joint_angles: Vec<f64>,
+    pub fingers: BTreeMap<String, FingerSolution>, pub converged: bool, #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// Solver time summed over the fingers, which are solved in parallel; above `elapsed_us` when that paid off.
+    #[serde(deserialize_with = "micros")] pub solve_us: u128,
+    /// The deadline passed before every finger converged.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FingerSolution {
+    pub iterations: u32, pub converged: bool, pub error_distance: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub orientation_error: Option<f64>,
+    /// Jacobian conditioning at the finger's returned angles, as in `IkResponse`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub min_singular_value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub condition_number: Option<f64>,
+    /// This finger's solve alone.
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+}
+
+// ── Simulation ──────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SimRequest {
+    pub chain_id: String,
+    /// Starting angles, or a named configuration; all zeros by default.
+    pub joint_angles: Option<Joints>,
+    /// Starting joint velocities; at rest by default.
+    pub joint_velocities: Option<Vec<f64>>,
+    /// Fixed time step in seconds (default 0.001).
+    pub dt: Option<f64>,
+    /// m/s² in the world frame (default `[0, 0, -9.81]`).
+    pub gravity: Option<[f64; 3]>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SimStepRequest {
+    /// `velocity` (rad/s or m/s per joint) or `torque` (N·m or N per joint).
+    pub mode: String,
+    /// One value per joint, held for every step.
+    pub command: Vec<f64>,
+    /// Steps of `dt` to take (default 1).
+    pub steps: Option<u32>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SimState {
+    pub sim_id: String, pub chain: String, pub dt: f64, pub gravity: [f64; 3],
+    /// Simulated seconds, and steps taken, since creation.
+    pub time: f64, pub steps: u64,
+    pub joint_angles: Vec<f64>, pub joint_velocities: Vec<f64>,
+    pub end_effector_position: [f64; 3],
+    /// Unit quaternion with `w >= 0`, in `quaternion_order`.
+    pub end_effector_orientation: [f64; 4],
+    #[serde(default = "quaternion_order")] pub quaternion_order: String,
+    /// Joints stopped at a limit during the last step request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub limit_hits: Vec<usize>,
+}
+
+/// A planned joint trajectory run through a PD controller and actuator model, to see
+/// how closely a robot would follow it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrackingRequest {
+    pub chain_id: String,
+    /// Planned joint angles, one row per frame; execution starts at rest at the first.
+    pub joint_angles: Vec<Vec<f64>>,
+    /// Time of each frame (s, increasing), or a fixed `frame_time` between frames (default 0.01).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub times: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub frame_time: Option<f64>,
+    /// Proportional and derivative gains, one for every joint or one per joint. Under the
+    /// `kinematic` plant they give joint acceleration (1/s², 1/s); under `dynamic`, torque.
+    pub kp: Vec<f64>, pub kd: Vec<f64>,
+    /// Actuator bandwidth (Hz): the effort lags the controller through a first-order filter; instant when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub bandwidth_hz: Option<f64>,
+    /// Effort limit, one for every joint or one per joint (rad/s² or m/s² under `kinematic`, N·m or N under `dynamic`).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub max_effort: Option<Vec<f64>>,
+    /// `kinematic` (default): decoupled unit-inertia joints. `dynamic`: the chain's rigid-body
+    /// dynamics under gravity, which needs link masses.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub plant: Option<String>,
+    /// Under `dynamic`, add the gravity torques to the PD output (default true).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub gravity_compensation: Option<bool>,
+    /// Integration step (s, default 0.001).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub dt: Option<f64>,
+    /// Tip error (m) the execution must stay within; sets `within_tolerance`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tolerance: Option<f64>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrackingResponse {
+    /// The simulated state at every planned frame.
+    pub samples: Vec<TrackingSample>,
+    /// Per joint, over all frames.
+    pub max_joint_error: Vec<f64>, pub rms_joint_error: Vec<f64>,
+    /// End-effector distance from the planned pose (m): the largest, when it occurred, and the RMS.
+    pub max_tip_error: f64, pub max_tip_error_time: f64, pub rms_tip_error: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub within_tolerance: Option<bool>,
+    /// Joints whose effort hit `max_effort`, and joints stopped at a position limit.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub saturated_joints: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub limit_hits: Vec<usize>,
+    pub plant: String, pub steps: u64, pub chain: String,
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128,
+    /// The solve timeout passed before the end; later frames are missing.
+    #[serde(default, skip_serializing_if = "is_false")] pub timed_out: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TrackingSample { pub time: f64, pub joint_angles: Vec<f64>, pub joint_error: Vec<f64>, pub tip_error: f64 }
+
+// ── Sessions ────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SessionRequest {
+    pub chain_id: String,
+    /// Free-form name of the robot the session tracks.
+    pub robot: Option<String>,
+    /// Current joint angles, or a named configuration; all zeros by default.
+    pub joint_angles: Option<Joints>,
+}
+/// A joint state reported by the robot.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct StateReport { pub joint_angles: Joints }
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SessionState {
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub robot: Option<String>,
+    pub chain: String, pub joint_angles: Vec<f64>,
+    pub end_effector_position: [f64; 3],
+    /// Unit quaternion with `w >= 0`, in `quaternion_order`.
+    pub end_effector_orientation: [f64; 4],
+    #[serde(default = "quaternion_order")] pub quaternion_order: String,
+    /// What set the joint state last: `created`, `reported` or `trajectory`.
+    pub source: String,
+    /// Unix ms of the last update, and updates since creation.
+    pub updated_ms: u64, pub updates: u64,
+}
+
+// ── Jobs ────────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JobRequest {
+    /// `solve-ik`, `solve-ik/batch`, `solve-fk`, `compress-intent`, `optimize-trajectory` or `retarget`.
+    pub operation: String,
+    /// The body the synchronous endpoint takes.
+    #[cfg_attr(feature = "schema", schema(value_type = Object))] pub request: serde_json::Value,
+    /// http(s) URL POSTed the outcome when the job finishes or is cancelled.
+    pub callback_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobState { Queued, Running, Succeeded, Failed, Cancelled }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Job {
+    pub job_id: String, pub operation: String, pub state: JobState,
+    /// Fraction complete as reported by the operation; 1 once finished.
+    pub progress: f64,
+    pub created_at_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub started_at_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub finished_at_ms: Option<u64>,
+    /// A failed job's error, as the synchronous endpoint would have returned it.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub error: Option<ApiError>,
+    /// Completion callback registered with the job, and its delivery status.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub callback: Option<Delivery>,
+}
+
+impl Job {
+    pub fn finished(&self) -> bool { matches!(self.state, JobState::Succeeded | JobState::Failed | JobState::Cancelled) }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JobEvent {
+    pub seq: u32,
+    /// `queued`, `started`, `progress`, `milestone`, `succeeded`, `failed` or `cancelled`.
+    pub kind: String,
+    pub progress: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub message: Option<String>,
+    pub at_ms: u64,
+}
+
+impl JobEvent {
+    pub fn terminal(&self) -> bool { matches!(self.kind.as_str(), "succeeded" | "failed" | "cancelled") }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryState { Pending, Delivered, Failed }
+
+/// Where a job's completion callback goes and how delivery is going.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Delivery {
+    pub url: String, pub state: DeliveryState, pub attempts: u32,
+    /// Status of the last response, when there was one.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub last_status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub last_error: Option<String>,
+}
+
+impl Delivery {
+    pub fn new(url: String) -> Self { Self { url, state: DeliveryState::Pending, attempts: 0, last_status: None, last_error: None } }
+}
+
+// ── Audit ───────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct AuditQuery {
+    /// Recorded at or after / at or before, in ms since the epoch.
+    pub from: Option<u64>, pub to: Option<u64>,
+    /// A chain id matches every version of it; `id@vN` only that version.
+    pub chain_id: Option<String>,
+    pub hand_id: Option<String>, pub operation: Option<String>, pub caller: Option<String>,
+    pub limit: Option<usize>, pub offset: Option<usize>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct AuditResponse { pub total: usize, pub records: Vec<AuditRecord> }
+/// One solve, or one hot reload, as the audit log keeps it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct AuditRecord {
+    pub audit_id: String,
+    pub recorded_at_ms: u64,
+    /// `solve-ik`, `solve-ik-velocity`, `solve-ik-acceleration`, `reachable`, `self-motion`, `solve-ik-batch`, `retarget`, `mocap`, `solve-fk`, `hand-solve-ik`,
+    /// `hand-solve-fk`, `reload-chains` or `reload-config`.
+    pub operation: String,
+    /// Pinned chain (`id@vN`) or hand solved on; neither for the generic planar chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub chain_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub hand_id: Option<String>,
+    /// SHA-256 (hex) of the request as the engine parsed it, serialized as JSON; for a reload, of the file read.
+    pub input_hash: String,
+    /// API key id or token subject; absent while auth is disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub caller: Option<String>,
+    /// IK only: for a hand, whether every finger converged, the most iterations and the largest error.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub converged: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub iterations: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub error_distance: Option<f64>,
+    /// IK only: fetchable from `/solutions/{solution_id}` while retained.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub solution_id: Option<String>,
+    pub latency_us: u64,
+    /// Reloads only: what changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub detail: Option<String>,
+    /// Owning tenant; absent for the default tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tenant: Option<String>,
+}
+
+// ── Stats and admin ─────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct StatsResponse {
+    /// When the totals began counting (ms since the epoch): the first start with the
+    /// engine's stats store, or this start without one.
+    #[serde(default)] pub since_ms: u64,
+    pub total_ik_solves: u64, pub total_fk_solves: u64, pub total_compressions: u64, pub total_trajectories: u64,
+    /// Usage per API key or token subject: everyone's for admins, the caller's own otherwise.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub keys: BTreeMap<String, Usage>,
+    /// Usage per tenant, for admins.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub tenants: BTreeMap<String, Usage>,
+    /// Solves per chain id (all versions) of the caller's tenant.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub chains: BTreeMap<String, ChainStats>,
+    /// Traffic and latency per `METHOD /route/template`, for admins.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub endpoints: BTreeMap<String, EndpointStats>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChainStats {
+    pub ik_solves: u64, pub ik_converged: u64, pub fk_solves: u64,
+    /// `ik_converged / ik_solves`; absent before the first IK solve.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub convergence_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub avg_iterations: Option<f64>,
+}
+
+/// Latencies are time to response headers, estimated from the `/metrics` histogram buckets.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct EndpointStats { pub requests: u64, pub client_errors: u64, pub server_errors: u64, pub p50_ms: f64, pub p95_ms: f64, pub p99_ms: f64 }
+
+/// Work done with a key, or within a tenant, since `since_ms`.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Usage { pub requests: u64, pub ik_solves: u64, pub fk_solves: u64, pub compressions: u64, pub trajectories: u64 }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ApiKey {
+    pub key_id: String, pub name: String,
+    /// May manage keys and see every tenant's usage.
+    #[serde(default)] pub admin: bool,
+    /// Tenant whose chains, hands, intents and jobs the key works with; the default tenant when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tenant: Option<String>,
+    pub created_at_ms: u64,
+    /// From config rather than the admin API; cannot be revoked at runtime.
+    #[serde(default)] pub configured: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct CreateKeyRequest {
+    pub name: String, #[serde(default)] pub admin: bool,
+    /// Tenant the key works in; the default tenant when absent.
+    pub tenant: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct CreatedKey {
+    #[serde(flatten)] pub key: ApiKey,
+    /// The key itself; shown only in this response.
+    pub secret: String,
+}
+
+/// While enabled, solve endpoints answer `unavailable` (503) on every transport; health
+/// and readiness stay green so the instance can be drained without being restarted.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Maintenance {
+    pub enabled: bool,
+    /// Shown to rejected callers.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub reason: Option<String>,
+    /// When it was last switched on or off (ms since the epoch) and by which key.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub since_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub changed_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct MaintenanceRequest { pub enabled: bool, pub reason: Option<String> }
+
+/// Synthetic IK workload for `POST /admin/bench`: random reachable targets on a chain.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BenchRequest {
+    pub chain_id: String,
+    /// IK solves to run (1000 when absent) and threads to run them on (the compute pool's size when absent).
+    pub solves: Option<usize>, pub threads: Option<usize>,
+    /// Seeds the target generator, so runs are repeatable; 1 when absent.
+    pub random_seed: Option<u64>,
+    pub solver: Option<String>, pub seed_policy: Option<String>, pub constraints: Option<IkConstraints>,
+    /// `f64` or `f32`, as in `IkRequest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub precision: Option<String>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BenchResponse {
+    pub chain: String, pub solves: usize, pub threads: usize, pub converged: usize, pub timed_out: usize, pub avg_iterations: f64,
+    pub solver: SolverSettings,
+    /// Wall time of the whole run, and solves per second over it.
+    #[serde(deserialize_with = "micros")] pub elapsed_us: u128, pub solves_per_sec: f64,
+    /// Time per solve.
+    pub latency_us: BenchLatency,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BenchLatency { pub min: u64, pub mean: f64, pub p50: u64, pub p90: u64, pub p99: u64, pub max: u64 }
+
+/// What the engine holds in memory, for debugging.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct EngineState {
+    pub uptime_secs: u64, pub draining: bool, pub maintenance: Maintenance,
+    /// Configuration file in use.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub config_file: Option<String>,
+    /// Registry sizes per tenant seen since start, presets included.
+    pub tenants: BTreeMap<String, TenantState>,
+    /// Jobs retained, and of those queued or running.
+    pub jobs: usize, pub active_jobs: usize,
+    /// Entries in each in-memory store.
+    pub solutions: usize, pub sims: usize, pub sessions: usize, pub intents: usize, pub audit_records: usize, pub idempotency_keys: usize, pub cached_results: usize, pub rate_limit_buckets: usize,
+    pub streaming_sessions: usize, pub pending_events: usize, pub api_keys: usize,
+    /// Target regions with a remembered IK solution to warm-start from.
+    pub warm_starts: usize,
+    /// Requests and running jobs per route template, where there are any.
+    pub in_flight: BTreeMap<String, usize>,
+    /// Compute pool threads, and solves waiting for one or running on one.
+    pub compute_threads: usize, pub compute_queued: usize, pub compute_running: usize,
+    /// GPU adapter batch IK can use, if any, and the batch size from which `auto` picks it.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub gpu: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub gpu_batch_threshold: Option<usize>,
+    /// Engine-wide solver settings and body limits currently in effect.
+    pub solver_defaults: SolverDefaults, pub body_limits: BodyLimits,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TenantState { pub chains: usize, pub hands: usize }
+
+/// Maximum request body sizes in bytes, and waypoints, samples, frames or targets per request.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BodyLimits {
+    pub samples: usize, pub import: usize, pub default: usize,
+    /// Unlimited when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub points: Option<usize>,
+}
@@ -0,0 +1,296 @@
+// ── Models ──────────────────────────────────────────────────
+//
+// Chain and hand definitions as registered, stored and returned by the API, with the
+// structural checks the registry applies before accepting one, plus the specs for
+// generating chains from templates, compositions and anthropometric scaling.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// ── Chains ──────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JointType { Revolute, Prismatic }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JointDef {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub name: Option<String>,
+    #[serde(rename = "type")] pub joint_type: JointType,
+    #[serde(default = "default_axis")] pub axis: [f64; 3],
+    /// `[min, max]` in radians (revolute) or metres (prismatic).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub limits: Option<[f64; 2]>,
+    #[serde(default)] pub link_length: f64,
+    /// Pose of this joint's frame in the previous joint's frame (URDF `origin`). When
+    /// absent the joint sits at the end of the previous link: `[link_length, 0, 0]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub origin: Option<Origin>,
+    /// Denavit–Hartenberg parameters, carried as metadata for DH-based tooling.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub dh: Option<DhParams>,
+    /// Mass properties of the link that follows this joint.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub inertial: Option<Inertial>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Origin { pub xyz: [f64; 3], #[serde(default)] pub rpy: [f64; 3] }
+
+/// Classic DH row: link length `a`, twist `alpha`, offset `d`, joint angle offset `theta`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct DhParams { pub a: f64, pub alpha: f64, pub d: f64, #[serde(default)] pub theta: f64 }
+
+/// Link mass (kg), centre of mass in the link frame (m) and inertia tensor
+/// `[ixx, ixy, ixz, iyy, iyz, izz]` about the centre of mass (kg·m²).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Inertial { pub mass: f64, #[serde(default)] pub com: [f64; 3], #[serde(default)] pub inertia: [f64; 6] }
+
+/// Tool centre point relative to the tip of the last link; orientation is `[x, y, z, w]`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Tcp { #[serde(default)] pub position: [f64; 3], #[serde(default = "identity")] pub orientation: [f64; 4] }
+
+impl Default for Tcp {
+    fn default() -> Self { Self { position: [0.0; 3], orientation: identity() } }
+}
+
+fn identity() -> [f64; 4] { [0.0, 0.0, 0.0, 1.0] }
+
+fn default_axis() -> [f64; 3] { [0.0, 0.0, 1.0] }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ChainDef {
+    #[serde(default)] pub id: String,
+    /// Assigned by the registry: 1 on registration, +1 on every update.
+    #[serde(default = "first_version")] pub version: u32,
+    pub name: String, #[serde(default)] pub description: String,
+    pub joints: Vec<JointDef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tcp: Option<Tcp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub solver: Option<SolverDefaults>,
+    /// Named joint configurations (`home`, `ready`, …), one angle per joint.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")] pub configurations: BTreeMap<String, Vec<f64>>,
+    /// Link pairs (by source-model link name) exempt from self-collision checks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub disabled_collisions: Vec<[String; 2]>,
+}
+
+/// IK settings a chain's solves inherit unless the request overrides them.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SolverDefaults {
+    /// Solver backend; currently only `dls` (damped least squares).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub solver: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub damping: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tolerance: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub max_iterations: Option<u32>,
+    /// Starting configuration: `zero` (all joints at 0) or `mid` (centre of each joint's limits).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub seed_policy: Option<String>,
+}
+
+pub const SOLVERS: &[&str] = &["dls"];
+pub const SEED_POLICIES: &[&str] = &["zero", "mid"];
+pub const MAX_ITERATIONS: u32 = 100_000;
+
+impl SolverDefaults {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(s) = self.solver.as_deref().filter(|s| !SOLVERS.contains(s)) {
+            return Err(format!("solver '{s}' is not one of {}", SOLVERS.join(", ")));
+        }
+        if let Some(p) = self.seed_policy.as_deref().filter(|p| !SEED_POLICIES.contains(p)) {
+            return Err(format!("seed_policy '{p}' is not one of {}", SEED_POLICIES.join(", ")));
+        }
+        if self.damping.is_some_and(|d| !(d.is_finite() && d > 0.0)) { return Err("damping must be a positive number".into()); }
+        if self.tolerance.is_some_and(|t| !(t.is_finite() && t > 0.0)) { return Err("tolerance must be a positive number".into()); }
+        if self.max_iterations.is_some_and(|m| m == 0 || m > MAX_ITERATIONS) {
+            return Err(format!("max_iterations must be between 1 and {MAX_ITERATIONS}"));
+        }
+        Ok(())
+    }
+
+    /// These settings, with any left unset taken from `fallback`.
+    pub fn or(&self, fallback: &SolverDefaults) -> SolverDefaults {
+        SolverDefaults {
+            solver: self.solver.clone().or_else(|| fallback.solver.clone()), damping: self.damping.or(fallback.damping),
+            tolerance: self.tolerance.or(fallback.tolerance), max_iterations: self.max_iterations.or(fallback.max_iterations),
+            seed_policy: self.seed_policy.clone().or_else(|| fallback.seed_policy.clone()),
+        }
+    }
+}
+
+fn first_version() -> u32 { 1 }
+
+impl ChainDef {
+    /// A bare chain (version 1, no TCP, solver defaults or extras) over `joints`.
+    pub fn single(id: &str, joints: Vec<JointDef>) -> ChainDef {
+        ChainDef {
+            id: id.into(), version: 1, name: id.into(), description: String::new(), joints, tcp: None, solver: None,
+            configurations: BTreeMap::new(), disabled_collisions: Vec::new(),
+        }
+    }
+
+    pub fn dof(&self) -> usize { self.joints.len() }
+
+    /// `id@vN`, the reference that pins this exact version.
+    pub fn pinned_id(&self) -> String { format!("{}@v{}", self.id, self.version) }
+
+    /// `revolute`, `prismatic` or `revolute+prismatic`.
+    pub fn joint_type(&self) -> String {
+        let has = |t| self.joints.iter().any(|j| j.joint_type == t);
+        match (has(JointType::Revolute), has(JointType::Prismatic)) {
+            (true, true) => "revolute+prismatic".into(),
+            (false, true) => "prismatic".into(),
+            _ => "revolute".into(),
+        }
+    }
+
+    /// Sum of link masses, when every link declares one.
+    pub fn total_mass(&self) -> Option<f64> {
+        self.joints.iter().map(|j| j.inertial.map(|i| i.mass)).sum()
+    }
+
+    /// Named configuration; `zero` is implied for every chain unless stored explicitly.
+    pub fn configuration(&self, name: &str) -> Option<Vec<f64>> {
+        self.configurations.get(name).cloned().or_else(|| (name == "zero").then(|| vec![0.0; self.dof()]))
+    }
+
+    /// Origin of joint `i`, explicit or implied by the previous link.
+    pub fn origin(&self, i: usize) -> Origin {
+        self.joints[i].origin.unwrap_or_else(|| {
+            Origin { xyz: [i.checked_sub(1).map_or(0.0, |p| self.joints[p].link_length), 0.0, 0.0], rpy: [0.0; 3] }
+        })
+    }
+
+    /// Upper bound on the distance from the base to the tip: every joint offset and the
+    /// TCP, plus the largest extension of every prismatic joint.
+    pub fn reach(&self) -> f64 {
+        let norm = |v: [f64; 3]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let last = self.joints.last().map_or(0.0, |j| j.link_length.abs());
+        let tcp = self.tcp.map_or(0.0, |t| norm(t.position));
+        (0..self.joints.len()).map(|i| {
+            let stroke = match (self.joints[i].joint_type, self.joints[i].limits) {
+                (JointType::Prismatic, Some([lo, hi])) => lo.abs().max(hi.abs()),
+                _ => 0.0,
+            };
+            norm(self.origin(i).xyz) + stroke
+        }).sum::<f64>() + last + tcp
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() || !self.id.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)) {
+            return Err(format!("chain id '{}' must be non-empty and use only letters, digits, '_', '-' or '.'", self.id));
+        }
+        if self.joints.is_empty() { return Err("a chain needs at least one joint".into()); }
+        if let Some(solver) = &self.solver { solver.validate().map_err(|e| format!("solver: {e}"))?; }
+        if let Some(tcp) = self.tcp {
+            let norm = tcp.orientation.iter().map(|v| v * v).sum::<f64>().sqrt();
+            if !(tcp.orientation.iter().chain(&tcp.position).all(|v| v.is_finite()) && (norm - 1.0).abs() <= 1e-3) {
+                return Err(format!("tcp must be finite with a unit quaternion orientation [x, y, z, w] (norm is {norm:.4})"));
+            }
+        }
+        for (i, j) in self.joints.iter().enumerate() {
+            let norm = j.axis.iter().map(|a| a * a).sum::<f64>().sqrt();
+            if !(norm.is_finite() && norm > 1e-9) { return Err(format!("joints[{i}].axis must be a non-zero vector")); }
+            if !j.link_length.is_finite() || j.link_length < 0.0 {
+                return Err(format!("joints[{i}].link_length must be a non-negative number"));
+            }
+            if let Some([lo, hi]) = j.limits {
+                if !(lo.is_finite() && hi.is_finite() && lo <= hi) { return Err(format!("joints[{i}].limits must be [min, max] with min <= max")); }
+            }
+            if let Some(o) = j.origin {
+                if !o.xyz.iter().chain(&o.rpy).all(|v| v.is_finite()) { return Err(format!("joints[{i}].origin must be finite")); }
+            }
+            if let Some(m) = j.inertial {
+                if !(m.mass.is_finite() && m.mass >= 0.0) { return Err(format!("joints[{i}].inertial.mass must be a non-negative number")); }
+            }
+        }
+        for (name, q) in &self.configurations {
+            validate_configuration(self, name, q).map_err(|e| format!("configurations.{name}: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// A named configuration must be a valid name with one in-limit angle per joint.
+pub fn validate_configuration(chain: &ChainDef, name: &str, q: &[f64]) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "_-".contains(c)) {
+        return Err("names must be non-empty and use only letters, digits, '_' or '-'".into());
+    }
+    if q.len() != chain.dof() { return Err(format!("{} angles for {} joints", q.len(), chain.dof())); }
+    for (i, (v, j)) in q.iter().zip(&chain.joints).enumerate() {
+        if !v.is_finite() { return Err(format!("angle {i} must be finite")); }
+        if let Some([lo, hi]) = j.limits.filter(|[lo, hi]| !(lo - 1e-9..=hi + 1e-9).contains(v)) {
+            return Err(format!("angle {i} ({v}) is outside the joint limits [{lo}, {hi}]"));
+        }
+    }
+    Ok(())
+}
+
+/// Parameters for generating a chain from a common template.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TemplateSpec {
+    /// `planar`, `6r_spherical_wrist`, `scara` or `delta`.
+    pub template: String,
+    pub id: String, pub name: Option<String>, pub description: Option<String>,
+    /// Joint count for `planar` (defaults to the number of link lengths, else 3).
+    pub joints: Option<usize>,
+    pub link_lengths: Option<Vec<f64>>,
+    /// Per-joint `[min, max]`, replacing the template defaults.
+    pub limits: Option<Vec<[f64; 2]>>,
+}
+
+/// A tool chain (gripper, sensor mount, …) attached to the tip of a base chain.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ComposeSpec {
+    pub id: String, pub name: Option<String>, pub description: Option<String>,
+    /// Chain references, either latest (`id`) or pinned (`id@vN`).
+    pub base: String, pub tool: String,
+    /// Tool base pose in the base chain's TCP frame (identity by default).
+    pub mount: Option<Origin>,
+}
+
+/// A human preset scaled to one subject.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct SubjectSpec {
+    /// Id (and optional name) of the chain to register.
+    pub id: String, pub name: Option<String>,
+    pub height_m: Option<f64>,
+    /// Stature percentile (1–99), used when `height_m` is absent.
+    pub percentile: Option<f64>,
+    /// Measured segment lengths in metres (`upper_arm`, `forearm`, `hand`, `thigh`,
+    /// `shank`, `foot`), overriding the height-derived ones.
+    #[serde(default)] pub segments: HashMap<String, f64>,
+    /// Body mass; when given every scaled link carries its mass properties.
+    pub mass_kg: Option<f64>,
+}
+
+// ── Hands ───────────────────────────────────────────────────
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct FingerDef {
+    pub name: String,
+    /// Pose of the finger's first joint in the palm frame.
+    pub base: Origin,
+    pub joints: Vec<JointDef>,
+    /// Fingertip pose after the last link (e.g. the pad centre).
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub tcp: Option<Tcp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct HandDef {
+    pub id: String, pub name: String, #[serde(default)] pub description: String,
+    pub fingers: Vec<FingerDef>,
+}
+
+impl HandDef {
+    pub fn dof(&self) -> usize { self.fingers.iter().map(|f| f.joints.len()).sum() }
+
+    pub fn finger(&self, name: &str) -> Option<&FingerDef> { self.fingers.iter().find(|f| f.name == name) }
+}
+
+/// Per-finger joint angles keyed by finger name.
+pub type FingerAngles = BTreeMap<String, Vec<f64>>;
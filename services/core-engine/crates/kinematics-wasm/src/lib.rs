@@ -0,0 +1,92 @@
+// ── ALICE Kinematics Cloud solver for the browser ───────────
+//
+// JS bindings of `kinematics-solver` for `wasm32-unknown-unknown`, so a visualizer
+// can run FK on every frame and preview IK without a round trip, and call the cloud
+// only for planning. Objects cross the boundary in the REST API's JSON shapes: a
+// chain as registered with `POST /chains` (or from `GET /chains/export`), an
+// `IkRequest` body in, `IkResponse` / `FkResponse` bodies out.
+//
+//     import init, { Chain } from "kinematics-wasm";
+//     await init();
+//     const arm = Chain.builtin("robotic_arm_6dof");
+//     const { joint_angles } = arm.ik({ target_position: [0.3, 0.1, 0.5] });
+//     const points = arm.jointPositions(joint_angles); // x, y, z per joint, then the tip
+
+use kinematics_types::{ChainDef, IkRequest};
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::prelude::*;
+
+fn from_js<T: DeserializeOwned>(value: &JsValue, what: &str) -> Result<T, JsError> {
+    let text = js_sys::JSON::stringify(value).map_err(|_| JsError::new(&format!("{what} is not JSON-serializable")))?;
+    serde_json::from_str(&String::from(text)).map_err(|e| JsError::new(&format!("invalid {what}: {e}")))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let text = serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))?;
+    js_sys::JSON::parse(&text).map_err(|_| JsError::new("response is not valid JSON"))
+}
+
+/// Microseconds since `start` (ms), at the resolution of `Date.now()`.
+fn elapsed_us(start: f64) -> u128 { ((js_sys::Date::now() - start).max(0.0) * 1000.0) as u128 }
+
+/// A validated chain, parsed once and solved as often as needed.
+#[wasm_bindgen]
+pub struct Chain { def: ChainDef }
+
+#[wasm_bindgen]
+impl Chain {
+    /// From a chain definition object, validated like `POST /chains`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(definition: JsValue) -> Result<Chain, JsError> {
+        let def: ChainDef = from_js(&definition, "chain")?;
+        def.validate().map_err(|e| JsError::new(&e))?;
+        Ok(Chain { def })
+    }
+
+    /// One of the engine's built-in chains (`human_arm`, `robotic_arm_6dof`, ...).
+    pub fn builtin(id: &str) -> Result<Chain, JsError> {
+        kinematics_solver::presets().into_iter().find(|c| c.id == id).map(|def| Chain { def })
+            .ok_or_else(|| JsError::new(&format!("no built-in chain '{id}'")))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String { self.def.id.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn dof(&self) -> usize { self.def.dof() }
+
+    /// The definition as a plain object.
+    pub fn definition(&self) -> Result<JsValue, JsError> { to_js(&self.def) }
+
+    /// `FkResponse` of `angles`.
+    pub fn fk(&self, angles: &[f64]) -> Result<JsValue, JsError> {
+        let start = js_sys::Date::now();
+        let mut r = kinematics_solver::fk_response(&self.def, angles).map_err(|e| JsError::new(&e))?;
+        r.elapsed_us = elapsed_us(start);
+        to_js(&r)
+    }
+
+    /// Joint and tip positions as a flat `[x0, y0, z0, x1, ...]` array: the cheap
+    /// per-frame form of `fk` for drawing the chain.
+    #[wasm_bindgen(js_name = jointPositions)]
+    pub fn joint_positions(&self, angles: &[f64]) -> Result<Vec<f64>, JsError> {
+        if angles.len() != self.def.dof() {
+            return Err(JsError::new(&format!("chain '{}' has {} joints, got {} angles", self.def.id, self.def.dof(), angles.len())));
+        }
+        let pose = kinematics_solver::forward(&self.def, angles);
+        Ok(pose.joints.iter().chain([&pose.tip]).flat_map(|f| f.p).collect())
+    }
+
+    /// `IkResponse` for an `IkRequest` body; its `chain_id` is ignored.
+    pub fn ik(&self, request: JsValue) -> Result<JsValue, JsError> {
+        let start = js_sys::Date::now();
+        let req: IkRequest = from_js(&request, "IK request")?;
+        let mut r = kinematics_solver::ik_response(&self.def, &req).map_err(|e| JsError::new(&e))?;
+        r.elapsed_us = elapsed_us(start);
+        to_js(&r)
+    }
+}
+
+/// Ids of the built-in chains.
+#[wasm_bindgen]
+pub fn builtins() -> Vec<String> { kinematics_solver::presets().into_iter().map(|c| c.id).collect() }
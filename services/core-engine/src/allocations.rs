@@ -0,0 +1,54 @@
+// ── Heap allocation counts ──────────────────────────────────
+//
+// The engine's global allocator: the system one, counting every allocation (and
+// reallocation) engine-wide and per thread. `counted` reads the thread's count around
+// a solve, which is how `/metrics` shows that solves run out of the solver's reused
+// workspaces instead of allocating per request. Counting is two relaxed increments,
+// cheap enough to leave on.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub struct Counting;
+
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+fn count() {
+    TOTAL.fetch_add(1, Ordering::Relaxed);
+    // A thread being torn down has no counter left; it only misses the thread count
+    let _ = THREAD.try_with(|n| n.set(n.get() + 1));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { unsafe { System.dealloc(ptr, layout) } }
+}
+
+/// Allocations since the engine started, on every thread.
+pub fn total() -> u64 { TOTAL.load(Ordering::Relaxed) }
+
+/// `f`'s result and the allocations made on this thread while it ran.
+pub fn counted<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    let before = THREAD.with(Cell::get);
+    let out = f();
+    (out, THREAD.with(Cell::get) - before)
+}
@@ -0,0 +1,92 @@
+// ── Anthropometric scaling ──────────────────────────────────
+//
+// Subject-specific instances of the `human_arm` / `human_leg` presets. Segment
+// lengths, masses, centres of mass and radii of gyration follow Winter's
+// "Biomechanics and Motor Control of Human Movement" tables, as fractions of body
+// height and body mass. Stature percentiles use a pooled adult distribution.
+
+use crate::chains::{ChainDef, Inertial};
+
+pub use kinematics_types::SubjectSpec;
+
+const MEAN_HEIGHT_M: f64 = 1.71;
+const SD_HEIGHT_M: f64 = 0.095;
+
+/// Segment carried by the link after a preset joint.
+struct Segment {
+    joint: &'static str, name: &'static str,
+    /// Length, mass and proximal centre-of-mass offset as fractions of height, body
+    /// mass and segment length; `gyration` is the transverse radius about the COM.
+    length: f64, mass: f64, com: f64, gyration: f64,
+}
+
+const SEGMENTS: &[Segment] = &[
+    Segment { joint: "shoulder_rotation", name: "upper_arm", length: 0.186, mass: 0.028, com: 0.436, gyration: 0.322 },
+    Segment { joint: "elbow_flexion", name: "forearm", length: 0.146, mass: 0.016, com: 0.430, gyration: 0.303 },
+    Segment { joint: "wrist_deviation", name: "hand", length: 0.108, mass: 0.006, com: 0.506, gyration: 0.297 },
+    Segment { joint: "hip_rotation", name: "thigh", length: 0.245, mass: 0.100, com: 0.433, gyration: 0.323 },
+    Segment { joint: "knee_flexion", name: "shank", length: 0.246, mass: 0.0465, com: 0.433, gyration: 0.302 },
+    Segment { joint: "ankle_inversion", name: "foot", length: 0.152, mass: 0.0145, com: 0.500, gyration: 0.475 },
+];
+
+/// Scale `base` (a `human_arm` or `human_leg` preset) to the subject.
+pub fn scale(base: &ChainDef, spec: &SubjectSpec) -> Result<ChainDef, String> {
+    if base.id != "human_arm" && base.id != "human_leg" {
+        return Err(format!("'{}' is not a human chain; scaling applies to human_arm and human_leg", base.id));
+    }
+    let height = match (spec.height_m, spec.percentile) {
+        (Some(h), _) if !(h.is_finite() && (0.5..=2.8).contains(&h)) => return Err(format!("height_m {h} is outside 0.5–2.8 m")),
+        (Some(h), _) => Some(h),
+        (None, Some(p)) if !(1.0..=99.0).contains(&p) => return Err(format!("percentile {p} is outside 1–99")),
+        (None, Some(p)) => Some(MEAN_HEIGHT_M + SD_HEIGHT_M * probit(p / 100.0)),
+        (None, None) => None,
+    };
+    if let Some(m) = spec.mass_kg.filter(|m| !(m.is_finite() && *m > 0.0)) { return Err(format!("mass_kg {m} must be positive")); }
+    let present = |s: &&Segment| base.joints.iter().any(|j| j.name.as_deref() == Some(s.joint));
+    if let Some(unknown) = spec.segments.keys().find(|k| !SEGMENTS.iter().filter(present).any(|s| s.name == k.as_str())) {
+        return Err(format!("'{unknown}' is not a segment of {}", base.id));
+    }
+    let mut chain = base.clone();
+    for j in &mut chain.joints {
+        let Some(seg) = SEGMENTS.iter().find(|s| Some(s.joint) == j.name.as_deref()) else { continue };
+        let length = match (spec.segments.get(seg.name), height) {
+            (Some(&l), _) if !(l.is_finite() && l > 0.0) => return Err(format!("segments.{} must be a positive length", seg.name)),
+            (Some(&l), _) => l,
+            (None, Some(h)) => seg.length * h,
+            (None, None) => return Err(format!("give height_m, percentile or segments.{}", seg.name)),
+        };
+        j.link_length = length;
+        j.inertial = spec.mass_kg.map(|body| {
+            let mass = seg.mass * body;
+            let transverse = mass * (seg.gyration * length).powi(2);
+            // Links run along local x; the longitudinal moment is not tabulated
+            Inertial { mass, com: [seg.com * length, 0.0, 0.0], inertia: [0.0, 0.0, 0.0, transverse, 0.0, transverse] }
+        });
+    }
+    chain.id = spec.id.clone();
+    chain.version = 1;
+    chain.name = spec.name.clone().unwrap_or_else(|| format!("{} ({})", base.name, spec.id));
+    chain.description = match height {
+        Some(h) => format!("{} scaled to a {h:.3} m subject", base.id),
+        None => format!("{} with measured segment lengths", base.id),
+    };
+    Ok(chain)
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation, |error| < 1.2e-9).
+fn probit(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    let tail = |q: f64| (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0);
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
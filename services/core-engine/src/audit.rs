@@ -0,0 +1,181 @@
+// ── Solve audit log ─────────────────────────────────────────
+//
+// One record per IK or FK solve, hands included, whichever transport asked for it
+// (REST, the WebSocket stream, jobs, gRPC, MQTT, ROS 2): a hash of the inputs, the
+// chain, a result summary, the caller and the latency. Records are kept in a bounded
+// in-memory ring that `GET /api/v1/kinematics/audit` queries and, with
+// `KINEMATICS_AUDIT_LOG` set, appended to a JSON-lines file that is replayed at boot.
+// The engine only ever appends to the file, so it is the complete record; rotate or
+// archive it externally. Hot reloads of the configuration and chain stores are
+// recorded alongside (see `reload`).
+
+use crate::history::now_ms;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+pub use kinematics_types::{AccelerationIkResponse, AuditRecord, FkResponse, HandFkResponse, HandIkResponse, IkBatchResponse, IkResponse, MocapResponse, ReachableResponse, RetargetResponse, SelfMotionResponse, VelocityIkResponse};
+
+pub struct AuditLog { records: VecDeque<AuditRecord>, capacity: usize, file: Option<(PathBuf, File)> }
+
+impl AuditLog {
+    /// Open the log, replaying `path` if it exists; only the newest `capacity` records
+    /// stay queryable. Panics when the file cannot be opened for appending, since an
+    /// audit trail that silently stops is worse than none.
+    pub fn open(path: Option<PathBuf>, capacity: usize) -> Self {
+        let mut records = VecDeque::new();
+        if let Some(f) = path.as_ref().and_then(|p| File::open(p).ok()) {
+            for line in std::io::BufReader::new(f).lines().map_while(Result::ok) {
+                match serde_json::from_str::<AuditRecord>(&line) {
+                    Ok(r) => { records.push_back(r); if records.len() > capacity { records.pop_front(); } }
+                    Err(e) => tracing::warn!("skipping malformed audit log line: {e}"),
+                }
+            }
+        }
+        let file = path.map(|p| {
+            let f = std::fs::OpenOptions::new().create(true).append(true).open(&p)
+                .unwrap_or_else(|e| panic!("KINEMATICS_AUDIT_LOG {}: {e}", p.display()));
+            (p, f)
+        });
+        Self { records, capacity, file }
+    }
+
+    /// Records held in memory.
+    pub fn len(&self) -> usize { self.records.len() }
+
+    pub fn push(&mut self, record: AuditRecord) {
+        if let Some((path, f)) = &mut self.file {
+            let mut line = serde_json::to_string(&record).unwrap_or_default();
+            line.push('\n');
+            if let Err(e) = f.write_all(line.as_bytes()) { tracing::error!("failed to append to audit log {}: {e}", path.display()); }
+        }
+        self.records.push_back(record);
+        if self.records.len() > self.capacity { self.records.pop_front(); }
+    }
+
+    /// `tenant`'s records matching `q`, oldest first.
+    pub fn query(&self, tenant: Option<&str>, q: &kinematics_types::AuditQuery) -> Vec<&AuditRecord> {
+        let chain_matches = |r: &AuditRecord, id: &str| r.chain_id.as_deref().is_some_and(|c| c == id || c.split_once('@').is_some_and(|(base, _)| base == id));
+        self.records.iter()
+            .filter(|r| r.tenant.as_deref() == tenant)
+            .filter(|r| q.from.is_none_or(|f| r.recorded_at_ms >= f))
+            .filter(|r| q.to.is_none_or(|t| r.recorded_at_ms <= t))
+            .filter(|r| q.chain_id.as_deref().is_none_or(|id| chain_matches(r, id)))
+            .filter(|r| q.hand_id.as_deref().is_none_or(|id| r.hand_id.as_deref() == Some(id)))
+            .filter(|r| q.operation.as_deref().is_none_or(|op| r.operation == op))
+            .filter(|r| q.caller.as_deref().is_none_or(|c| r.caller.as_deref() == Some(c)))
+            .collect()
+    }
+}
+
+/// SHA-256 (hex) of `request` serialized as JSON.
+pub fn input_hash(request: &impl Serialize) -> String { digest(&serde_json::to_vec(request).unwrap_or_default()) }
+
+/// SHA-256 (hex) of `bytes`.
+pub fn digest(bytes: &[u8]) -> String { hex::encode(Sha256::digest(bytes)) }
+
+/// A record of `operation` by the current caller, to be completed from the response.
+pub fn record(operation: &str, input_hash: String) -> AuditRecord {
+    AuditRecord {
+        audit_id: uuid::Uuid::new_v4().to_string(), recorded_at_ms: now_ms(), operation: operation.into(), input_hash,
+        caller: crate::auth::current().map(|p| p.key_id), tenant: crate::tenant::tag(), ..Default::default()
+    }
+}
+
+/// What a solve response contributes to its audit record.
+pub trait Summary {
+    fn summarize(&self, r: &mut AuditRecord);
+}
+
+impl Summary for IkResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        (r.chain_id, r.solution_id) = (self.chain.clone(), Some(self.solution_id.clone()));
+        (r.converged, r.iterations, r.error_distance) = (Some(self.converged), Some(self.iterations), Some(self.error_distance));
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for IkBatchResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.chain.clone());
+        r.converged = Some(self.converged == self.solutions.len());
+        r.iterations = self.solutions.iter().map(|s| s.iterations).max();
+        r.error_distance = self.solutions.iter().map(|s| s.error_distance).reduce(f64::max);
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for RetargetResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.target_chain.clone());
+        r.converged = Some(self.converged == self.frames.len() && !self.timed_out);
+        r.iterations = self.frames.iter().map(|f| f.iterations).max();
+        r.error_distance = Some(self.max_error);
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for MocapResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        (r.chain_id, r.solution_id) = (Some(self.chain.clone()), Some(self.solution_id.clone()));
+        r.converged = Some(self.converged == self.frames.len() && !self.timed_out);
+        r.iterations = self.frames.iter().map(|f| f.iterations).max();
+        r.error_distance = Some(self.max_error);
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for FkResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = self.chain.clone();
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for HandIkResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.solution_id = Some(self.solution_id.clone());
+        r.converged = Some(self.converged);
+        r.iterations = self.fingers.values().map(|f| f.iterations).max();
+        r.error_distance = self.fingers.values().map(|f| f.error_distance).reduce(f64::max);
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for VelocityIkResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.chain.clone());
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for AccelerationIkResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.chain.clone());
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for ReachableResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.chain.clone());
+        (r.converged, r.error_distance) = (Some(self.reachable), Some(self.position_error));
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for SelfMotionResponse {
+    fn summarize(&self, r: &mut AuditRecord) {
+        r.chain_id = Some(self.chain.clone());
+        r.converged = Some(self.feasible > 0);
+        r.error_distance = self.samples.iter().filter(|p| p.feasible).map(|p| p.position_error).reduce(f64::max);
+        r.latency_us = self.elapsed_us as u64;
+    }
+}
+
+impl Summary for HandFkResponse {
+    fn summarize(&self, r: &mut AuditRecord) { r.latency_us = self.elapsed_us as u64; }
+}
@@ -0,0 +1,233 @@
+// ── Authentication ──────────────────────────────────────────
+//
+// Every `/api/v1` route requires credentials once any API key exists or an OIDC
+// issuer is configured: an API key as `X-API-Key` or `Authorization: Bearer`, or a
+// JWT from the issuer as a bearer token (see `oidc`). Keys come from config
+// (`KINEMATICS_API_KEYS`, plus `KINEMATICS_ADMIN_KEY` for the admin endpoints) or are
+// issued at runtime through `/api/v1/admin/keys` and kept in `KINEMATICS_KEY_STORE`.
+// Only SHA-256 hashes of the secrets are held. API keys may call everything but the
+// admin endpoints (unless they are admin keys); tokens are limited to their scopes.
+// The authenticated `Principal` is task-local for the rest of the request, so work
+// and stats can be attributed to the caller.
+
+use crate::error::{ApiError, ErrorCode};
+use crate::history::now_ms;
+use crate::{ratelimit, tenant, AppState};
+use axum::{extract::{Request, State}, http::{header, HeaderMap, HeaderValue, Method}, middleware::Next, response::{IntoResponse, Response}};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub const SCOPE_SOLVE: &str = "kinematics:solve";
+pub const SCOPE_READ: &str = "kinematics:read";
+pub const SCOPE_CHAINS_WRITE: &str = "chains:write";
+pub const SCOPE_ADMIN: &str = "kinematics:admin";
+
+/// Who a request was authenticated as: an API key id, or `sub:<subject>` for tokens.
+#[derive(Clone)]
+pub struct Principal {
+    pub key_id: String, pub admin: bool,
+    /// Token scopes; `None` for API keys.
+    pub scopes: Option<Vec<String>>,
+    /// `None` for the default tenant.
+    pub tenant: Option<String>,
+}
+
+impl Principal {
+    pub fn allows(&self, scope: &str) -> bool { self.scopes.as_ref().is_none_or(|s| s.iter().any(|x| x == scope)) }
+}
+
+/// Scope a route needs: solving work (including job and simulation control) takes `kinematics:solve`,
+/// registry writes `chains:write`, reads `kinematics:read`, the admin routes `kinematics:admin`.
+pub fn required_scope(method: &Method, path: &str) -> &'static str {
+    if path.starts_with("/api/v1/admin/") { return SCOPE_ADMIN; }
+    match ratelimit::Class::of(method, path) {
+        Some(ratelimit::Class::Solve) => SCOPE_SOLVE,
+        Some(ratelimit::Class::Write) if path.contains("/jobs/") || path.contains("/sims/") || path.contains("/sessions") => SCOPE_SOLVE,
+        Some(ratelimit::Class::Write) => SCOPE_CHAINS_WRITE,
+        _ => SCOPE_READ,
+    }
+}
+
+tokio::task_local! { static PRINCIPAL: Principal; }
+
+/// Run `task` as `principal`; a no-op scope when there is none (auth disabled).
+pub async fn scope<F: std::future::Future>(principal: Option<Principal>, task: F) -> F::Output {
+    match principal { Some(p) => PRINCIPAL.scope(p, task).await, None => task.await }
+}
+
+/// Caller of the current request or job, if authenticated.
+pub fn current() -> Option<Principal> { PRINCIPAL.try_with(Principal::clone).ok() }
+
+/// Fail unless the caller holds an admin key or the `kinematics:admin` scope.
+pub fn require_admin() -> Result<Principal, ApiError> {
+    current().filter(|p| p.admin).ok_or_else(|| ApiError::forbidden("Admin rights required", "send an admin key (KINEMATICS_ADMIN_KEY) or a token with the kinematics:admin scope"))
+}
+
+pub use kinematics_types::{ApiKey, Usage};
+
+#[derive(Serialize, Deserialize)]
+struct StoredKey { #[serde(flatten)] key: ApiKey, hash: String }
+
+pub struct KeyStore { keys: Vec<StoredKey>, usage: HashMap<String, Usage>, tenants: HashMap<String, Usage>, file: Option<PathBuf> }
+
+fn hash(secret: &str) -> String { hex::encode(Sha256::digest(secret.as_bytes())) }
+
+impl KeyStore {
+    /// Keys stored in `file` plus `configured` keys, which take their name as id.
+    pub fn open(file: Option<PathBuf>, configured: Vec<ConfiguredKey>) -> Self {
+        let stored = file.as_ref().and_then(|p| std::fs::read_to_string(p).ok()).map(|text| {
+            serde_json::from_str::<Vec<StoredKey>>(&text).unwrap_or_else(|e| {
+                tracing::warn!("ignoring unreadable key store: {e}");
+                Vec::new()
+            })
+        });
+        let mut keys: Vec<StoredKey> = configured.into_iter().map(|c| StoredKey {
+            key: ApiKey { key_id: c.name.clone(), name: c.name, admin: c.admin, tenant: c.tenant, created_at_ms: now_ms(), configured: true }, hash: hash(&c.secret),
+        }).collect();
+        for k in stored.unwrap_or_default() {
+            if keys.iter().any(|c| c.key.key_id == k.key.key_id) { tracing::warn!("skipping stored key {}: id taken by config", k.key.key_id); continue; }
+            // Only config can make a key unrevocable, whatever the file says
+            keys.push(StoredKey { key: ApiKey { configured: false, ..k.key }, ..k });
+        }
+        Self { keys, usage: HashMap::new(), tenants: HashMap::new(), file }
+    }
+
+    /// Auth is enforced once any key exists.
+    pub fn enabled(&self) -> bool { !self.keys.is_empty() }
+
+    pub fn authenticate(&self, secret: &str) -> Option<Principal> {
+        let h = hash(secret);
+        self.keys.iter().find(|k| k.hash == h).map(|k| Principal { key_id: k.key.key_id.clone(), admin: k.key.admin, scopes: None, tenant: k.key.tenant.clone() })
+    }
+
+    pub fn list(&self) -> Vec<ApiKey> { self.keys.iter().map(|k| k.key.clone()).collect() }
+
+    /// Issue a key; the secret is returned once and only its hash is kept.
+    pub fn create(&mut self, name: String, admin: bool, tenant: Option<String>) -> Result<(ApiKey, String), ApiError> {
+        let secret = format!("kin_{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let key_id = format!("key_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+        let key = ApiKey { key_id, name, admin, tenant, created_at_ms: now_ms(), configured: false };
+        self.keys.push(StoredKey { key: key.clone(), hash: hash(&secret) });
+        self.persist().inspect_err(|_| { self.keys.pop(); })?;
+        Ok((key, secret))
+    }
+
+    pub fn revoke(&mut self, key_id: &str) -> Result<(), ApiError> {
+        let i = self.keys.iter().position(|k| k.key.key_id == key_id).ok_or_else(|| ApiError::not_found("Key not found", key_id))?;
+        if self.keys[i].key.configured { return Err(ApiError::forbidden("Configured key", format!("'{key_id}' comes from config and cannot be revoked at runtime"))); }
+        let removed = self.keys.remove(i);
+        self.persist().inspect_err(|_| self.keys.insert(i, removed))?;
+        self.usage.remove(key_id);
+        Ok(())
+    }
+
+    /// Count work against `p` and its tenant.
+    pub fn record(&mut self, p: &Principal, f: impl Fn(&mut Usage)) {
+        f(self.usage.entry(p.key_id.clone()).or_default());
+        f(self.tenants.entry(p.tenant.clone().unwrap_or_else(|| tenant::DEFAULT.into())).or_default());
+    }
+
+    pub fn usage(&self, key_id: &str) -> Usage { self.usage.get(key_id).cloned().unwrap_or_default() }
+
+    pub fn tenant_usage(&self, tenant: &str) -> Usage { self.tenants.get(tenant).cloned().unwrap_or_default() }
+
+    pub fn all_tenant_usage(&self) -> std::collections::BTreeMap<String, Usage> { self.tenants.iter().map(|(t, u)| (t.clone(), u.clone())).collect() }
+
+    /// Usage of every key and token subject, keys that have done nothing included.
+    pub fn all_usage(&self) -> std::collections::BTreeMap<String, Usage> {
+        let mut all: std::collections::BTreeMap<String, Usage> = self.keys.iter().map(|k| (k.key.key_id.clone(), Usage::default())).collect();
+        all.extend(self.usage.iter().map(|(k, u)| (k.clone(), u.clone())));
+        all
+    }
+
+    /// Per-key and per-tenant usage, for the stats snapshot.
+    pub fn usage_counters(&self) -> (HashMap<String, Usage>, HashMap<String, Usage>) { (self.usage.clone(), self.tenants.clone()) }
+
+    pub fn restore_usage(&mut self, usage: HashMap<String, Usage>, tenants: HashMap<String, Usage>) { (self.usage, self.tenants) = (usage, tenants); }
+
+    /// Rewrite the store with the runtime-issued keys; no-op without a store.
+    fn persist(&self) -> Result<(), ApiError> {
+        let Some(path) = &self.file else { return Ok(()) };
+        let issued: Vec<&StoredKey> = self.keys.iter().filter(|k| !k.key.configured).collect();
+        let json = serde_json::to_string_pretty(&issued).map_err(|e| ApiError::internal("Key store write failed", e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| ApiError::internal("Key store write failed", format!("{}: {e}", path.display())))
+    }
+}
+
+pub struct ConfiguredKey { pub name: String, pub secret: String, pub admin: bool, pub tenant: Option<String> }
+
+/// `name:secret` or `name:secret:tenant` entries, comma separated.
+pub fn parse_keys(spec: &str) -> Result<Vec<ConfiguredKey>, String> {
+    spec.split(',').map(str::trim).filter(|e| !e.is_empty()).map(|entry| {
+        let mut parts = entry.splitn(3, ':').map(str::trim);
+        let (Some(name), Some(secret)) = (parts.next(), parts.next()) else { return Err(format!("'{entry}' is not name:secret[:tenant]")) };
+        if name.is_empty() || secret.is_empty() { return Err(format!("'{entry}' has an empty name or secret")); }
+        let tenant = parts.next().map(|t| tenant::check(t).map(|_| t.to_string())).transpose()?;
+        Ok(ConfiguredKey { name: name.into(), secret: secret.into(), admin: false, tenant })
+    }).collect()
+}
+
+enum Credential<'a> { Key(&'a str), Bearer(&'a str) }
+
+/// `X-API-Key`, else an `Authorization: Bearer` token.
+fn presented(headers: &HeaderMap) -> Option<Credential<'_>> {
+    let value = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    if let Some(key) = value("x-api-key") { return Some(Credential::Key(key)); }
+    value(header::AUTHORIZATION.as_str()).and_then(|v| v.strip_prefix("Bearer ")).map(|t| Credential::Bearer(t.trim()))
+}
+
+/// The caller's principal: `Ok(None)` while auth is disabled.
+pub async fn authenticate(s: &AppState, headers: &HeaderMap) -> Result<Option<Principal>, ApiError> {
+    if s.oidc.is_none() && !s.keys.lock().unwrap().enabled() { return Ok(None); }
+    let credential = presented(headers).ok_or_else(|| ApiError::new(ErrorCode::Unauthorized, "Credentials required", "send X-API-Key or Authorization: Bearer <key or token>"))?;
+    let principal = match (credential, &s.oidc) {
+        // API keys never contain dots; JWTs always do
+        (Credential::Bearer(token), Some(oidc)) if token.contains('.') => oidc.verify(token).await?,
+        (Credential::Key(secret) | Credential::Bearer(secret), _) => s.keys.lock().unwrap().authenticate(secret)
+            .ok_or_else(|| ApiError::new(ErrorCode::Unauthorized, "Invalid API key", "the key is unknown or was revoked"))?,
+    };
+    s.keys.lock().unwrap().record(&principal, |u| u.requests += 1);
+    Ok(Some(principal))
+}
+
+pub fn missing_scope(scope: &str) -> ApiError { ApiError::forbidden("Insufficient scope", format!("this operation requires the '{scope}' scope")) }
+
+/// Admit one message of a broker bridge (MQTT, ROS 2) that acts as the key in `var`:
+/// the key is re-checked on every message so revocation applies at once, then the
+/// solve scope and the key's solve bucket are checked. `Ok(None)` while auth is disabled.
+#[cfg_attr(not(any(feature = "mqtt", feature = "ros2")), allow(dead_code))]
+pub fn bridge(s: &AppState, var: &str, api_key: Option<&str>) -> Result<Option<Principal>, ApiError> {
+    let principal = match (s.oidc.is_some() || s.keys.lock().unwrap().enabled(), api_key) {
+        (false, _) => None,
+        (true, None) => return Err(ApiError::new(ErrorCode::Unauthorized, "Credentials required", format!("set {var} for the bridge"))),
+        (true, Some(secret)) => {
+            let p = s.keys.lock().unwrap().authenticate(secret).ok_or_else(|| ApiError::new(ErrorCode::Unauthorized, "Invalid API key", format!("{var} is unknown or was revoked")))?;
+            s.keys.lock().unwrap().record(&p, |u| u.requests += 1);
+            Some(p)
+        }
+    };
+    if principal.as_ref().is_some_and(|p| !p.allows(SCOPE_SOLVE)) { return Err(missing_scope(SCOPE_SOLVE)); }
+    let who = ratelimit::client(principal.as_ref(), &HeaderMap::new(), None, false);
+    match s.limiter.lock().unwrap().take(&who, ratelimit::Class::Solve) {
+        Some(ratelimit::Verdict::Limited { limit, retry_after }) => Err(ratelimit::exceeded(limit, retry_after)),
+        _ => Ok(principal),
+    }
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if !req.uri().path().starts_with("/api/v1/") { return next.run(req).await; }
+    match authenticate(&s, req.headers()).await {
+        Ok(Some(p)) if !p.allows(required_scope(req.method(), req.uri().path())) => missing_scope(required_scope(req.method(), req.uri().path())).into_response(),
+        Ok(principal) => scope(principal, next.run(req)).await,
+        Err(e) => {
+            let mut res = e.into_response();
+            res.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            res
+        }
+    }
+}
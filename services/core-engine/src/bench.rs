@@ -0,0 +1,70 @@
+// ── Built-in benchmark ──────────────────────────────────────
+//
+// `POST /admin/bench` runs a synthetic IK workload inside the engine, so a deployment's
+// capacity can be checked on its own hardware without a load generator. Targets are
+// the tip positions of random in-limit configurations, so every one is reachable;
+// each solve starts from the chain's seed, as a cold request would. The run has its own
+// thread pool of the requested size and times every solve; it competes with live
+// traffic for cores but is not counted in stats, metrics or warm starts.
+
+use crate::chains::{ChainDef, JointType};
+use kinematics_solver::IkSettings;
+use kinematics_types::{BenchLatency, BenchResponse, SolverSettings};
+use std::time::{Duration, Instant};
+
+/// splitmix64: tiny, seedable and good enough to spread targets over the workspace.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) as f64 / u64::MAX as f64
+    }
+}
+
+/// `count` reachable targets: FK of joint values drawn within each joint's limits, or
+/// a full turn (revolute) or stroke of 0 (prismatic) for unlimited joints.
+fn targets(chain: &ChainDef, count: usize, seed: u64) -> Vec<[f64; 3]> {
+    let mut rng = Rng(seed);
+    let model = kinematics_solver::Model::new(chain);
+    (0..count).map(|_| {
+        let q: Vec<f64> = chain.joints.iter().map(|j| {
+            let [lo, hi] = j.limits.unwrap_or(if j.joint_type == JointType::Revolute { [-std::f64::consts::PI, std::f64::consts::PI] } else { [0.0, 0.0] });
+            lo + (hi - lo) * rng.next()
+        }).collect();
+        model.forward(&q).tip.p
+    }).collect()
+}
+
+/// Run `solves` IK solves on `threads` threads; each stops after `timeout` if set.
+pub fn run(chain: &ChainDef, settings: SolverSettings, solves: usize, threads: usize, random_seed: u64, timeout: Option<Duration>) -> Result<BenchResponse, String> {
+    use rayon::prelude::*;
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).thread_name(|i| format!("bench-{i}")).build().map_err(|e| e.to_string())?;
+    let targets = targets(chain, solves, random_seed);
+    let seed = kinematics_solver::seed(chain, &settings.seed_policy);
+    let ik = IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let t = Instant::now();
+    let results: Vec<(u64, bool, bool, u32)> = pool.install(|| targets.par_iter().map(|&target| {
+        let start = Instant::now();
+        let r = kinematics_solver::solve_ik_at(settings.precision.as_deref(), chain, target, None, seed.clone(), &ik, || timeout.is_some_and(|d| start.elapsed() >= d));
+        (start.elapsed().as_micros() as u64, r.converged, r.stopped, r.iterations)
+    }).collect());
+    let elapsed = t.elapsed();
+    let mut latencies: Vec<u64> = results.iter().map(|r| r.0).collect();
+    latencies.sort_unstable();
+    let rank = |q: f64| latencies[((q * latencies.len() as f64).ceil() as usize).clamp(1, latencies.len()) - 1];
+    Ok(BenchResponse {
+        chain: chain.pinned_id(), solves, threads,
+        converged: results.iter().filter(|r| r.1).count(), timed_out: results.iter().filter(|r| r.2).count(),
+        avg_iterations: results.iter().map(|r| r.3 as f64).sum::<f64>() / solves as f64,
+        solver: settings,
+        elapsed_us: elapsed.as_micros(), solves_per_sec: solves as f64 / elapsed.as_secs_f64(),
+        latency_us: BenchLatency {
+            min: latencies[0], mean: latencies.iter().sum::<u64>() as f64 / solves as f64,
+            p50: rank(0.5), p90: rank(0.9), p99: rank(0.99), max: latencies[solves - 1],
+        },
+    })
+}
@@ -0,0 +1,289 @@
+// ── Kinematic chain registry ────────────────────────────────
+//
+// Serial chains as an ordered list of joints. Each joint moves about (revolute) or
+// along (prismatic) its local `axis`, then a rigid link of `link_length` follows.
+// The built-in presets are always present; custom chains are registered at runtime
+// and, when a store file is configured, written through to it as a JSON array that
+// is reloaded at boot, and again whenever something else rewrites it. Updating a custom chain appends a new immutable version, so
+// `id@vN` keeps resolving to exactly the model a past solve used. Deleting a chain keeps
+// its last version number (in the store too, which then becomes `{"chains": [...],
+// "retired": {id: N}}`), so registering the id again carries on from there and a pinned
+// id never names two different models.
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::path::PathBuf;
+use kinematics_solver::joint;
+use serde::Deserialize;
+
+pub use kinematics_types::{validate_configuration, ChainDef, ComposeSpec, Inertial, JointDef, JointType, Origin, SolverDefaults, Tcp, TemplateSpec, MAX_ITERATIONS};
+
+/// `digest` is the SHA-256 of the store file as last read or written, to tell the
+/// registry's own writes from someone else's. `retired` is the last version of each
+/// deleted id.
+pub struct ChainRegistry { chains: Vec<ChainDef>, builtin: usize, file: Option<PathBuf>, modified_ms: u64, digest: Option<String>, retired: BTreeMap<String, u32> }
+
+/// The store file once an id has been deleted; a plain array of chains otherwise.
+#[derive(Deserialize, Default)]
+struct Store { chains: Vec<ChainDef>, #[serde(default)] retired: BTreeMap<String, u32> }
+
+fn parse_store(text: &str) -> Result<Store, serde_json::Error> {
+    if text.trim_start().starts_with('[') { serde_json::from_str(text).map(|chains| Store { chains, retired: BTreeMap::new() }) } else { serde_json::from_str(text) }
+}
+
+impl ChainRegistry {
+    /// Presets plus the custom chains stored in `file`, if it exists. Invalid or
+    /// clashing entries are skipped with a warning.
+    pub fn open(file: Option<PathBuf>) -> Self {
+        let mut registry = Self { chains: Vec::new(), builtin: 0, file, modified_ms: 0, digest: None, retired: BTreeMap::new() };
+        let text = registry.file.as_ref().and_then(|p| std::fs::read_to_string(p).ok());
+        registry.digest = text.as_deref().map(|t| crate::audit::digest(t.as_bytes()));
+        let stored = text.and_then(|text| {
+            parse_store(&text).map_err(|e| tracing::warn!("ignoring unreadable chain store: {e}")).ok()
+        });
+        registry.load(stored.unwrap_or_default());
+        registry
+    }
+
+    /// Replace the custom chains with `stored`. Retired versions only ever go up, so a
+    /// rewrite that leaves them out (a plain array) does not free a deleted id's numbers.
+    fn load(&mut self, stored: Store) {
+        self.chains = kinematics_solver::presets();
+        self.builtin = self.chains.len();
+        self.modified_ms = crate::history::now_ms();
+        for (id, version) in stored.retired { self.retire(id, version); }
+        for chain in stored.chains {
+            let id = chain.id.clone();
+            if let Err(e) = self.insert(chain) { tracing::warn!("skipping stored chain {id}: {e}"); }
+        }
+    }
+
+    /// Re-read the store file if it changed since the registry last read or wrote it,
+    /// replacing the custom chains. Returns the file's digest and what changed, or
+    /// `None` when it is unchanged or missing. An unparsable file is reported once and
+    /// the current chains are kept.
+    pub fn reload(&mut self) -> Result<Option<(String, String)>, String> {
+        let Some(path) = &self.file else { return Ok(None) };
+        let Ok(text) = std::fs::read_to_string(path) else { return Ok(None) };
+        let digest = crate::audit::digest(text.as_bytes());
+        if self.digest.as_ref() == Some(&digest) { return Ok(None); }
+        self.digest = Some(digest.clone());
+        let stored = parse_store(&text).map_err(|e| format!("not reloading chain store {}: {e}", path.display()))?;
+        let before: Vec<String> = self.custom().iter().map(ChainDef::pinned_id).collect();
+        self.load(stored);
+        let after: Vec<String> = self.custom().iter().map(ChainDef::pinned_id).collect();
+        let added: Vec<&str> = after.iter().filter(|id| !before.contains(id)).map(String::as_str).collect();
+        let removed: Vec<&str> = before.iter().filter(|id| !after.contains(id)).map(String::as_str).collect();
+        let list = |ids: &[&str]| if ids.is_empty() { "none".to_string() } else { ids.join(", ") };
+        Ok(Some((digest, format!("added {}; removed {}", list(&added), list(&removed)))))
+    }
+
+    /// Latest version of every chain: presets first, then custom chains.
+    pub fn list(&self) -> Vec<&ChainDef> {
+        self.chains.iter().filter(|c| !self.chains.iter().any(|o| o.id == c.id && o.version > c.version)).collect()
+    }
+
+    /// When a chain was last added, updated or deleted (or the registry opened).
+    pub fn modified_ms(&self) -> u64 { self.modified_ms }
+
+    /// Every version of every registered (non-preset) chain.
+    pub fn custom(&self) -> &[ChainDef] { &self.chains[self.builtin..] }
+
+    pub fn is_preset(&self, id: &str) -> bool { self.chains[..self.builtin].iter().any(|c| c.id == id) }
+
+    /// All stored version numbers of `id`, oldest first.
+    pub fn versions(&self, id: &str) -> Vec<u32> {
+        self.chains.iter().filter(|c| c.id == id).map(|c| c.version).collect()
+    }
+
+    /// Resolve `id` (latest version) or `id@vN` / `id@N` (pinned version).
+    pub fn get(&self, id: &str) -> Option<&ChainDef> {
+        match id.split_once('@') {
+            Some((id, v)) => {
+                let version: u32 = v.strip_prefix('v').unwrap_or(v).parse().ok()?;
+                self.chains.iter().find(|c| c.id == id && c.version == version)
+            }
+            None => self.chains.iter().filter(|c| c.id == id).max_by_key(|c| c.version),
+        }
+    }
+
+    /// Add a new chain as version 1, or after the last version of a deleted chain of the same id.
+    pub fn register(&mut self, mut chain: ChainDef) -> Result<&ChainDef, String> {
+        if self.chains.iter().any(|c| c.id == chain.id) { return Err(format!("chain '{}' already exists", chain.id)); }
+        chain.version = self.retired.get(&chain.id).map_or(1, |v| v + 1);
+        self.insert(chain)?;
+        self.persist();
+        Ok(self.chains.last().unwrap())
+    }
+
+    /// Store `chain` as the next version of custom chain `id`; earlier versions stay pinnable.
+    pub fn update(&mut self, id: &str, mut chain: ChainDef) -> Result<&ChainDef, String> {
+        let latest = self.custom().iter().filter(|c| c.id == id).map(|c| c.version).max()
+            .ok_or_else(|| format!("no registered chain '{id}'"))?;
+        chain.id = id.into();
+        chain.version = latest + 1;
+        self.insert(chain)?;
+        self.persist();
+        Ok(self.chains.last().unwrap())
+    }
+
+    /// Remove every version of custom chain `id`, returning how many were dropped. Its
+    /// last version number stays retired.
+    pub fn delete(&mut self, id: &str) -> usize {
+        let custom = self.chains.split_off(self.builtin);
+        let before = custom.len();
+        let (gone, kept): (Vec<ChainDef>, Vec<ChainDef>) = custom.into_iter().partition(|c| c.id == id);
+        self.chains.extend(kept);
+        if let Some(last) = gone.iter().map(|c| c.version).max() {
+            self.retire(id.into(), last);
+            self.modified_ms = crate::history::now_ms();
+            self.persist();
+        }
+        before - self.custom().len()
+    }
+
+    fn retire(&mut self, id: String, version: u32) {
+        let last = self.retired.entry(id).or_insert(version);
+        *last = (*last).max(version);
+    }
+
+    fn insert(&mut self, chain: ChainDef) -> Result<(), String> {
+        chain.validate()?;
+        if self.is_preset(&chain.id) { return Err(format!("'{}' is a preset chain", chain.id)); }
+        if self.chains.iter().any(|c| c.id == chain.id && c.version == chain.version) {
+            return Err(format!("chain '{}' already exists", chain.pinned_id()));
+        }
+        self.chains.push(chain);
+        self.modified_ms = crate::history::now_ms();
+        Ok(())
+    }
+
+    fn persist(&mut self) {
+        if let Err(e) = self.flush() { tracing::warn!("{e}"); }
+    }
+
+    /// Rewrite the store file with the current custom chains (via a temp file and
+    /// rename, so a crash never leaves it half written). No-op without a store.
+    pub fn flush(&mut self) -> Result<Option<&PathBuf>, String> {
+        let Some(path) = &self.file else { return Ok(None) };
+        let json = if self.retired.is_empty() { serde_json::to_string_pretty(self.custom()) } else {
+            serde_json::to_string_pretty(&serde_json::json!({ "chains": self.custom(), "retired": &self.retired }))
+        }.map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, &json).and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| format!("failed to write chain store {}: {e}", path.display()))?;
+        self.digest = Some(crate::audit::digest(json.as_bytes()));
+        Ok(Some(path))
+    }
+}
+
+// ── Templates ───────────────────────────────────────────────
+
+/// Build (but do not register) a chain from a template.
+///
+/// - `planar`: N revolute z joints, `link_lengths` one per joint (default 0.3 m each).
+/// - `6r_spherical_wrist`: `[base height, upper arm, forearm, flange]`; the last three axes intersect.
+/// - `scara`: `[inner arm, outer arm, quill stroke]`.
+/// - `delta`: `[upper arm, forearm]`, as the Cartesian-equivalent x/y/z stack plus tool roll.
+pub fn template(spec: &TemplateSpec) -> Result<ChainDef, String> {
+    use JointType::{Prismatic as P, Revolute as R};
+    let (x, y, z) = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]);
+    let lengths = |defaults: &[f64]| -> Result<Vec<f64>, String> {
+        match &spec.link_lengths {
+            Some(l) if l.len() != defaults.len() => Err(format!("template '{}' takes {} link_lengths, got {}", spec.template, defaults.len(), l.len())),
+            Some(l) => Ok(l.clone()),
+            None => Ok(defaults.to_vec()),
+        }
+    };
+    let at = |xyz: [f64; 3]| Some(Origin { xyz, rpy: [0.0; 3] });
+    let mut joints = match spec.template.as_str() {
+        "planar" => {
+            let n = spec.joints.or(spec.link_lengths.as_ref().map(Vec::len)).unwrap_or(3);
+            if n == 0 || n > 64 { return Err(format!("planar chains take 1 to 64 joints, got {n}")); }
+            lengths(&vec![0.3; n])?.into_iter().enumerate()
+                .map(|(i, l)| joint(R, z, [-PI, PI], l, &format!("joint_{}", i + 1))).collect::<Vec<_>>()
+        }
+        "6r_spherical_wrist" => {
+            let l = lengths(&[0.3, 0.4, 0.35, 0.08])?;
+            let mut j = vec![
+                joint(R, z, [-PI, PI], 0.0, "base"),
+                joint(R, y, [-PI / 2.0, PI / 2.0], l[1], "shoulder"),
+                joint(R, y, [-2.6, 2.6], l[2], "elbow"),
+                joint(R, x, [-PI, PI], 0.0, "wrist_1"),
+                joint(R, y, [-2.0, 2.0], 0.0, "wrist_2"),
+                joint(R, x, [-PI, PI], l[3], "wrist_3"),
+            ];
+            j[1].origin = at([0.0, 0.0, l[0]]);
+            j
+        }
+        "scara" => {
+            let l = lengths(&[0.35, 0.3, 0.2])?;
+            vec![
+                joint(R, z, [-2.5, 2.5], l[0], "shoulder"),
+                joint(R, z, [-2.5, 2.5], l[1], "elbow"),
+                joint(P, z, [-l[2], 0.0], 0.0, "quill"),
+                joint(R, z, [-PI, PI], 0.0, "tool_roll"),
+            ]
+        }
+        "delta" => {
+            // Working envelope of the parallel linkage, approximated per axis
+            let l = lengths(&[0.2, 0.45])?;
+            let (upper, fore) = (l[0], l[1]);
+            vec![
+                joint(P, x, [-upper, upper], 0.0, "x"),
+                joint(P, y, [-upper, upper], 0.0, "y"),
+                joint(P, z, [-(upper + fore), -(fore - upper).abs()], 0.0, "z"),
+                joint(R, z, [-PI, PI], 0.0, "tool_roll"),
+            ]
+        }
+        other => return Err(format!("'{other}' is not one of planar, 6r_spherical_wrist, scara, delta")),
+    };
+    if let Some(limits) = &spec.limits {
+        if limits.len() != joints.len() { return Err(format!("{} limits for {} joints", limits.len(), joints.len())); }
+        for (j, l) in joints.iter_mut().zip(limits) { j.limits = Some(*l); }
+    }
+    let chain = ChainDef {
+        id: spec.id.clone(), version: 1, name: spec.name.clone().unwrap_or_else(|| spec.id.clone()),
+        description: spec.description.clone().unwrap_or_else(|| format!("{}-DOF {} chain", joints.len(), spec.template)),
+        joints, tcp: None, solver: None, configurations: BTreeMap::new(), disabled_collisions: Vec::new(),
+    };
+    chain.validate()?;
+    Ok(chain)
+}
+
+// ── Composition ─────────────────────────────────────────────
+
+/// Build (but do not register) `base` + `tool`: the tool's joints follow the base's,
+/// its first joint placed at the base TCP, and the composite takes the tool's TCP.
+/// Link masses carry over per joint, so `total_mass` covers both. Configurations
+/// stored under the same name on both chains are concatenated.
+pub fn compose(spec: &ComposeSpec, base: &ChainDef, tool: &ChainDef) -> Result<ChainDef, String> {
+    use kinematics_solver::Frame;
+    let frame = |o: Origin| Frame::from_xyz_rpy(o.xyz, o.rpy);
+    let tcp = base.tcp.unwrap_or_default();
+    let [x, y, z, w] = tcp.orientation;
+    let last = base.joints.last().map_or(0.0, |j| j.link_length);
+    let mount = Frame::translation([last, 0.0, 0.0]).mul(&Frame::from_quat_wxyz(tcp.position, [w, x, y, z]))
+        .mul(&frame(spec.mount.unwrap_or(Origin { xyz: [0.0; 3], rpy: [0.0; 3] })))
+        .mul(&frame(tool.origin(0)));
+    let mut joints = base.joints.clone();
+    for (i, j) in tool.joints.iter().enumerate() {
+        let mut j = j.clone();
+        if i == 0 { j.origin = Some(Origin { xyz: mount.p, rpy: mount.rpy() }); }
+        if let Some(name) = j.name.as_mut().filter(|n| base.joints.iter().any(|b| b.name.as_ref() == Some(n))) {
+            *name = format!("{}_{name}", tool.id);
+        }
+        joints.push(j);
+    }
+    let configurations = base.configurations.iter()
+        .filter_map(|(name, q)| tool.configurations.get(name).map(|t| (name.clone(), [q.as_slice(), t].concat())))
+        .collect();
+    let chain = ChainDef {
+        id: spec.id.clone(), version: 1, name: spec.name.clone().unwrap_or_else(|| spec.id.clone()),
+        description: spec.description.clone().unwrap_or_else(|| format!("{} with {} attached", base.pinned_id(), tool.pinned_id())),
+        joints, tcp: tool.tcp, solver: base.solver.clone(), configurations,
+        disabled_collisions: [base.disabled_collisions.as_slice(), &tool.disabled_collisions].concat(),
+    };
+    chain.validate()?;
+    Ok(chain)
+}
@@ -0,0 +1,71 @@
+// ── Model-backed intent classifier ──────────────────────────
+//
+// Optional ONNX Runtime backend (`onnx` feature, runtime library loaded dynamically).
+// The model receives one float32 row of shape [1, 6]:
+//   [magnitude, avg_speed, dz, rotation_angle, rotation_alignment, has_rotation]
+// and must return one score per label (logits or probabilities) as its first output.
+
+use crate::intent::IntentFeatures;
+
+pub struct ModelClassifier {
+    #[cfg(feature = "onnx")]
+    session: std::sync::Mutex<ort::session::Session>,
+    labels: Vec<String>,
+}
+
+impl ModelClassifier {
+    /// Load the model at `path`. `labels` names the model's output classes in order.
+    #[cfg(feature = "onnx")]
+    pub fn load(path: &str, labels: Vec<String>) -> Result<Self, String> {
+        let session = ort::session::Session::builder()
+            .and_then(|b| b.commit_from_file(path))
+            .map_err(|e| format!("failed to load intent model {path}: {e}"))?;
+        Ok(Self { session: std::sync::Mutex::new(session), labels })
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    pub fn load(path: &str, _labels: Vec<String>) -> Result<Self, String> {
+        Err(format!("cannot load {path}: this build was compiled without the `onnx` feature"))
+    }
+
+    /// Score the window with the model, strongest class first.
+    #[cfg(feature = "onnx")]
+    pub fn classify(&self, f: &IntentFeatures) -> Result<Vec<(String, f64)>, String> {
+        let input = ort::value::Tensor::from_array(([1usize, 6], features(f).to_vec())).map_err(|e| e.to_string())?;
+        let mut session = self.session.lock().unwrap();
+        let outputs = session.run(ort::inputs![input]).map_err(|e| e.to_string())?;
+        let (_, raw) = outputs[0].try_extract_tensor::<f32>().map_err(|e| e.to_string())?;
+        if raw.len() != self.labels.len() {
+            return Err(format!("model returned {} scores for {} labels", raw.len(), self.labels.len()));
+        }
+        Ok(rank(&self.labels, raw.iter().map(|&v| v as f64).collect()))
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    pub fn classify(&self, _f: &IntentFeatures) -> Result<Vec<(String, f64)>, String> {
+        Err(format!("onnx support not compiled in ({} labels configured)", self.labels.len()))
+    }
+}
+
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn features(f: &IntentFeatures) -> [f32; 6] {
+    let (angle, alignment) = f.rotation.unwrap_or((0.0, 0.0));
+    [f.magnitude as f32, f.avg_speed as f32, f.dz as f32, angle as f32, alignment as f32, f.rotation.is_some() as u8 as f32]
+}
+
+/// Normalize raw scores into probabilities (softmax unless they already form a
+/// distribution) and sort descending.
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn rank(labels: &[String], scores: Vec<f64>) -> Vec<(String, f64)> {
+    let sum: f64 = scores.iter().sum();
+    let is_distribution = scores.iter().all(|&p| (0.0..=1.0).contains(&p)) && (sum - 1.0).abs() < 1e-3;
+    let probs = if is_distribution { scores } else {
+        let max = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let exp: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+        let total: f64 = exp.iter().sum();
+        exp.into_iter().map(|e| e / total).collect()
+    };
+    let mut ranked: Vec<(String, f64)> = labels.iter().cloned().zip(probs).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
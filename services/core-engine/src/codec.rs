@@ -0,0 +1,146 @@
+// ── Delta + quantization motion codec ───────────────────────
+//
+// Layout (little-endian):
+//   magic "AKQ1" | flags u8 | channels u16 | frames u32 | step f64 | body
+// flags: bit 0 = body is zstd-compressed, bit 1 = timestamps present.
+// Body: optional timestamp column, then one column per channel. Each column is the
+// first value followed by successive differences, zigzag + LEB128 varint encoded.
+// Channel values are quantized to integer multiples of `step`, so decoding is exact
+// to within `step / 2`; timestamps are integer milliseconds and round-trip exactly.
+
+const MAGIC: &[u8; 4] = b"AKQ1";
+const HEADER_LEN: usize = 4 + 1 + 2 + 4 + 8;
+const FLAG_ZSTD: u8 = 1;
+const FLAG_TIMESTAMPS: u8 = 2;
+
+/// Optional entropy stage applied after delta/varint packing.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Entropy { None, Zstd }
+
+impl Entropy {
+    pub fn parse(s: Option<&str>) -> Result<Self, String> {
+        match s.unwrap_or("none") {
+            "none" => Ok(Entropy::None),
+            "zstd" if cfg!(feature = "zstd") => Ok(Entropy::Zstd),
+            "zstd" => Err("zstd entropy stage is not compiled into this build (enable the `zstd` feature)".into()),
+            other => Err(format!("'{other}' is not one of none, zstd")),
+        }
+    }
+}
+
+/// A decoded stream: `frames[i][c]` is channel `c` of frame `i`.
+pub struct Stream { pub timestamps_ms: Option<Vec<u64>>, pub frames: Vec<Vec<f64>>, pub step: f64 }
+
+/// Encode `frames` (all rows the same width) with quantization `step`.
+pub fn encode(frames: &[Vec<f64>], timestamps_ms: Option<&[u64]>, step: f64, entropy: Entropy) -> Result<Vec<u8>, String> {
+    if !(step.is_finite() && step > 0.0) { return Err(format!("quantization step must be a positive finite number, got {step}")); }
+    let channels = frames.first().map_or(0, |f| f.len());
+    if let Some(i) = frames.iter().position(|f| f.len() != channels) {
+        return Err(format!("frame {i} has {} channels, frame 0 has {channels}", frames[i].len()));
+    }
+    if channels > u16::MAX as usize || frames.len() > u32::MAX as usize { return Err("stream too large for codec header".into()); }
+    if let Some(ts) = timestamps_ms {
+        if ts.len() != frames.len() { return Err(format!("{} timestamps for {} frames", ts.len(), frames.len())); }
+    }
+
+    let mut body = Vec::with_capacity(frames.len() * channels * 2);
+    if let Some(ts) = timestamps_ms { put_column(&mut body, ts.iter().map(|&t| t as i64)); }
+    for c in 0..channels {
+        put_column(&mut body, frames.iter().map(|f| quantize(f[c], step)));
+    }
+    let body = match entropy {
+        Entropy::None => body,
+        Entropy::Zstd => compress(&body)?,
+    };
+
+    let mut flags = 0u8;
+    if entropy == Entropy::Zstd { flags |= FLAG_ZSTD; }
+    if timestamps_ms.is_some() { flags |= FLAG_TIMESTAMPS; }
+    let mut out = Vec::with_capacity(HEADER_LEN + body.len());
+    out.extend_from_slice(MAGIC);
+    out.push(flags);
+    out.extend_from_slice(&(channels as u16).to_le_bytes());
+    out.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    out.extend_from_slice(&step.to_le_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+pub fn decode(data: &[u8]) -> Result<Stream, String> {
+    if data.len() < HEADER_LEN || &data[..4] != MAGIC { return Err("not an AKQ1 stream".into()); }
+    let flags = data[4];
+    let channels = u16::from_le_bytes([data[5], data[6]]) as usize;
+    let count = u32::from_le_bytes([data[7], data[8], data[9], data[10]]) as usize;
+    let step = f64::from_le_bytes(data[11..19].try_into().unwrap());
+    let body = if flags & FLAG_ZSTD != 0 { decompress(&data[HEADER_LEN..])? } else { data[HEADER_LEN..].to_vec() };
+    // Every value takes at least one byte, which bounds allocations for forged headers
+    let columns = channels + usize::from(flags & FLAG_TIMESTAMPS != 0);
+    if count.saturating_mul(columns) > body.len() { return Err("stream truncated".into()); }
+
+    let mut pos = 0usize;
+    let timestamps_ms = if flags & FLAG_TIMESTAMPS != 0 {
+        Some(take_column(&body, &mut pos, count)?.into_iter().map(|t| t.max(0) as u64).collect())
+    } else { None };
+    let mut frames = vec![Vec::with_capacity(channels); count];
+    for _ in 0..channels {
+        for (frame, q) in frames.iter_mut().zip(take_column(&body, &mut pos, count)?) {
+            frame.push(q as f64 * step);
+        }
+    }
+    if pos != body.len() { return Err(format!("{} trailing bytes after stream", body.len() - pos)); }
+    Ok(Stream { timestamps_ms, frames, step })
+}
+
+fn quantize(x: f64, step: f64) -> i64 {
+    if x.is_finite() { (x / step).round() as i64 } else { 0 }
+}
+
+fn put_column(out: &mut Vec<u8>, values: impl Iterator<Item = i64>) {
+    let mut prev = 0i64;
+    for v in values {
+        let delta = v.wrapping_sub(prev);
+        prev = v;
+        let mut z = ((delta << 1) ^ (delta >> 63)) as u64;
+        loop {
+            let byte = (z & 0x7f) as u8;
+            z >>= 7;
+            if z == 0 { out.push(byte); break; }
+            out.push(byte | 0x80);
+        }
+    }
+}
+
+fn take_column(body: &[u8], pos: &mut usize, count: usize) -> Result<Vec<i64>, String> {
+    let mut out = Vec::with_capacity(count);
+    let mut prev = 0i64;
+    for _ in 0..count {
+        let mut z = 0u64;
+        let mut shift = 0u32;
+        loop {
+            let byte = *body.get(*pos).ok_or("stream truncated")?;
+            *pos += 1;
+            if shift >= 64 { return Err("malformed varint".into()); }
+            z |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 { break; }
+        }
+        let delta = ((z >> 1) as i64) ^ -((z & 1) as i64);
+        prev = prev.wrapping_add(delta);
+        out.push(prev);
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "zstd")]
+fn compress(body: &[u8]) -> Result<Vec<u8>, String> { zstd::bulk::compress(body, 9).map_err(|e| e.to_string()) }
+#[cfg(not(feature = "zstd"))]
+fn compress(_: &[u8]) -> Result<Vec<u8>, String> { Err("zstd support not compiled in".into()) }
+
+#[cfg(feature = "zstd")]
+fn decompress(body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    zstd::stream::copy_decode(body, &mut out).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+#[cfg(not(feature = "zstd"))]
+fn decompress(_: &[u8]) -> Result<Vec<u8>, String> { Err("stream is zstd-compressed but zstd support is not compiled in".into()) }
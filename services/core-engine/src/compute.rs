@@ -0,0 +1,48 @@
+// ── Compute pool ────────────────────────────────────────────
+//
+// IK solves (single, hand and batch), intent analysis and path timing run on a fixed
+// pool of `KINEMATICS_SOLVE_THREADS` threads, one per core by default, never on the
+// tokio workers that serve requests, so a burst of long solves queues for the pool instead of starving health checks,
+// metrics and I/O. The pool is rayon's global one, so `par_iter` inside a task fans out
+// over the same threads. Unlike `spawn_blocking`, whose pool grows to hundreds of
+// threads and oversubscribes the cores, work beyond the pool's size waits in its queue;
+// `queued` and `running` show how deep.
+
+use crate::error::ApiError;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static QUEUED: AtomicUsize = AtomicUsize::new(0);
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Build the pool with `threads` threads, or one per core.
+pub fn init(threads: Option<usize>) {
+    let builder = rayon::ThreadPoolBuilder::new().thread_name(|i| format!("solver-{i}"));
+    builder.num_threads(threads.unwrap_or(0)).build_global().expect("solver pool already built");
+    tracing::info!("Compute pool: {} threads", size());
+}
+
+pub fn size() -> usize { rayon::current_num_threads() }
+
+/// Tasks waiting for a pool thread, and tasks running on one.
+pub fn queued() -> usize { QUEUED.load(Ordering::Relaxed) }
+pub fn running() -> usize { RUNNING.load(Ordering::Relaxed) }
+
+/// Run CPU-bound `work` on the pool, inside the caller's tracing span, and wait for it
+/// without holding an async worker. A panic in `work` is an internal error, not an abort.
+pub async fn run<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T, ApiError> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let span = tracing::Span::current();
+    QUEUED.fetch_add(1, Ordering::Relaxed);
+    rayon::spawn(move || {
+        QUEUED.fetch_sub(1, Ordering::Relaxed);
+        RUNNING.fetch_add(1, Ordering::Relaxed);
+        let out = std::panic::catch_unwind(AssertUnwindSafe(|| span.in_scope(work)));
+        RUNNING.fetch_sub(1, Ordering::Relaxed);
+        let _ = tx.send(out);
+    });
+    match rx.await {
+        Ok(Ok(out)) => Ok(out),
+        Ok(Err(_)) | Err(_) => Err(ApiError::internal("Solver task failed", "the solve panicked")),
+    }
+}
@@ -0,0 +1,149 @@
+// ── Concurrency limits ──────────────────────────────────────
+//
+// Caps on how many API requests run at once, engine-wide and per route, so a burst of
+// batch work (trajectories, intent compression, jobs) cannot push the latency of
+// real-time IK up. Excess requests are shed straight away rather than queued: over a
+// route's limit they get 429, over the global limit 503. Either way `Retry-After`
+// estimates when a slot frees up, from the route's recent average latency. Jobs never
+// get shed. A running job holds a slot on its operation's route, but not the global
+// one, and a job that finds the route full stays `queued` until a slot frees. Health,
+// metrics, the OpenAPI document and admin routes are never limited.
+
+use crate::error::{ApiError, ErrorCode};
+use crate::AppState;
+use axum::{extract::{MatchedPath, Request, State}, http::{header, HeaderValue}, middleware::Next, response::{IntoResponse, Response}};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Weight of the newest latency in a route's running average.
+const SMOOTHING: f64 = 0.2;
+
+/// Limit per route: the longest matching route suffix, plus the engine-wide one.
+#[derive(Default)]
+pub struct Limits { global: Option<usize>, routes: Vec<(String, usize)> }
+
+impl Limits {
+    /// `N` for the whole engine and/or `ROUTE=N` entries, comma separated; `off` or
+    /// empty limits nothing.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut l = Limits::default();
+        let n = |v: &str| v.trim().parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(|| format!("'{}' is not a positive request count", v.trim()));
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty() && *e != "off") {
+            match entry.split_once('=') {
+                Some((route, v)) => l.routes.push((route.trim().trim_start_matches('/').to_string(), n(v)?)),
+                None => l.global = Some(n(entry)?),
+            }
+        }
+        Ok(l)
+    }
+
+    fn route(&self, route: &str) -> Option<usize> {
+        self.routes.iter().filter(|(r, _)| route.ends_with(r.as_str())).max_by_key(|(r, _)| r.len()).map(|(_, n)| *n)
+    }
+}
+
+/// Requests in flight and their recent average latency.
+#[derive(Default)]
+struct Load { in_flight: usize, avg: Option<Duration> }
+
+impl Load {
+    fn finished(&mut self, took: Duration) {
+        self.in_flight -= 1;
+        self.avg = Some(self.avg.map_or(took, |a| a.mul_f64(1.0 - SMOOTHING) + took.mul_f64(SMOOTHING)));
+    }
+
+    /// When a slot should free up, with `limit` of them all taken.
+    fn retry_after(&self, limit: usize) -> Duration { self.avg.unwrap_or(Duration::from_secs(1)).mul_f64(self.in_flight as f64 / limit as f64) }
+}
+
+struct Inner { limits: Limits, global: Load, routes: HashMap<String, Load> }
+
+enum Shed { Route { limit: usize, retry_after: Duration }, Global { limit: usize, retry_after: Duration } }
+
+pub struct Concurrency { inner: Mutex<Inner>, freed: Notify }
+
+impl Concurrency {
+    pub fn new(limits: Limits) -> Self { Self { inner: Mutex::new(Inner { limits, global: Load::default(), routes: HashMap::new() }), freed: Notify::new() } }
+
+    /// Change the limits; requests in flight keep their slots.
+    pub fn set_limits(&self, limits: Limits) {
+        self.inner.lock().unwrap().limits = limits;
+        self.freed.notify_waiters();
+    }
+
+    /// Requests and running jobs per route, where there are any.
+    pub fn in_flight(&self) -> BTreeMap<String, usize> {
+        self.inner.lock().unwrap().routes.iter().filter(|(_, l)| l.in_flight > 0).map(|(r, l)| (r.clone(), l.in_flight)).collect()
+    }
+
+    /// Take a slot on `route`, and an engine-wide one when `global`.
+    fn acquire(&self, route: &str, global: bool) -> Result<(), Shed> {
+        let mut inner = self.inner.lock().unwrap();
+        let Inner { limits, global: all, routes } = &mut *inner;
+        let load = routes.entry(route.to_string()).or_default();
+        if let Some(limit) = limits.route(route).filter(|&n| load.in_flight >= n) {
+            return Err(Shed::Route { limit, retry_after: load.retry_after(limit) });
+        }
+        if let Some(limit) = limits.global.filter(|&n| global && all.in_flight >= n) {
+            return Err(Shed::Global { limit, retry_after: all.retry_after(limit) });
+        }
+        load.in_flight += 1;
+        if global { all.in_flight += 1; }
+        Ok(())
+    }
+
+    fn release(&self, route: &str, global: bool, took: Duration) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(load) = inner.routes.get_mut(route) { load.finished(took); }
+            if global { inner.global.finished(took); }
+        }
+        self.freed.notify_waiters();
+    }
+}
+
+/// A slot held until dropped.
+pub struct Slot { s: Arc<AppState>, route: String, global: bool, since: Instant }
+
+impl Drop for Slot {
+    fn drop(&mut self) { self.s.concurrency.release(&self.route, self.global, self.since.elapsed()) }
+}
+
+/// Wait for a slot on `route` for a job's run; jobs do not count against the global limit.
+pub async fn job_slot(s: &Arc<AppState>, route: String) -> Slot {
+    loop {
+        let freed = s.concurrency.freed.notified();
+        if s.concurrency.acquire(&route, false).is_ok() { return Slot { s: s.clone(), route, global: false, since: Instant::now() }; }
+        freed.await;
+    }
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let path = req.uri().path();
+    if !path.starts_with("/api/v1/") || path.starts_with("/api/v1/admin/") || path == "/api/v1/openapi.json" { return next.run(req).await; }
+    let route = req.extensions().get::<MatchedPath>().map_or_else(|| "unmatched".to_string(), |p| p.as_str().to_string());
+    match s.concurrency.acquire(&route, true) {
+        Ok(()) => {
+            let _slot = Slot { s: s.clone(), route, global: true, since: Instant::now() };
+            next.run(req).await
+        }
+        Err(shed) => {
+            let retry_after = match shed { Shed::Route { retry_after, .. } | Shed::Global { retry_after, .. } => retry_after };
+            let mut res = shed_error(&route, shed).into_response();
+            res.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after.as_secs_f64().ceil().max(1.0) as u64));
+            res
+        }
+    }
+}
+
+fn shed_error(route: &str, shed: Shed) -> ApiError {
+    let ms = |d: Duration| d.as_millis().max(1);
+    match shed {
+        Shed::Route { limit, retry_after } => ApiError::new(ErrorCode::TooManyRequests, "Too many concurrent requests",
+            format!("{route} is running its limit of {limit} requests; retry in about {} ms", ms(retry_after))),
+        Shed::Global { limit, retry_after } => ApiError::unavailable("Engine overloaded",
+            format!("{limit} requests are already running; retry in about {} ms, or on another instance", ms(retry_after))),
+    }
+}
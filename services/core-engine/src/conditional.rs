@@ -0,0 +1,63 @@
+// ── Conditional registry reads ──────────────────────────────
+//
+// `GET`s of chains and hands carry a weak `ETag` (a hash of the body and the requested
+// `Accept`, so JSON and binary renderings differ) and `Last-Modified` (the caller's
+// tenant registry's last change). A request repeating the tag in `If-None-Match`, or
+// failing that a date no older than the change in `If-Modified-Since`, gets `304 Not
+// Modified` without a body, so polling clients only download models that changed.
+
+use crate::AppState;
+use axum::{body::{to_bytes, Body}, extract::{Request, State}, http::{header, HeaderValue, Method, StatusCode}, middleware::Next, response::{IntoResponse, Response}};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Last change of the registry `path` reads from, if it is a registry read.
+fn modified_ms(s: &AppState, path: &str) -> Option<u64> {
+    let rest = path.strip_prefix("/api/v1/kinematics/")?;
+    match rest.split('/').next()? {
+        "chains" => Some(s.chains.current().lock().unwrap().modified_ms()),
+        "hands" => Some(s.hands.current().lock().unwrap().modified_ms()),
+        _ => None,
+    }
+}
+
+/// `If-None-Match` lists `etag` (weak comparison) or is `*`.
+fn matches(if_none_match: &str, etag: &str) -> bool {
+    let bare = |t: &str| t.trim().trim_start_matches("W/").to_string();
+    if_none_match.split(',').any(|t| t.trim() == "*" || bare(t) == bare(etag))
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if !matches!(*req.method(), Method::GET | Method::HEAD) { return next.run(req).await; }
+    let Some(modified_ms) = modified_ms(&s, req.uri().path()) else { return next.run(req).await };
+    let (accept, if_none_match, if_modified_since) = {
+        let h = req.headers();
+        let value = |name| h.get(name).and_then(|v: &HeaderValue| v.to_str().ok()).map(String::from);
+        (value(header::ACCEPT), value(header::IF_NONE_MATCH), value(header::IF_MODIFIED_SINCE))
+    };
+    let res = next.run(req).await;
+    if res.status() != StatusCode::OK { return res; }
+    let (mut parts, body) = res.into_parts();
+    let Ok(body) = to_bytes(body, usize::MAX).await else { return StatusCode::INTERNAL_SERVER_ERROR.into_response() };
+    let mut h = Sha256::new();
+    h.update(accept.as_deref().unwrap_or_default());
+    h.update(b"\n");
+    h.update(&body);
+    let etag = format!("W/\"{}\"", hex::encode(&h.finalize()[..16]));
+    let modified = UNIX_EPOCH + Duration::from_secs(modified_ms / 1000);
+    let not_modified = match (&if_none_match, &if_modified_since) {
+        (Some(tags), _) => matches(tags, &etag),
+        (None, Some(date)) => httpdate::parse_http_date(date).is_ok_and(|since: SystemTime| modified <= since),
+        (None, None) => false,
+    };
+    parts.headers.insert(header::ETAG, HeaderValue::from_str(&etag).expect("hex etag"));
+    parts.headers.insert(header::LAST_MODIFIED, HeaderValue::from_str(&httpdate::fmt_http_date(modified)).expect("http date"));
+    if not_modified {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_TYPE);
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(body))
+}
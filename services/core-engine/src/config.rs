@@ -0,0 +1,197 @@
+// ── Configuration ───────────────────────────────────────────
+//
+// Settings come in three layers, each overriding the one before: a TOML file
+// (`--config` or `KINEMATICS_CONFIG`), `KINEMATICS_*` environment variables, and
+// command-line flags (`--addr`, `--set section.key=value`). Every file key has an
+// environment variable twin, listed in `KEYS`, and the engine reads settings by
+// variable name through `var`, so the precedence is the same for all of them. An
+// unknown section or key, or a value that is not a string, number, boolean or array
+// of those, stops startup instead of being silently ignored. `reload` re-reads the
+// file while running; see `reload.rs` for which settings take effect.
+
+use std::collections::HashMap;
+use std::env::VarError;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// File key (`section.key`) and the environment variable it stands in for.
+const KEYS: &[(&str, &str)] = &[
+    ("server.addr", "KINEMATICS_ADDR"),
+    ("server.grpc_addr", "KINEMATICS_GRPC_ADDR"),
+    ("server.shutdown_timeout_secs", "KINEMATICS_SHUTDOWN_TIMEOUT_SECS"),
+    ("server.compression_min_bytes", "KINEMATICS_COMPRESSION_MIN_BYTES"),
+    ("server.trust_forwarded_for", "KINEMATICS_TRUST_FORWARDED_FOR"),
+    ("server.reload_secs", "KINEMATICS_RELOAD_SECS"),
+    ("server.solve_threads", "KINEMATICS_SOLVE_THREADS"),
+    ("server.selftest_on_start", "KINEMATICS_SELFTEST_ON_START"),
+    ("log.format", "KINEMATICS_LOG_FORMAT"),
+    ("log.filter", "RUST_LOG"),
+    ("log.slow_request_ms", "KINEMATICS_SLOW_REQUEST_MS"),
+    ("cors.allowed_origins", "KINEMATICS_CORS_ORIGINS"),
+    ("limits.body_samples", "KINEMATICS_BODY_LIMIT_SAMPLES"),
+    ("limits.body_import", "KINEMATICS_BODY_LIMIT_IMPORT"),
+    ("limits.body_default", "KINEMATICS_BODY_LIMIT_DEFAULT"),
+    ("limits.max_points", "KINEMATICS_MAX_POINTS"),
+    ("limits.rate_solve", "KINEMATICS_RATE_LIMIT_SOLVE"),
+    ("limits.rate_write", "KINEMATICS_RATE_LIMIT_WRITE"),
+    ("limits.rate_read", "KINEMATICS_RATE_LIMIT_READ"),
+    ("limits.concurrency", "KINEMATICS_CONCURRENCY_LIMIT"),
+    ("limits.trajectory_stream_points", "KINEMATICS_TRAJECTORY_STREAM_POINTS"),
+    ("solver.solver", "KINEMATICS_SOLVER"),
+    ("solver.max_iterations", "KINEMATICS_SOLVER_MAX_ITERATIONS"),
+    ("solver.tolerance", "KINEMATICS_SOLVER_TOLERANCE"),
+    ("solver.damping", "KINEMATICS_SOLVER_DAMPING"),
+    ("solver.seed_policy", "KINEMATICS_SOLVER_SEED_POLICY"),
+    ("solver.timeout_ms", "KINEMATICS_SOLVE_TIMEOUT_MS"),
+    ("solver.warm_start_cell", "KINEMATICS_WARM_START_CELL"),
+    ("solver.warm_start_entries", "KINEMATICS_WARM_START_ENTRIES"),
+    ("solver.gpu_batch_threshold", "KINEMATICS_GPU_BATCH_THRESHOLD"),
+    ("storage.chain_store", "KINEMATICS_CHAIN_STORE"),
+    ("storage.key_store", "KINEMATICS_KEY_STORE"),
+    ("storage.intent_log", "KINEMATICS_INTENT_LOG"),
+    ("storage.intent_history", "KINEMATICS_INTENT_HISTORY"),
+    ("storage.stats_store", "KINEMATICS_STATS_STORE"),
+    ("storage.stats_flush_secs", "KINEMATICS_STATS_FLUSH_SECS"),
+    ("storage.audit_log", "KINEMATICS_AUDIT_LOG"),
+    ("storage.audit_history", "KINEMATICS_AUDIT_HISTORY"),
+    ("storage.job_retention_secs", "KINEMATICS_JOB_RETENTION_SECS"),
+    ("storage.solution_retention_secs", "KINEMATICS_SOLUTION_RETENTION_SECS"),
+    ("storage.sim_idle_secs", "KINEMATICS_SIM_IDLE_SECS"),
+    ("storage.session_idle_secs", "KINEMATICS_SESSION_IDLE_SECS"),
+    ("storage.idempotency_ttl_secs", "KINEMATICS_IDEMPOTENCY_TTL_SECS"),
+    ("storage.result_cache_ttl_secs", "KINEMATICS_RESULT_CACHE_TTL_SECS"),
+    ("storage.result_cache_entries", "KINEMATICS_RESULT_CACHE_ENTRIES"),
+    ("auth.api_keys", "KINEMATICS_API_KEYS"),
+    ("auth.admin_key", "KINEMATICS_ADMIN_KEY"),
+    ("auth.oidc_issuer", "KINEMATICS_OIDC_ISSUER"),
+    ("auth.oidc_audience", "KINEMATICS_OIDC_AUDIENCE"),
+    ("auth.oidc_jwks_uri", "KINEMATICS_OIDC_JWKS_URI"),
+    ("auth.oidc_tenant_claim", "KINEMATICS_OIDC_TENANT_CLAIM"),
+    ("intent.model", "KINEMATICS_INTENT_MODEL"),
+    ("intent.model_labels", "KINEMATICS_INTENT_MODEL_LABELS"),
+    ("webhooks.secret", "KINEMATICS_WEBHOOK_SECRET"),
+    ("webhooks.attempts", "KINEMATICS_WEBHOOK_ATTEMPTS"),
+    ("webhooks.allow_private", "KINEMATICS_WEBHOOK_ALLOW_PRIVATE"),
+    ("events.nats_url", "KINEMATICS_NATS_URL"),
+    ("events.subject", "KINEMATICS_EVENTS_SUBJECT"),
+    ("events.kinds", "KINEMATICS_EVENTS"),
+    ("mqtt.host", "KINEMATICS_MQTT_HOST"),
+    ("mqtt.client_id", "KINEMATICS_MQTT_CLIENT_ID"),
+    ("mqtt.topic_prefix", "KINEMATICS_MQTT_TOPIC_PREFIX"),
+    ("mqtt.username", "KINEMATICS_MQTT_USERNAME"),
+    ("mqtt.password", "KINEMATICS_MQTT_PASSWORD"),
+    ("mqtt.api_key", "KINEMATICS_MQTT_API_KEY"),
+    ("ros2.zenoh", "KINEMATICS_ROS2_ZENOH"),
+    ("ros2.namespace", "KINEMATICS_ROS2_NAMESPACE"),
+    ("ros2.frame_id", "KINEMATICS_ROS2_FRAME_ID"),
+    ("ros2.api_key", "KINEMATICS_ROS2_API_KEY"),
+];
+
+#[derive(clap::Parser)]
+#[command(name = "kinematics-engine", version, about = "ALICE Kinematics Cloud engine")]
+struct Args {
+    /// TOML configuration file
+    #[arg(long, env = "KINEMATICS_CONFIG", value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Listen address, overriding `server.addr` and KINEMATICS_ADDR
+    #[arg(long, value_name = "HOST:PORT")]
+    addr: Option<String>,
+    /// Override any file key, e.g. `--set limits.rate_solve=off`; repeatable
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    set: Vec<String>,
+}
+
+/// Values from the file (with the digest of the text they came from) and from flags,
+/// by environment variable name.
+struct Layers { file: Mutex<(String, HashMap<&'static str, String>)>, flags: HashMap<&'static str, String>, path: Option<PathBuf> }
+
+static LAYERS: OnceLock<Layers> = OnceLock::new();
+
+/// Parse the command line and read the configuration file. Exits with usage on a bad
+/// flag; panics on an unreadable or invalid file.
+pub fn init() {
+    let args = <Args as clap::Parser>::parse();
+    let file = args.config.as_ref().map(|p| {
+        let text = std::fs::read_to_string(p).unwrap_or_else(|e| panic!("config {}: {e}", p.display()));
+        (crate::audit::digest(text.as_bytes()), read(&text).unwrap_or_else(|e| panic!("config {}: {e}", p.display())))
+    }).unwrap_or_default();
+    let mut flags = HashMap::new();
+    for kv in &args.set {
+        let (key, value) = kv.split_once('=').unwrap_or_else(|| exit(format!("--set {kv}: expected KEY=VALUE")));
+        let var = env_name(key.trim()).unwrap_or_else(|| exit(format!("--set {kv}: unknown key '{}'", key.trim())));
+        flags.insert(var, value.to_string());
+    }
+    if let Some(addr) = args.addr { flags.insert("KINEMATICS_ADDR", addr); }
+    let _ = LAYERS.set(Layers { file: Mutex::new(file), flags, path: args.config });
+}
+
+/// The configuration file in use, if any.
+pub fn path() -> Option<&'static PathBuf> { LAYERS.get().and_then(|l| l.path.as_ref()) }
+
+/// Setting `name`, named by its environment variable: a flag's value, else the
+/// environment's, else the file's. A drop-in for `std::env::var`.
+pub fn var(name: &str) -> Result<String, VarError> {
+    let layers = LAYERS.get();
+    if let Some(v) = layers.and_then(|l| l.flags.get(name)) { return Ok(v.clone()); }
+    match std::env::var(name) {
+        Err(VarError::NotPresent) => layers.and_then(|l| l.file.lock().unwrap().1.get(name).cloned()).ok_or(VarError::NotPresent),
+        other => other,
+    }
+}
+
+/// Re-read the configuration file if it changed since last read. Returns its digest
+/// and the keys whose effective values changed (those not overridden by the
+/// environment or a flag), or `None` when there is no file or it is unchanged. A file
+/// that no longer parses is reported once and the previous values are kept.
+pub fn reload() -> Result<Option<(String, Vec<&'static str>)>, String> {
+    let Some(layers) = LAYERS.get() else { return Ok(None) };
+    let Some(path) = &layers.path else { return Ok(None) };
+    let Ok(text) = std::fs::read_to_string(path) else { return Ok(None) };
+    let digest = crate::audit::digest(text.as_bytes());
+    let mut file = layers.file.lock().unwrap();
+    if file.0 == digest { return Ok(None); }
+    file.0 = digest.clone();
+    let values = read(&text).map_err(|e| format!("not reloading config {}: {e}", path.display()))?;
+    let changed = KEYS.iter()
+        .filter(|(_, var)| file.1.get(var) != values.get(var) && !layers.flags.contains_key(var) && std::env::var_os(var).is_none())
+        .map(|(key, _)| *key).collect();
+    file.1 = values;
+    Ok(Some((digest, changed)))
+}
+
+fn env_name(key: &str) -> Option<&'static str> { KEYS.iter().find(|(k, _)| *k == key).map(|(_, v)| *v) }
+
+fn exit(message: String) -> ! {
+    eprintln!("error: {message}");
+    std::process::exit(2)
+}
+
+/// Flatten the file's tables into values by environment variable name. Arrays become
+/// comma-separated lists, as the variables spell them.
+fn read(text: &str) -> Result<HashMap<&'static str, String>, String> {
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let mut out = HashMap::new();
+    for (section, entries) in &table {
+        let toml::Value::Table(entries) = entries else { return Err(format!("'{section}' must be a [section]")) };
+        for (key, value) in entries {
+            let path = format!("{section}.{key}");
+            let var = env_name(&path).ok_or_else(|| format!("unknown key '{path}'"))?;
+            let value = match value {
+                toml::Value::Array(items) => items.iter().map(scalar).collect::<Option<Vec<_>>>().map(|v| v.join(",")),
+                v => scalar(v),
+            }.ok_or_else(|| format!("'{path}' must be a string, number, boolean or array of those"))?;
+            out.insert(var, value);
+        }
+    }
+    Ok(out)
+}
+
+fn scalar(v: &toml::Value) -> Option<String> {
+    match v {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(n) => Some(n.to_string()),
+        toml::Value::Float(x) => Some(x.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
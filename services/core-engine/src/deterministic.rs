@@ -0,0 +1,49 @@
+// ── Deterministic mode ──────────────────────────────────────
+//
+// A request carrying `X-Deterministic-Seed: <u64>` is answered reproducibly: ids in the
+// response (`solution_id`, `intent_id`, `trajectory_id`) are derived from the seed and a
+// hash of the request instead of drawn at random, timings read 0 and warm starts are
+// skipped, so identical inputs get byte-identical responses, for tests and caches keyed
+// on content. `seed()` is what any stochastic step should draw from. The seed is
+// task-local, like the caller's principal, and follows the request into jobs, streams
+// and WebSockets.
+
+use crate::error::ApiError;
+use axum::{extract::Request, middleware::Next, response::{IntoResponse, Response}};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+
+pub const HEADER: &str = "x-deterministic-seed";
+
+tokio::task_local! { static SEED: u64; }
+
+/// Run `task` with `seed`; unscoped (the normal, random mode) when there is none.
+pub async fn scope<F: std::future::Future>(seed: Option<u64>, task: F) -> F::Output {
+    match seed { Some(seed) => SEED.scope(seed, task).await, None => task.await }
+}
+
+/// The current request's seed, in deterministic mode.
+pub fn seed() -> Option<u64> { SEED.try_with(|s| *s).ok() }
+
+/// A fresh id for a `kind` of result computed from `input`: a v4 UUID, or in
+/// deterministic mode one built from the hash of the seed, `kind` and `input`.
+pub fn id(kind: &str, input: &(impl Serialize + ?Sized)) -> String {
+    let Some(seed) = seed() else { return uuid::Uuid::new_v4().to_string() };
+    let mut h = Sha256::new();
+    h.update(format!("{seed}\n{kind}\n"));
+    h.update(serde_json::to_vec(input).unwrap_or_default());
+    let digest: [u8; 32] = h.finalize().into();
+    uuid::Builder::from_custom_bytes(digest[..16].try_into().expect("16 of 32 bytes")).into_uuid().to_string()
+}
+
+/// µs since `t`, or 0 in deterministic mode.
+pub fn elapsed_us(t: Instant) -> u128 { if seed().is_some() { 0 } else { t.elapsed().as_micros() } }
+
+pub async fn layer(req: Request, next: Next) -> Response {
+    let Some(value) = req.headers().get(HEADER) else { return next.run(req).await };
+    match value.to_str().ok().and_then(|v| v.trim().parse::<u64>().ok()) {
+        Some(seed) => SEED.scope(seed, next.run(req)).await,
+        None => ApiError::bad_request("Invalid X-Deterministic-Seed", "expected an unsigned 64-bit integer").into_response(),
+    }
+}
@@ -0,0 +1,81 @@
+// ── Errors ──────────────────────────────────────────────────
+//
+// Every failure is an `ApiError` (defined in `kinematics-types`, so clients decode the
+// same envelope), rendered as one body on every transport. The `Json` and `Query`
+// extractors below replace axum's so that body and query parse failures use the
+// same envelope (with the serde path as `field`) instead of plain-text rejections.
+
+use axum::{async_trait, body::Bytes, extract::{FromRequest, FromRequestParts, Request}, http::{header, request::Parts, HeaderMap, StatusCode}, response::{IntoResponse, Response}};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use kinematics_types::{ApiError, ErrorCode, FieldError};
+
+pub type ApiResult<T> = Result<Json<T>, ApiError>;
+
+// ── Extractors ──────────────────────────────────────────────
+/// JSON body / response. Syntax errors are `bad_request`; values of the wrong shape
+/// are `invalid` with their serde path.
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for Json<T> {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, ApiError> {
+        if !is_json(req.headers()) {
+            return Err(ApiError::new(ErrorCode::UnsupportedMediaType, "Expected a JSON body", "send Content-Type: application/json (or application/msgpack, application/cbor)"));
+        }
+        let bytes = Bytes::from_request(req, state).await.map_err(|e| match e.status() {
+            StatusCode::PAYLOAD_TOO_LARGE => ApiError::new(ErrorCode::PayloadTooLarge, "Request body too large", e.body_text()),
+            _ => ApiError::bad_request("Unreadable request body", e.body_text()),
+        })?;
+        let _phase = crate::slow::phase("parse");
+        tracing::info_span!("parse", bytes = bytes.len()).in_scope(|| from_slice(&bytes)).map(Json)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        let _phase = crate::slow::phase("serialize");
+        tracing::info_span!("serialize").in_scope(|| axum::Json(self.0).into_response())
+    }
+}
+
+/// Deserialize JSON with the same error mapping as the `Json` extractor.
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ApiError> {
+    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(bytes)).map_err(rejection)
+}
+
+pub fn from_value<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, ApiError> {
+    serde_path_to_error::deserialize(value).map_err(rejection)
+}
+
+fn rejection(e: serde_path_to_error::Error<serde_json::Error>) -> ApiError {
+    let field = e.path().to_string();
+    let inner = e.into_inner();
+    match inner.classify() {
+        serde_json::error::Category::Data if field != "." => ApiError::invalid(field, "Invalid request", inner.to_string()),
+        serde_json::error::Category::Data => ApiError::new(ErrorCode::Invalid, "Invalid request", inner.to_string()),
+        _ => ApiError::bad_request("Malformed JSON", inner.to_string()),
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    let Some(ct) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) else { return false };
+    let media = ct.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    media == "application/json" || (media.starts_with("application/") && media.ends_with("+json"))
+}
+
+/// Query string; parse failures are `bad_request`.
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T: DeserializeOwned, S: Send + Sync> FromRequestParts<S> for Query<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+        axum::extract::Query::from_request_parts(parts, state).await
+            .map(|axum::extract::Query(q)| Query(q))
+            .map_err(|e| ApiError::bad_request("Invalid query string", e.body_text()))
+    }
+}
@@ -0,0 +1,78 @@
+// ── Result events ───────────────────────────────────────────
+//
+// Every completed solve, intent analysis and trajectory can be published to a message
+// bus as an event, so analytics and digital-twin consumers subscribe instead of polling
+// `/stats`. Events go to NATS subject `{prefix}.{tenant}.{kind}` (e.g.
+// `kinematics.results.default.ik`) and carry the response body exactly as the caller
+// got it. Publishing never blocks a request: events queue on a bounded channel and are
+// dropped, with a warning, when the bus falls behind.
+
+use crate::history::now_ms;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc;
+
+/// Events buffered while the bus is slow or reconnecting.
+#[cfg_attr(not(feature = "nats"), allow(dead_code))]
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// Kinds an event can have, as named in `KINEMATICS_EVENTS`.
+pub const KINDS: [&str; 4] = ["ik", "fk", "intent", "trajectory"];
+
+#[derive(Serialize)]
+struct Event<'a, T> {
+    event_id: String, kind: &'a str, tenant: &'a str,
+    /// Key or token subject that made the request, when authenticated.
+    #[serde(skip_serializing_if = "Option::is_none")] key: Option<String>,
+    at_ms: u64, result: &'a T,
+}
+
+pub struct Events { tx: Option<mpsc::Sender<(String, Vec<u8>)>>, prefix: String, kinds: Vec<String>, dropped: AtomicU64 }
+
+impl Events {
+    /// No bus configured: `publish` is a no-op.
+    pub fn disabled() -> Self { Self { tx: None, prefix: String::new(), kinds: Vec::new(), dropped: AtomicU64::new(0) } }
+
+    /// Publish to the NATS server at `url` from a background task, reconnecting as needed.
+    #[cfg(feature = "nats")]
+    pub fn nats(url: String, prefix: String, kinds: Vec<String>) -> Self {
+        let (tx, mut rx) = mpsc::channel::<(String, Vec<u8>)>(CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            let client = match async_nats::ConnectOptions::new().retry_on_initial_connect().connect(&url).await {
+                Ok(c) => c,
+                Err(e) => return tracing::error!("NATS {url}: {e}; result events disabled"),
+            };
+            tracing::info!("Publishing result events to NATS {url}");
+            while let Some((subject, body)) = rx.recv().await {
+                if let Err(e) = client.publish(subject, body.into()).await { tracing::warn!("NATS publish: {e}"); }
+            }
+        });
+        Self { tx: Some(tx), prefix, kinds, dropped: AtomicU64::new(0) }
+    }
+
+    /// Events queued but not yet handed to the bus client.
+    pub fn pending(&self) -> usize { self.tx.as_ref().map_or(0, |tx| tx.max_capacity() - tx.capacity()) }
+
+    /// Queue `result` as a `kind` event of the current request, if that kind is published.
+    pub fn publish<T: Serialize>(&self, kind: &str, result: &T) {
+        let Some(tx) = &self.tx else { return };
+        if !self.kinds.iter().any(|k| k == kind) { return; }
+        let tenant = crate::tenant::current();
+        let event = Event { event_id: uuid::Uuid::new_v4().to_string(), kind, tenant: &tenant, key: crate::auth::current().map(|p| p.key_id), at_ms: now_ms(), result };
+        let Ok(body) = serde_json::to_vec(&event) else { return };
+        if tx.try_send((format!("{}.{tenant}.{kind}", self.prefix), body)).is_err() {
+            let n = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if n.is_power_of_two() { tracing::warn!("Result event bus is behind; {n} events dropped so far"); }
+        }
+    }
+}
+
+/// `KINEMATICS_EVENTS`: a comma-separated subset of `KINDS`, or `all`.
+#[cfg_attr(not(feature = "nats"), allow(dead_code))]
+pub fn parse_kinds(spec: &str) -> Result<Vec<String>, String> {
+    if spec.trim() == "all" { return Ok(KINDS.iter().map(|k| k.to_string()).collect()); }
+    spec.split(',').map(str::trim).filter(|k| !k.is_empty()).map(|k| match KINDS.contains(&k) {
+        true => Ok(k.to_string()),
+        false => Err(format!("'{k}' is not one of {}", KINDS.join(", "))),
+    }).collect()
+}
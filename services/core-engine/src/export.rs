@@ -0,0 +1,166 @@
+// ── URDF and BVH export ─────────────────────────────────────
+//
+// URDF: one link per joint: `base_link` → joint_1 → link_1 → … → link_n, plus a fixed
+// `tool0` at the TCP. Joint limits are kept; effort and velocity are not modelled by
+// the registry, so placeholder values are written for tools that require them.
+//
+// BVH: a joint trajectory as an animation. Chain joints at the same point (a 3-DoF
+// shoulder, say) merge into one BVH joint with Z-X-Y Euler channels, under a fixed
+// root at the chain base, and the tip becomes the End Site. The chain frame (x out,
+// y forward, z up, as in motion capture import) is turned into BVH's Y-up, Z-forward
+// frame; a left limb is mirrored across the body's midline.
+
+use crate::chains::{ChainDef, JointType};
+use kinematics_solver::{matmul, mat_vec, transpose, Frame, Mat};
+use std::fmt::Write;
+
+const PLACEHOLDER_EFFORT: f64 = 100.0;
+const PLACEHOLDER_VELOCITY: f64 = 1.0;
+
+pub fn urdf(chain: &ChainDef) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0"?>"#);
+    let _ = writeln!(out, "<!-- {} exported from chain {} -->", escape(&chain.name).replace("--", "- -"), escape(&chain.pinned_id()));
+    let _ = writeln!(out, r#"<robot name="{}">"#, escape(&chain.id));
+    let _ = writeln!(out, r#"  <link name="base_link"/>"#);
+    let link_name = |i: usize| if i == 0 { "base_link".to_string() } else { format!("link_{i}") };
+    for (i, j) in chain.joints.iter().enumerate() {
+        let child = link_name(i + 1);
+        match j.inertial {
+            Some(m) => {
+                let [ixx, ixy, ixz, iyy, iyz, izz] = m.inertia.map(num);
+                let _ = writeln!(out, r#"  <link name="{child}">"#);
+                let _ = writeln!(out, r#"    <inertial><origin xyz="{}" rpy="0 0 0"/><mass value="{}"/><inertia ixx="{ixx}" ixy="{ixy}" ixz="{ixz}" iyy="{iyy}" iyz="{iyz}" izz="{izz}"/></inertial>"#, v3(m.com), num(m.mass));
+                let _ = writeln!(out, "  </link>");
+            }
+            None => { let _ = writeln!(out, r#"  <link name="{child}"/>"#); }
+        }
+        let kind = match (j.joint_type, j.limits) {
+            (JointType::Revolute, None) => "continuous",
+            (JointType::Revolute, Some(_)) => "revolute",
+            (JointType::Prismatic, _) => "prismatic",
+        };
+        let name = j.name.clone().unwrap_or_else(|| format!("joint_{}", i + 1));
+        let o = chain.origin(i);
+        let _ = writeln!(out, r#"  <joint name="{}" type="{kind}">"#, escape(&name));
+        let _ = writeln!(out, r#"    <parent link="{}"/><child link="{child}"/>"#, link_name(i));
+        let _ = writeln!(out, r#"    <origin xyz="{}" rpy="{}"/>"#, v3(o.xyz), v3(o.rpy));
+        let _ = writeln!(out, r#"    <axis xyz="{}"/>"#, v3(j.axis));
+        // URDF requires limits on prismatic joints; unlimited ones get ±reach
+        let limits = j.limits.or((j.joint_type == JointType::Prismatic).then(|| [-chain.reach(), chain.reach()]));
+        if let Some([lo, hi]) = limits {
+            let (lo, hi) = (num(lo), num(hi));
+            let _ = writeln!(out, r#"    <limit lower="{lo}" upper="{hi}" effort="{PLACEHOLDER_EFFORT}" velocity="{PLACEHOLDER_VELOCITY}"/>"#);
+        }
+        let _ = writeln!(out, "  </joint>");
+    }
+    // The last link's own length, then the TCP, lead to tool0
+    let last = chain.joints.last().map_or(0.0, |j| j.link_length);
+    let tcp = chain.tcp.unwrap_or_default();
+    let [x, y, z, w] = tcp.orientation;
+    let tool = Frame { r: Frame::IDENTITY.r, p: [last, 0.0, 0.0] }.mul(&Frame::from_quat_wxyz(tcp.position, [w, x, y, z]));
+    let _ = writeln!(out, r#"  <link name="tool0"/>"#);
+    let _ = writeln!(out, r#"  <joint name="tool0_fixed" type="fixed">"#);
+    let _ = writeln!(out, r#"    <parent link="{}"/><child link="tool0"/>"#, link_name(chain.dof()));
+    let _ = writeln!(out, r#"    <origin xyz="{}" rpy="{}"/>"#, v3(tool.p), v3(tool.rpy()));
+    let _ = writeln!(out, "  </joint>");
+    out.push_str("</robot>\n");
+    out
+}
+
+/// BVH names (after `Right`/`Left`) of the human presets' segments, by their first joint.
+const HUMAN_SEGMENTS: &[(&str, &str)] = &[
+    ("shoulder_abduction", "Arm"), ("elbow_flexion", "ForeArm"), ("forearm_pronation", "Hand"),
+    ("hip_flexion", "UpLeg"), ("knee_flexion", "Leg"), ("ankle_dorsiflexion", "Foot"),
+];
+
+/// `frames` of joint angles on `chain` as a BVH file, `units` per metre.
+pub fn bvh(chain: &ChainDef, frames: &[&[f64]], frame_time: f64, left: bool, units: f64) -> Result<String, String> {
+    if let Some(i) = chain.joints.iter().position(|j| j.joint_type == JointType::Prismatic) {
+        return Err(format!("joint {i} of {} is prismatic; BVH animates rotations only", chain.pinned_id()));
+    }
+    let model = kinematics_solver::Model::new(chain);
+    let joints: Vec<(Frame, [f64; 3])> = model.joints().map(|(origin, axis, _)| (origin, axis)).collect();
+    // Segments: runs of joints whose origins add no offset
+    let mut segments: Vec<Vec<usize>> = Vec::new();
+    for (i, (origin, _)) in joints.iter().enumerate() {
+        match segments.last_mut() {
+            Some(segment) if origin.p.iter().all(|c| c.abs() < 1e-9) => segment.push(i),
+            _ => segments.push(vec![i]),
+        }
+    }
+    // Chain x out, y forward, z up → BVH X (left of the body), Y up, Z forward
+    let c: Mat = [[if left { 1.0 } else { -1.0 }, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+    let offset = |p: [f64; 3]| v3(mat_vec(&c, p).map(|v| v * units));
+    let side = if left { "Left" } else { "Right" };
+    let name = |i: usize| {
+        let joint = chain.joints[i].name.as_deref();
+        match HUMAN_SEGMENTS.iter().find(|(first, _)| Some(*first) == joint) {
+            Some((_, segment)) => format!("{side}{segment}"),
+            None => joint.map_or_else(|| format!("joint_{}", i + 1), |n| n.split_whitespace().collect::<Vec<_>>().join("_")),
+        }
+    };
+    let pose = model.forward(&vec![0.0; chain.dof()]);
+    let end = pose.joints.last().map_or(pose.tip, |last| last.inverse().mul(&pose.tip));
+
+    let mut out = String::from("HIERARCHY\n");
+    let _ = writeln!(out, "ROOT {}\n{{\n  OFFSET 0 0 0\n  CHANNELS 6 Xposition Yposition Zposition Zrotation Xrotation Yrotation", chain.id);
+    for (depth, segment) in segments.iter().enumerate() {
+        let pad = "  ".repeat(depth + 1);
+        let _ = writeln!(out, "{pad}JOINT {}\n{pad}{{\n{pad}  OFFSET {}\n{pad}  CHANNELS 3 Zrotation Xrotation Yrotation", name(segment[0]), offset(joints[segment[0]].0.p));
+    }
+    let pad = "  ".repeat(segments.len() + 1);
+    let _ = writeln!(out, "{pad}End Site\n{pad}{{\n{pad}  OFFSET {}\n{pad}}}", offset(end.p));
+    for depth in (0..=segments.len()).rev() { let _ = writeln!(out, "{}}}", "  ".repeat(depth)); }
+    let _ = writeln!(out, "MOTION\nFrames: {}\nFrame Time: {}", frames.len(), num(frame_time));
+    let mut previous = vec![[0.0; 3]; segments.len()];
+    for q in frames {
+        let mut row = vec!["0".to_string(); 6];
+        for (segment, previous) in segments.iter().zip(&mut previous) {
+            let r = segment.iter().fold(Frame::IDENTITY.r, |r, &i| {
+                let (origin, [x, y, z]) = joints[i];
+                let (s, w) = (q[i] / 2.0).sin_cos();
+                matmul(&matmul(&r, &origin.r), &Frame::from_quat_wxyz([0.0; 3], [w, x * s, y * s, z * s]).r)
+            });
+            // Unwrap each angle towards the frame before, so curves have no 360° jumps
+            let angles = zxy(&matmul(&matmul(&c, &r), &transpose(&c)));
+            for (a, p) in angles.iter().zip(previous.iter_mut()) {
+                *p = a + (((*p - a) / 360.0).round() * 360.0);
+                row.push(num6(*p));
+            }
+        }
+        let _ = writeln!(out, "{}", row.join(" "));
+    }
+    Ok(out)
+}
+
+/// Z-X-Y Euler angles (degrees) of `r` = Rz · Rx · Ry, as BVH applies its channels.
+fn zxy(r: &Mat) -> [f64; 3] {
+    let x = r[2][1].clamp(-1.0, 1.0).asin();
+    let (z, y) = if x.cos() > 1e-9 { ((-r[0][1]).atan2(r[1][1]), (-r[2][0]).atan2(r[2][2])) } else { (0.0, r[0][2].atan2(r[0][0])) };
+    [z, x, y].map(f64::to_degrees)
+}
+
+/// `frames` (at `times`, if given) held at every `step` seconds from the first.
+pub fn resample<'a>(frames: &'a [Vec<f64>], times: Option<&[f64]>, step: f64) -> Vec<&'a [f64]> {
+    let Some(times) = times.filter(|t| t.len() == frames.len() && !t.is_empty()) else { return frames.iter().map(Vec::as_slice).collect() };
+    let slots = ((times[times.len() - 1] - times[0]) / step).round() as usize + 1;
+    let mut j = 0;
+    (0..slots).map(|k| {
+        let t = times[0] + k as f64 * step;
+        while j + 1 < times.len() && times[j + 1] <= t + step * 1e-6 { j += 1; }
+        frames[j].as_slice()
+    }).collect()
+}
+
+fn v3(v: [f64; 3]) -> String { v.map(num).join(" ") }
+
+/// Round away float noise (1e-12) so values print as written, without `-0`.
+fn num(x: f64) -> String { format!("{}", (x * 1e12).round() / 1e12 + 0.0) }
+
+/// Six decimals, enough for degrees and centimetres.
+fn num6(x: f64) -> String { format!("{}", (x * 1e6).round() / 1e6 + 0.0) }
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
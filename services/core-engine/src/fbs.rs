@@ -0,0 +1,90 @@
+// ── FlatBuffers ─────────────────────────────────────────────
+//
+// Zero-copy trajectory ingestion: `POST /api/v1/kinematics/fb/optimize-trajectory`
+// takes a `kinematics.v1.fb.TrajectoryRequest` (proto/trajectory.fbs) built with any
+// FlatBuffers library. Waypoints are read in place from the request body, so a
+// 100k-point path costs no per-point parsing or allocation. The reader covers just
+// this schema, in safe Rust: every offset is bounds-checked up front and a malformed
+// buffer is a 400. Responses are the JSON `TrajectoryResponse` (or MessagePack/CBOR
+// per `Accept`).
+
+use crate::error::{ApiError, ApiResult, Json};
+use crate::{AppState, TrajectoryResponse};
+use axum::{body::Bytes, extract::State};
+use std::sync::Arc;
+use std::time::Instant;
+
+const VEC3_SIZE: usize = 24;
+
+/// A verified `TrajectoryRequest`, borrowing the body.
+struct TrajectoryRequest<'a> { buf: &'a [u8], waypoints: usize, len: usize, max_velocity: f64, chain_id: Option<&'a str> }
+
+impl TrajectoryRequest<'_> {
+    fn point(&self, i: usize) -> [f64; 3] { point_at(self.buf, self.waypoints, i) }
+}
+
+fn bytes<const N: usize>(buf: &[u8], at: usize) -> Result<[u8; N], String> {
+    buf.get(at..at.checked_add(N).ok_or("offset overflows")?).map(|b| b.try_into().expect("N bytes")).ok_or_else(|| format!("offset {at} is outside the {}-byte buffer", buf.len()))
+}
+fn u16_at(buf: &[u8], at: usize) -> Result<usize, String> { bytes(buf, at).map(|b| u16::from_le_bytes(b) as usize) }
+fn u32_at(buf: &[u8], at: usize) -> Result<usize, String> { bytes(buf, at).map(|b| u32::from_le_bytes(b) as usize) }
+/// In-bounds read; callers check the range first.
+fn f64_at(buf: &[u8], at: usize) -> f64 { f64::from_le_bytes(buf[at..at + 8].try_into().expect("8 bytes")) }
+
+/// Where a `uoffset` field at `at` points.
+fn follow(buf: &[u8], at: usize) -> Result<usize, String> { at.checked_add(u32_at(buf, at)?).ok_or_else(|| "offset overflows".into()) }
+
+/// Waypoint `i` of the vector at `waypoints`.
+fn point_at(buf: &[u8], waypoints: usize, i: usize) -> [f64; 3] {
+    let at = waypoints + i * VEC3_SIZE;
+    [f64_at(buf, at), f64_at(buf, at + 8), f64_at(buf, at + 16)]
+}
+
+fn parse(buf: &[u8]) -> Result<TrajectoryRequest<'_>, String> {
+    let table = u32_at(buf, 0)?;
+    let soffset = i32::from_le_bytes(bytes(buf, table)?) as i64;
+    let vtable = usize::try_from(table as i64 - soffset).map_err(|_| "vtable offset is out of range")?;
+    let vtable_len = u16_at(buf, vtable)?;
+    // Location of field `i`, or `None` when it is absent (defaulted)
+    let field = |i: usize| -> Result<Option<usize>, String> {
+        let slot = 4 + 2 * i;
+        if slot + 2 > vtable_len { return Ok(None); }
+        Ok(Some(u16_at(buf, vtable + slot)?).filter(|&o| o != 0).map(|o| table + o))
+    };
+    let vector = follow(buf, field(0)?.ok_or("waypoints is required")?)?;
+    let len = u32_at(buf, vector)?;
+    let waypoints = vector + 4;
+    if len.checked_mul(VEC3_SIZE).and_then(|n| n.checked_add(waypoints)).is_none_or(|end| end > buf.len()) {
+        return Err(format!("waypoints claims {len} points, more than the buffer holds"));
+    }
+    let max_velocity = match field(1)? { Some(at) => f64::from_le_bytes(bytes(buf, at)?), None => 0.0 };
+    let chain_id = match field(2)? {
+        Some(at) => {
+            let string = follow(buf, at)?;
+            let n = u32_at(buf, string)?;
+            let raw = buf.get(string + 4..(string + 4).saturating_add(n)).ok_or("chain_id runs past the buffer")?;
+            Some(std::str::from_utf8(raw).map_err(|_| "chain_id is not UTF-8")?)
+        }
+        None => None,
+    };
+    Ok(TrajectoryRequest { buf, waypoints, len, max_velocity, chain_id })
+}
+
+/// `optimize-trajectory` with a FlatBuffers body; see proto/trajectory.fbs.
+#[utoipa::path(post, path = "/api/v1/kinematics/fb/optimize-trajectory", request_body(content = Vec<u8>, content_type = "application/x-flatbuffers", description = "kinematics.v1.fb.TrajectoryRequest"), responses((status = 200, body = TrajectoryResponse), (status = "4XX", body = ApiError)))]
+pub async fn optimize_trajectory(State(s): State<Arc<AppState>>, body: Bytes) -> ApiResult<TrajectoryResponse> {
+    crate::in_service(&s)?;
+    let t = Instant::now();
+    let req = parse(&body).map_err(|e| ApiError::bad_request("Invalid FlatBuffers body", e))?;
+    if req.len == 0 { return Err(ApiError::invalid("waypoints", "Validation failed", "at least one waypoint is required")); }
+    crate::limits::Points::Waypoints.check(&s, "waypoints", req.len)?;
+    if !(req.max_velocity.is_finite() && req.max_velocity >= 0.0) { return Err(ApiError::invalid("max_velocity", "Validation failed", "must be a positive number, or 0 for the default")); }
+    if let Some(i) = (0..req.len).find(|&i| !req.point(i).iter().all(|v| v.is_finite())) {
+        return Err(ApiError::invalid(format!("waypoints[{i}]"), "Validation failed", "must be finite numbers"));
+    }
+    let chain = crate::lookup_chain(&s, req.chain_id.filter(|c| !c.is_empty()))?;
+    let max_vel = if req.max_velocity > 0.0 { req.max_velocity } else { 1.0 };
+    // Timed on the pool, reading the waypoints from its own handle on the body
+    let (buf, at) = (body.clone(), req.waypoints);
+    crate::time_path(&s, crate::deterministic::id("fb-trajectory", &body[..]), req.len, move |i| point_at(&buf, at, i), max_vel, chain, t).await.map(Json)
+}
@@ -0,0 +1,235 @@
+// ── GPU batch IK ────────────────────────────────────────────
+//
+// Optional compute backend (`gpu` feature, wgpu over Vulkan, Metal or DX12) for batch
+// IK: one shader invocation per target runs the same damped least squares as the CPU
+// solver, position only, in f32. Every result is then finished on the CPU in f64 (or
+// f32 for `precision: "f32"`) from where the GPU got to, which takes an iteration or
+// two, so converged solutions meet the requested tolerance exactly as CPU ones do. Targets go up in chunks and the
+// deadline is checked between them; targets left over when it passes come back
+// `timed_out` from their seed. Chains deeper than `MAX_JOINTS` stay on the CPU.
+
+use crate::chains::ChainDef;
+use kinematics_solver::{IkResult, IkSettings};
+
+/// Deepest chain the shader handles (its per-invocation arrays are this long).
+pub const MAX_JOINTS: usize = 64;
+
+pub struct Gpu {
+    #[cfg(feature = "gpu")]
+    device: wgpu::Device,
+    #[cfg(feature = "gpu")]
+    queue: wgpu::Queue,
+    #[cfg(feature = "gpu")]
+    pipeline: wgpu::ComputePipeline,
+    adapter: String,
+}
+
+impl Gpu {
+    /// The first high-performance adapter, with the IK pipeline built on it.
+    #[cfg(feature = "gpu")]
+    pub fn open() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let options = wgpu::RequestAdapterOptions { power_preference: wgpu::PowerPreference::HighPerformance, ..Default::default() };
+        let adapter = pollster::block_on(instance.request_adapter(&options)).ok_or("no GPU adapter found")?;
+        let descriptor = wgpu::DeviceDescriptor { label: Some("batch-ik"), ..Default::default() };
+        let (device, queue) = pollster::block_on(adapter.request_device(&descriptor, None)).map_err(|e| e.to_string())?;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor { label: Some("batch-ik"), source: wgpu::ShaderSource::Wgsl(SHADER.into()) });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("batch-ik"), layout: None, module: &module, entry_point: Some("main"), compilation_options: Default::default(), cache: None,
+        });
+        if let Some(e) = pollster::block_on(device.pop_error_scope()) { return Err(e.to_string()); }
+        let info = adapter.get_info();
+        Ok(Self { device, queue, pipeline, adapter: format!("{} ({:?})", info.name, info.backend) })
+    }
+
+    #[cfg(not(feature = "gpu"))]
+    pub fn open() -> Result<Self, String> { Err("this build was compiled without the `gpu` feature".into()) }
+
+    /// Adapter name and graphics API.
+    pub fn adapter(&self) -> &str { &self.adapter }
+
+    /// Solve for each of `targets` from `seed`; results in target order.
+    #[cfg(feature = "gpu")]
+    pub fn solve(&self, chain: &ChainDef, targets: &[[f64; 3]], seed: &[f64], s: &IkSettings, precision: Option<&str>, stop: impl Fn() -> bool + Sync) -> Result<Vec<IkResult>, String> {
+        use rayon::prelude::*;
+        let n = chain.dof();
+        if n > MAX_JOINTS { return Err(format!("{n} joints is more than the GPU backend's {MAX_JOINTS}")); }
+        let mut reached: Vec<(Vec<f64>, u32)> = Vec::with_capacity(targets.len());
+        let (geometry, seed32) = (geometry(chain), seed.iter().map(|&v| v as f32).collect::<Vec<_>>());
+        for chunk in targets.chunks(CHUNK) {
+            if !reached.is_empty() && stop() { break; }
+            let (angles, iterations) = self.dispatch(&geometry, &seed32, chunk, n, s)?;
+            reached.extend(angles.chunks(n.max(1)).zip(iterations).map(|(q, it)| (q.iter().map(|&v| v as f64).collect(), it)));
+        }
+        // Finish at the requested precision with the iterations left; targets the deadline cut off start from the seed
+        Ok(targets.par_iter().enumerate().map(|(k, &target)| {
+            let (q, used) = reached.get(k).cloned().unwrap_or_else(|| (seed.to_vec(), 0));
+            let rest = IkSettings { max_iterations: s.max_iterations - used.min(s.max_iterations - 1), tolerance: s.tolerance, damping: s.damping };
+            let mut r = kinematics_solver::solve_ik_at(precision, chain, target, None, q, &rest, &stop);
+            r.iterations = (r.iterations + used.saturating_sub(1)).min(s.max_iterations);
+            r
+        }).collect())
+    }
+
+    #[cfg(not(feature = "gpu"))]
+    pub fn solve(&self, _chain: &ChainDef, _targets: &[[f64; 3]], _seed: &[f64], _s: &IkSettings, _precision: Option<&str>, _stop: impl Fn() -> bool + Sync) -> Result<Vec<IkResult>, String> {
+        Err(format!("GPU support not compiled in ({})", self.adapter))
+    }
+
+    /// One chunk through the shader: the angles reached (`n` per target) and iterations used.
+    #[cfg(feature = "gpu")]
+    fn dispatch(&self, geometry: &[[f32; 4]], seed: &[f32], targets: &[[f64; 3]], n: usize, s: &IkSettings) -> Result<(Vec<f32>, Vec<u32>), String> {
+        use wgpu::util::DeviceExt;
+        let d = &self.device;
+        let init = |label, contents: &[u8], usage| d.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some(label), contents, usage });
+        let empty = |label, size: usize, usage| d.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size: size.max(4) as u64, usage, mapped_at_creation: false });
+        let params = [targets.len() as u32, n as u32, s.max_iterations, (s.tolerance.max(F32_TOLERANCE) as f32).to_bits(), (s.damping as f32).to_bits(), 0, 0, 0];
+        let targets: Vec<[f32; 4]> = targets.iter().map(|t| [t[0] as f32, t[1] as f32, t[2] as f32, 0.0]).collect();
+        let storage = wgpu::BufferUsages::STORAGE;
+        let (out, readback) = (storage | wgpu::BufferUsages::COPY_SRC, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+        let buffers = [
+            init("params", bytemuck::cast_slice(&params), wgpu::BufferUsages::UNIFORM),
+            init("chain", bytemuck::cast_slice(geometry), storage),
+            init("seed", bytemuck::cast_slice(if seed.is_empty() { &[0.0f32] } else { seed }), storage),
+            init("targets", bytemuck::cast_slice(&targets), storage),
+            empty("angles", 4 * n * targets.len(), out),
+            empty("iterations", 4 * targets.len(), out),
+        ];
+        let layout = self.pipeline.get_bind_group_layout(0);
+        let entries: Vec<_> = buffers.iter().enumerate().map(|(i, b)| wgpu::BindGroupEntry { binding: i as u32, resource: b.as_entire_binding() }).collect();
+        let group = d.create_bind_group(&wgpu::BindGroupDescriptor { label: Some("batch-ik"), layout: &layout, entries: &entries });
+        let [angles_out, iterations_out] = [&buffers[4], &buffers[5]].map(|b| empty("readback", b.size() as usize, readback));
+        let mut encoder = d.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("batch-ik") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("batch-ik"), timestamp_writes: None });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &group, &[]);
+            pass.dispatch_workgroups((targets.len() as u32).div_ceil(WORKGROUP), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buffers[4], 0, &angles_out, 0, angles_out.size());
+        encoder.copy_buffer_to_buffer(&buffers[5], 0, &iterations_out, 0, iterations_out.size());
+        self.queue.submit([encoder.finish()]);
+        let read = |b: &wgpu::Buffer| -> Result<Vec<u8>, String> {
+            let (tx, rx) = std::sync::mpsc::channel();
+            b.slice(..).map_async(wgpu::MapMode::Read, move |r| { let _ = tx.send(r); });
+            let _ = d.poll(wgpu::Maintain::Wait);
+            rx.recv().map_err(|e| e.to_string())?.map_err(|e| e.to_string())?;
+            let bytes = b.slice(..).get_mapped_range().to_vec();
+            b.unmap();
+            Ok(bytes)
+        };
+        let (angles, iterations) = (read(&angles_out)?, read(&iterations_out)?);
+        let count = targets.len();
+        Ok((bytemuck::cast_slice::<u8, f32>(&angles)[..n * count].to_vec(), bytemuck::cast_slice::<u8, u32>(&iterations)[..count].to_vec()))
+    }
+}
+
+/// Targets per dispatch, between which the deadline is checked.
+#[cfg(feature = "gpu")]
+const CHUNK: usize = 16_384;
+#[cfg(feature = "gpu")]
+const WORKGROUP: u32 = 64;
+/// Tighter than this is left to the f64 finish.
+#[cfg(feature = "gpu")]
+const F32_TOLERANCE: f64 = 1e-5;
+
+/// The chain as the shader reads it: per joint its origin's three rows `[r, p]`, the axis
+/// with `w` 0 (revolute) or 1 (prismatic), and `[lo, hi, limited, 0]`; then the tip's rows.
+#[cfg(feature = "gpu")]
+fn geometry(chain: &ChainDef) -> Vec<[f32; 4]> {
+    let rows = |f: kinematics_solver::Frame| (0..3).map(move |i| [f.r[i][0] as f32, f.r[i][1] as f32, f.r[i][2] as f32, f.p[i] as f32]);
+    let model = kinematics_solver::Model::new(chain);
+    let mut out = Vec::new();
+    for ((origin, axis, joint_type), j) in model.joints().zip(&chain.joints) {
+        out.extend(rows(origin));
+        out.push([axis[0] as f32, axis[1] as f32, axis[2] as f32, f32::from(u8::from(joint_type == crate::chains::JointType::Prismatic))]);
+        out.push(j.limits.map_or([0.0; 4], |[lo, hi]| [lo as f32, hi as f32, 1.0, 0.0]));
+    }
+    out.extend(rows(model.tip()));
+    out
+}
+
+#[cfg(feature = "gpu")]
+const SHADER: &str = r#"
+const MAX_JOINTS: u32 = 64u;
+struct Params { count: u32, joints: u32, max_iterations: u32, tolerance: f32, damping: f32 }
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> chain: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read> seed: array<f32>;
+@group(0) @binding(3) var<storage, read> targets: array<vec4<f32>>;
+@group(0) @binding(4) var<storage, read_write> angles: array<f32>;
+@group(0) @binding(5) var<storage, read_write> iterations: array<u32>;
+
+var<private> q: array<f32, MAX_JOINTS>;
+var<private> origins: array<vec3<f32>, MAX_JOINTS>;
+var<private> axes: array<vec3<f32>, MAX_JOINTS>;
+
+fn rows(b: u32) -> mat3x3<f32> { return transpose(mat3x3<f32>(chain[b].xyz, chain[b + 1u].xyz, chain[b + 2u].xyz)); }
+fn offset(b: u32) -> vec3<f32> { return vec3<f32>(chain[b].w, chain[b + 1u].w, chain[b + 2u].w); }
+
+fn rotation(a: vec3<f32>, t: f32) -> mat3x3<f32> {
+    let c = cos(t); let s = sin(t); let k = 1.0 - c;
+    return mat3x3<f32>(
+        vec3<f32>(c + a.x * a.x * k, a.y * a.x * k + a.z * s, a.z * a.x * k - a.y * s),
+        vec3<f32>(a.x * a.y * k - a.z * s, c + a.y * a.y * k, a.z * a.y * k + a.x * s),
+        vec3<f32>(a.x * a.z * k + a.y * s, a.y * a.z * k - a.x * s, c + a.z * a.z * k));
+}
+
+fn clamp_joint(i: u32) {
+    let l = chain[i * 5u + 4u];
+    if (l.z > 0.5) { q[i] = clamp(q[i], l.x, l.y); }
+}
+
+// Tip position; joint positions and world axes into `origins` and `axes`
+fn forward() -> vec3<f32> {
+    var r = mat3x3<f32>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0));
+    var p = vec3<f32>(0.0);
+    for (var i = 0u; i < params.joints; i++) {
+        let b = i * 5u;
+        p += r * offset(b);
+        r = r * rows(b);
+        let a = chain[b + 3u];
+        origins[i] = p; axes[i] = r * a.xyz;
+        if (a.w < 0.5) { r = r * rotation(a.xyz, q[i]); } else { p += axes[i] * q[i]; }
+    }
+    return p + r * offset(params.joints * 5u);
+}
+
+fn column(i: u32, tip: vec3<f32>) -> vec3<f32> {
+    if (chain[i * 5u + 3u].w < 0.5) { return cross(axes[i], tip - origins[i]); }
+    return axes[i];
+}
+
+fn inverse3(m: mat3x3<f32>) -> mat3x3<f32> {
+    let c = transpose(mat3x3<f32>(cross(m[1], m[2]), cross(m[2], m[0]), cross(m[0], m[1])));
+    return c * (1.0 / dot(m[0], cross(m[1], m[2])));
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let k = id.x;
+    if (k >= params.count) { return; }
+    let n = params.joints;
+    for (var i = 0u; i < n; i++) { q[i] = seed[i]; clamp_joint(i); }
+    let goal = targets[k].xyz;
+    let l2 = params.damping * params.damping;
+    var used = 0u;
+    loop {
+        used += 1u;
+        let tip = forward();
+        let e = goal - tip;
+        if (length(e) < params.tolerance || used >= params.max_iterations) { break; }
+        // dq = Jᵀ (J Jᵀ + λ² I)⁻¹ e
+        var a = mat3x3<f32>(vec3<f32>(l2, 0.0, 0.0), vec3<f32>(0.0, l2, 0.0), vec3<f32>(0.0, 0.0, l2));
+        for (var i = 0u; i < n; i++) {
+            let j = column(i, tip);
+            a += mat3x3<f32>(j * j.x, j * j.y, j * j.z);
+        }
+        let y = inverse3(a) * e;
+        for (var i = 0u; i < n; i++) { q[i] += dot(column(i, tip), y); clamp_joint(i); }
+    }
+    for (var i = 0u; i < n; i++) { angles[k * n + i] = q[i]; }
+    iterations[k] = used;
+}
+"#;
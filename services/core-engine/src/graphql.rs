@@ -0,0 +1,166 @@
+// ── GraphQL ─────────────────────────────────────────────────
+//
+// Read-only GraphQL over chains, stats, intent history and stored IK solutions at
+// `POST /api/v1/graphql`, so dashboards fetch exactly the fields they need in one
+// request. Resolvers read the same tenant-scoped stores as the REST handlers, and
+// nested model parts (origins, inertials, solver defaults) come through as `JSON`.
+// `GET /api/v1/graphql` returns the schema as SDL.
+
+use crate::error::Json;
+use crate::history::IntentRecord;
+use crate::{chains, tenant, AppState};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use axum::extract::State;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+
+type KinematicsSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 1000;
+/// Default and maximum page size for lists.
+const MAX_ITEMS: usize = 1000;
+
+fn schema() -> &'static KinematicsSchema {
+    static SCHEMA: OnceLock<KinematicsSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::build(Query, EmptyMutation, EmptySubscription).limit_depth(MAX_DEPTH).limit_complexity(MAX_COMPLEXITY).finish())
+}
+
+pub async fn execute(State(s): State<Arc<AppState>>, Json(req): Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    Json(schema().execute(req.data(s)).await)
+}
+
+pub async fn sdl() -> String { schema().sdl() }
+
+fn state<'a>(ctx: &Context<'a>) -> &'a Arc<AppState> { ctx.data_unchecked::<Arc<AppState>>() }
+
+fn page(limit: Option<usize>) -> usize { limit.unwrap_or(MAX_ITEMS).min(MAX_ITEMS) }
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Latest version of every chain, filtered as `GET /chains` does.
+    async fn chains(&self, ctx: &Context<'_>, dof: Option<u32>, joint_type: Option<String>, q: Option<String>, limit: Option<usize>, #[graphql(default)] offset: usize) -> Vec<Chain> {
+        let needle = q.map(|q| q.to_lowercase());
+        let chains = state(ctx).chains.current();
+        let registry = chains.lock().unwrap();
+        registry.list().into_iter()
+            .filter(|c| dof.is_none_or(|d| c.dof() == d as usize))
+            .filter(|c| joint_type.as_ref().is_none_or(|t| &c.joint_type() == t))
+            .filter(|c| needle.as_ref().is_none_or(|n| c.id.to_lowercase().contains(n) || c.name.to_lowercase().contains(n)))
+            .skip(offset).take(page(limit))
+            .map(|c| Chain::new(&registry, c)).collect()
+    }
+
+    /// A chain by `id` (latest version) or `id@vN`.
+    async fn chain(&self, ctx: &Context<'_>, id: String) -> Option<Chain> {
+        let chains = state(ctx).chains.current();
+        let registry = chains.lock().unwrap();
+        registry.get(&id).map(|c| Chain::new(&registry, c))
+    }
+
+    /// Totals as `GET /stats` reports them to the caller.
+    async fn stats(&self, ctx: &Context<'_>) -> Stats {
+        let Json(st) = crate::stats(State(state(ctx).clone())).await;
+        Stats {
+            total_ik_solves: st.total_ik_solves, total_fk_solves: st.total_fk_solves, total_compressions: st.total_compressions, total_trajectories: st.total_trajectories,
+            keys: async_graphql::Json(st.keys), tenants: async_graphql::Json(st.tenants),
+        }
+    }
+
+    /// Recorded intents, oldest first; `last` keeps only the newest ones.
+    async fn intents(&self, ctx: &Context<'_>, session: Option<String>, from: Option<u64>, to: Option<u64>, last: Option<usize>) -> Vec<IntentRecord> {
+        let tenant = tenant::tag();
+        let history = state(ctx).intents.lock().unwrap();
+        let matches = history.query(tenant.as_deref(), session.as_deref(), from, to);
+        matches[matches.len().saturating_sub(page(last))..].iter().map(|r| (*r).clone()).collect()
+    }
+
+    async fn intent(&self, ctx: &Context<'_>, id: String) -> Option<IntentRecord> {
+        state(ctx).intents.lock().unwrap().get(tenant::tag().as_deref(), &id).cloned()
+    }
+
+    /// Stored IK solutions (solve history), newest first.
+    async fn solutions(&self, ctx: &Context<'_>, last: Option<usize>) -> Vec<Solution> {
+        let tenant = tenant::tag();
+        state(ctx).solutions.lock().unwrap().recent(tenant.as_deref(), page(last)).into_iter().map(|(id, s)| Solution::new(id, s)).collect()
+    }
+
+    async fn solution(&self, ctx: &Context<'_>, id: String) -> Option<Solution> {
+        state(ctx).solutions.lock().unwrap().get(tenant::tag().as_deref(), &id).map(|s| Solution::new(&id, s))
+    }
+}
+
+pub struct Chain { def: chains::ChainDef, versions: Vec<u32> }
+
+impl Chain {
+    fn new(registry: &chains::ChainRegistry, def: &chains::ChainDef) -> Self {
+        Self { versions: registry.versions(&def.id), def: def.clone() }
+    }
+}
+
+#[Object]
+impl Chain {
+    async fn id(&self) -> &str { &self.def.id }
+    async fn version(&self) -> u32 { self.def.version }
+    /// `id@vN`, for reproducing a solve against exactly this model.
+    async fn pinned_id(&self) -> String { self.def.pinned_id() }
+    /// Every stored version number, oldest first.
+    async fn versions(&self) -> &[u32] { &self.versions }
+    async fn name(&self) -> &str { &self.def.name }
+    async fn description(&self) -> &str { &self.def.description }
+    async fn dof(&self) -> usize { self.def.dof() }
+    async fn joint_type(&self) -> String { self.def.joint_type() }
+    async fn total_mass(&self) -> Option<f64> { self.def.total_mass() }
+    async fn reach(&self) -> f64 { self.def.reach() }
+    async fn joints(&self) -> Vec<Joint> { self.def.joints.iter().cloned().map(Joint).collect() }
+    async fn configurations(&self) -> async_graphql::Json<&BTreeMap<String, Vec<f64>>> { async_graphql::Json(&self.def.configurations) }
+    async fn tcp(&self) -> Option<async_graphql::Json<chains::Tcp>> { self.def.tcp.map(async_graphql::Json) }
+    async fn solver(&self) -> Option<async_graphql::Json<&chains::SolverDefaults>> { self.def.solver.as_ref().map(async_graphql::Json) }
+    /// The full model, as `GET /chains/{id}` stores it.
+    async fn definition(&self) -> async_graphql::Json<&chains::ChainDef> { async_graphql::Json(&self.def) }
+}
+
+pub struct Joint(chains::JointDef);
+
+#[Object]
+impl Joint {
+    async fn name(&self) -> Option<&str> { self.0.name.as_deref() }
+    /// `revolute` or `prismatic`.
+    async fn joint_type(&self) -> &str { match self.0.joint_type { chains::JointType::Revolute => "revolute", chains::JointType::Prismatic => "prismatic" } }
+    async fn axis(&self) -> [f64; 3] { self.0.axis }
+    async fn limits(&self) -> Option<[f64; 2]> { self.0.limits }
+    async fn link_length(&self) -> f64 { self.0.link_length }
+    async fn origin(&self) -> Option<async_graphql::Json<chains::Origin>> { self.0.origin.map(async_graphql::Json) }
+    async fn inertial(&self) -> Option<async_graphql::Json<chains::Inertial>> { self.0.inertial.map(async_graphql::Json) }
+}
+
+#[derive(SimpleObject)]
+pub struct Stats {
+    total_ik_solves: u64, total_fk_solves: u64, total_compressions: u64, total_trajectories: u64,
+    /// Usage per key: everyone's for admins, the caller's own otherwise.
+    keys: async_graphql::Json<BTreeMap<String, crate::auth::Usage>>,
+    /// Usage per tenant, for admins.
+    tenants: async_graphql::Json<BTreeMap<String, crate::auth::Usage>>,
+}
+
+pub struct Solution { id: String, solved_at_ms: u64, body: serde_json::Value }
+
+impl Solution {
+    fn new(id: &str, stored: &crate::solutions::Stored) -> Self {
+        Self { id: id.to_string(), solved_at_ms: stored.at_ms, body: serde_json::from_str(&stored.body).unwrap_or_default() }
+    }
+}
+
+#[Object]
+impl Solution {
+    async fn solution_id(&self) -> &str { &self.id }
+    async fn solved_at_ms(&self) -> u64 { self.solved_at_ms }
+    async fn converged(&self) -> Option<bool> { self.body["converged"].as_bool() }
+    async fn joint_angles(&self) -> Vec<f64> { self.body["joint_angles"].as_array().map_or_else(Vec::new, |a| a.iter().filter_map(|v| v.as_f64()).collect()) }
+    /// Pinned chain of a chain solve.
+    async fn chain(&self) -> Option<&str> { self.body["chain"].as_str() }
+    /// The solve's full response body.
+    async fn response(&self) -> async_graphql::Json<&serde_json::Value> { async_graphql::Json(&self.body) }
+}
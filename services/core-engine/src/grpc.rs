@@ -0,0 +1,77 @@
+// ── gRPC service ────────────────────────────────────────────
+//
+// `kinematics.v1.Kinematics` (proto/kinematics.proto) over tonic, alongside the REST
+// API. Each RPC converts its message to the JSON request type and runs the same
+// handler, so results, defaults and validation are identical on both transports.
+
+use crate::proto::{self, pb};
+use crate::error::{ApiError, ErrorCode, Json};
+use crate::auth::{self, Principal};
+use crate::ratelimit::{self, Class, Verdict};
+use crate::AppState;
+use axum::extract::State;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use pb::kinematics_server::{Kinematics, KinematicsServer};
+
+pub struct Service { state: Arc<AppState> }
+
+pub fn server(state: Arc<AppState>) -> KinematicsServer<Service> { KinematicsServer::new(Service { state }) }
+
+impl Service {
+    /// Authenticate the caller from metadata, check the solve scope and charge its
+    /// solve bucket, as the REST layers do.
+    async fn admit<T>(&self, req: &Request<T>) -> Result<Option<Principal>, ApiError> {
+        let headers = req.metadata().clone().into_headers();
+        let principal = auth::authenticate(&self.state, &headers).await?;
+        if principal.as_ref().is_some_and(|p| !p.allows(auth::SCOPE_SOLVE)) { return Err(auth::missing_scope(auth::SCOPE_SOLVE)); }
+        let who = ratelimit::client(principal.as_ref(), &headers, req.remote_addr(), self.state.trust_forwarded);
+        match self.state.limiter.lock().unwrap().take(&who, Class::Solve) {
+            Some(Verdict::Limited { limit, retry_after }) => Err(ratelimit::exceeded(limit, retry_after)),
+            _ => Ok(principal),
+        }
+    }
+}
+
+fn status(e: ApiError) -> Status {
+    let message = e.summary();
+    match e.code {
+        ErrorCode::BadRequest | ErrorCode::Invalid | ErrorCode::UnsupportedMediaType => Status::invalid_argument(message),
+        ErrorCode::Unauthorized => Status::unauthenticated(message),
+        ErrorCode::NotFound => Status::not_found(message),
+        ErrorCode::Conflict => Status::already_exists(message),
+        ErrorCode::Forbidden => Status::permission_denied(message),
+        ErrorCode::PayloadTooLarge | ErrorCode::TooManyRequests => Status::resource_exhausted(message),
+        ErrorCode::Unavailable => Status::unavailable(message),
+        ErrorCode::Timeout => Status::deadline_exceeded(message),
+        ErrorCode::Internal => Status::internal(message),
+    }
+}
+
+#[tonic::async_trait]
+impl Kinematics for Service {
+    async fn solve_ik(&self, req: Request<pb::IkRequest>) -> Result<Response<pb::IkResponse>, Status> {
+        let principal = self.admit(&req).await.map_err(status)?;
+        let Json(out) = auth::scope(principal, crate::solve_ik(State(self.state.clone()), Json(proto::ik_request(req.into_inner())))).await.map_err(status)?;
+        Ok(Response::new(proto::ik_response(out)))
+    }
+
+    async fn solve_fk(&self, req: Request<pb::FkRequest>) -> Result<Response<pb::FkResponse>, Status> {
+        let principal = self.admit(&req).await.map_err(status)?;
+        let Json(out) = auth::scope(principal, crate::solve_fk(State(self.state.clone()), Json(proto::fk_request(req.into_inner())))).await.map_err(status)?;
+        Ok(Response::new(proto::fk_response(out)))
+    }
+
+    async fn analyze_intent(&self, req: Request<pb::IntentRequest>) -> Result<Response<pb::IntentResponse>, Status> {
+        let principal = self.admit(&req).await.map_err(status)?;
+        let Json(out) = auth::scope(principal, crate::compress_intent(State(self.state.clone()), Json(proto::intent_request(req.into_inner())))).await.map_err(status)?;
+        Ok(Response::new(proto::intent_response(out)))
+    }
+
+    async fn optimize_trajectory(&self, req: Request<pb::TrajectoryRequest>) -> Result<Response<pb::TrajectoryResponse>, Status> {
+        let principal = self.admit(&req).await.map_err(status)?;
+        let Json(out) = auth::scope(principal, crate::optimize_trajectory(State(self.state.clone()), Json(proto::trajectory_request(req.into_inner())))).await.map_err(status)?;
+        Ok(Response::new(proto::trajectory_response(out)))
+    }
+}
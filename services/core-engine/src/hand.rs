@@ -0,0 +1,101 @@
+// ── Hand models ─────────────────────────────────────────────
+//
+// A hand is a kinematic tree: a palm frame with one serial finger chain per digit.
+// Each finger is solved as its own chain (the registry's serial model), placed at
+// `base` in the palm frame, so per-finger IK targets combine into a grasp posture.
+// Custom hands are kept in memory only.
+
+use crate::chains::{ChainDef, JointType, Origin};
+use kinematics_solver::{joint, Frame};
+
+pub use kinematics_types::{FingerAngles, FingerDef, HandDef};
+
+const MAX_FINGERS: usize = 10;
+
+/// Finger `f` of `hand` as a serial chain rooted at the palm.
+pub fn finger_chain(hand: &HandDef, f: &FingerDef) -> ChainDef {
+    let mut joints = f.joints.clone();
+    if let Some(j) = joints.first_mut() {
+        let first = j.origin.map_or(Frame::IDENTITY, |o| Frame::from_xyz_rpy(o.xyz, o.rpy));
+        let base = Frame::from_xyz_rpy(f.base.xyz, f.base.rpy).mul(&first);
+        j.origin = Some(Origin { xyz: base.p, rpy: base.rpy() });
+    }
+    ChainDef { tcp: f.tcp, ..ChainDef::single(&format!("{}.{}", hand.id, f.name), joints) }
+}
+
+pub fn validate(hand: &HandDef) -> Result<(), String> {
+    if hand.fingers.is_empty() || hand.fingers.len() > MAX_FINGERS { return Err(format!("a hand needs 1 to {MAX_FINGERS} fingers")); }
+    for (i, f) in hand.fingers.iter().enumerate() {
+        if f.name.is_empty() || hand.fingers[..i].iter().any(|g| g.name == f.name) { return Err(format!("fingers[{i}]: names must be non-empty and unique")); }
+        if !f.base.xyz.iter().chain(&f.base.rpy).all(|v| v.is_finite()) { return Err(format!("fingers[{i}].base must be finite")); }
+        finger_chain(hand, f).validate().map_err(|e| format!("finger '{}': {e}", f.name))?;
+    }
+    Ok(())
+}
+
+pub struct HandRegistry { hands: Vec<HandDef>, builtin: usize, modified_ms: u64 }
+
+impl HandRegistry {
+    pub fn new() -> Self {
+        let hands = vec![human_hand()];
+        Self { builtin: hands.len(), hands, modified_ms: crate::history::now_ms() }
+    }
+
+    pub fn list(&self) -> &[HandDef] { &self.hands }
+
+    pub fn get(&self, id: &str) -> Option<&HandDef> { self.hands.iter().find(|h| h.id == id) }
+
+    /// When a hand was last registered (or the registry created).
+    pub fn modified_ms(&self) -> u64 { self.modified_ms }
+
+    pub fn is_preset(&self, id: &str) -> bool { self.hands[..self.builtin].iter().any(|h| h.id == id) }
+
+    pub fn register(&mut self, hand: HandDef) -> Result<&HandDef, String> {
+        if hand.id.is_empty() || !hand.id.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)) {
+            return Err(format!("hand id '{}' must be non-empty and use only letters, digits, '_', '-' or '.'", hand.id));
+        }
+        validate(&hand)?;
+        self.hands.push(hand);
+        self.modified_ms = crate::history::now_ms();
+        Ok(self.hands.last().unwrap())
+    }
+}
+
+/// 20-DoF anthropomorphic hand, adult proportions. Palm frame: x towards the fingers,
+/// y towards the thumb, z out of the back of the hand; flexion (about y) curls a
+/// finger towards the palm (-z), abduction is about z.
+fn human_hand() -> HandDef {
+    use JointType::Revolute as R;
+    let (y, z) = ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]);
+    let finger = |name: &str, xyz: [f64; 3], [proximal, middle, distal]: [f64; 3]| FingerDef {
+        name: name.into(), base: Origin { xyz, rpy: [0.0; 3] }, tcp: None,
+        joints: vec![
+            joint(R, z, [-0.35, 0.35], 0.0, &format!("{name}_mcp_abduction")),
+            joint(R, y, [-0.3, 1.57], proximal, &format!("{name}_mcp_flexion")),
+            joint(R, y, [0.0, 1.9], middle, &format!("{name}_pip")),
+            joint(R, y, [0.0, 1.4], distal, &format!("{name}_dip")),
+        ],
+    };
+    let thumb = FingerDef {
+        name: "thumb".into(),
+        // Rotated out of the palm plane so thumb flexion opposes the fingers
+        base: Origin { xyz: [0.025, 0.03, -0.01], rpy: [-1.0, 0.0, 0.9] }, tcp: None,
+        joints: vec![
+            joint(R, z, [-0.2, 1.0], 0.0, "thumb_cmc_abduction"),
+            joint(R, y, [-0.5, 0.8], 0.045, "thumb_cmc_flexion"),
+            joint(R, y, [0.0, 1.0], 0.035, "thumb_mcp"),
+            joint(R, y, [-0.3, 1.4], 0.03, "thumb_ip"),
+        ],
+    };
+    HandDef {
+        id: "human_hand".into(), name: "Human Hand".into(),
+        description: "20-DOF anthropomorphic hand: thumb(4) + four fingers(4 each)".into(),
+        fingers: vec![
+            thumb,
+            finger("index", [0.09, 0.025, 0.0], [0.045, 0.025, 0.02]),
+            finger("middle", [0.095, 0.0, 0.0], [0.05, 0.03, 0.022]),
+            finger("ring", [0.09, -0.02, 0.0], [0.045, 0.028, 0.02]),
+            finger("little", [0.08, -0.04, 0.0], [0.035, 0.02, 0.018]),
+        ],
+    }
+}
@@ -0,0 +1,98 @@
+// ── Health probes ───────────────────────────────────────────
+//
+// `/healthz` answers whenever the process serves HTTP (liveness). `/readyz` answers
+// 200 only once the engine can do useful work (readiness): the default tenant's
+// chain registry has loaded, every configured store directory is writable and the
+// job queue has room, and the latest self-test (see `selftest.rs`) passed; otherwise
+// 503 with the failing checks. Point the liveness
+// probe at the first and the readiness probe at the second, so a slow registry load
+// neither gets the pod restarted nor routed to. `/health` stays as an alias. Both
+// report the build's and deployment's capabilities (commit, compiled features, store
+// backends, solvers, registry size) so orchestration and clients can feature-detect.
+
+use crate::{error::Json, tenant, AppState};
+use axum::{extract::State, http::StatusCode};
+use kinematics_types::{Capabilities, Health, Readiness, ReadinessCheck, SEED_POLICIES, SOLVERS};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Readiness state that is not derived from the rest of `AppState`.
+pub struct Probes { chains_loaded: AtomicBool, stores: Vec<(&'static str, Option<PathBuf>)>, selftest: Mutex<Result<(), String>> }
+
+impl Probes {
+    /// `stores`: each store by name with its file, if configured; those directories must stay writable.
+    pub fn new(stores: Vec<(&'static str, Option<PathBuf>)>) -> Self { Self { chains_loaded: AtomicBool::new(false), stores, selftest: Mutex::new(Ok(())) } }
+
+    /// Outcome of the latest self-test, or why none has finished yet.
+    pub fn set_selftest(&self, outcome: Result<(), String>) { *self.selftest.lock().unwrap() = outcome; }
+}
+
+/// Optional features this binary was built with.
+const FEATURES: &[(&str, bool)] = &[
+    ("grpc", cfg!(feature = "grpc")), ("protobuf", cfg!(feature = "protobuf")), ("graphql", cfg!(feature = "graphql")),
+    ("mqtt", cfg!(feature = "mqtt")), ("nats", cfg!(feature = "nats")), ("ros2", cfg!(feature = "ros2")),
+    ("onnx", cfg!(feature = "onnx")), ("otel", cfg!(feature = "otel")), ("zstd", cfg!(feature = "zstd")), ("alice-core", cfg!(feature = "alice-core")),
+];
+
+fn capabilities(s: &AppState) -> Capabilities {
+    let chains = s.probes.chains_loaded.load(Ordering::Acquire).then(|| s.chains.get(tenant::DEFAULT).lock().unwrap().list().len());
+    Capabilities {
+        git_sha: option_env!("KINEMATICS_GIT_SHA").unwrap_or("unknown").into(),
+        features: FEATURES.iter().filter(|(_, on)| *on).map(|(f, _)| f.to_string()).collect(),
+        storage: s.probes.stores.iter().map(|(name, file)| (name.to_string(), if file.is_some() { "file" } else { "memory" }.into())).collect(),
+        intent_model: s.intent_model.is_some(),
+        solvers: SOLVERS.iter().map(|s| s.to_string()).collect(), seed_policies: SEED_POLICIES.iter().map(|p| p.to_string()).collect(),
+        chains,
+    }
+}
+
+/// Load the default tenant's chain registry off the async runtime, then mark it ready.
+pub fn preload(s: Arc<AppState>) {
+    tokio::task::spawn_blocking(move || {
+        let custom = s.chains.get(tenant::DEFAULT).lock().unwrap().custom().len();
+        s.probes.chains_loaded.store(true, Ordering::Release);
+        tracing::info!("Chain registry loaded ({custom} stored chain versions)");
+    });
+}
+
+/// The directory holding `file` exists and is not read-only.
+fn writable_dir(file: &Path) -> Result<(), String> {
+    let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    match std::fs::metadata(dir) {
+        Ok(m) if !m.is_dir() => Err(format!("{} is not a directory", dir.display())),
+        Ok(m) if m.permissions().readonly() => Err(format!("{} is read-only", dir.display())),
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("{}: {e}", dir.display())),
+    }
+}
+
+fn check(outcome: Result<(), String>) -> ReadinessCheck { ReadinessCheck { ok: outcome.is_ok(), detail: outcome.err() } }
+
+#[utoipa::path(get, path = "/healthz", operation_id = "healthz", responses((status = 200, body = Health)))]
+pub async fn liveness(State(s): State<Arc<AppState>>) -> Json<Health> {
+    let total_solves = { let st = s.stats.lock().unwrap(); st.total_ik_solves + st.total_fk_solves };
+    Json(Health {
+        status: "ok".into(), version: env!("CARGO_PKG_VERSION").into(),
+        uptime_secs: s.start_time.elapsed().as_secs(), total_solves,
+        capabilities: capabilities(&s),
+    })
+}
+
+/// Alias of `/healthz`, kept for existing probes and clients.
+#[utoipa::path(get, path = "/health", operation_id = "health", responses((status = 200, body = Health)))]
+pub async fn health(s: State<Arc<AppState>>) -> Json<Health> { liveness(s).await }
+
+#[utoipa::path(get, path = "/readyz", operation_id = "readyz", responses((status = 200, body = Readiness), (status = 503, body = Readiness)))]
+pub async fn readiness(State(s): State<Arc<AppState>>) -> (StatusCode, Json<Readiness>) {
+    let loaded = s.probes.chains_loaded.load(Ordering::Acquire);
+    let mut checks = BTreeMap::new();
+    checks.insert("chains".to_string(), check(if loaded { Ok(()) } else { Err("chain registry still loading".into()) }));
+    checks.insert("storage".to_string(), check(s.probes.stores.iter().filter_map(|(_, f)| f.as_deref()).try_for_each(writable_dir)));
+    checks.insert("selftest".to_string(), check(s.probes.selftest.lock().unwrap().clone()));
+    checks.insert("jobs".to_string(), check(if s.jobs.lock().unwrap().has_room() { Ok(()) } else { Err(format!("{} jobs retained", crate::MAX_JOBS)) }));
+    let ready = checks.values().all(|c| c.ok);
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(Readiness { status: if ready { "ready" } else { "not_ready" }.into(), checks }))
+}
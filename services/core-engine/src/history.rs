@@ -0,0 +1,65 @@
+// ── Intent history ──────────────────────────────────────────
+//
+// Bounded in-memory ring of intent results, optionally mirrored to an append-only
+// JSON-lines file that is replayed at boot so history survives restarts.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+pub use kinematics_types::IntentRecord;
+
+pub struct IntentHistory {
+    records: VecDeque<IntentRecord>,
+    capacity: usize,
+    file: Option<PathBuf>,
+}
+
+impl IntentHistory {
+    /// Open the history, replaying `file` if it exists. Only the newest `capacity`
+    /// records are kept in memory.
+    pub fn open(file: Option<PathBuf>, capacity: usize) -> Self {
+        let mut records = VecDeque::new();
+        if let Some(f) = file.as_ref().and_then(|p| std::fs::File::open(p).ok()) {
+            for line in std::io::BufReader::new(f).lines().map_while(Result::ok) {
+                match serde_json::from_str::<IntentRecord>(&line) {
+                    Ok(r) => { records.push_back(r); if records.len() > capacity { records.pop_front(); } }
+                    Err(e) => tracing::warn!("skipping malformed intent history line: {e}"),
+                }
+            }
+        }
+        Self { records, capacity, file }
+    }
+
+    /// Records held in memory.
+    pub fn len(&self) -> usize { self.records.len() }
+
+    pub fn push(&mut self, record: IntentRecord) {
+        if let Some(path) = &self.file {
+            let line = serde_json::to_string(&record).unwrap_or_default();
+            let written = std::fs::OpenOptions::new().create(true).append(true).open(path)
+                .and_then(|mut f| writeln!(f, "{line}"));
+            if let Err(e) = written { tracing::warn!("failed to append intent history to {}: {e}", path.display()); }
+        }
+        self.records.push_back(record);
+        if self.records.len() > self.capacity { self.records.pop_front(); }
+    }
+
+    /// `tenant`'s records matching `session` within `[from_ms, to_ms]`, oldest first.
+    pub fn query(&self, tenant: Option<&str>, session: Option<&str>, from_ms: Option<u64>, to_ms: Option<u64>) -> Vec<&IntentRecord> {
+        self.records.iter()
+            .filter(|r| r.tenant.as_deref() == tenant)
+            .filter(|r| session.is_none_or(|s| r.session_id.as_deref() == Some(s)))
+            .filter(|r| from_ms.is_none_or(|f| r.recorded_at_ms >= f))
+            .filter(|r| to_ms.is_none_or(|t| r.recorded_at_ms <= t))
+            .collect()
+    }
+
+    pub fn get(&self, tenant: Option<&str>, intent_id: &str) -> Option<&IntentRecord> {
+        self.records.iter().rev().find(|r| r.intent_id == intent_id && r.tenant.as_deref() == tenant)
+    }
+}
+
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
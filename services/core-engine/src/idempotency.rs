@@ -0,0 +1,133 @@
+// ── Idempotency keys ────────────────────────────────────────
+//
+// A POST carrying an `Idempotency-Key` header is executed once; retries with the same
+// key and the same request (method, path, query and body) get the stored response
+// back, marked `Idempotent-Replayed: true`, without running the handler again, so
+// ids stay the same and stats count the work once. Reusing a key for a different
+// request is rejected, as is a retry while the first attempt is still running.
+// Server errors (5xx) are not stored, so they can be retried for real, nor are
+// streamed responses (SSE, NDJSON). Keys are
+// scoped to the calling API key and expire after `ttl`.
+
+use crate::auth;
+use crate::error::{ApiError, ErrorCode};
+use crate::history::now_ms;
+use crate::AppState;
+use axum::{body::{to_bytes, Body, Bytes}, extract::{Request, State}, http::{header, HeaderMap, HeaderValue, Method, StatusCode}, middleware::Next, response::{IntoResponse, Response}};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const HEADER: &str = "idempotency-key";
+const MAX_KEY_LEN: usize = 255;
+
+#[derive(Clone)]
+struct Stored { status: StatusCode, headers: HeaderMap, body: Bytes }
+
+struct Entry { fingerprint: [u8; 32], at_ms: u64, response: Option<Stored> }
+
+enum Lookup { Miss, Replay(Stored), Running, Mismatch }
+
+pub struct IdempotencyCache { entries: HashMap<String, Entry>, ttl: Duration, capacity: usize }
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self { Self { entries: HashMap::new(), ttl, capacity } }
+
+    /// Keys held, expired ones included until the next purge.
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Look `key` up, claiming it for this request on a miss.
+    fn begin(&mut self, key: &str, fingerprint: [u8; 32]) -> Lookup {
+        self.purge();
+        if let Some(e) = self.entries.get(key) {
+            return match &e.response {
+                _ if e.fingerprint != fingerprint => Lookup::Mismatch,
+                Some(r) => Lookup::Replay(r.clone()),
+                None => Lookup::Running,
+            };
+        }
+        if self.entries.len() >= self.capacity {
+            // Full of live keys: drop the oldest finished one
+            let oldest = self.entries.iter().filter(|(_, e)| e.response.is_some()).min_by_key(|(_, e)| e.at_ms).map(|(k, _)| k.clone());
+            if let Some(k) = oldest { self.entries.remove(&k); }
+        }
+        self.entries.insert(key.to_string(), Entry { fingerprint, at_ms: now_ms(), response: None });
+        Lookup::Miss
+    }
+
+    fn complete(&mut self, key: &str, response: Stored) {
+        if let Some(e) = self.entries.get_mut(key) { e.at_ms = now_ms(); e.response = Some(response); }
+    }
+
+    fn release(&mut self, key: &str) {
+        if self.entries.get(key).is_some_and(|e| e.response.is_none()) { self.entries.remove(key); }
+    }
+
+    fn purge(&mut self) {
+        let cutoff = now_ms().saturating_sub(self.ttl.as_millis() as u64);
+        self.entries.retain(|_, e| e.response.is_none() || e.at_ms >= cutoff);
+    }
+}
+
+/// Releases a claimed key if the request ends without storing a response
+/// (handler error, cancelled request).
+struct Claim { state: Arc<AppState>, key: Option<String> }
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if let Some(k) = self.key.take() { self.state.idempotency.lock().unwrap().release(&k); }
+    }
+}
+
+fn fingerprint(method: &Method, uri: &str, body: &[u8]) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(method.as_str());
+    h.update(b" ");
+    h.update(uri);
+    h.update(b"\n");
+    h.update(body);
+    h.finalize().into()
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let Some(key) = req.headers().get(HEADER).filter(|_| req.method() == Method::POST) else { return next.run(req).await };
+    let key = match key.to_str().ok().filter(|k| !k.is_empty() && k.len() <= MAX_KEY_LEN) {
+        Some(k) => k.to_string(),
+        None => return ApiError::bad_request("Invalid Idempotency-Key", format!("expected 1 to {MAX_KEY_LEN} visible ASCII characters")).into_response(),
+    };
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => return ApiError::new(ErrorCode::PayloadTooLarge, "Request body too large", e.to_string()).into_response(),
+    };
+    let uri = parts.uri.path_and_query().map_or("", |p| p.as_str());
+    let scoped = format!("{}:{key}", auth::current().map_or(String::new(), |p| p.key_id));
+    let lookup = s.idempotency.lock().unwrap().begin(&scoped, fingerprint(&parts.method, uri, &bytes));
+    match lookup {
+        Lookup::Miss => {}
+        Lookup::Replay(r) => {
+            let mut res = (r.status, r.body).into_response();
+            res.headers_mut().extend(r.headers);
+            res.headers_mut().insert("idempotent-replayed", HeaderValue::from_static("true"));
+            return res;
+        }
+        Lookup::Running => return ApiError::conflict("Request in progress", format!("a request with Idempotency-Key '{key}' is still running")).into_response(),
+        Lookup::Mismatch => return ApiError::new(ErrorCode::Invalid, "Idempotency-Key reused", format!("'{key}' was used for a different request")).into_response(),
+    }
+    let mut claim = Claim { state: s.clone(), key: Some(scoped) };
+    let res = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    let streaming = res.headers().get(header::CONTENT_TYPE).is_some_and(|v| [&b"text/event-stream"[..], b"application/x-ndjson"].iter().any(|t| v.as_bytes().starts_with(t)));
+    if res.status().is_server_error() || streaming { return res; }
+    let (parts, body) = res.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => return ApiError::internal("Response buffering failed", e.to_string()).into_response(),
+    };
+    let key = claim.key.take().expect("claimed");
+    let mut headers = parts.headers.clone();
+    headers.remove(header::CONTENT_LENGTH);
+    let stored = Stored { status: parts.status, headers, body: body.clone() };
+    s.idempotency.lock().unwrap().complete(&key, stored);
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,535 @@
+// ── Robot description import ────────────────────────────────
+//
+// URDF, Gazebo SDF and MuJoCo MJCF models are flattened to the registry's serial
+// chain form: one root-to-tip path through the kinematic tree (the deepest one, or
+// the path ending at a named `tip` link/body). Fixed joints are folded into the
+// next movable joint's `origin`, the offset after the last movable joint becomes the
+// TCP, and link mass properties are merged into the movable joint that carries them.
+
+use crate::chains::{ChainDef, Inertial, JointDef, JointType, Origin, Tcp};
+use kinematics_solver::{mat_vec, matmul, rot, transpose, Frame, Mat};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+const MAX_JOINTS: usize = 256;
+const MAX_LINKS: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format { Urdf, Sdf, Mjcf }
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "urdf" => Ok(Format::Urdf),
+            "sdf" => Ok(Format::Sdf),
+            "mjcf" => Ok(Format::Mjcf),
+            other => Err(format!("'{other}' is not one of urdf, sdf, mjcf")),
+        }
+    }
+}
+
+/// Parse `xml` into a chain (version 1; `id` and `name` come from the model name).
+/// `format` is detected from the root element when not given.
+pub fn import(xml: &str, format: Option<Format>, tip: Option<&str>) -> Result<ChainDef, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("invalid XML: {e}"))?;
+    let root = doc.root_element();
+    let detected = match root.tag_name().name() {
+        "robot" => Format::Urdf,
+        "sdf" => Format::Sdf,
+        "mujoco" => Format::Mjcf,
+        other => return Err(format!("unrecognised root element <{other}>")),
+    };
+    let format = format.unwrap_or(detected);
+    if format != detected { return Err(format!("document root <{}> does not match the requested format", root.tag_name().name())); }
+    let (name, tree) = match format {
+        Format::Urdf => urdf(root)?,
+        Format::Sdf => sdf(root)?,
+        Format::Mjcf => mjcf(root)?,
+    };
+    build(&name, &tree, None, tip).map(|(chain, _)| chain)
+}
+
+// ── Common tree form ────────────────────────────────────────
+
+/// One link/body with its world pose at the zero configuration.
+struct Link {
+    name: String, parent: Option<usize>, frame: Frame,
+    /// Joints between the parent and this link, in order.
+    joints: Vec<RawJoint>,
+    /// Mass properties in this link's frame, with the inertia as a full matrix.
+    inertial: Option<(f64, [f64; 3], Mat)>,
+}
+
+/// A joint with its world frame at the zero configuration; `axis` is in that frame.
+struct RawJoint { name: String, kind: Option<JointType>, axis: [f64; 3], limits: Option<[f64; 2]>, frame: Frame }
+
+/// Reduce the tree to the path from `base` (default: the root) to `tip`, returning the
+/// chain and the link indices on that path, `base` included.
+fn build(model: &str, links: &[Link], base: Option<&str>, tip: Option<&str>) -> Result<(ChainDef, Vec<usize>), String> {
+    if links.len() > MAX_LINKS { return Err(format!("{} links exceeds the {MAX_LINKS} link limit", links.len())); }
+    // Root-to-link path of every link, rejecting parent cycles
+    let path_to = |mut i: usize| {
+        let mut path = vec![i];
+        while let Some(p) = links[i].parent {
+            if path.len() > links.len() { return Err(format!("kinematic loop at link '{}'", links[p].name)); }
+            path.push(p);
+            i = p;
+        }
+        path.reverse();
+        Ok(path)
+    };
+    let paths = (0..links.len()).map(path_to).collect::<Result<Vec<_>, String>>()?;
+    let movable = |path: &[usize]| path.iter().map(|&i| links[i].joints.iter().filter(|j| j.kind.is_some()).count()).sum::<usize>();
+    let end = match tip {
+        Some(t) => links.iter().position(|l| l.name == t).ok_or_else(|| format!("no link or body named '{t}'"))?,
+        // Deepest by movable joints, then by fixed links so trailing tool frames are kept
+        None => (0..links.len()).rev().max_by_key(|&i| (movable(&paths[i]), paths[i].len())).ok_or("model has no links")?,
+    };
+    let (path, mut prev) = match base {
+        Some(b) => {
+            let at = paths[end].iter().position(|&i| links[i].name == b)
+                .ok_or_else(|| format!("link '{b}' is not an ancestor of '{}'", links[end].name))?;
+            (&paths[end][at..], links[paths[end][at]].frame)
+        }
+        None => (&paths[end][..], Frame::IDENTITY),
+    };
+
+    let mut joints: Vec<JointDef> = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut masses: Vec<Vec<(f64, [f64; 3], Mat)>> = Vec::new();
+    // The base link's own joints lie upstream of it
+    for &li in path.iter().skip(base.is_some() as usize) {
+        let link = &links[li];
+        for j in &link.joints {
+            let Some(kind) = j.kind else { continue };
+            let origin = prev.inverse().mul(&j.frame);
+            let n = j.axis.iter().map(|a| a * a).sum::<f64>().sqrt();
+            if n < 1e-9 { return Err(format!("joint '{}' has a zero axis", j.name)); }
+            joints.push(JointDef {
+                name: Some(j.name.clone()), joint_type: kind, axis: j.axis.map(|a| a / n), limits: j.limits,
+                link_length: 0.0, origin: Some(Origin { xyz: origin.p, rpy: origin.rpy() }), dh: None, inertial: None,
+            });
+            frames.push(j.frame);
+            masses.push(Vec::new());
+            prev = j.frame;
+        }
+        // Mass properties go to the closest movable joint upstream, in world coordinates
+        if let (Some(bucket), Some((m, com, inertia))) = (masses.last_mut(), link.inertial) {
+            let world_com = link.frame.mul(&Frame { r: Frame::IDENTITY.r, p: com }).p;
+            bucket.push((m, world_com, matmul(&matmul(&link.frame.r, &inertia), &transpose(&link.frame.r))));
+        }
+    }
+    if joints.is_empty() { return Err("no movable joints between the root and the tip".into()); }
+    if joints.len() > MAX_JOINTS { return Err(format!("{} movable joints exceeds the {MAX_JOINTS} joint limit", joints.len())); }
+
+    // Link lengths record each offset's length as a hint; the origins carry the geometry
+    for i in 0..joints.len() - 1 {
+        let o = joints[i + 1].origin.unwrap().xyz;
+        joints[i].link_length = o.iter().map(|v| v * v).sum::<f64>().sqrt();
+    }
+    for ((joint, bucket), frame) in joints.iter_mut().zip(&masses).zip(&frames) {
+        joint.inertial = merge_inertial(bucket, frame);
+    }
+    let tool = prev.inverse().mul(&links[end].frame);
+    let tcp = Tcp { position: tool.p, orientation: tool.quat_xyzw() };
+    let id: String = model.chars().map(|c| if c.is_ascii_alphanumeric() || "_-.".contains(c) { c } else { '_' }).collect();
+    let chain = ChainDef {
+        id, version: 1, name: model.into(), description: String::new(), joints, tcp: Some(tcp), solver: None,
+        configurations: Default::default(), disabled_collisions: Vec::new(),
+    };
+    Ok((chain, path.to_vec()))
+}
+
+/// Combine link masses (world coordinates) into one body expressed in `frame`,
+/// moving each inertia to the combined centre of mass (parallel-axis theorem).
+fn merge_inertial(parts: &[(f64, [f64; 3], Mat)], frame: &Frame) -> Option<Inertial> {
+    let mass: f64 = parts.iter().map(|p| p.0).sum();
+    if parts.is_empty() || mass <= 0.0 { return None; }
+    let mut com = [0.0; 3];
+    for (m, c, _) in parts { for k in 0..3 { com[k] += m * c[k] / mass; } }
+    let mut total = [[0.0; 3]; 3];
+    for (m, c, inertia) in parts {
+        let d = [c[0] - com[0], c[1] - com[1], c[2] - com[2]];
+        let d2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+        for i in 0..3 {
+            for j in 0..3 { total[i][j] += inertia[i][j] + m * (if i == j { d2 } else { 0.0 } - d[i] * d[j]); }
+        }
+    }
+    let local = matmul(&matmul(&transpose(&frame.r), &total), &frame.r);
+    let com = frame.inverse().mul(&Frame { r: Frame::IDENTITY.r, p: com }).p;
+    Some(Inertial { mass, com, inertia: [local[0][0], local[0][1], local[0][2], local[1][1], local[1][2], local[2][2]] })
+}
+
+fn sym(ixx: f64, ixy: f64, ixz: f64, iyy: f64, iyz: f64, izz: f64) -> Mat { [[ixx, ixy, ixz], [ixy, iyy, iyz], [ixz, iyz, izz]] }
+
+// ── Attribute helpers ───────────────────────────────────────
+
+fn nums(s: &str, what: &str) -> Result<Vec<f64>, String> {
+    s.split_whitespace().map(|t| t.parse::<f64>().map_err(|_| format!("{what}: '{t}' is not a number"))).collect()
+}
+
+fn vec_n<const N: usize>(s: Option<&str>, default: [f64; N], what: &str) -> Result<[f64; N], String> {
+    let Some(s) = s else { return Ok(default) };
+    nums(s, what)?.try_into().map_err(|v: Vec<f64>| format!("{what}: expected {N} numbers, got {}", v.len()))
+}
+
+fn child<'a, 'i>(n: Node<'a, 'i>, tag: &str) -> Option<Node<'a, 'i>> { n.children().find(|c| c.has_tag_name(tag)) }
+
+fn child_text<'a>(n: Node<'a, '_>, tag: &str) -> Option<&'a str> { child(n, tag).and_then(|c| c.text()).map(str::trim) }
+
+fn child_num(n: Node, tag: &str) -> Result<Option<f64>, String> {
+    child_text(n, tag).map(|t| t.parse::<f64>().map_err(|_| format!("<{tag}>: '{t}' is not a number"))).transpose()
+}
+
+// ── URDF ────────────────────────────────────────────────────
+
+fn urdf_origin(n: Node) -> Result<Frame, String> {
+    let o = child(n, "origin");
+    Ok(Frame::from_xyz_rpy(vec_n(o.and_then(|o| o.attribute("xyz")), [0.0; 3], "origin xyz")?, vec_n(o.and_then(|o| o.attribute("rpy")), [0.0; 3], "origin rpy")?))
+}
+
+fn urdf(robot: Node) -> Result<(String, Vec<Link>), String> {
+    let mut links = Vec::new();
+    for l in robot.children().filter(|c| c.has_tag_name("link")) {
+        let name = l.attribute("name").ok_or("<link> without a name")?.to_string();
+        let inertial = child(l, "inertial").map(|i| -> Result<_, String> {
+            let origin = urdf_origin(i)?;
+            let mass: f64 = child(i, "mass").and_then(|m| m.attribute("value")).unwrap_or("0").parse().map_err(|_| format!("link '{name}': bad mass"))?;
+            let t = child(i, "inertia");
+            let a = |k: &str| t.and_then(|t| t.attribute(k)).unwrap_or("0").parse::<f64>().map_err(|_| format!("link '{name}': bad inertia {k}"));
+            let inertia = sym(a("ixx")?, a("ixy")?, a("ixz")?, a("iyy")?, a("iyz")?, a("izz")?);
+            Ok((mass, origin.p, matmul(&matmul(&origin.r, &inertia), &transpose(&origin.r))))
+        }).transpose()?;
+        links.push(Link { name, parent: None, frame: Frame::IDENTITY, joints: Vec::new(), inertial });
+    }
+    let index: HashMap<String, usize> = links.iter().enumerate().map(|(i, l)| (l.name.clone(), i)).collect();
+    let mut local = vec![Frame::IDENTITY; links.len()];
+    for j in robot.children().filter(|c| c.has_tag_name("joint")) {
+        let name = j.attribute("name").unwrap_or("joint").to_string();
+        let link_of = |tag: &str| child(j, tag).and_then(|c| c.attribute("link")).and_then(|l| index.get(l).copied())
+            .ok_or_else(|| format!("joint '{name}': missing or unknown <{tag}> link"));
+        let (parent, child_link) = (link_of("parent")?, link_of("child")?);
+        let kind = match j.attribute("type").unwrap_or("") {
+            "revolute" | "continuous" => Some(JointType::Revolute),
+            "prismatic" => Some(JointType::Prismatic),
+            "fixed" => None,
+            other => return Err(format!("joint '{name}': unsupported type '{other}'")),
+        };
+        let limits = match (j.attribute("type"), child(j, "limit")) {
+            (Some("revolute" | "prismatic"), Some(l)) => Some([
+                l.attribute("lower").unwrap_or("0").parse().map_err(|_| format!("joint '{name}': bad lower limit"))?,
+                l.attribute("upper").unwrap_or("0").parse().map_err(|_| format!("joint '{name}': bad upper limit"))?,
+            ]),
+            _ => None,
+        };
+        let axis = vec_n(child(j, "axis").and_then(|a| a.attribute("xyz")), [1.0, 0.0, 0.0], &format!("joint '{name}' axis"))?;
+        if links[child_link].parent.is_some() { return Err(format!("link '{}' has two parent joints", links[child_link].name)); }
+        links[child_link].parent = Some(parent);
+        local[child_link] = urdf_origin(j)?;
+        // The joint frame is the child link frame; world poses are filled in below
+        links[child_link].joints.push(RawJoint { name, kind, axis, limits, frame: Frame::IDENTITY });
+    }
+    resolve_frames(&mut links, &local, |link, frame| for j in &mut link.joints { j.frame = frame; })?;
+    Ok((robot.attribute("name").unwrap_or("robot").to_string(), links))
+}
+
+/// Compose world frames from per-link transforms relative to the parent, then let
+/// `finish` place each link's joints. Fails on cycles.
+fn resolve_frames(links: &mut [Link], local: &[Frame], finish: impl Fn(&mut Link, Frame)) -> Result<(), String> {
+    let mut world: Vec<Option<Frame>> = vec![None; links.len()];
+    for start in 0..links.len() {
+        let mut stack = vec![start];
+        while let Some(&i) = stack.last() {
+            if world[i].is_some() { stack.pop(); continue; }
+            match links[i].parent {
+                None => { world[i] = Some(local[i]); stack.pop(); }
+                Some(p) => match world[p] {
+                    Some(pf) => { world[i] = Some(pf.mul(&local[i])); stack.pop(); }
+                    None if stack.contains(&p) || stack.len() > links.len() => return Err(format!("kinematic loop at link '{}'", links[p].name)),
+                    None => stack.push(p),
+                },
+            }
+        }
+    }
+    for (link, frame) in links.iter_mut().zip(world) {
+        let frame = frame.unwrap();
+        link.frame = frame;
+        finish(link, frame);
+    }
+    Ok(())
+}
+
+// ── SDF ─────────────────────────────────────────────────────
+
+fn sdf_pose(n: Node) -> Result<(Frame, Option<String>), String> {
+    let Some(p) = child(n, "pose") else { return Ok((Frame::IDENTITY, None)) };
+    let v: [f64; 6] = vec_n(p.text().map(str::trim).filter(|t| !t.is_empty()), [0.0; 6], "<pose>")?;
+    let relative_to = p.attribute("relative_to").filter(|r| !r.is_empty() && *r != "__model__").map(String::from);
+    Ok((Frame::from_xyz_rpy([v[0], v[1], v[2]], [v[3], v[4], v[5]]), relative_to))
+}
+
+fn sdf(root: Node) -> Result<(String, Vec<Link>), String> {
+    let model = child(root, "model").or_else(|| child(root, "world").and_then(|w| child(w, "model"))).ok_or("no <model> in SDF")?;
+    let mut links = Vec::new();
+    let mut poses = Vec::new();
+    for l in model.children().filter(|c| c.has_tag_name("link")) {
+        let name = l.attribute("name").ok_or("<link> without a name")?.to_string();
+        let inertial = child(l, "inertial").map(|i| -> Result<_, String> {
+            let (pose, _) = sdf_pose(i)?;
+            let t = child(i, "inertia");
+            let a = |k: &str| t.map_or(Ok(None), |t| child_num(t, k)).map(|v| v.unwrap_or(0.0));
+            let inertia = sym(a("ixx")?, a("ixy")?, a("ixz")?, a("iyy")?, a("iyz")?, a("izz")?);
+            Ok((child_num(i, "mass")?.unwrap_or(0.0), pose.p, matmul(&matmul(&pose.r, &inertia), &transpose(&pose.r))))
+        }).transpose()?;
+        poses.push(sdf_pose(l)?);
+        links.push(Link { name, parent: None, frame: Frame::IDENTITY, joints: Vec::new(), inertial });
+    }
+    // Link poses are in the model frame unless `relative_to` names another link
+    let index: HashMap<String, usize> = links.iter().enumerate().map(|(i, l)| (l.name.clone(), i)).collect();
+    let mut world: Vec<Option<Frame>> = vec![None; links.len()];
+    for i in 0..links.len() {
+        let mut chain = vec![i];
+        while let Some(r) = poses[*chain.last().unwrap()].1.as_ref() {
+            let r = *index.get(r).ok_or_else(|| format!("pose relative to unknown frame '{r}'"))?;
+            if chain.contains(&r) { return Err(format!("pose cycle at link '{}'", links[r].name)); }
+            if world[r].is_some() { break; }
+            chain.push(r);
+        }
+        for &c in chain.iter().rev() {
+            if world[c].is_some() { continue; }
+            let base = poses[c].1.as_ref().map_or(Frame::IDENTITY, |r| world[index[r]].unwrap());
+            world[c] = Some(base.mul(&poses[c].0));
+        }
+    }
+    for (link, frame) in links.iter_mut().zip(&world) { link.frame = frame.unwrap(); }
+
+    for j in model.children().filter(|c| c.has_tag_name("joint")) {
+        let name = j.attribute("name").unwrap_or("joint").to_string();
+        let parent = child_text(j, "parent").ok_or_else(|| format!("joint '{name}' has no <parent>"))?;
+        let child_name = child_text(j, "child").ok_or_else(|| format!("joint '{name}' has no <child>"))?;
+        let c = *index.get(child_name).ok_or_else(|| format!("joint '{name}': unknown child '{child_name}'"))?;
+        let p = if parent == "world" { None } else {
+            Some(*index.get(parent).ok_or_else(|| format!("joint '{name}': unknown parent '{parent}'"))?)
+        };
+        let kind = match j.attribute("type").unwrap_or("") {
+            "revolute" | "continuous" => Some(JointType::Revolute),
+            "prismatic" => Some(JointType::Prismatic),
+            "fixed" => None,
+            other => return Err(format!("joint '{name}': unsupported type '{other}'")),
+        };
+        // Joint pose is relative to the child link frame
+        let (pose, relative_to) = sdf_pose(j)?;
+        if relative_to.is_some() { return Err(format!("joint '{name}': <pose relative_to> is not supported on joints")); }
+        let frame = links[c].frame.mul(&pose);
+        let axis_node = child(j, "axis");
+        let mut axis = vec_n(axis_node.and_then(|a| child_text(a, "xyz")), [0.0, 0.0, 1.0], &format!("joint '{name}' axis"))?;
+        let model_frame = axis_node.is_some_and(|a| {
+            child_text(a, "use_parent_model_frame") == Some("true")
+                || child(a, "xyz").and_then(|x| x.attribute("expressed_in")).is_some_and(|e| e == "__model__")
+        });
+        if model_frame { axis = mat_vec(&transpose(&frame.r), axis); }
+        let limit = axis_node.and_then(|a| child(a, "limit"));
+        let bound = |tag| limit.map_or(Ok(None), |l| child_num(l, tag));
+        let limits = match (kind, bound("lower")?, bound("upper")?) {
+            // SDF spells "unlimited" as ±1e16
+            (Some(_), Some(lo), Some(hi)) if lo.abs() < 1e15 && hi.abs() < 1e15 => Some([lo, hi]),
+            _ => None,
+        };
+        if links[c].parent.is_some() { return Err(format!("link '{child_name}' has two parent joints")); }
+        links[c].parent = p;
+        links[c].joints.push(RawJoint { name, kind, axis, limits, frame });
+    }
+    Ok((model.attribute("name").unwrap_or("model").to_string(), links))
+}
+
+// ── MJCF ────────────────────────────────────────────────────
+
+/// Joint attributes after applying `<default>` classes.
+#[derive(Clone, Default)]
+struct MjJointDefaults { attrs: HashMap<String, String> }
+
+fn mj_defaults(n: Node, inherited: &MjJointDefaults, out: &mut HashMap<String, MjJointDefaults>) {
+    let mut mine = inherited.clone();
+    if let Some(j) = child(n, "joint") {
+        for a in j.attributes() { mine.attrs.insert(a.name().into(), a.value().into()); }
+    }
+    out.insert(n.attribute("class").unwrap_or("main").into(), mine.clone());
+    for d in n.children().filter(|c| c.has_tag_name("default")) { mj_defaults(d, &mine, out); }
+}
+
+struct Mjcf { degrees: bool, defaults: HashMap<String, MjJointDefaults> }
+
+impl Mjcf {
+    fn angle(&self, v: f64) -> f64 { if self.degrees { v.to_radians() } else { v } }
+
+    fn orientation(&self, n: Node, pos: [f64; 3]) -> Result<Frame, String> {
+        if let Some(q) = n.attribute("quat") { return Ok(Frame::from_quat_wxyz(pos, vec_n(Some(q), [1.0, 0.0, 0.0, 0.0], "quat")?)); }
+        if let Some(e) = n.attribute("euler") {
+            let e: [f64; 3] = vec_n(Some(e), [0.0; 3], "euler")?;
+            let r = matmul(&matmul(&rot(0, self.angle(e[0])), &rot(1, self.angle(e[1]))), &rot(2, self.angle(e[2])));
+            return Ok(Frame { r, p: pos });
+        }
+        if let Some(a) = n.attribute("axisangle") {
+            let [x, y, z, angle]: [f64; 4] = vec_n(Some(a), [0.0, 0.0, 1.0, 0.0], "axisangle")?;
+            let norm = (x * x + y * y + z * z).sqrt().max(1e-12);
+            let (s, c) = (self.angle(angle) / 2.0).sin_cos();
+            return Ok(Frame::from_quat_wxyz(pos, [c, x / norm * s, y / norm * s, z / norm * s]));
+        }
+        if n.attribute("xyaxes").is_some() || n.attribute("zaxis").is_some() {
+            return Err(format!("<{}>: xyaxes/zaxis orientations are not supported; use quat, euler or axisangle", n.tag_name().name()));
+        }
+        Ok(Frame { r: Frame::IDENTITY.r, p: pos })
+    }
+
+    fn body(&self, n: Node, parent: Option<usize>, parent_frame: Frame, class: &str, out: &mut Vec<Link>) -> Result<(), String> {
+        let pos = vec_n(n.attribute("pos"), [0.0; 3], "body pos")?;
+        let frame = parent_frame.mul(&self.orientation(n, pos)?);
+        let name = n.attribute("name").map_or_else(|| format!("body{}", out.len()), String::from);
+        let class = n.attribute("childclass").unwrap_or(class);
+        let mut joints = Vec::new();
+        for j in n.children().filter(|c| c.has_tag_name("joint")) {
+            let defaults = self.defaults.get(j.attribute("class").unwrap_or(class)).cloned().unwrap_or_default();
+            let attr = |k: &str| j.attribute(k).or_else(|| defaults.attrs.get(k).map(String::as_str));
+            let jname = attr("name").map_or_else(|| format!("{name}_joint{}", joints.len()), String::from);
+            let jpos = vec_n(attr("pos"), [0.0; 3], "joint pos")?;
+            let jframe = frame.mul(&Frame { r: Frame::IDENTITY.r, p: jpos });
+            let range = attr("range").map(|r| vec_n::<2>(Some(r), [0.0; 2], "joint range")).transpose()?;
+            let limited = attr("limited").unwrap_or("auto") != "false" && range.is_some_and(|[lo, hi]| lo < hi);
+            match attr("type").unwrap_or("hinge") {
+                "hinge" | "slide" => {
+                    let hinge = attr("type").unwrap_or("hinge") == "hinge";
+                    let limits = range.filter(|_| limited).map(|r| if hinge { r.map(|v| self.angle(v)) } else { r });
+                    joints.push(RawJoint {
+                        name: jname, kind: Some(if hinge { JointType::Revolute } else { JointType::Prismatic }),
+                        axis: vec_n(attr("axis"), [0.0, 0.0, 1.0], "joint axis")?, limits, frame: jframe,
+                    });
+                }
+                // A ball joint becomes three coincident hinges about the body axes
+                "ball" => for (k, axis) in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].into_iter().enumerate() {
+                    let limits = range.filter(|_| limited).map(|[_, hi]| [-self.angle(hi), self.angle(hi)]);
+                    joints.push(RawJoint { name: format!("{jname}_{}", ["x", "y", "z"][k]), kind: Some(JointType::Revolute), axis, limits, frame: jframe });
+                },
+                // Floating bases are not part of a fixed-base chain
+                "free" => {}
+                other => return Err(format!("joint '{jname}': unsupported type '{other}'")),
+            }
+        }
+        let inertial = child(n, "inertial").map(|i| -> Result<_, String> {
+            let ipos = vec_n(i.attribute("pos"), [0.0; 3], "inertial pos")?;
+            let orient = self.orientation(i, ipos)?;
+            let inertia = if let Some(d) = i.attribute("diaginertia") {
+                let d: [f64; 3] = vec_n(Some(d), [0.0; 3], "diaginertia")?;
+                sym(d[0], 0.0, 0.0, d[1], 0.0, d[2])
+            } else {
+                let f: [f64; 6] = vec_n(i.attribute("fullinertia"), [0.0; 6], "fullinertia")?;
+                // MJCF order: ixx iyy izz ixy ixz iyz
+                sym(f[0], f[3], f[4], f[1], f[5], f[2])
+            };
+            let mass = i.attribute("mass").unwrap_or("0").parse().map_err(|_| format!("body '{name}': bad mass"))?;
+            Ok((mass, ipos, matmul(&matmul(&orient.r, &inertia), &transpose(&orient.r))))
+        }).transpose()?;
+        let index = out.len();
+        out.push(Link { name, parent, frame, joints, inertial });
+        for b in n.children().filter(|c| c.has_tag_name("body")) { self.body(b, Some(index), frame, class, out)?; }
+        Ok(())
+    }
+}
+
+fn mjcf(root: Node) -> Result<(String, Vec<Link>), String> {
+    let compiler = child(root, "compiler");
+    if compiler.and_then(|c| c.attribute("eulerseq")).is_some_and(|s| s != "xyz") {
+        return Err("only the default eulerseq=\"xyz\" is supported".into());
+    }
+    let mut defaults = HashMap::new();
+    for d in root.children().filter(|c| c.has_tag_name("default")) { mj_defaults(d, &MjJointDefaults::default(), &mut defaults); }
+    let mj = Mjcf { degrees: compiler.and_then(|c| c.attribute("angle")) != Some("radian"), defaults };
+    let world = child(root, "worldbody").ok_or("no <worldbody> in MJCF")?;
+    let mut links = vec![Link { name: "world".into(), parent: None, frame: Frame::IDENTITY, joints: Vec::new(), inertial: None }];
+    for b in world.children().filter(|c| c.has_tag_name("body")) { mj.body(b, Some(0), Frame::IDENTITY, "main", &mut links)?; }
+    Ok((root.attribute("model").unwrap_or("mujoco").to_string(), links))
+}
+
+// ── MoveIt ──────────────────────────────────────────────────
+
+pub use kinematics_types::SkippedGroup;
+
+/// Import the planning groups of a MoveIt config (URDF + SRDF) as chains named
+/// `{robot}_{group}`. Groups given as a `<chain>` or as a joint list along one
+/// path are supported; `<group_state>`s become named configurations and
+/// `<disable_collisions>` pairs between links of a group are kept on its chain.
+/// With `only`, just those groups are imported and any failure is an error.
+pub fn moveit(urdf_xml: &str, srdf_xml: &str, only: Option<&[String]>) -> Result<(Vec<ChainDef>, Vec<SkippedGroup>), String> {
+    let doc = Document::parse(urdf_xml).map_err(|e| format!("invalid URDF: {e}"))?;
+    if !doc.root_element().has_tag_name("robot") { return Err("the URDF root must be <robot>".into()); }
+    let (model, links) = urdf(doc.root_element())?;
+    let srdf = Document::parse(srdf_xml).map_err(|e| format!("invalid SRDF: {e}"))?;
+    let robot = srdf.root_element();
+    if !robot.has_tag_name("robot") { return Err("the SRDF root must be <robot>".into()); }
+    let groups: Vec<Node> = robot.children().filter(|c| c.has_tag_name("group")).collect();
+    if let Some(missing) = only.and_then(|o| o.iter().find(|g| !groups.iter().any(|n| n.attribute("name") == Some(g.as_str())))) {
+        return Err(format!("no planning group named '{missing}'"));
+    }
+    let disabled: Vec<[&str; 2]> = robot.children().filter(|c| c.has_tag_name("disable_collisions"))
+        .filter_map(|c| Some([c.attribute("link1")?, c.attribute("link2")?])).collect();
+    let (mut chains, mut skipped) = (Vec::new(), Vec::new());
+    for g in groups {
+        let name = g.attribute("name").unwrap_or("group").to_string();
+        if only.is_some_and(|o| !o.contains(&name)) { continue; }
+        let imported = group_chain(&model, &links, g).map(|(mut chain, path)| {
+            let on_path = |l: &str| path.iter().any(|&i| links[i].name == l);
+            chain.disabled_collisions = disabled.iter().filter(|[a, b]| on_path(a) && on_path(b)).map(|p| p.map(String::from)).collect();
+            for state in robot.children().filter(|c| c.has_tag_name("group_state") && c.attribute("group") == Some(name.as_str())) {
+                let values: HashMap<&str, &str> = state.children().filter(|c| c.has_tag_name("joint"))
+                    .filter_map(|j| Some((j.attribute("name")?, j.attribute("value")?))).collect();
+                let q: Option<Vec<f64>> = chain.joints.iter().map(|j| values.get(j.name.as_deref()?)?.trim().parse().ok()).collect();
+                let state_name = state.attribute("name").unwrap_or_default();
+                match q {
+                    Some(q) if crate::chains::validate_configuration(&chain, state_name, &q).is_ok() => { chain.configurations.insert(state_name.into(), q); }
+                    _ => tracing::warn!("group '{name}': skipping group_state '{state_name}'"),
+                }
+            }
+            chain
+        });
+        match imported {
+            Ok(chain) => chains.push(chain),
+            Err(e) if only.is_some() => return Err(format!("group '{name}': {e}")),
+            Err(reason) => skipped.push(SkippedGroup { group: name, reason }),
+        }
+    }
+    if chains.is_empty() && skipped.is_empty() { return Err("the SRDF defines no planning groups".into()); }
+    Ok((chains, skipped))
+}
+
+/// Resolve a planning group to its base and tip links and build the chain between them.
+fn group_chain(model: &str, links: &[Link], g: Node) -> Result<(ChainDef, Vec<usize>), String> {
+    let group = g.attribute("name").unwrap_or("group");
+    let id = format!("{model}_{group}");
+    let (base, tip) = match child(g, "chain") {
+        Some(c) => (
+            c.attribute("base_link").ok_or("<chain> without base_link")?.to_string(),
+            c.attribute("tip_link").ok_or("<chain> without tip_link")?.to_string(),
+        ),
+        None => {
+            let names: Vec<&str> = g.children().filter(|c| c.has_tag_name("joint")).filter_map(|c| c.attribute("name")).collect();
+            if names.is_empty() { return Err("only <chain> groups and joint-list groups are supported".into()); }
+            // Child link of each listed joint; the deepest is the tip, the parent of the shallowest the base
+            let owners = names.iter().map(|n| links.iter().position(|l| l.joints.iter().any(|j| j.name == *n)).ok_or_else(|| format!("unknown joint '{n}'")))
+                .collect::<Result<Vec<_>, String>>()?;
+            let depth = |mut i: usize| { let mut d = 0; while let Some(p) = links[i].parent { d += 1; i = p; if d > links.len() { break; } } d };
+            let tip = *owners.iter().max_by_key(|&&i| depth(i)).unwrap();
+            let top = *owners.iter().min_by_key(|&&i| depth(i)).unwrap();
+            let base = links[top].parent.ok_or_else(|| format!("joint of root link '{}'", links[top].name))?;
+            (links[base].name.clone(), links[tip].name.clone())
+        }
+    };
+    let (mut chain, path) = build(model, links, Some(&base), Some(&tip))?;
+    let listed: Vec<&str> = g.children().filter(|c| c.has_tag_name("joint")).filter_map(|c| c.attribute("name")).collect();
+    let fixed = |n: &str| links.iter().any(|l| l.joints.iter().any(|j| j.name == n && j.kind.is_none()));
+    if let Some(off) = listed.iter().find(|&&n| !fixed(n) && !chain.joints.iter().any(|j| j.name.as_deref() == Some(n))) {
+        return Err(format!("joint '{off}' is not on a single path from '{base}' to '{tip}'"));
+    }
+    chain.id = id.chars().map(|c| if c.is_ascii_alphanumeric() || "_-.".contains(c) { c } else { '_' }).collect();
+    chain.name = format!("{model} {group}");
+    chain.description = format!("MoveIt group '{group}': {base} → {tip}");
+    Ok((chain, path))
+}
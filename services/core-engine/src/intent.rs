@@ -0,0 +1,497 @@
+// ── Intent analysis helpers ─────────────────────────────────
+
+/// Principal component decomposition of a sample window.
+pub struct Pca {
+    pub mean: Vec<f64>,
+    /// Row-major principal axes, strongest first.
+    pub basis: Vec<Vec<f64>>,
+    pub explained_variance: Vec<f64>,
+    pub explained_variance_ratio: Vec<f64>,
+    /// Per-sample projection onto `basis`.
+    pub coefficients: Vec<Vec<f64>>,
+}
+
+/// Project `rows` (one observation per row) onto their `k` strongest principal components.
+pub fn pca(rows: &[Vec<f64>], k: usize) -> Pca {
+    let n = rows.len();
+    let d = rows.first().map_or(0, |r| r.len());
+    let k = k.clamp(1, d.max(1)).min(d);
+
+    let mut mean = vec![0.0f64; d];
+    for r in rows { for (m, v) in mean.iter_mut().zip(r) { *m += v; } }
+    for m in &mut mean { *m /= n.max(1) as f64; }
+
+    // Sample covariance (n - 1 denominator, population for a single sample)
+    let denom = (n.max(2) - 1) as f64;
+    let mut cov = vec![vec![0.0f64; d]; d];
+    for r in rows {
+        for (i, row) in cov.iter_mut().enumerate() {
+            let ci = r[i] - mean[i];
+            for (c, (v, m)) in row.iter_mut().zip(r.iter().zip(&mean)) { *c += ci * (v - m); }
+        }
+    }
+    for c in cov.iter_mut().flatten() { *c /= denom; }
+
+    let (values, vectors) = jacobi_eigen(cov);
+    let mut order: Vec<usize> = (0..d).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+
+    let total: f64 = values.iter().map(|v| v.max(0.0)).sum();
+    let basis: Vec<Vec<f64>> = order.iter().take(k).map(|&c| (0..d).map(|r| vectors[r][c]).collect()).collect();
+    let explained_variance: Vec<f64> = order.iter().take(k).map(|&c| values[c].max(0.0)).collect();
+    let explained_variance_ratio = explained_variance.iter()
+        .map(|v| if total > 1e-15 { v / total } else { 0.0 }).collect();
+    let coefficients = rows.iter().map(|r| {
+        basis.iter().map(|axis| axis.iter().zip(r).zip(&mean).map(|((a, v), m)| a * (v - m)).sum()).collect()
+    }).collect();
+
+    Pca { mean, basis, explained_variance, explained_variance_ratio, coefficients }
+}
+
+/// Cyclic Jacobi eigen-decomposition of a symmetric matrix. Returns eigenvalues and
+/// a matrix whose columns are the matching unit eigenvectors.
+fn jacobi_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let d = a.len();
+    let mut v = vec![vec![0.0f64; d]; d];
+    for (i, row) in v.iter_mut().enumerate() { row[i] = 1.0; }
+
+    for _ in 0..64 {
+        let off: f64 = (0..d).flat_map(|i| (0..d).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j]).sum();
+        if off < 1e-22 { break; }
+        for p in 0..d {
+            for q in (p + 1)..d {
+                if a[p][q].abs() < 1e-300 { continue; }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..d).map(|i| a[i][i]).collect(), v)
+}
+
+/// Central-difference velocity estimate for each sample. `times_s` comes from the sample
+/// timestamps; non-increasing stamps (duplicated or reordered frames) fall back to the
+/// nominal `1 / rate_hz` spacing so a single bad stamp cannot produce an infinite spike.
+pub fn estimate_velocities(times_s: &[f64], positions: &[[f64; 3]], rate_hz: f64) -> Vec<[f64; 3]> {
+    let n = positions.len();
+    let nominal = 1.0 / rate_hz.max(1e-9);
+    (0..n).map(|i| {
+        if n < 2 { return [0.0; 3]; }
+        let (a, b) = (i.saturating_sub(1), (i + 1).min(n - 1));
+        let dt = times_s[b] - times_s[a];
+        let dt = if dt > 1e-9 { dt } else { nominal * (b - a) as f64 };
+        let (p, q) = (positions[a], positions[b]);
+        [(q[0] - p[0]) / dt, (q[1] - p[1]) / dt, (q[2] - p[2]) / dt]
+    }).collect()
+}
+
+// ── Pre-analysis filters ────────────────────────────────────
+
+/// Smoothing applied to a sample window before classification.
+pub enum Filter {
+    /// Constant-velocity Kalman filter with process noise `q` and measurement noise `r`.
+    Kalman { q: f64, r: f64 },
+    /// Savitzky-Golay local polynomial fit over an odd `window` of samples.
+    SavGol { window: usize, order: usize },
+    /// Zero-phase (forward-backward) 2nd-order Butterworth low-pass.
+    Butterworth { cutoff_hz: f64 },
+}
+
+/// Filter every axis of a 3D sample stream independently.
+pub fn filter_positions(filter: &Filter, times_s: &[f64], positions: &[[f64; 3]], rate_hz: f64) -> Vec<[f64; 3]> {
+    let axes: Vec<Vec<f64>> = (0..3).map(|a| {
+        let xs: Vec<f64> = positions.iter().map(|p| p[a]).collect();
+        filter_channel(filter, times_s, &xs, rate_hz)
+    }).collect();
+    (0..positions.len()).map(|i| [axes[0][i], axes[1][i], axes[2][i]]).collect()
+}
+
+/// Filter a single scalar channel.
+pub fn filter_channel(filter: &Filter, times_s: &[f64], xs: &[f64], rate_hz: f64) -> Vec<f64> {
+    if xs.len() < 3 { return xs.to_vec(); }
+    match *filter {
+        Filter::Kalman { q, r } => kalman(times_s, xs, q, r, 1.0 / rate_hz.max(1e-9)),
+        Filter::SavGol { window, order } => savgol(xs, window, order),
+        Filter::Butterworth { cutoff_hz } => {
+            // Odd-reflection padding keeps the endpoints from being dragged by start-up transients
+            let n = xs.len();
+            let pad = (n - 1).min(12);
+            let (x0, xn) = (xs[0], xs[n - 1]);
+            let padded: Vec<f64> = (1..=pad).rev().map(|k| 2.0 * x0 - xs[k])
+                .chain(xs.iter().copied())
+                .chain((1..=pad).map(|k| 2.0 * xn - xs[n - 1 - k]))
+                .collect();
+            let fwd = butterworth(&padded, cutoff_hz, rate_hz);
+            let mut back: Vec<f64> = fwd.into_iter().rev().collect();
+            back = butterworth(&back, cutoff_hz, rate_hz);
+            back.reverse();
+            back[pad..pad + n].to_vec()
+        }
+    }
+}
+
+fn kalman(times_s: &[f64], xs: &[f64], q: f64, r: f64, nominal_dt: f64) -> Vec<f64> {
+    // State [position, velocity], covariance P (symmetric 2x2 stored as p00, p01, p11)
+    let (mut x, mut v) = (xs[0], 0.0f64);
+    let (mut p00, mut p01, mut p11) = (r, 0.0f64, 1.0f64);
+    let mut out = Vec::with_capacity(xs.len());
+    out.push(x);
+    for i in 1..xs.len() {
+        let dt = times_s[i] - times_s[i - 1];
+        let dt = if dt > 1e-9 { dt } else { nominal_dt };
+        // Predict with white-acceleration process noise
+        x += v * dt;
+        let (dt2, dt3, dt4) = (dt * dt, dt * dt * dt, dt * dt * dt * dt);
+        p00 += 2.0 * dt * p01 + dt2 * p11 + q * dt4 / 4.0;
+        p01 += dt * p11 + q * dt3 / 2.0;
+        p11 += q * dt2;
+        // Update
+        let s = p00 + r;
+        let (k0, k1) = (p00 / s, p01 / s);
+        let innov = xs[i] - x;
+        x += k0 * innov;
+        v += k1 * innov;
+        p11 -= k1 * p01;
+        p01 -= k0 * p01;
+        p00 -= k0 * p00;
+        out.push(x);
+    }
+    out
+}
+
+fn savgol(xs: &[f64], window: usize, order: usize) -> Vec<f64> {
+    let n = xs.len();
+    let window = (window.max(3) | 1).min(if n % 2 == 1 { n } else { n - 1 });
+    let order = order.min(window - 1);
+    let half = window / 2;
+    (0..n).map(|i| {
+        // Shift the window inside the series at the edges and evaluate the fit off-centre
+        let start = i.saturating_sub(half).min(n - window);
+        let offset = i as f64 - (start + half) as f64;
+        let ts: Vec<f64> = (0..window).map(|k| k as f64 - half as f64).collect();
+        let coeffs = polyfit(&ts, &xs[start..start + window], order);
+        coeffs.iter().rev().fold(0.0, |acc, c| acc * offset + c)
+    }).collect()
+}
+
+/// Least-squares polynomial fit; returns coefficients lowest order first.
+fn polyfit(ts: &[f64], ys: &[f64], order: usize) -> Vec<f64> {
+    let m = order + 1;
+    let mut a = vec![vec![0.0f64; m + 1]; m];
+    for (t, y) in ts.iter().zip(ys) {
+        let powers: Vec<f64> = (0..m).map(|k| t.powi(k as i32)).collect();
+        for (row, pr) in a.iter_mut().zip(&powers) {
+            for (cell, pc) in row.iter_mut().zip(&powers) { *cell += pr * pc; }
+            row[m] += pr * y;
+        }
+    }
+    kinematics_solver::solve_augmented(a)
+}
+
+fn butterworth(xs: &[f64], cutoff_hz: f64, rate_hz: f64) -> Vec<f64> {
+    // Bilinear-transformed 2nd-order low-pass, cutoff kept below Nyquist
+    let fc = cutoff_hz.clamp(1e-6, rate_hz * 0.49);
+    let k = (std::f64::consts::PI * fc / rate_hz).tan();
+    let norm = 1.0 / (1.0 + std::f64::consts::SQRT_2 * k + k * k);
+    let b0 = k * k * norm;
+    let (b1, b2) = (2.0 * b0, b0);
+    let a1 = 2.0 * (k * k - 1.0) * norm;
+    let a2 = (1.0 - std::f64::consts::SQRT_2 * k + k * k) * norm;
+    // Start from steady state at the first sample to avoid a step transient
+    let (mut x1, mut x2, mut y1, mut y2) = (xs[0], xs[0], xs[0], xs[0]);
+    xs.iter().map(|&x| {
+        let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        (x2, x1, y2, y1) = (x1, x, y1, y);
+        y
+    }).collect()
+}
+
+/// Summary statistics of a sample stream: positions, or joint angles in joint space.
+pub struct PathStats { pub displacement: f64, pub path_length: f64, pub avg_speed: f64, pub jitter: f64 }
+
+/// Displacement, travelled path, mean speed and RMS second difference (jitter) of a
+/// stream, with Euclidean distances over however many channels a sample has.
+pub fn path_stats<P: AsRef<[f64]>>(times_s: &[f64], positions: &[P]) -> PathStats {
+    let n = positions.len();
+    if n == 0 { return PathStats { displacement: 0.0, path_length: 0.0, avg_speed: 0.0, jitter: 0.0 }; }
+    let dist = |a: &P, b: &P| a.as_ref().iter().zip(b.as_ref()).map(|(a, b)| (b - a).powi(2)).sum::<f64>().sqrt();
+    let displacement = dist(&positions[0], &positions[n - 1]);
+    let path_length: f64 = positions.windows(2).map(|w| dist(&w[0], &w[1])).sum();
+    let duration = times_s[n - 1] - times_s[0];
+    let avg_speed = if duration > 1e-9 { path_length / duration } else { 0.0 };
+    let jitter = if n >= 3 {
+        let sq: f64 = positions.windows(3).map(|w| {
+            let (a, b, c) = (w[0].as_ref(), w[1].as_ref(), w[2].as_ref());
+            (0..a.len()).map(|k| (c[k] - 2.0 * b[k] + a[k]).powi(2)).sum::<f64>()
+        }).sum();
+        (sq / (n - 2) as f64).sqrt()
+    } else { 0.0 };
+    PathStats { displacement, path_length, avg_speed, jitter }
+}
+
+// ── Classification ──────────────────────────────────────────
+
+/// Window features the heuristic classifier works from.
+pub struct IntentFeatures {
+    pub magnitude: f64, pub avg_speed: f64, pub dz: f64,
+    /// Net rotation angle and |cos| between rotation axis and travel direction, when
+    /// the samples carry orientation.
+    pub rotation: Option<(f64, f64)>,
+}
+
+/// Soft decision list over the intent rules. Each rule fires with a sigmoid probability
+/// around its threshold and a class receives whatever probability mass earlier rules left,
+/// so the scores always sum to one. Returned strongest first.
+pub fn classify(f: &IntentFeatures) -> Vec<(&'static str, f64)> {
+    let rise = if f.magnitude > 1e-12 { f.dz / f.magnitude } else { 0.0 };
+    let (turn, alignment) = f.rotation.unwrap_or((0.0, 0.0));
+    let rotational = if f.rotation.is_some() { 1.0 } else { 0.0 };
+    let rules = [
+        ("screw", rotational * above(turn, 0.5) * above(f.magnitude, 0.02) * above(alignment, 0.8)),
+        ("twist", rotational * above(turn, 0.5) * below(f.magnitude, 0.05)),
+        ("idle", below(f.magnitude, 0.01)),
+        ("grasp", below(f.magnitude, 0.1) * below(f.avg_speed, 0.05)),
+        ("release", above(rise, 0.7)),
+        ("traverse", above(f.magnitude, 0.5)),
+        ("reach", 1.0),
+    ];
+    rank(&rules)
+}
+
+/// Features of a joint-space window: per-joint net displacement (rad or m) and mean joint speed.
+pub struct JointFeatures { pub displacement: Vec<f64>, pub avg_speed: f64 }
+
+/// Joint-space counterpart of [`classify`]: `hold` when no joint moves appreciably,
+/// `single_joint` when one joint carries most of the motion, `coordinated` otherwise.
+pub fn classify_joint(f: &JointFeatures) -> Vec<(&'static str, f64)> {
+    let total: f64 = f.displacement.iter().map(|d| d.abs()).sum();
+    let peak = f.displacement.iter().fold(0.0f64, |m, d| m.max(d.abs()));
+    let dominance = if total > 1e-12 { peak / total } else { 0.0 };
+    let rules = [
+        ("hold", below(peak, 0.02) * below(f.avg_speed, 0.05)),
+        ("single_joint", above(dominance, 0.7)),
+        ("coordinated", 1.0),
+    ];
+    rank(&rules)
+}
+
+/// Turn a soft decision list into normalized, descending scores.
+fn rank(rules: &[(&'static str, f64)]) -> Vec<(&'static str, f64)> {
+    let mut remaining = 1.0f64;
+    let mut scores: Vec<(&'static str, f64)> = rules.iter().map(|&(label, p)| {
+        let score = remaining * p;
+        remaining -= score;
+        (label, score)
+    }).filter(|&(_, score)| score > 0.0).collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+fn sigmoid(x: f64) -> f64 { 1.0 / (1.0 + (-x).exp()) }
+fn below(x: f64, t: f64) -> f64 { sigmoid((t - x) / (t * 0.1)) }
+fn above(x: f64, t: f64) -> f64 { sigmoid((x - t) / (t * 0.1)) }
+
+/// Split a stream into alternating rest/motion segments by thresholding `speeds`.
+/// Returns `(start, end_inclusive, moving)` index ranges.
+pub fn segment(speeds: &[f64], threshold: f64) -> Vec<(usize, usize, bool)> {
+    let mut out: Vec<(usize, usize, bool)> = Vec::new();
+    for (i, &v) in speeds.iter().enumerate() {
+        let moving = v > threshold;
+        match out.last_mut() {
+            Some(last) if last.2 == moving => last.1 = i,
+            _ => out.push((i, i, moving)),
+        }
+    }
+    out
+}
+
+/// Central-difference speed (Euclidean norm of the per-channel rate) of an N-channel stream.
+pub fn channel_speeds(times_s: &[f64], rows: &[Vec<f64>], rate_hz: f64) -> Vec<f64> {
+    let n = rows.len();
+    let nominal = 1.0 / rate_hz.max(1e-9);
+    (0..n).map(|i| {
+        if n < 2 { return 0.0; }
+        let (a, b) = (i.saturating_sub(1), (i + 1).min(n - 1));
+        let dt = times_s[b] - times_s[a];
+        let dt = if dt > 1e-9 { dt } else { nominal * (b - a) as f64 };
+        rows[b].iter().zip(&rows[a]).map(|(q, p)| ((q - p) / dt).powi(2)).sum::<f64>().sqrt()
+    }).collect()
+}
+
+// ── Orientation ─────────────────────────────────────────────
+// Quaternions are `[x, y, z, w]` (w-last), matching the FK output.
+
+/// Net rotation between the first and last orientation plus the total angle swept
+/// sample-to-sample.
+pub struct RotationStats { pub axis: [f64; 3], pub angle: f64, pub path_angle: f64 }
+
+pub fn rotation_stats(orientations: &[[f64; 4]]) -> RotationStats {
+    let n = orientations.len();
+    if n < 2 { return RotationStats { axis: [0.0; 3], angle: 0.0, path_angle: 0.0 }; }
+    let (axis, angle) = axis_angle(quat_mul(quat_normalize(orientations[n - 1]), quat_conj(quat_normalize(orientations[0]))));
+    let path_angle = orientations.windows(2)
+        .map(|w| axis_angle(quat_mul(quat_normalize(w[1]), quat_conj(quat_normalize(w[0])))).1)
+        .sum();
+    RotationStats { axis, angle, path_angle }
+}
+
+pub fn quat_mul(a: [f64; 4], b: [f64; 4]) -> [f64; 4] {
+    [
+        a[3] * b[0] + a[0] * b[3] + a[1] * b[2] - a[2] * b[1],
+        a[3] * b[1] - a[0] * b[2] + a[1] * b[3] + a[2] * b[0],
+        a[3] * b[2] + a[0] * b[1] - a[1] * b[0] + a[2] * b[3],
+        a[3] * b[3] - a[0] * b[0] - a[1] * b[1] - a[2] * b[2],
+    ]
+}
+
+pub fn quat_conj(q: [f64; 4]) -> [f64; 4] { [-q[0], -q[1], -q[2], q[3]] }
+
+pub fn quat_normalize(q: [f64; 4]) -> [f64; 4] {
+    let n = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if n < 1e-12 { [0.0, 0.0, 0.0, 1.0] } else { [q[0] / n, q[1] / n, q[2] / n, q[3] / n] }
+}
+
+/// Unit rotation axis and angle in `[0, pi]`, taking the shortest arc.
+pub fn axis_angle(q: [f64; 4]) -> ([f64; 3], f64) {
+    let q = if q[3] < 0.0 { [-q[0], -q[1], -q[2], -q[3]] } else { q };
+    let s = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    let angle = 2.0 * s.atan2(q[3]);
+    if s < 1e-12 { ([0.0; 3], 0.0) } else { ([q[0] / s, q[1] / s, q[2] / s], angle) }
+}
+
+// ── Prediction ──────────────────────────────────────────────
+
+/// Extrapolated end of the current movement.
+pub struct Prediction { pub endpoint: [f64; 3], pub eta_s: f64, pub reaches_rest: bool }
+
+/// Fit a minimum-jerk reach `x(t) = x0 + (xf - x0) * s((t - t0) / T)` to the window and
+/// report its endpoint `xf` and remaining time `t0 + T - t_end`. Onset `t0` is searched
+/// over the first half of the window and duration `T` up to `horizon_s` past the last
+/// sample; for each pair the endpoint has a closed-form least-squares solution.
+pub fn predict_endpoint(times_s: &[f64], positions: &[[f64; 3]], horizon_s: f64) -> Prediction {
+    let n = positions.len();
+    let (x0, last, t_end) = (positions[0], positions[n - 1], times_s[n - 1]);
+    let span = t_end - times_s[0];
+    if n < 4 || span < 1e-9 { return Prediction { endpoint: last, eta_s: 0.0, reaches_rest: true }; }
+
+    let min_jerk = |tau: f64| { let t = tau.clamp(0.0, 1.0); t * t * t * (10.0 - 15.0 * t + 6.0 * t * t) };
+    // Returns (residual, displacement) of the best endpoint for a given onset and duration
+    let fit = |t0: f64, dur: f64| {
+        let (mut ss, mut sd) = (0.0f64, [0.0f64; 3]);
+        let shape: Vec<f64> = times_s.iter().map(|t| min_jerk((t - t0) / dur)).collect();
+        for (si, p) in shape.iter().zip(positions) {
+            ss += si * si;
+            for a in 0..3 { sd[a] += si * (p[a] - x0[a]); }
+        }
+        if ss < 1e-12 { return (f64::MAX, [0.0; 3]); }
+        let d = [sd[0] / ss, sd[1] / ss, sd[2] / ss];
+        let residual = shape.iter().zip(positions)
+            .map(|(si, p)| (0..3).map(|a| (p[a] - x0[a] - d[a] * si).powi(2)).sum::<f64>()).sum();
+        (residual, d)
+    };
+
+    const ONSETS: usize = 16;
+    const DURATIONS: usize = 160;
+    let (lo, hi) = (span * 0.3, span + horizon_s);
+    let mut best = (f64::MAX, times_s[0], hi, [0.0f64; 3]);
+    for j in 0..ONSETS {
+        let t0 = times_s[0] + span * 0.5 * j as f64 / ONSETS as f64;
+        for k in 0..=DURATIONS {
+            let dur = lo + (hi - lo) * k as f64 / DURATIONS as f64;
+            let (r, d) = fit(t0, dur);
+            if r < best.0 { best = (r, t0, dur, d); }
+        }
+    }
+    // Refine the duration around the coarse optimum
+    let step = (hi - lo) / DURATIONS as f64;
+    for k in 0..=40 {
+        let dur = (best.2 - step + 2.0 * step * k as f64 / 40.0).max(1e-6);
+        let (r, d) = fit(best.1, dur);
+        if r < best.0 { best = (r, best.1, dur, d); }
+    }
+
+    let (_, t0, dur, d) = best;
+    let remaining = (t0 + dur - t_end).max(0.0);
+    Prediction {
+        endpoint: [x0[0] + d[0], x0[1] + d[1], x0[2] + d[2]],
+        eta_s: remaining.min(horizon_s), reaches_rest: remaining <= horizon_s,
+    }
+}
+
+// ── Multi-channel coordination ──────────────────────────────
+
+/// Net motion of one labelled channel.
+pub struct ChannelMotion<'a> { pub label: &'a str, pub direction: [f64; 3], pub magnitude: f64 }
+
+/// Combined label for several simultaneously tracked effectors. Channels labelled
+/// `head` are ignored; of the rest, the two with the largest displacement decide:
+/// `idle`, `unimanual`, or `bimanual_lift` / `bimanual_parallel` / `bimanual_opposed` /
+/// `bimanual_asymmetric` depending on how their directions relate.
+pub fn coordinate(channels: &[ChannelMotion]) -> &'static str {
+    let mut moving: Vec<&ChannelMotion> = channels.iter()
+        .filter(|c| !c.label.eq_ignore_ascii_case("head") && c.magnitude > 0.05).collect();
+    moving.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+    match moving.as_slice() {
+        [] => "idle",
+        [_] => "unimanual",
+        [a, b, ..] => {
+            let cos = a.direction.iter().zip(&b.direction).map(|(x, y)| x * y).sum::<f64>();
+            if cos > 0.7 && a.direction[2] > 0.5 && b.direction[2] > 0.5 { "bimanual_lift" }
+            else if cos > 0.7 { "bimanual_parallel" }
+            else if cos < -0.7 { "bimanual_opposed" }
+            else { "bimanual_asymmetric" }
+        }
+    }
+}
+
+// ── Timing ──────────────────────────────────────────────────
+
+/// Observed timing of a sample stream against its nominal rate.
+pub struct Timing { pub duration_s: f64, pub effective_rate_hz: f64, pub gaps: Vec<(usize, usize)> }
+
+/// Effective rate plus every interval longer than 1.5 nominal periods (as `(before, after)`
+/// sample index pairs), i.e. where at least one frame was dropped.
+pub fn timing(times_s: &[f64], nominal_rate_hz: f64) -> Timing {
+    let n = times_s.len();
+    let duration_s = if n >= 2 { times_s[n - 1] - times_s[0] } else { 0.0 };
+    let effective_rate_hz = if duration_s > 1e-9 { (n - 1) as f64 / duration_s } else { 0.0 };
+    let period = 1.0 / nominal_rate_hz.max(1e-9);
+    let gaps = (1..n).filter(|&i| times_s[i] - times_s[i - 1] > 1.5 * period).map(|i| (i - 1, i)).collect();
+    Timing { duration_s, effective_rate_hz, gaps }
+}
+
+/// Uniform grid at `rate_hz` spanning non-decreasing `times_s`. Each point is
+/// `(t, i, alpha)`: interpolate between samples `i` and `i + 1` with weight `alpha`.
+pub fn resample_grid(times_s: &[f64], rate_hz: f64) -> Vec<(f64, usize, f64)> {
+    let n = times_s.len();
+    if n < 2 { return times_s.iter().map(|&t| (t, 0, 0.0)).collect(); }
+    let step = 1.0 / rate_hz;
+    let count = ((times_s[n - 1] - times_s[0]) / step + 1e-9).floor() as usize + 1;
+    let mut i = 0usize;
+    (0..count).map(|k| {
+        let t = times_s[0] + k as f64 * step;
+        while i + 2 < n && times_s[i + 1] <= t { i += 1; }
+        let span = times_s[i + 1] - times_s[i];
+        let alpha = if span > 1e-12 { ((t - times_s[i]) / span).clamp(0.0, 1.0) } else { 1.0 };
+        (t, i, alpha)
+    }).collect()
+}
@@ -0,0 +1,196 @@
+// ── Async jobs ──────────────────────────────────────────────
+//
+// Long-running operations submitted for background execution. A job is created
+// `queued`, becomes `running` when its task starts and ends `succeeded`, `failed` or
+// `cancelled`. Finished jobs (and their results) are kept for `retention`, then
+// dropped on the next store access.
+//
+// Every transition, progress step and milestone is recorded as a `JobEvent` and
+// broadcast, so an events stream can replay a job's history and then follow it live.
+// Operations report progress through `progress`, a no-op outside a job.
+
+use crate::error::ApiError;
+use crate::history::now_ms;
+use crate::webhook::{Delivery, DeliveryState};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Events kept per job; progress is recorded at whole-percent steps, so this only
+/// bounds jobs that emit many milestones.
+const MAX_EVENTS: usize = 256;
+
+pub use kinematics_types::{Job, JobEvent, JobState};
+
+/// A stored job: its public record plus what only the engine needs.
+#[derive(Clone)]
+pub struct Entry {
+    pub job: Job,
+    pub result: Option<serde_json::Value>,
+    /// Owning tenant; `None` for the default tenant.
+    pub tenant: Option<String>,
+    abort: Option<tokio::task::AbortHandle>,
+    events: Vec<JobEvent>,
+}
+
+/// Events of all jobs, tagged with the job id.
+pub type EventFeed = broadcast::Receiver<(String, JobEvent)>;
+
+pub struct JobStore { jobs: HashMap<String, Entry>, retention: Duration, max_jobs: usize, events: broadcast::Sender<(String, JobEvent)> }
+
+/// Record an event on `entry` and broadcast it.
+fn emit(events: &broadcast::Sender<(String, JobEvent)>, entry: &mut Entry, kind: &str, message: Option<String>) {
+    if entry.events.len() >= MAX_EVENTS && !matches!(kind, "succeeded" | "failed" | "cancelled") { return; }
+    let event = JobEvent { seq: entry.events.len() as u32, kind: kind.into(), progress: entry.job.progress, message, at_ms: now_ms() };
+    entry.events.push(event.clone());
+    let _ = events.send((entry.job.job_id.clone(), event));
+}
+
+impl JobStore {
+    pub fn new(retention: Duration, max_jobs: usize) -> Self {
+        Self { jobs: HashMap::new(), retention, max_jobs, events: broadcast::channel(1024).0 }
+    }
+
+    /// Queue a job, or `None` when the store is full of unfinished or retained jobs.
+    pub fn create(&mut self, operation: &str, callback_url: Option<String>, tenant: Option<String>) -> Option<String> {
+        self.purge();
+        if self.jobs.len() >= self.max_jobs { return None; }
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let job = Job {
+            job_id: job_id.clone(), operation: operation.into(), state: JobState::Queued, progress: 0.0,
+            created_at_ms: now_ms(), started_at_ms: None, finished_at_ms: None, error: None, callback: callback_url.map(Delivery::new),
+        };
+        let mut entry = Entry { job, result: None, tenant, abort: None, events: Vec::new() };
+        emit(&self.events, &mut entry, "queued", None);
+        self.jobs.insert(job_id.clone(), entry);
+        Some(job_id)
+    }
+
+    pub fn attach(&mut self, job_id: &str, abort: tokio::task::AbortHandle) {
+        if let Some(e) = self.jobs.get_mut(job_id) { e.abort = Some(abort); }
+    }
+
+    pub fn start(&mut self, job_id: &str) {
+        if let Some(e) = self.jobs.get_mut(job_id).filter(|e| e.job.state == JobState::Queued) {
+            e.job.state = JobState::Running;
+            e.job.started_at_ms = Some(now_ms());
+            emit(&self.events, e, "started", None);
+        }
+    }
+
+    /// Record progress (`0..=1`) and/or a milestone for a running job. Progress is
+    /// only recorded when it advances by at least a whole percent.
+    pub fn progress(&mut self, job_id: &str, fraction: Option<f64>, milestone: Option<&str>) {
+        let Some(e) = self.jobs.get_mut(job_id).filter(|e| e.job.state == JobState::Running) else { return };
+        if let Some(f) = fraction.filter(|f| f.is_finite()).map(|f| f.clamp(0.0, 1.0)) {
+            if (f * 100.0).floor() > (e.job.progress * 100.0).floor() {
+                e.job.progress = f;
+                emit(&self.events, e, "progress", None);
+            }
+        }
+        if let Some(m) = milestone { emit(&self.events, e, "milestone", Some(m.into())); }
+    }
+
+    /// A job's events so far plus a receiver for the ones that follow.
+    pub fn subscribe(&mut self, job_id: &str) -> Option<(Vec<JobEvent>, EventFeed)> {
+        let rx = self.events.subscribe();
+        self.get(job_id).map(|e| (e.events.clone(), rx))
+    }
+
+    /// Events of `job_id` after `seq`, for a subscriber that fell behind.
+    pub fn events_since(&self, job_id: &str, seq: Option<u32>) -> Vec<JobEvent> {
+        self.jobs.get(job_id).map_or_else(Vec::new, |e| e.events.iter().filter(|ev| seq.is_none_or(|s| ev.seq > s)).cloned().collect())
+    }
+
+    pub fn finish(&mut self, job_id: &str, outcome: Result<serde_json::Value, ApiError>) {
+        let Some(e) = self.jobs.get_mut(job_id).filter(|e| !e.job.finished()) else { return };
+        match outcome {
+            Ok(v) => { e.job.state = JobState::Succeeded; e.result = Some(v); }
+            Err(err) => { e.job.state = JobState::Failed; e.job.error = Some(err); }
+        }
+        e.job.progress = 1.0;
+        e.job.finished_at_ms = Some(now_ms());
+        e.abort = None;
+        let (kind, message) = match &e.job.error { Some(err) => ("failed", Some(err.message.clone())), None => ("succeeded", None) };
+        emit(&self.events, e, kind, message);
+    }
+
+    /// Abort an unfinished job; finished ones are left as they are. `false` if unknown.
+    pub fn cancel(&mut self, job_id: &str) -> bool {
+        let Some(e) = self.jobs.get_mut(job_id) else { return false };
+        if !e.job.finished() {
+            if let Some(a) = e.abort.take() { a.abort(); }
+            e.job.state = JobState::Cancelled;
+            e.job.finished_at_ms = Some(now_ms());
+            emit(&self.events, e, "cancelled", None);
+        }
+        true
+    }
+
+    /// Record one callback delivery attempt; `last` settles the delivery state.
+    pub fn delivery(&mut self, job_id: &str, outcome: Result<u16, String>, last: bool) {
+        let Some(d) = self.jobs.get_mut(job_id).and_then(|e| e.job.callback.as_mut()) else { return };
+        d.attempts += 1;
+        let ok = matches!(outcome, Ok(200..=299));
+        match outcome { Ok(status) => { d.last_status = Some(status); d.last_error = None; } Err(e) => d.last_error = Some(e) }
+        if ok { d.state = DeliveryState::Delivered; } else if last { d.state = DeliveryState::Failed; }
+    }
+
+    pub fn remove(&mut self, job_id: &str) -> Option<Entry> {
+        self.cancel(job_id);
+        self.jobs.remove(job_id)
+    }
+
+    pub fn get(&mut self, job_id: &str) -> Option<&Entry> {
+        self.purge();
+        self.jobs.get(job_id)
+    }
+
+    /// All jobs, newest first.
+    pub fn list(&mut self) -> Vec<&Entry> {
+        self.purge();
+        let mut jobs: Vec<&Entry> = self.jobs.values().collect();
+        jobs.sort_by_key(|e| std::cmp::Reverse(e.job.created_at_ms));
+        jobs
+    }
+
+    /// Jobs retained, finished ones included.
+    pub fn len(&self) -> usize { self.jobs.len() }
+
+    /// Jobs queued or running.
+    pub fn active(&self) -> usize { self.jobs.values().filter(|e| matches!(e.job.state, JobState::Queued | JobState::Running)).count() }
+
+    /// Whether `create` would accept another job.
+    pub fn has_room(&mut self) -> bool { self.purge(); self.jobs.len() < self.max_jobs }
+
+    fn purge(&mut self) {
+        let cutoff = now_ms().saturating_sub(self.retention.as_millis() as u64);
+        self.jobs.retain(|_, e| e.job.finished_at_ms.is_none_or(|t| t >= cutoff));
+    }
+}
+
+/// Where a running job's progress goes.
+pub type Reporter = Arc<dyn Fn(Option<f64>, Option<&str>) + Send + Sync>;
+
+tokio::task_local! { static REPORTER: Reporter; }
+
+/// Run `task` with `reporter` receiving its `report` calls.
+pub async fn with_reporter<F: std::future::Future>(reporter: Reporter, task: F) -> F::Output { REPORTER.scope(reporter, task).await }
+
+/// Whether the caller is running as a job.
+pub fn in_job() -> bool { REPORTER.try_with(|_| ()).is_ok() }
+
+/// Where an operation's progress goes; taken on the request's task and moved along with
+/// the work, e.g. onto the compute pool, where the task-local is out of reach.
+pub struct Progress(Option<Reporter>);
+
+/// The calling operation's `Progress`; reports are no-ops when it is not running as a job.
+pub fn progress() -> Progress { Progress(REPORTER.try_with(Reporter::clone).ok()) }
+
+impl Progress {
+    /// Report progress (`0..=1`) and/or a milestone.
+    pub fn report(&self, fraction: Option<f64>, milestone: Option<&str>) {
+        if let Some(r) = &self.0 { r(fraction, milestone) }
+    }
+}
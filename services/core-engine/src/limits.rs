@@ -0,0 +1,83 @@
+// ── Request body limits ─────────────────────────────────────
+//
+// Per-route caps on request bodies, enforced before anything else buffers them. Bodies
+// announcing a larger `Content-Length` are refused up front; chunked ones are cut off
+// at the cap. Either way the caller gets 413 naming the allowed maximum and how to
+// send the data in smaller pieces. Inner layers and extractors do not limit again.
+//
+// Compact encodings (MessagePack, CBOR, FlatBuffers) fit many more points under a byte
+// cap than JSON, so the waypoints, samples, frames, poses or targets in one request are also
+// capped by count, once parsed and before any work is done on them.
+
+use crate::error::{ApiError, ErrorCode};
+use crate::AppState;
+use axum::{body::{to_bytes, Body}, extract::{Request, State}, http::header, middleware::Next, response::{IntoResponse, Response}};
+use std::sync::Arc;
+
+pub use kinematics_types::BodyLimits;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Class { Samples, Import, Default }
+
+impl Class {
+    /// Sample and waypoint payloads (intents, codec, trajectories, retargeting, motion capture, batch IK, jobs, protobuf),
+    /// robot description imports, or anything else.
+    pub fn of(path: &str) -> Class {
+        const SAMPLES: &[&str] = &["/compress-intent", "/compress-intent/channels", "/optimize-trajectory", "/simulate-tracking", "/retarget", "/mocap/import", "/codec/encode", "/codec/decode", "/solve-ik/batch", "/jobs"];
+        if SAMPLES.iter().any(|s| path.ends_with(s)) || path.starts_with("/api/v1/kinematics/pb/") { return Class::Samples; }
+        if path.ends_with("/chains/import") || path.ends_with("/chains/import/moveit") { return Class::Import; }
+        Class::Default
+    }
+
+    /// This class's maximum body size in bytes.
+    fn max(self, limits: &BodyLimits) -> usize {
+        match self { Class::Samples => limits.samples, Class::Import => limits.import, Class::Default => limits.default }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Class::Samples => "split long recordings into consecutive requests sharing a session_id, or stream IK targets over /api/v1/kinematics/solve-ik/stream",
+            Class::Import => "strip meshes and unused links from the description before importing",
+            Class::Default => "send many IK targets over /api/v1/kinematics/solve-ik/stream instead of one large request",
+        }
+    }
+}
+
+/// What a request carries many of, for the per-request point cap.
+#[derive(Clone, Copy)]
+pub enum Points { Waypoints, Samples, Frames, Poses, Targets }
+
+impl Points {
+    fn hint(self) -> &'static str {
+        match self {
+            Points::Waypoints => "split the path into consecutive trajectories, each starting at the last waypoint of the one before; long results stream back as NDJSON",
+            Points::Samples => "split long recordings into consecutive requests sharing a session_id",
+            Points::Frames => "encode the recording in consecutive chunks",
+            Points::Poses => "retarget the motion in consecutive chunks, seeding each with the last joint angles of the one before; thin imported takes with stride",
+            Points::Targets => "stream targets over /api/v1/kinematics/solve-ik/stream, or split them over several batches or jobs",
+        }
+    }
+
+    /// Refuse `count` of these in `field` beyond the engine's `KINEMATICS_MAX_POINTS` (413).
+    pub fn check(self, s: &AppState, field: &str, count: usize) -> Result<(), ApiError> {
+        let Some(max) = s.body_limits.lock().unwrap().points.filter(|&max| count > max) else { return Ok(()) };
+        let details = format!("{count} {field} given, at most {max} per request; {}", self.hint());
+        Err(ApiError { field: Some(field.into()), ..ApiError::new(ErrorCode::PayloadTooLarge, "Too many points", details) })
+    }
+}
+
+fn too_large(path: &str, max: usize) -> ApiError {
+    ApiError::new(ErrorCode::PayloadTooLarge, "Request body too large", format!("{path} accepts at most {max} bytes; {}", Class::of(path).hint()))
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    let max = Class::of(&path).max(&s.body_limits.lock().unwrap());
+    let declared = req.headers().get(header::CONTENT_LENGTH).and_then(|v| v.to_str().ok()).and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|n| n > max as u64) { return too_large(&path, max).into_response(); }
+    let (parts, body) = req.into_parts();
+    match to_bytes(body, max).await {
+        Ok(bytes) => next.run(Request::from_parts(parts, Body::from(bytes))).await,
+        Err(_) => too_large(&path, max).into_response(),
+    }
+}
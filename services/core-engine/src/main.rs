@@ -1,212 +1,1267 @@
-use axum::{extract::State, response::Json, routing::{get, post}, Router};
-use serde::{Deserialize, Serialize};
+use axum::{extract::{ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade}, Path, State}, http::{header, StatusCode}, response::{sse::{Event, KeepAlive, Sse}, IntoResponse}, routing::{get, post, put}, Router};
+use futures_util::{stream, Stream, StreamExt};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tower_http::cors::{Any, CorsLayer};
+use tokio::sync::broadcast;
+use tower_http::compression::{predicate::{NotForContentType, Predicate, SizeAbove}, CompressionLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
+use error::{ApiError, ApiResult, ErrorCode, Json, Query};
+use kinematics_types::*;
+use validate::Validate;
+
+mod allocations;
+mod anthropometry;
+mod audit;
+mod auth;
+mod bench;
+mod chains;
+mod classifier;
+mod config;
+mod codec;
+mod compute;
+mod concurrency;
+mod conditional;
+mod deterministic;
+mod error;
+mod events;
+mod export;
+mod fbs;
+mod gpu;
+#[cfg(feature = "graphql")]
+mod graphql;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod hand;
+mod health;
+mod history;
+mod idempotency;
+mod import;
+mod intent;
+mod jobs;
+mod limits;
+mod metrics;
+mod mocap;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+mod negotiate;
+mod oidc;
+mod openapi;
+#[cfg(feature = "protobuf")]
+mod proto;
+mod ratelimit;
+mod reload;
+mod resultcache;
+mod retarget;
+#[cfg(feature = "ros2")]
+mod ros2;
+mod selftest;
+mod sessions;
+mod shutdown;
+mod sim;
+mod slow;
+mod snapshot;
+mod solutions;
+mod telemetry;
+mod tenant;
+mod tracking;
+mod validate;
+mod warmstart;
+mod webhook;
+
+#[global_allocator]
+static ALLOCATOR: allocations::Counting = allocations::Counting;
 
 // ── State ───────────────────────────────────────────────────
 struct AppState {
     start_time: Instant,
     stats: Mutex<EngineStats>,
+    intents: Mutex<history::IntentHistory>,
+    audit: Mutex<audit::AuditLog>,
+    intent_model: Option<classifier::ModelClassifier>,
+    chains: tenant::Tenants<chains::ChainRegistry>,
+    hands: tenant::Tenants<hand::HandRegistry>,
+    jobs: Mutex<jobs::JobStore>,
+    solutions: Mutex<solutions::SolutionStore>,
+    sims: Mutex<sim::SimStore>,
+    sessions: Mutex<sessions::SessionStore>,
+    webhooks: webhook::Webhooks,
+    events: events::Events,
+    idempotency: Mutex<idempotency::IdempotencyCache>,
+    result_cache: Mutex<resultcache::ResultCache>,
+    keys: Mutex<auth::KeyStore>,
+    oidc: Option<oidc::Oidc>,
+    limiter: Mutex<ratelimit::RateLimiter>,
+    concurrency: concurrency::Concurrency,
+    body_limits: Mutex<limits::BodyLimits>,
+    /// Engine-wide solver settings, below each chain's own defaults.
+    solver_defaults: Mutex<chains::SolverDefaults>,
+    slow_requests: Mutex<slow::Thresholds>,
+    /// `KINEMATICS_SOLVE_TIMEOUT_MS`: how long an IK solve may iterate.
+    solve_timeout: Mutex<Option<std::time::Duration>>,
+    warm_starts: Mutex<warmstart::WarmStarts>,
+    /// Batch IK compute backend, when built with `gpu` and an adapter was found.
+    gpu: Option<gpu::Gpu>,
+    /// `KINEMATICS_GPU_BATCH_THRESHOLD`: batches this large go to the GPU by default.
+    gpu_threshold: Mutex<Option<usize>>,
+    /// `KINEMATICS_TRAJECTORY_STREAM_POINTS`: longer trajectories stream as NDJSON.
+    trajectory_stream: Mutex<Option<usize>>,
+    metrics: metrics::Metrics,
+    probes: health::Probes,
+    /// `KINEMATICS_STATS_STORE`, where `snapshot` saves the counters.
+    stats_store: Option<std::path::PathBuf>,
+    shutdown: shutdown::Shutdown,
+    /// Set through the admin API; refuses new solves while health stays green.
+    maintenance: Mutex<Maintenance>,
+    /// Take the client address from `X-Forwarded-For` (behind a trusted proxy).
+    trust_forwarded: bool,
 }
 
+#[derive(Clone, Serialize, serde::Deserialize)]
 struct EngineStats {
+    /// When counting began: the first start with the current stats store, else this start.
+    since_ms: u64,
     total_ik_solves: u64,
     total_fk_solves: u64,
     total_compressions: u64,
     total_trajectories: u64,
 }
 
-// ── Types ───────────────────────────────────────────────────
-#[derive(Serialize)]
-struct Health { status: String, version: String, uptime_secs: u64, total_solves: u64 }
-
-// IK
-#[derive(Deserialize)]
-struct IkRequest {
-    #[allow(dead_code)]
-    chain_id: Option<String>,
-    target_position: [f64; 3],
-    target_orientation: Option<[f64; 4]>,
-    joint_count: Option<u32>,
-    constraints: Option<IkConstraints>,
-}
-#[derive(Deserialize)]
-struct IkConstraints { max_iterations: Option<u32>, tolerance: Option<f64> }
-#[derive(Serialize)]
-struct IkResponse {
-    solution_id: String, joint_angles: Vec<f64>, iterations: u32,
-    converged: bool, error_distance: f64, elapsed_us: u128,
+impl EngineStats {
+    fn new() -> Self { Self { since_ms: history::now_ms(), total_ik_solves: 0, total_fk_solves: 0, total_compressions: 0, total_trajectories: 0 } }
 }
 
-// FK
-#[derive(Deserialize)]
-struct FkRequest { #[allow(dead_code)] chain_id: Option<String>, joint_angles: Vec<f64>, link_lengths: Option<Vec<f64>> }
-#[derive(Serialize)]
-struct FkResponse {
-    end_effector_position: [f64; 3], end_effector_orientation: [f64; 4],
-    joint_positions: Vec<[f64; 3]>, elapsed_us: u128,
+#[derive(Clone, Copy)]
+enum Op { Ik, Fk, Compression, Trajectory }
+
+impl Op {
+    /// Result event kind (see `events`).
+    fn kind(self) -> &'static str { match self { Op::Ik => "ik", Op::Fk => "fk", Op::Compression => "intent", Op::Trajectory => "trajectory" } }
 }
 
-// Intent compression
-#[derive(Deserialize)]
-struct IntentRequest { samples: Vec<MotionSample>, sample_rate_hz: Option<u32> }
-#[derive(Deserialize)]
-struct MotionSample { #[allow(dead_code)] timestamp_ms: u64, position: [f64; 3], velocity: Option<[f64; 3]> }
+// ── Types ───────────────────────────────────────────────────
+// Request and response bodies live in the `kinematics-types` crate, shared with the client SDK.
 #[derive(Serialize)]
-struct IntentResponse {
-    intent_id: String, compressed_bytes: u64, original_samples: usize,
-    compression_ratio: f64, intent_type: String, direction: [f64; 3],
-    magnitude: f64, elapsed_us: u128,
-}
+struct IkStreamReply<T> { #[serde(skip_serializing_if = "Option::is_none")] seq: Option<u64>, #[serde(flatten)] body: T }
 
-// Trajectory
-#[derive(Deserialize)]
-struct TrajectoryRequest {
-    waypoints: Vec<Vec<f64>>, max_velocity: Option<f64>,
-    #[allow(dead_code)] max_acceleration: Option<f64>, #[allow(dead_code)] smoothness: Option<f64>,
+impl From<intent::Pca> for PcaSummary {
+    fn from(p: intent::Pca) -> Self {
+        Self {
+            mean: p.mean, basis: p.basis, explained_variance: p.explained_variance,
+            explained_variance_ratio: p.explained_variance_ratio, coefficients: p.coefficients,
+        }
+    }
 }
-#[derive(Serialize)]
-struct TrajectoryResponse {
-    trajectory_id: String, optimized_waypoints: Vec<TrajectoryPoint>,
-    total_distance: f64, total_time: f64, max_velocity_reached: f64, elapsed_us: u128,
+
+impl From<intent::PathStats> for PathStatsSummary {
+    fn from(p: intent::PathStats) -> Self {
+        Self { displacement: p.displacement, path_length: p.path_length, avg_speed: p.avg_speed, jitter: p.jitter }
+    }
 }
-#[derive(Serialize)]
-struct TrajectoryPoint { position: [f64; 3], velocity: [f64; 3], time: f64 }
 
-#[derive(Serialize)]
-struct ChainInfo { id: String, name: String, description: String, dof: u32, joint_type: String }
+type JobTask = std::pin::Pin<Box<dyn std::future::Future<Output = Result<serde_json::Value, ApiError>> + Send>>;
 
-#[derive(Serialize)]
-struct StatsResponse { total_ik_solves: u64, total_fk_solves: u64, total_compressions: u64, total_trajectories: u64 }
+const CHAIN_PAGE_MAX: usize = 1000;
+const MAX_JOBS: usize = 10_000;
+const MAX_SOLUTIONS: usize = 100_000;
+const MAX_SIMS: usize = 1_000;
+const MAX_SESSIONS: usize = 10_000;
+const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+/// Planar FK link length when a request gives no `link_lengths` and no `pad_link_length`.
+const DEFAULT_LINK_LENGTH: f64 = 0.2;
+/// IK solves stop iterating after this unless `KINEMATICS_SOLVE_TIMEOUT_MS` says otherwise.
+const DEFAULT_SOLVE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Requests running at once, engine-wide, unless `KINEMATICS_CONCURRENCY_LIMIT` says otherwise.
+const DEFAULT_CONCURRENCY: &str = "1024";
+/// Batch IK goes to the GPU from this many targets unless `KINEMATICS_GPU_BATCH_THRESHOLD` says otherwise.
+const DEFAULT_GPU_BATCH_THRESHOLD: usize = 4096;
+/// Trajectories longer than this stream as NDJSON unless `KINEMATICS_TRAJECTORY_STREAM_POINTS` says otherwise.
+const DEFAULT_TRAJECTORY_STREAM_POINTS: usize = 10_000;
+/// Waypoints, samples, frames or targets per request unless `KINEMATICS_MAX_POINTS` says otherwise.
+const DEFAULT_MAX_POINTS: usize = 500_000;
+/// Points per streamed chunk, and chunks timed ahead of a slow client.
+const STREAM_CHUNK: usize = 256;
+const STREAM_CHUNKS_AHEAD: usize = 8;
+const NDJSON: &str = "application/x-ndjson";
 
 // ── Main ────────────────────────────────────────────────────
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| "kinematics_engine=info".into()))
-        .init();
+    config::init();
+    telemetry::init();
+    compute::init(parsed::<usize>("KINEMATICS_SOLVE_THREADS").unwrap_or_else(|e| panic!("{e}")).filter(|&n| n > 0));
+    if let Some(path) = config::path() { tracing::info!("Loaded configuration from {}", path.display()); }
+    let intent_log = config::var("KINEMATICS_INTENT_LOG").ok().map(std::path::PathBuf::from);
+    let intent_capacity = config::var("KINEMATICS_INTENT_HISTORY").ok().and_then(|v| v.parse().ok()).unwrap_or(10_000);
+    let job_retention_secs = config::var("KINEMATICS_JOB_RETENTION_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600);
+    let solution_retention_secs = config::var("KINEMATICS_SOLUTION_RETENTION_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600);
+    let sim_idle_secs = config::var("KINEMATICS_SIM_IDLE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(600);
+    let session_idle_secs = config::var("KINEMATICS_SESSION_IDLE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(86_400);
+    let key_store = config::var("KINEMATICS_KEY_STORE").ok().map(std::path::PathBuf::from);
+    let mut configured_keys = auth::parse_keys(&config::var("KINEMATICS_API_KEYS").unwrap_or_default()).unwrap_or_else(|e| panic!("KINEMATICS_API_KEYS: {e}"));
+    if let Some(secret) = config::var("KINEMATICS_ADMIN_KEY").ok().filter(|k| !k.is_empty()) { configured_keys.push(auth::ConfiguredKey { name: "admin".into(), secret, admin: true, tenant: None }); }
+    let idempotency_ttl_secs = config::var("KINEMATICS_IDEMPOTENCY_TTL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(86_400);
+    let result_cache_ttl_secs = config::var("KINEMATICS_RESULT_CACHE_TTL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+    let result_cache_entries = config::var("KINEMATICS_RESULT_CACHE_ENTRIES").ok().and_then(|v| v.parse().ok()).unwrap_or(10_000);
+    let webhook_attempts = config::var("KINEMATICS_WEBHOOK_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(5);
+    let chain_store = config::var("KINEMATICS_CHAIN_STORE").ok().map(std::path::PathBuf::from);
+    let stats_store = config::var("KINEMATICS_STATS_STORE").ok().map(std::path::PathBuf::from);
+    let audit_log = config::var("KINEMATICS_AUDIT_LOG").ok().map(std::path::PathBuf::from);
+    let audit_capacity = config::var("KINEMATICS_AUDIT_HISTORY").ok().and_then(|v| v.parse().ok()).unwrap_or(100_000);
+    let Tunables { rates: [solve_rate, write_rate, read_rate], concurrency, body, solver, slow, solve_timeout, warm_start: (cell, entries), gpu_threshold, trajectory_stream } = Tunables::read().unwrap_or_else(|e| panic!("{e}"));
+    let stores = [("chains", &chain_store), ("keys", &key_store), ("intents", &intent_log), ("stats", &stats_store), ("audit", &audit_log)]
+        .into_iter().map(|(name, path)| (name, path.clone())).collect();
+    let intent_model = config::var("KINEMATICS_INTENT_MODEL").ok().and_then(|path| {
+        let labels = config::var("KINEMATICS_INTENT_MODEL_LABELS")
+            .unwrap_or_else(|_| "idle,grasp,release,traverse,reach,twist,screw".into())
+            .split(',').map(|l| l.trim().to_string()).collect();
+        classifier::ModelClassifier::load(&path, labels)
+            .inspect(|_| tracing::info!("Loaded intent model {path}"))
+            .map_err(|e| tracing::error!("{e}; falling back to heuristic classification only")).ok()
+    });
     let state = Arc::new(AppState {
         start_time: Instant::now(),
-        stats: Mutex::new(EngineStats { total_ik_solves: 0, total_fk_solves: 0, total_compressions: 0, total_trajectories: 0 }),
+        stats: Mutex::new(EngineStats::new()),
+        intents: Mutex::new(history::IntentHistory::open(intent_log, intent_capacity)),
+        audit: Mutex::new(audit::AuditLog::open(audit_log, audit_capacity)),
+        intent_model,
+        chains: tenant::Tenants::new(move |t| chains::ChainRegistry::open(tenant::store_path(chain_store.as_deref(), t))),
+        hands: tenant::Tenants::new(|_| hand::HandRegistry::new()),
+        jobs: Mutex::new(jobs::JobStore::new(std::time::Duration::from_secs(job_retention_secs), MAX_JOBS)),
+        solutions: Mutex::new(solutions::SolutionStore::new(std::time::Duration::from_secs(solution_retention_secs), MAX_SOLUTIONS)),
+        sims: Mutex::new(sim::SimStore::new(std::time::Duration::from_secs(sim_idle_secs), MAX_SIMS)),
+        sessions: Mutex::new(sessions::SessionStore::new(std::time::Duration::from_secs(session_idle_secs), MAX_SESSIONS)),
+        webhooks: webhook::Webhooks::new(config::var("KINEMATICS_WEBHOOK_SECRET").ok(), webhook_attempts, config::var("KINEMATICS_WEBHOOK_ALLOW_PRIVATE").is_ok_and(|v| v == "1" || v == "true")),
+        events: result_events(),
+        keys: Mutex::new(auth::KeyStore::open(key_store, configured_keys)),
+        oidc: config::var("KINEMATICS_OIDC_ISSUER").ok().filter(|i| !i.is_empty())
+            .map(|issuer| oidc::Oidc::new(issuer, config::var("KINEMATICS_OIDC_AUDIENCE").ok(), config::var("KINEMATICS_OIDC_JWKS_URI").ok(),
+                config::var("KINEMATICS_OIDC_TENANT_CLAIM").unwrap_or_else(|_| "tenant".into()))),
+        limiter: Mutex::new(ratelimit::RateLimiter::new(solve_rate, write_rate, read_rate)),
+        concurrency: concurrency::Concurrency::new(concurrency),
+        body_limits: Mutex::new(body),
+        solver_defaults: Mutex::new(solver),
+        slow_requests: Mutex::new(slow),
+        solve_timeout: Mutex::new(solve_timeout),
+        warm_starts: Mutex::new(warmstart::WarmStarts::new(cell, entries)),
+        gpu: cfg!(feature = "gpu").then(gpu::Gpu::open).and_then(|g| g
+            .inspect(|g| tracing::info!("Batch IK can use GPU {}", g.adapter()))
+            .map_err(|e| tracing::warn!("No GPU for batch IK ({e}); solving batches on the CPU")).ok()),
+        gpu_threshold: Mutex::new(gpu_threshold),
+        trajectory_stream: Mutex::new(trajectory_stream),
+        metrics: metrics::Metrics::default(),
+        probes: health::Probes::new(stores),
+        stats_store,
+        shutdown: shutdown::Shutdown::default(),
+        maintenance: Mutex::new(Maintenance::default()),
+        trust_forwarded: config::var("KINEMATICS_TRUST_FORWARDED_FOR").is_ok_and(|v| v == "1" || v == "true"),
+        idempotency: Mutex::new(idempotency::IdempotencyCache::new(std::time::Duration::from_secs(idempotency_ttl_secs), MAX_IDEMPOTENCY_KEYS)),
+        result_cache: Mutex::new(resultcache::ResultCache::new(std::time::Duration::from_secs(result_cache_ttl_secs), result_cache_entries)),
     });
-    let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
+    health::preload(state.clone());
+    selftest::startup(state.clone());
+    if let Some(path) = &state.stats_store { snapshot::load(&state, path); }
+    snapshot::spawn(state.clone());
+    reload::spawn(state.clone());
+    match &state.oidc {
+        Some(o) => tracing::info!("Accepting bearer tokens from {}", o.issuer()),
+        None if !state.keys.lock().unwrap().enabled() => tracing::warn!("No API keys or OIDC issuer configured; /api/v1 is open to anyone"),
+        None => {}
+    }
+    #[cfg(feature = "grpc")]
+    {
+        let grpc_addr = config::var("KINEMATICS_GRPC_ADDR").unwrap_or_else(|_| "0.0.0.0:50051".into());
+        let (service, drain) = (grpc::server(state.clone()), state.shutdown.wait());
+        tokio::spawn(async move {
+            let addr = grpc_addr.parse().expect("KINEMATICS_GRPC_ADDR must be host:port");
+            tracing::info!("gRPC on {grpc_addr}");
+            if let Err(e) = tonic::transport::Server::builder().add_service(service).serve_with_shutdown(addr, drain).await { tracing::error!("gRPC server: {e}"); }
+        });
+    }
+    #[cfg(feature = "mqtt")]
+    if let Some(broker) = config::var("KINEMATICS_MQTT_HOST").ok().filter(|h| !h.is_empty()) {
+        let (host, port) = match broker.rsplit_once(':') {
+            Some((h, p)) => (h.to_string(), p.parse().expect("KINEMATICS_MQTT_HOST must be host or host:port")),
+            None => (broker, 1883),
+        };
+        let config = mqtt::Config {
+            host, port,
+            client_id: config::var("KINEMATICS_MQTT_CLIENT_ID").unwrap_or_else(|_| "kinematics-engine".into()),
+            prefix: config::var("KINEMATICS_MQTT_TOPIC_PREFIX").unwrap_or_else(|_| "kinematics".into()).trim_end_matches('/').to_string(),
+            username: config::var("KINEMATICS_MQTT_USERNAME").ok(), password: config::var("KINEMATICS_MQTT_PASSWORD").ok(),
+            api_key: config::var("KINEMATICS_MQTT_API_KEY").ok(),
+        };
+        tokio::spawn(mqtt::run(state.clone(), config));
+    }
+    #[cfg(feature = "ros2")]
+    if let Some(endpoint) = config::var("KINEMATICS_ROS2_ZENOH").ok().filter(|e| !e.is_empty()) {
+        let config = ros2::Config {
+            endpoint,
+            namespace: config::var("KINEMATICS_ROS2_NAMESPACE").unwrap_or_else(|_| "kinematics".into()).trim_matches('/').to_string(),
+            frame_id: config::var("KINEMATICS_ROS2_FRAME_ID").unwrap_or_else(|_| "base_link".into()),
+            api_key: config::var("KINEMATICS_ROS2_API_KEY").ok(),
+        };
+        tokio::spawn(ros2::run(state.clone(), config));
+    }
+    let cors = CorsLayer::new().allow_origin(cors_origins()).allow_methods(Any).allow_headers(Any)
+        .expose_headers([header::HeaderName::from_static("x-total-count"), header::HeaderName::from_static("idempotent-replayed"),
+            header::HeaderName::from_static("x-ratelimit-limit"), header::HeaderName::from_static("x-ratelimit-remaining"), header::HeaderName::from_static("x-request-id"), header::HeaderName::from_static("x-cache"), header::AGE, header::RETRY_AFTER, header::ETAG, header::LAST_MODIFIED]);
     let app = Router::new()
-        .route("/health", get(health))
+        .route("/health", get(health::health))
+        .route("/healthz", get(health::liveness))
+        .route("/readyz", get(health::readiness))
+        .route("/metrics", get(metrics::handler))
+        .route("/selftest", get(selftest::handler))
+        .route("/api/v1/openapi.json", get(openapi_spec))
         .route("/api/v1/kinematics/solve-ik", post(solve_ik))
+        .route("/api/v1/kinematics/solve-ik/stream", get(solve_ik_stream))
+        .route("/api/v1/kinematics/solve-ik/batch", post(solve_ik_batch))
+        .route("/api/v1/kinematics/solve-ik/velocity", post(solve_ik_velocity))
+        .route("/api/v1/kinematics/solve-ik/acceleration", post(solve_ik_acceleration))
+        .route("/api/v1/kinematics/reachable", post(check_reachable))
+        .route("/api/v1/kinematics/self-motion", post(sample_self_motion))
+        .route("/api/v1/kinematics/compliance", post(joint_compliance))
         .route("/api/v1/kinematics/solve-fk", post(solve_fk))
+        .route("/api/v1/kinematics/verify", post(verify))
+        .route("/api/v1/kinematics/retarget", post(retarget_motion))
+        .route("/api/v1/kinematics/mocap/import", post(import_mocap))
+        .route("/api/v1/kinematics/calibrate/hand-eye", post(calibrate_hand_eye))
+        .route("/api/v1/kinematics/sims", post(create_sim))
+        .route("/api/v1/kinematics/sims/:sim_id", get(get_sim).delete(delete_sim))
+        .route("/api/v1/kinematics/sims/:sim_id/step", post(step_sim))
+        .route("/api/v1/kinematics/simulate-tracking", post(simulate_tracking))
+        .route("/api/v1/kinematics/sessions", post(create_session))
+        .route("/api/v1/kinematics/sessions/:session_id", get(get_session).delete(delete_session))
+        .route("/api/v1/kinematics/sessions/:session_id/state", put(report_state))
         .route("/api/v1/kinematics/compress-intent", post(compress_intent))
-        .route("/api/v1/kinematics/optimize-trajectory", post(optimize_trajectory))
-        .route("/api/v1/kinematics/chains", get(chains))
+        .route("/api/v1/kinematics/compress-intent/channels", post(compress_intent_channels))
+        .route("/api/v1/kinematics/intents", get(list_intents))
+        .route("/api/v1/kinematics/intents/:intent_id", get(get_intent))
+        .route("/api/v1/kinematics/codec/encode", post(codec_encode))
+        .route("/api/v1/kinematics/codec/decode", post(codec_decode))
+        .route("/api/v1/kinematics/optimize-trajectory", post(optimize_trajectory_route))
+        .route("/api/v1/kinematics/fb/optimize-trajectory", post(fbs::optimize_trajectory))
+        .route("/api/v1/kinematics/chains", get(list_chains).post(register_chain))
+        .route("/api/v1/kinematics/chains/export", get(export_chains))
+        .route("/api/v1/kinematics/chains/import", post(import_chain))
+        .route("/api/v1/kinematics/chains/import/moveit", post(import_moveit))
+        .route("/api/v1/kinematics/chains/generate", post(generate_chain))
+        .route("/api/v1/kinematics/chains/compose", post(compose_chain))
+        .route("/api/v1/kinematics/chains/:chain_id/scale", post(scale_chain))
+        .route("/api/v1/kinematics/chains/flush", post(flush_chains))
+        .route("/api/v1/kinematics/chains/:chain_id", get(get_chain).put(update_chain).delete(delete_chain))
+        .route("/api/v1/kinematics/chains/:chain_id/export", get(export_chain))
+        .route("/api/v1/kinematics/hands", get(list_hands).post(register_hand))
+        .route("/api/v1/kinematics/hands/:hand_id", get(get_hand))
+        .route("/api/v1/kinematics/hands/:hand_id/solve-fk", post(solve_hand_fk))
+        .route("/api/v1/kinematics/hands/:hand_id/solve-ik", post(solve_hand_ik))
+        .route("/api/v1/kinematics/chains/:chain_id/configurations", get(list_configurations))
+        .route("/api/v1/kinematics/chains/:chain_id/configurations/:name", get(get_configuration).put(put_configuration))
+        .route("/api/v1/kinematics/solutions/:solution_id", get(get_solution))
+        .route("/api/v1/kinematics/solutions/:solution_id/bvh", get(export_bvh))
+        .route("/api/v1/kinematics/jobs", get(list_jobs).post(submit_job))
+        .route("/api/v1/kinematics/jobs/:job_id", get(get_job).delete(delete_job))
+        .route("/api/v1/kinematics/jobs/:job_id/result", get(job_result))
+        .route("/api/v1/kinematics/jobs/:job_id/events", get(job_events))
+        .route("/api/v1/kinematics/jobs/:job_id/cancel", post(cancel_job))
         .route("/api/v1/kinematics/stats", get(stats))
-        .layer(cors).layer(TraceLayer::new_for_http()).with_state(state);
-    let addr = std::env::var("KINEMATICS_ADDR").unwrap_or_else(|_| "0.0.0.0:8081".into());
+        .route("/api/v1/kinematics/audit", get(list_audit))
+        .route("/api/v1/admin/keys", get(list_keys).post(create_key))
+        .route("/api/v1/admin/keys/:key_id", axum::routing::delete(revoke_key))
+        .route("/api/v1/admin/stats/reset", post(reset_stats))
+        .route("/api/v1/admin/maintenance", get(get_maintenance).put(set_maintenance))
+        .route("/api/v1/admin/state", get(engine_state))
+        .route("/api/v1/admin/bench", post(run_bench));
+    #[cfg(feature = "protobuf")]
+    let app = app
+        .route("/api/v1/kinematics/pb/solve-ik", post(proto::solve_ik))
+        .route("/api/v1/kinematics/pb/solve-fk", post(proto::solve_fk))
+        .route("/api/v1/kinematics/pb/compress-intent", post(proto::compress_intent))
+        .route("/api/v1/kinematics/pb/optimize-trajectory", post(proto::optimize_trajectory));
+    #[cfg(feature = "graphql")]
+    let app = app.route("/api/v1/graphql", get(graphql::sdl).post(graphql::execute));
+    let app = app
+        .layer(axum::middleware::from_fn_with_state(state.clone(), conditional::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), resultcache::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), idempotency::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), ratelimit::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), slow::layer))
+        .layer(axum::middleware::from_fn(deterministic::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), auth::layer))
+        .layer(axum::middleware::from_fn(negotiate::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), limits::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), concurrency::layer))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), metrics::layer))
+        .layer(axum::extract::DefaultBodyLimit::disable());
+    let app = match compression() { Some(c) => app.layer(c), None => app };
+    let app = app
+        .layer(cors).layer(TraceLayer::new_for_http().make_span_with(telemetry::request_span).on_response(telemetry::on_response))
+        .layer(PropagateRequestIdLayer::x_request_id()).layer(SetRequestIdLayer::x_request_id(MakeRequestUuid)).with_state(state.clone());
+    let addr = config::var("KINEMATICS_ADDR").unwrap_or_else(|_| "0.0.0.0:8081".into());
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
     tracing::info!("Kinematics Engine on {addr}");
-    axum::serve(listener, app).await.unwrap();
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).with_graceful_shutdown(state.shutdown.wait());
+    shutdown::serve(server, state).await;
 }
 
-// ── Handlers ────────────────────────────────────────────────
-async fn health(State(s): State<Arc<AppState>>) -> Json<Health> {
-    let st = s.stats.lock().unwrap();
-    Json(Health {
-        status: "ok".into(), version: env!("CARGO_PKG_VERSION").into(),
-        uptime_secs: s.start_time.elapsed().as_secs(),
-        total_solves: st.total_ik_solves + st.total_fk_solves,
-    })
+/// The settings `reload` can change while running: rate and concurrency limits, body
+/// limits, solver defaults, timeout and warm starts, and slow-request thresholds.
+struct Tunables {
+    rates: [Option<ratelimit::Limit>; 3], concurrency: concurrency::Limits, body: limits::BodyLimits, solver: chains::SolverDefaults, slow: slow::Thresholds,
+    solve_timeout: Option<std::time::Duration>, warm_start: (f64, usize), gpu_threshold: Option<usize>, trajectory_stream: Option<usize>,
 }
 
-async fn solve_ik(State(s): State<Arc<AppState>>, Json(req): Json<IkRequest>) -> Json<IkResponse> {
+impl Tunables {
+    fn read() -> Result<Tunables, String> {
+        let solver = chains::SolverDefaults {
+            solver: config::var("KINEMATICS_SOLVER").ok(), seed_policy: config::var("KINEMATICS_SOLVER_SEED_POLICY").ok(),
+            max_iterations: parsed("KINEMATICS_SOLVER_MAX_ITERATIONS")?, tolerance: parsed("KINEMATICS_SOLVER_TOLERANCE")?, damping: parsed("KINEMATICS_SOLVER_DAMPING")?,
+        };
+        solver.validate().map_err(|e| format!("KINEMATICS_SOLVER*: {e}"))?;
+        let cell = parsed::<f64>("KINEMATICS_WARM_START_CELL")?.unwrap_or(0.05);
+        if !(cell.is_finite() && cell > 0.0) { return Err(format!("KINEMATICS_WARM_START_CELL: {cell} is not a positive size in metres")); }
+        Ok(Tunables {
+            rates: [rate_limit("SOLVE", "600/60")?, rate_limit("WRITE", "60/60")?, rate_limit("READ", "1200/60")?],
+            concurrency: concurrency::Limits::parse(&config::var("KINEMATICS_CONCURRENCY_LIMIT").unwrap_or_else(|_| DEFAULT_CONCURRENCY.into()))
+                .map_err(|e| format!("KINEMATICS_CONCURRENCY_LIMIT: {e}"))?,
+            body: limits::BodyLimits {
+                samples: body_limit("SAMPLES", 16 << 20)?, import: body_limit("IMPORT", 8 << 20)?, default: body_limit("DEFAULT", 2 << 20)?,
+                points: match config::var("KINEMATICS_MAX_POINTS") {
+                    Ok(v) if v.eq_ignore_ascii_case("off") => None,
+                    _ => Some(parsed("KINEMATICS_MAX_POINTS")?.unwrap_or(DEFAULT_MAX_POINTS)),
+                },
+            },
+            solver,
+            slow: slow::Thresholds::parse(&config::var("KINEMATICS_SLOW_REQUEST_MS").unwrap_or_default()).map_err(|e| format!("KINEMATICS_SLOW_REQUEST_MS: {e}"))?,
+            solve_timeout: parsed::<u64>("KINEMATICS_SOLVE_TIMEOUT_MS")?.map_or(Some(DEFAULT_SOLVE_TIMEOUT), |ms| (ms > 0).then(|| std::time::Duration::from_millis(ms))),
+            warm_start: (cell, parsed("KINEMATICS_WARM_START_ENTRIES")?.unwrap_or(10_000)),
+            gpu_threshold: match config::var("KINEMATICS_GPU_BATCH_THRESHOLD") {
+                Ok(v) if v.eq_ignore_ascii_case("off") => None,
+                _ => Some(parsed("KINEMATICS_GPU_BATCH_THRESHOLD")?.unwrap_or(DEFAULT_GPU_BATCH_THRESHOLD)),
+            },
+            trajectory_stream: match config::var("KINEMATICS_TRAJECTORY_STREAM_POINTS") {
+                Ok(v) if v.eq_ignore_ascii_case("off") => None,
+                _ => Some(parsed("KINEMATICS_TRAJECTORY_STREAM_POINTS")?.unwrap_or(DEFAULT_TRAJECTORY_STREAM_POINTS)),
+            },
+        })
+    }
+
+    fn apply(self, s: &AppState) {
+        let [solve, write, read] = self.rates;
+        s.limiter.lock().unwrap().set_limits(solve, write, read);
+        s.concurrency.set_limits(self.concurrency);
+        *s.body_limits.lock().unwrap() = self.body;
+        *s.solver_defaults.lock().unwrap() = self.solver;
+        *s.slow_requests.lock().unwrap() = self.slow;
+        *s.solve_timeout.lock().unwrap() = self.solve_timeout;
+        s.warm_starts.lock().unwrap().configure(self.warm_start.0, self.warm_start.1);
+        *s.gpu_threshold.lock().unwrap() = self.gpu_threshold;
+        *s.trajectory_stream.lock().unwrap() = self.trajectory_stream;
+    }
+}
+
+/// `KINEMATICS_RATE_LIMIT_<class>` as `N/S` or `off`, else `default`.
+fn rate_limit(class: &str, default: &str) -> Result<Option<ratelimit::Limit>, String> {
+    let var = format!("KINEMATICS_RATE_LIMIT_{class}");
+    let spec = config::var(&var).unwrap_or_else(|_| default.into());
+    ratelimit::Limit::parse(&spec).map_err(|e| format!("{var}: {e}"))
+}
+
+/// gzip / brotli for responses of at least `KINEMATICS_COMPRESSION_MIN_BYTES` (default
+/// 1024, `off` to disable), by `Accept-Encoding`. Event streams and images are sent as is.
+fn compression() -> Option<CompressionLayer<impl Predicate>> {
+    let min = match config::var("KINEMATICS_COMPRESSION_MIN_BYTES") {
+        Ok(v) if v.eq_ignore_ascii_case("off") => return None,
+        Ok(v) => v.parse().unwrap_or_else(|_| panic!("KINEMATICS_COMPRESSION_MIN_BYTES: '{v}' is not a byte count or off")),
+        Err(_) => 1024,
+    };
+    let when = SizeAbove::new(min).and(NotForContentType::GRPC).and(NotForContentType::IMAGES).and(NotForContentType::SSE);
+    Some(CompressionLayer::new().gzip(true).br(true).compress_when(when))
+}
+
+/// `KINEMATICS_BODY_LIMIT_<class>` in bytes, else `default`.
+fn body_limit(class: &str, default: usize) -> Result<usize, String> {
+    let var = format!("KINEMATICS_BODY_LIMIT_{class}");
+    config::var(&var).map_or(Ok(default), |v| v.parse().map_err(|_| format!("{var}: '{v}' is not a byte count")))
+}
+
+/// `var` parsed, when set.
+fn parsed<T: std::str::FromStr>(var: &str) -> Result<Option<T>, String> {
+    config::var(var).ok().map(|v| v.parse().map_err(|_| format!("{var}: '{v}' is not valid"))).transpose()
+}
+
+/// Origins allowed cross-origin: `KINEMATICS_CORS_ORIGINS` comma separated, or any (`*`, the default).
+fn cors_origins() -> AllowOrigin {
+    let spec = config::var("KINEMATICS_CORS_ORIGINS").unwrap_or_default();
+    let origins: Vec<&str> = spec.split(',').map(str::trim).filter(|o| !o.is_empty()).collect();
+    if origins.is_empty() || origins.contains(&"*") { return AllowOrigin::any(); }
+    AllowOrigin::list(origins.into_iter().map(|o| o.parse::<header::HeaderValue>().unwrap_or_else(|_| panic!("KINEMATICS_CORS_ORIGINS: '{o}' is not a valid origin"))))
+}
+
+/// Result events to `KINEMATICS_NATS_URL`, when set (`nats` feature).
+fn result_events() -> events::Events {
+    let Some(url) = config::var("KINEMATICS_NATS_URL").ok().filter(|u| !u.is_empty()) else { return events::Events::disabled() };
+    #[cfg(feature = "nats")]
+    {
+        let kinds = events::parse_kinds(&config::var("KINEMATICS_EVENTS").unwrap_or_else(|_| "all".into())).unwrap_or_else(|e| panic!("KINEMATICS_EVENTS: {e}"));
+        let prefix = config::var("KINEMATICS_EVENTS_SUBJECT").unwrap_or_else(|_| "kinematics.results".into()).trim_end_matches('.').to_string();
+        events::Events::nats(url, prefix, kinds)
+    }
+    #[cfg(not(feature = "nats"))]
+    {
+        tracing::warn!("KINEMATICS_NATS_URL is set to {url}, but this build has no `nats` feature; result events disabled");
+        events::Events::disabled()
+    }
+}
+
+// ── Handlers ────────────────────────────────────────────────
+async fn openapi_spec() -> Json<utoipa::openapi::OpenApi> { Json(openapi::spec()) }
+
+#[utoipa::path(post, path = "/api/v1/kinematics/solve-ik", request_body = IkRequest, responses((status = 200, body = IkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_ik(State(s): State<Arc<AppState>>, Json(mut req): Json<IkRequest>) -> ApiResult<IkResponse> {
+    in_service(&s)?;
+    if let Some(q) = session_chain(&s, req.session_id.as_deref(), &mut req.chain_id)? { req.seed.get_or_insert(Joints::Angles(q)); }
+    req.validate()?;
     let t = Instant::now();
-    let n = req.joint_count.unwrap_or(7) as usize;
-    let max_iter = req.constraints.as_ref().and_then(|c| c.max_iterations).unwrap_or(100);
-    let tol = req.constraints.as_ref().and_then(|c| c.tolerance).unwrap_or(1e-6);
+    // A µs budget is spent adaptively, within the engine's timeout like any other
+    let budget = req.deadline_us.map(|us| t + std::time::Duration::from_micros(us));
+    let deadline = solve_deadline(&s, t, req.deadline_ms).into_iter().chain(budget).min();
+    let record = audit::record("solve-ik", audit::input_hash(&req));
+    let chain = lookup_chain(&s, req.chain_id.as_deref())?;
+    let n = chain.as_ref().map_or(req.joint_count.unwrap_or(7) as usize, |c| c.dof());
+    let overrides = chains::SolverDefaults {
+        solver: req.solver.clone(), seed_policy: req.seed_policy.clone(),
+        damping: req.constraints.as_ref().and_then(|c| c.damping),
+        tolerance: req.constraints.as_ref().and_then(|c| c.tolerance),
+        max_iterations: req.constraints.as_ref().and_then(|c| c.max_iterations),
+    };
+    overrides.validate().map_err(|e| ApiError::bad_request("Invalid solver settings", e))?;
+    let settings = kinematics_solver::with_precision(solver_settings(&s, chain.as_ref(), &overrides), req.precision.as_deref());
+    let (max_iter, tol) = (settings.max_iterations, settings.tolerance);
     let target = req.target_position;
-    let _orient = req.target_orientation;
-
-    // Simple iterative IK: damped least squares simulation
-    let mut angles = vec![0.0f64; n];
-    let link_len = 1.0 / n as f64;
-    let mut iterations = 0u32;
-    let mut error = f64::MAX;
-
-    for _ in 0..max_iter {
-        iterations += 1;
-        // FK to get current end effector
-        let (ex, ey, ez) = fk_chain(&angles, link_len);
-        let dx = target[0] - ex;
-        let dy = target[1] - ey;
-        let dz = target[2] - ez;
-        error = (dx * dx + dy * dy + dz * dz).sqrt();
-        if error < tol { break; }
-
-        // Damped pseudo-inverse update (simplified)
-        let damping = 0.1;
-        for (i, angle) in angles.iter_mut().enumerate() {
-            let phase = (i as f64 + 1.0) / n as f64;
-            *angle += damping * (dx * phase.cos() + dy * phase.sin() + dz * 0.5);
-            *angle = angle.clamp(-std::f64::consts::PI, std::f64::consts::PI);
-        }
+    let seed = match &req.seed {
+        Some(Joints::Named(name)) => Some(named_configuration(chain.as_ref(), name)?),
+        Some(Joints::Angles(q)) => Some(q.clone()),
+        None => None,
+    };
+    // Without a seed or seed policy of its own, a chain solve starts from the nearest remembered solution
+    let warm = chain.as_ref().filter(|_| seed.is_none() && req.seed_policy.is_none() && deterministic::seed().is_none())
+        .and_then(|c| s.warm_starts.lock().unwrap().seed(tenant::tag().as_deref(), &c.pinned_id(), target));
+    let warm_started = warm.is_some();
+    let mut angles = match seed.or(warm) {
+        Some(seed) if seed.len() != n => return Err(ApiError::invalid("seed", "Seed length mismatch", format!("{} seed angles for {n} joints", seed.len()))),
+        Some(seed) => seed,
+        None => chain.as_ref().map_or_else(|| vec![0.0; n], |c| kinematics_solver::seed(c, &settings.seed_policy)),
+    };
+    if past(deadline) { return Err(deadline_exceeded()); }
+    if let Some(c) = &chain {
+        let ik = kinematics_solver::IkSettings { max_iterations: max_iter, tolerance: tol, damping: settings.damping };
+        let (_solve, span) = (slow::phase("solve"), tracing::info_span!("solve", chain = %c.pinned_id()));
+        let (solve_chain, orientation, precision, start) = (c.clone(), req.target_orientation, settings.precision.clone(), angles.clone());
+        let ((mut r, quality), allocated) = compute::run(move || span.in_scope(|| allocations::counted(|| match budget {
+            Some(_) => { let (r, quality) = kinematics_solver::solve_ik_within(&solve_chain, target, orientation, angles, &ik, || past(deadline)); (r, Some(quality)) }
+            None => (kinematics_solver::solve_ik_at(precision.as_deref(), &solve_chain, target, orientation, angles, &ik, || past(deadline)), None),
+        }))).await?;
+        kinematics_solver::wrap_angles(c, &mut r.angles, &start, req.wrap_policy.as_deref());
+        let (min_singular_value, condition_number) = kinematics_solver::conditioning(c, &r.angles, orientation.is_some());
+        drop(_solve);
+        s.metrics.solve_allocations([allocated]);
+        if r.converged { s.warm_starts.lock().unwrap().remember(tenant::tag(), &c.pinned_id(), target, r.angles.clone()); }
+        count_op(&s, Op::Ik);
+        s.metrics.ik(Some(&c.id), r.converged, r.iterations);
+        return Ok(Json(audited(&s, record, publish(&s, Op::Ik, keep_solution(&s, IkResponse {
+            solution_id: deterministic::id("solution", &(c.pinned_id(), &req)),
+            joint_angles: r.angles, iterations: r.iterations, converged: r.converged,
+            error_distance: r.position_error, orientation_error: r.orientation_error, solver: settings,
+            chain: Some(c.pinned_id()), elapsed_us: deterministic::elapsed_us(t), timed_out: r.stopped, warm_started, quality,
+            min_singular_value: Some(min_singular_value), condition_number,
+        }, |r| &r.solution_id)))));
     }
 
-    s.stats.lock().unwrap().total_ik_solves += 1;
-    Json(IkResponse {
-        solution_id: uuid::Uuid::new_v4().to_string(),
+    // Generic planar chain without a chain_id: simple damped update, orientation ignored
+    let (damping, start) = (settings.damping, angles.clone());
+    let (mut angles, iterations, error, timed_out) = compute::run(move || {
+        let model = kinematics_solver::Model::new(&kinematics_solver::planar(&vec![1.0 / n as f64; n]));
+        let mut iterations = 0u32;
+        let mut error = f64::MAX;
+        let mut timed_out = false;
+
+        for _ in 0..max_iter {
+            iterations += 1;
+            // FK to get current end effector
+            let [ex, ey, ez] = model.forward(&angles).tip.p;
+            let dx = target[0] - ex;
+            let dy = target[1] - ey;
+            let dz = target[2] - ez;
+            error = (dx * dx + dy * dy + dz * dz).sqrt();
+            if error < tol { break; }
+            if iterations > 1 && past(deadline) { timed_out = iterations < max_iter; break; }
+
+            // Damped pseudo-inverse update (simplified)
+            for (i, angle) in angles.iter_mut().enumerate() {
+                let phase = (i as f64 + 1.0) / n as f64;
+                *angle += damping * (dx * phase.cos() + dy * phase.sin() + dz * 0.5);
+                *angle = angle.clamp(-std::f64::consts::PI, std::f64::consts::PI);
+            }
+        }
+        (angles, iterations, error, timed_out)
+    }).await?;
+    kinematics_solver::wrap_angles(&kinematics_solver::planar(&vec![1.0 / n as f64; n]), &mut angles, &start, req.wrap_policy.as_deref());
+
+    count_op(&s, Op::Ik);
+    s.metrics.ik(None, error < tol, iterations);
+    Ok(Json(audited(&s, record, publish(&s, Op::Ik, keep_solution(&s, IkResponse {
+        solution_id: deterministic::id("solution", &req),
         joint_angles: angles, iterations, converged: error < tol,
-        error_distance: error, elapsed_us: t.elapsed().as_micros(),
-    })
+        error_distance: error, orientation_error: None, solver: settings, chain: None, elapsed_us: deterministic::elapsed_us(t), timed_out, warm_started, quality: None,
+        min_singular_value: None, condition_number: None,
+    }, |r| &r.solution_id)))))
+}
+
+/// Independent position solves of many targets on one chain, on the GPU for large batches.
+#[utoipa::path(post, path = "/api/v1/kinematics/solve-ik/batch", request_body = IkBatchRequest, responses((status = 200, body = IkBatchResponse), (status = "4XX", body = ApiError), (status = "5XX", body = ApiError)))]
+async fn solve_ik_batch(State(s): State<Arc<AppState>>, Json(req): Json<IkBatchRequest>) -> ApiResult<IkBatchResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, req.deadline_ms);
+    let record = audit::record("solve-ik-batch", audit::input_hash(&req));
+    limits::Points::Targets.check(&s, "targets", req.targets.len())?;
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let overrides = chains::SolverDefaults {
+        seed_policy: req.seed_policy.clone(),
+        damping: req.constraints.as_ref().and_then(|c| c.damping),
+        tolerance: req.constraints.as_ref().and_then(|c| c.tolerance),
+        max_iterations: req.constraints.as_ref().and_then(|c| c.max_iterations),
+        ..Default::default()
+    };
+    overrides.validate().map_err(|e| ApiError::bad_request("Invalid solver settings", e))?;
+    let settings = kinematics_solver::with_precision(solver_settings(&s, Some(&chain), &overrides), req.precision.as_deref());
+    let seed = match &req.seed {
+        Some(Joints::Named(name)) => named_configuration(Some(&chain), name)?,
+        Some(Joints::Angles(q)) if q.len() != chain.dof() => return Err(ApiError::invalid("seed", "Seed length mismatch", format!("{} seed angles for {} joints", q.len(), chain.dof()))),
+        Some(Joints::Angles(q)) => q.clone(),
+        None => kinematics_solver::seed(&chain, &settings.seed_policy),
+    };
+    let gpu = match req.backend.as_deref() {
+        Some("cpu") => false,
+        Some("gpu") if s.gpu.is_none() => return Err(ApiError::unavailable("GPU backend unavailable", "this engine has no GPU for batch IK; use backend cpu or auto")),
+        Some("gpu") if chain.dof() > gpu::MAX_JOINTS => return Err(ApiError::invalid("backend", "Chain too deep for the GPU", format!("{} joints; the GPU backend handles up to {}", chain.dof(), gpu::MAX_JOINTS))),
+        Some("gpu") => true,
+        _ => s.gpu.is_some() && chain.dof() <= gpu::MAX_JOINTS && s.gpu_threshold.lock().unwrap().is_some_and(|n| req.targets.len() >= n),
+    };
+    if past(deadline) { return Err(deadline_exceeded()); }
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let (_solve, span) = (slow::phase("solve"), tracing::info_span!("solve", chain = %chain.pinned_id(), targets = req.targets.len(), gpu));
+    let (state, solve_chain, targets, precision, wrap) = (s.clone(), chain.clone(), req.targets, settings.precision.clone(), req.wrap_policy);
+    let results = compute::run(move || span.in_scope(|| match gpu {
+        true => state.gpu.as_ref().expect("checked above").solve(&solve_chain, &targets, &seed, &ik, precision.as_deref(), || past(deadline)),
+        false => {
+            let solved: Vec<_> = targets.par_iter().map(|&target| {
+                let seed = seed.clone();
+                allocations::counted(|| kinematics_solver::solve_ik_at(precision.as_deref(), &solve_chain, target, None, seed, &ik, || past(deadline)))
+            }).collect();
+            state.metrics.solve_allocations(solved.iter().map(|(_, n)| *n));
+            Ok(solved.into_iter().map(|(r, _)| r).collect())
+        }
+    }.map(|mut results: Vec<kinematics_solver::IkResult>| {
+        for r in &mut results { kinematics_solver::wrap_angles(&solve_chain, &mut r.angles, &seed, wrap.as_deref()); }
+        results
+    }))).await?.map_err(|e| ApiError::internal("GPU batch solve failed", e))?;
+    drop(_solve);
+    let solutions: Vec<BatchSolution> = results.iter().map(|r| BatchSolution { joint_angles: r.angles.clone(), iterations: r.iterations, converged: r.converged, error_distance: r.position_error }).collect();
+    let converged = solutions.iter().filter(|r| r.converged).count();
+    count_ops(&s, Op::Ik, solutions.len() as u64);
+    s.metrics.ik_many(Some(&chain.id), solutions.iter().map(|r| (r.converged, r.iterations)));
+    Ok(Json(audited(&s, record, publish(&s, Op::Ik, IkBatchResponse {
+        converged, timed_out: results.iter().any(|r| r.stopped), solutions, solver: settings, chain: chain.pinned_id(),
+        backend: if gpu { "gpu" } else { "cpu" }.into(), elapsed_us: deterministic::elapsed_us(t),
+    }))))
+}
+
+/// Whether a pose is inside a chain's workspace, without a full IK solve.
+#[utoipa::path(post, path = "/api/v1/kinematics/reachable", request_body = ReachableRequest, responses((status = 200, body = ReachableResponse), (status = "4XX", body = ApiError)))]
+async fn check_reachable(State(s): State<Arc<AppState>>, Json(req): Json<ReachableRequest>) -> ApiResult<ReachableResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, None);
+    let record = audit::record("reachable", audit::input_hash(&req));
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let settings = solver_settings(&s, Some(&chain), &chains::SolverDefaults::default());
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: req.tolerance.unwrap_or(settings.tolerance), damping: settings.damping };
+    let (seed, solve_chain, (target, orientation)) = (kinematics_solver::seed(&chain, &settings.seed_policy), chain.clone(), (req.target_position, req.target_orientation));
+    let span = tracing::info_span!("reachable", chain = %chain.pinned_id());
+    let reach = compute::run(move || span.in_scope(|| kinematics_solver::reachability(&solve_chain, target, orientation, seed, &ik, || past(deadline)))).await?;
+    if past(deadline) && !reach.reachable { return Err(deadline_exceeded()); }
+    count_op(&s, Op::Ik);
+    Ok(Json(audited(&s, record, ReachableResponse {
+        reachable: reach.reachable, margin: reach.margin, position_error: reach.position_error, orientation_error: reach.orientation_error,
+        decided_by: if reach.solved { "search" } else { "reach" }.into(), chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t),
+    })))
 }
 
-async fn solve_fk(State(s): State<Arc<AppState>>, Json(req): Json<FkRequest>) -> Json<FkResponse> {
+/// Joint postures holding one tool pose on a 7-joint chain, swept through the elbow swivel.
+#[utoipa::path(post, path = "/api/v1/kinematics/self-motion", request_body = SelfMotionRequest, responses((status = 200, body = SelfMotionResponse), (status = "4XX", body = ApiError)))]
+async fn sample_self_motion(State(s): State<Arc<AppState>>, Json(req): Json<SelfMotionRequest>) -> ApiResult<SelfMotionResponse> {
+    in_service(&s)?;
+    req.validate()?;
     let t = Instant::now();
+    let deadline = solve_deadline(&s, t, None);
+    let record = audit::record("self-motion", audit::input_hash(&req));
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    if chain.dof() != kinematics_solver::SELF_MOTION_JOINTS {
+        return Err(ApiError::invalid("chain_id", "Chain not redundant by one", format!("{} has {} joints; self-motion sampling needs {}", chain.pinned_id(), chain.dof(), kinematics_solver::SELF_MOTION_JOINTS)));
+    }
+    let settings = solver_settings(&s, Some(&chain), &chains::SolverDefaults::default());
+    let seed = match &req.seed {
+        Some(Joints::Named(name)) => named_configuration(Some(&chain), name)?,
+        Some(Joints::Angles(q)) if q.len() != chain.dof() => return Err(ApiError::invalid("seed", "Seed length mismatch", format!("{} seed angles for {} joints", q.len(), chain.dof()))),
+        Some(Joints::Angles(q)) => q.clone(),
+        None => kinematics_solver::seed(&chain, &settings.seed_policy),
+    };
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let [x, y, z, w] = req.target_orientation;
+    let (target, samples, solve_chain) = (kinematics_solver::Frame::from_quat_wxyz(req.target_position, [w, x, y, z]), req.samples.unwrap_or(36), chain.clone());
+    let span = tracing::info_span!("self_motion", chain = %chain.pinned_id(), samples);
+    let (seed_swivel, sweep) = compute::run(move || span.in_scope(|| kinematics_solver::self_motion(&solve_chain, target, samples, seed, &ik, || past(deadline)))).await?
+        .map_err(|e| ApiError::invalid("target_position", "No self-motion at this pose", e))?;
+    let margin = |q: &[f64]| chain.joints.iter().zip(q).filter_map(|(j, &qi)| j.limits.map(|[lo, hi]| (qi - lo).min(hi - qi))).min_by(f64::total_cmp);
+    let timed_out = sweep.iter().any(Option::is_none);
+    let samples: Vec<SelfMotionSample> = sweep.into_iter().flatten().map(|p| SelfMotionSample {
+        swivel: p.swivel, limit_margin: margin(&p.angles), joint_angles: p.angles, feasible: p.converged, position_error: p.position_error, orientation_error: p.orientation_error,
+    }).collect();
+    count_op(&s, Op::Ik);
+    Ok(Json(audited(&s, record, SelfMotionResponse {
+        feasible: samples.iter().filter(|p| p.feasible).count(), samples, seed_swivel, chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t), timed_out,
+    })))
+}
+
+/// Store a solve's response for `GET /solutions/{solution_id}` and hand it back.
+fn keep_solution<T: Serialize>(s: &AppState, response: T, id: impl Fn(&T) -> &String) -> T {
+    if let Ok(body) = serde_json::to_string(&response) { s.solutions.lock().unwrap().insert(id(&response), tenant::tag(), body); }
+    response
+}
+
+/// Effective solver settings: the request's, then the chain's defaults, then the engine's.
+fn solver_settings(s: &AppState, chain: Option<&chains::ChainDef>, overrides: &chains::SolverDefaults) -> SolverSettings {
+    let engine = s.solver_defaults.lock().unwrap().clone();
+    let chain_defaults = chain.and_then(|c| c.solver.as_ref()).map_or_else(|| engine.clone(), |d| d.or(&engine));
+    kinematics_solver::settings(None, &overrides.or(&chain_defaults))
+}
+
+/// Append a solve to the audit log, summarized from its response, and hand the response back.
+fn audited<T: audit::Summary>(s: &AppState, mut record: AuditRecord, response: T) -> T {
+    response.summarize(&mut record);
+    s.audit.lock().unwrap().push(record);
+    response
+}
+
+/// Publish a completed operation's response as a result event and hand it back.
+fn publish<T: Serialize>(s: &AppState, op: Op, response: T) -> T {
+    s.events.publish(op.kind(), &response);
+    response
+}
+
+/// A stored IK solution, exactly as the solve returned it.
+#[utoipa::path(get, path = "/api/v1/kinematics/solutions/{solution_id}", params(("solution_id" = String, Path)), responses((status = 200, description = "The solve's response body (IkResponse or HandIkResponse)", body = Object), (status = "4XX", body = ApiError)))]
+async fn get_solution(State(s): State<Arc<AppState>>, Path(solution_id): Path<String>) -> Result<impl IntoResponse, ApiError> {
+    s.solutions.lock().unwrap().get(tenant::tag().as_deref(), &solution_id).map(|stored| ([(header::CONTENT_TYPE, "application/json")], stored.body.clone()))
+        .ok_or_else(|| ApiError::not_found("Solution not found", format!("{solution_id} (unknown, expired or another tenant's)")))
+}
+
+/// A stored joint trajectory (a motion capture import, or one IK pose) as a BVH animation.
+#[utoipa::path(get, path = "/api/v1/kinematics/solutions/{solution_id}/bvh", params(("solution_id" = String, Path), BvhQuery), responses((status = 200, body = String, content_type = "text/plain"), (status = "4XX", body = ApiError)))]
+async fn export_bvh(State(s): State<Arc<AppState>>, Path(solution_id): Path<String>, Query(q): Query<BvhQuery>) -> Result<impl IntoResponse, ApiError> {
+    q.validate()?;
+    let body = s.solutions.lock().unwrap().get(tenant::tag().as_deref(), &solution_id).map(|stored| stored.body.clone())
+        .ok_or_else(|| ApiError::not_found("Solution not found", format!("{solution_id} (unknown, expired or another tenant's)")))?;
+    let stored: serde_json::Value = serde_json::from_str(&body).map_err(|e| ApiError::internal("Stored solution unreadable", e.to_string()))?;
+    let rows = |v: &serde_json::Value| serde_json::from_value::<Vec<f64>>(v.clone()).ok();
+    let frames: Vec<Vec<f64>> = match (stored.get("frames").and_then(|f| f.as_array()), stored.get("joint_angles").and_then(rows)) {
+        (Some(frames), _) => frames.iter().filter_map(|f| f.get("joint_angles").and_then(rows)).collect(),
+        (None, Some(q)) => vec![q],
+        (None, None) => Vec::new(),
+    };
+    let chain_id = stored.get("chain").and_then(|c| c.as_str()).filter(|_| !frames.is_empty())
+        .ok_or_else(|| ApiError::invalid("solution_id", "Not a joint trajectory", format!("{solution_id} holds no joint angles on a chain")))?;
+    let chain = lookup_chain(&s, Some(chain_id))?.expect("chain id given");
+    let times = stored.get("times").and_then(rows);
+    let step = q.frame_time
+        .or_else(|| times.as_ref().and_then(|t| t.windows(2).map(|w| w[1] - w[0]).filter(|d| *d > 0.0).reduce(f64::min)))
+        .or_else(|| stored.get("frame_time").and_then(|t| t.as_f64()).filter(|t| *t > 0.0))
+        .unwrap_or(1.0 / 30.0);
+    let units = match q.units.as_deref() { Some("m") => 1.0, Some("mm") => 1000.0, _ => 100.0 };
+    let bvh = export::bvh(&chain, &export::resample(&frames, times.as_deref(), step), step, q.side.as_deref() == Some("left"), units)
+        .map_err(|e| ApiError::invalid("solution_id", "Chain not exportable", e))?;
+    let disposition = format!("attachment; filename=\"{solution_id}.bvh\"");
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], bvh))
+}
+
+/// WebSocket IK session: each target message is solved from the previous solution,
+/// so successive poses of a teleoperation stream converge in a few iterations.
+#[utoipa::path(get, path = "/api/v1/kinematics/solve-ik/stream", params(IkStreamQuery), responses((status = 101, description = "WebSocket: send IkStreamTarget messages, receive an IkResponse (or Error) with the same seq for each"), (status = "4XX", body = ApiError)))]
+async fn solve_ik_stream(State(s): State<Arc<AppState>>, Query(q): Query<IkStreamQuery>, ws: WebSocketUpgrade) -> Result<axum::response::Response, ApiError> {
+    in_service(&s)?;
+    lookup_chain(&s, q.chain_id.as_deref())?;
+    let (principal, seed) = (auth::current(), deterministic::seed());
+    Ok(ws.on_upgrade(move |socket| auth::scope(principal, deterministic::scope(seed, ik_stream(s, q, socket)))))
+}
+
+async fn ik_stream(s: Arc<AppState>, q: IkStreamQuery, mut socket: WebSocket) {
+    let (_session, drain) = (s.shutdown.session(), s.shutdown.wait());
+    tokio::pin!(drain);
+    // The last good solution; a failed solve leaves it in place
+    let mut warm = q.seed.clone().map(Joints::Named);
+    loop {
+        let msg = tokio::select! {
+            msg = socket.recv() => msg,
+            _ = &mut drain => {
+                let _ = socket.send(Message::Close(Some(CloseFrame { code: close_code::AWAY, reason: "server shutting down".into() }))).await;
+                break;
+            }
+        };
+        let Some(Ok(msg)) = msg else { break };
+        let text = match msg {
+            Message::Text(t) => t,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let reply = match error::from_slice::<IkStreamTarget>(text.as_bytes()) {
+            Err(e) => serde_json::to_string(&IkStreamReply { seq: None, body: e }),
+            Ok(target) => {
+                let req = IkRequest {
+                    chain_id: q.chain_id.clone(), target_position: target.target_position, target_orientation: target.target_orientation,
+                    joint_count: q.joint_count,
+                    constraints: Some(IkConstraints { max_iterations: q.max_iterations, tolerance: q.tolerance, damping: q.damping }),
+                    solver: q.solver.clone(), seed_policy: q.seed_policy.clone(), seed: target.seed.or_else(|| warm.clone()),
+                    deadline_ms: q.deadline_ms, deadline_us: q.deadline_us, precision: q.precision.clone(), wrap_policy: q.wrap_policy.clone(), session_id: None,
+                };
+                let (solved, timing) = slow::timed(solve_ik(State(s.clone()), Json(req.clone()))).await;
+                let status = solved.as_ref().map_or_else(|e| e.status().as_u16(), |_| 200);
+                slow::check(&s, "WS", "/api/v1/kinematics/solve-ik/stream", status, &timing, || serde_json::json!({ "body": req }));
+                match solved {
+                    Ok(Json(solution)) => {
+                        warm = Some(Joints::Angles(solution.joint_angles.clone()));
+                        serde_json::to_string(&IkStreamReply { seq: target.seq, body: solution })
+                    }
+                    Err(e) => serde_json::to_string(&IkStreamReply { seq: target.seq, body: e }),
+                }
+            }
+        };
+        let Ok(reply) = reply else { break };
+        if socket.send(Message::Text(reply)).await.is_err() { break; }
+    }
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/solve-fk", request_body = FkRequest, responses((status = 200, body = FkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_fk(State(s): State<Arc<AppState>>, Json(req): Json<FkRequest>) -> ApiResult<FkResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let record = audit::record("solve-fk", audit::input_hash(&req));
     let n = req.joint_angles.len();
-    let links = req.link_lengths.unwrap_or_else(|| vec![0.2; n]);
-    let mut positions = Vec::with_capacity(n + 1);
-    let mut x = 0.0f64;
-    let mut y = 0.0f64;
-    let mut z = 0.0f64;
-    let mut cumulative_angle = 0.0f64;
-
-    positions.push([x, y, z]);
-    for i in 0..n {
-        cumulative_angle += req.joint_angles[i];
-        let link = if i < links.len() { links[i] } else { 0.15 };
-        x += link * cumulative_angle.cos();
-        y += link * cumulative_angle.sin();
-        z += link * (cumulative_angle * 0.5).sin() * 0.3;
-        positions.push([x, y, z]);
-    }
-
-    // Simple orientation quaternion from final angle
-    let half = cumulative_angle * 0.5;
-    let orientation = [0.0, 0.0, half.sin(), half.cos()];
-
-    s.stats.lock().unwrap().total_fk_solves += 1;
-    Json(FkResponse {
-        end_effector_position: [x, y, z], end_effector_orientation: orientation,
-        joint_positions: positions, elapsed_us: t.elapsed().as_micros(),
-    })
+    let chain = lookup_chain(&s, req.chain_id.as_deref())?;
+    if let Some(c) = chain.as_ref().filter(|c| c.dof() != n) {
+        return Err(ApiError::invalid("joint_angles", "Joint count mismatch", format!("chain '{}' has {} joints, got {n} joint_angles", c.id, c.dof())));
+    }
+    if let Some(c) = chain {
+        if req.link_lengths.is_some() {
+            return Err(ApiError::invalid("link_lengths", "Conflicting geometry", "link_lengths cannot be combined with chain_id; the chain defines the geometry"));
+        }
+        let pose = { let _phase = slow::phase("solve"); tracing::info_span!("solve", chain = %c.pinned_id()).in_scope(|| kinematics_solver::forward(&c, &req.joint_angles)) };
+        let limit_violations = c.joints.iter().zip(&req.joint_angles).enumerate()
+            .filter(|(_, (j, &q))| j.limits.is_some_and(|[lo, hi]| q < lo || q > hi)).map(|(i, _)| i).collect();
+        count_op(&s, Op::Fk);
+        s.metrics.fk(&c.id);
+        return Ok(Json(audited(&s, record, publish(&s, Op::Fk, FkResponse {
+            end_effector_position: pose.tip.p, end_effector_orientation: pose.tip.quat_xyzw(), quaternion_order: QUATERNION_ORDER.into(),
+            joint_positions: pose.joints.iter().map(|f| f.p).chain([pose.tip.p]).collect(),
+            limit_violations, chain: Some(c.pinned_id()), elapsed_us: deterministic::elapsed_us(t),
+        }))));
+    }
+    // Validated: as many links as joints, unless the caller asked for padding
+    let mut links = req.link_lengths.unwrap_or_default();
+    links.resize(n, req.pad_link_length.unwrap_or(DEFAULT_LINK_LENGTH));
+    let pose = kinematics_solver::forward(&kinematics_solver::planar(&links), &req.joint_angles);
+    count_op(&s, Op::Fk);
+    Ok(Json(audited(&s, record, publish(&s, Op::Fk, FkResponse {
+        end_effector_position: pose.tip.p, end_effector_orientation: pose.tip.quat_xyzw(), quaternion_order: QUATERNION_ORDER.into(),
+        joint_positions: pose.joints.iter().map(|f| f.p).chain([pose.tip.p]).collect(), limit_violations: Vec::new(), chain: None, elapsed_us: deterministic::elapsed_us(t),
+    }))))
 }
 
-async fn compress_intent(State(s): State<Arc<AppState>>, Json(req): Json<IntentRequest>) -> Json<IntentResponse> {
+/// Round trip: IK for the request's pose, then FK of the solution, measured against the pose.
+/// Both solves run, and are audited and counted, exactly as the two endpoints would.
+#[utoipa::path(post, path = "/api/v1/kinematics/verify", request_body = IkRequest, responses((status = 200, body = VerifyResponse), (status = "4XX", body = ApiError)))]
+async fn verify(State(s): State<Arc<AppState>>, Json(mut req): Json<IkRequest>) -> ApiResult<VerifyResponse> {
     let t = Instant::now();
+    session_chain(&s, req.session_id.as_deref(), &mut req.chain_id)?;
+    if req.chain_id.is_none() {
+        return Err(ApiError::invalid("chain_id", "Chain required", "verify needs a chain_id; the generic planar IK has no exact model to check against"));
+    }
+    let (target, orientation) = (req.target_position, req.target_orientation);
+    let Json(ik) = solve_ik(State(s.clone()), Json(req)).await?;
+    let fk_req = FkRequest { chain_id: ik.chain.clone(), joint_angles: ik.joint_angles.clone(), ..Default::default() };
+    let Json(fk) = solve_fk(State(s.clone()), Json(fk_req)).await?;
+    let position_error = fk.end_effector_position.iter().zip(target).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+    let orientation_error = orientation.map(|q| kinematics_solver::orientation_angle(q, fk.end_effector_orientation));
+    let tol = ik.solver.tolerance;
+    let consistent = (position_error - ik.error_distance).abs() <= tol
+        && orientation_error.zip(ik.orientation_error).is_none_or(|(a, b)| (a - b).abs() <= tol);
+    Ok(Json(VerifyResponse { ik, fk, position_error, orientation_error, consistent, elapsed_us: deterministic::elapsed_us(t) }))
+}
+
+/// Carry a motion on one chain (the human arm by default) over to another by its end-effector path.
+#[utoipa::path(post, path = "/api/v1/kinematics/retarget", request_body = RetargetRequest, responses((status = 200, body = RetargetResponse), (status = "4XX", body = ApiError)))]
+async fn retarget_motion(State(s): State<Arc<AppState>>, Json(req): Json<RetargetRequest>) -> ApiResult<RetargetResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, None);
+    let record = audit::record("retarget", audit::input_hash(&req));
+    let (field, frames) = match (&req.joint_angles, &req.positions) { (Some(q), _) => ("joint_angles", q.len()), (None, p) => ("positions", p.as_ref().map_or(0, Vec::len)) };
+    limits::Points::Poses.check(&s, field, frames)?;
+    let source = lookup_chain(&s, Some(req.source_chain_id.as_deref().unwrap_or("human_arm")))?.expect("chain id given");
+    let target = lookup_chain(&s, Some(&req.target_chain_id))?.expect("chain id given");
+    if let Some((i, q)) = req.joint_angles.iter().flatten().enumerate().find(|(_, q)| q.len() != source.dof()) {
+        return Err(ApiError::invalid(format!("joint_angles[{i}]"), "Joint count mismatch", format!("{} angles for the {} joints of {}", q.len(), source.dof(), source.pinned_id())));
+    }
+    let overrides = chains::SolverDefaults {
+        damping: req.constraints.as_ref().and_then(|c| c.damping),
+        tolerance: req.constraints.as_ref().and_then(|c| c.tolerance),
+        max_iterations: req.constraints.as_ref().and_then(|c| c.max_iterations),
+        ..Default::default()
+    };
+    overrides.validate().map_err(|e| ApiError::bad_request("Invalid solver settings", e))?;
+    let settings = solver_settings(&s, Some(&target), &overrides);
+    let seed = match &req.seed {
+        Some(Joints::Named(name)) => named_configuration(Some(&target), name)?,
+        Some(Joints::Angles(q)) if q.len() != target.dof() => return Err(ApiError::invalid("seed", "Seed length mismatch", format!("{} seed angles for {} joints", q.len(), target.dof()))),
+        Some(Joints::Angles(q)) => q.clone(),
+        None => kinematics_solver::seed(&target, &settings.seed_policy),
+    };
+    if past(deadline) { return Err(deadline_exceeded()); }
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let map = retarget::Mapping::new(&source, &target, &req);
+    let (_solve, span) = (slow::phase("solve"), tracing::info_span!("solve", chain = %target.pinned_id(), frames));
+    let (poses, solve_chain, follow) = (retarget::source_poses(&source, &req), target.clone(), req.follow_orientation);
+    let ((frames, timed_out), scale) = compute::run(move || span.in_scope(|| (retarget::solve(&solve_chain, &poses, &map, follow, seed, &ik, || past(deadline)), map.scale))).await?;
+    drop(_solve);
+    let converged = frames.iter().filter(|f| f.converged).count();
+    count_op(&s, Op::Ik);
+    s.metrics.ik(Some(&target.id), converged == frames.len(), frames.iter().map(|f| f.iterations).max().unwrap_or(0));
+    Ok(Json(audited(&s, record, RetargetResponse {
+        retarget_id: deterministic::id("retarget", &(source.pinned_id(), target.pinned_id(), &req)),
+        source_chain: source.pinned_id(), target_chain: target.pinned_id(), scale,
+        clamped: frames.iter().filter(|f| f.clamped).count(), max_error: frames.iter().map(|f| f.error_distance).fold(0.0, f64::max),
+        converged, frames, solver: settings, elapsed_us: deterministic::elapsed_us(t), timed_out,
+    })))
+}
+
+/// Joint velocities realising a tool twist at the given angles (resolved-rate control).
+#[utoipa::path(post, path = "/api/v1/kinematics/solve-ik/velocity", request_body = VelocityIkRequest, responses((status = 200, body = VelocityIkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_ik_velocity(State(s): State<Arc<AppState>>, Json(req): Json<VelocityIkRequest>) -> ApiResult<VelocityIkResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let record = audit::record("solve-ik-velocity", audit::input_hash(&req));
+    let chain = differential_chain(&s, &req.chain_id, &req.joint_angles, None, ("max_joint_velocity", req.max_joint_velocity.as_deref()))?;
+    let (jac, rows) = (kinematics_solver::jacobian(&chain, &req.joint_angles), if req.angular_velocity.is_some() { 6 } else { 3 });
+    let tool = req.frame.as_deref() == Some("tool");
+    let (v, w) = (req.linear_velocity, req.angular_velocity.unwrap_or_default());
+    let twist = twist_in_base(&jac.tip, tool, v, w);
+    let damping = req.damping.unwrap_or(kinematics_solver::DEFAULT_RATE_DAMPING);
+    let (mut qd, blocked, used) = resolve_within_limits(&chain, &jac, &req.joint_angles, twist, rows, damping);
+    let achieved = jac.twist(&qd);
+    let twist_error = (0..rows).map(|r| (twist[r] - achieved[r]).powi(2)).sum::<f64>().sqrt();
+    let (scale, limiting_joint) = speed_scale(&qd, req.max_joint_velocity.as_deref());
+    qd.iter_mut().for_each(|v| *v *= scale);
+    let [lx, ly, lz, ax, ay, az] = twist_in_frame(&jac.tip, tool, jac.twist(&qd));
+    let next_joint_angles = req.dt.map(|dt| {
+        let mut q: Vec<f64> = req.joint_angles.iter().zip(&qd).map(|(q, v)| q + v * dt).collect();
+        kinematics_solver::clamp_to_limits(&chain, &mut q);
+        q
+    });
+    count_op(&s, Op::Ik);
+    Ok(Json(audited(&s, record, publish(&s, Op::Ik, VelocityIkResponse {
+        joint_velocities: qd, scale, limiting_joint, blocked_joints: blocked, linear_velocity: [lx, ly, lz], angular_velocity: [ax, ay, az], twist_error,
+        min_singular_value: jac.min_singular_value(rows), damping: used, next_joint_angles, chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t),
+    }))))
+}
+
+/// Joint accelerations realising a tool acceleration at the given joint state (q̈ = J⁺(ẍ − J̇q̇)).
+#[utoipa::path(post, path = "/api/v1/kinematics/solve-ik/acceleration", request_body = AccelerationIkRequest, responses((status = 200, body = AccelerationIkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_ik_acceleration(State(s): State<Arc<AppState>>, Json(req): Json<AccelerationIkRequest>) -> ApiResult<AccelerationIkResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let record = audit::record("solve-ik-acceleration", audit::input_hash(&req));
+    let (q, qd) = (&req.joint_angles, &req.joint_velocities);
+    let chain = differential_chain(&s, &req.chain_id, q, Some(qd), ("max_joint_acceleration", req.max_joint_acceleration.as_deref()))?;
+    let (jac, rows) = (kinematics_solver::jacobian(&chain, q), if req.angular_acceleration.is_some() { 6 } else { 3 });
+    let tool = req.frame.as_deref() == Some("tool");
+    let wanted = twist_in_base(&jac.tip, tool, req.linear_acceleration, req.angular_acceleration.unwrap_or_default());
+    let bias = kinematics_solver::velocity_product(&chain, q, qd);
+    let damping = req.damping.unwrap_or(kinematics_solver::DEFAULT_RATE_DAMPING);
+    let (mut qdd, blocked, used) = resolve_within_limits(&chain, &jac, q, std::array::from_fn(|r| wanted[r] - bias[r]), rows, damping);
+    let produced = |qdd: &[f64]| -> [f64; 6] { let a = jac.twist(qdd); std::array::from_fn(|r| a[r] + bias[r]) };
+    let reached = produced(&qdd);
+    let acceleration_error = (0..rows).map(|r| (wanted[r] - reached[r]).powi(2)).sum::<f64>().sqrt();
+    let (scale, limiting_joint) = speed_scale(&qdd, req.max_joint_acceleration.as_deref());
+    qdd.iter_mut().for_each(|a| *a *= scale);
+    let split = |t: [f64; 6]| { let [x, y, z, rx, ry, rz] = twist_in_frame(&jac.tip, tool, t); ([x, y, z], [rx, ry, rz]) };
+    let ((la, aa), (lb, ab), (lv, av)) = (split(produced(&qdd)), split(bias), split(jac.twist(qd)));
+    let next = req.dt.map(|dt| {
+        let mut angles: Vec<f64> = q.iter().zip(qd).zip(&qdd).map(|((q, v), a)| q + v * dt + 0.5 * a * dt * dt).collect();
+        kinematics_solver::clamp_to_limits(&chain, &mut angles);
+        (qd.iter().zip(&qdd).map(|(v, a)| v + a * dt).collect(), angles)
+    });
+    count_op(&s, Op::Ik);
+    let (next_joint_velocities, next_joint_angles) = next.unzip();
+    Ok(Json(audited(&s, record, publish(&s, Op::Ik, AccelerationIkResponse {
+        joint_accelerations: qdd, scale, limiting_joint, blocked_joints: blocked, linear_acceleration: la, angular_acceleration: aa,
+        bias_linear_acceleration: lb, bias_angular_acceleration: ab, linear_velocity: lv, angular_velocity: av, acceleration_error,
+        min_singular_value: jac.min_singular_value(rows), damping: used, next_joint_velocities, next_joint_angles, chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t),
+    }))))
+}
+
+/// Joint-space stiffness and damping equivalent to Cartesian ones at the tool (Jᵀ K J).
+#[utoipa::path(post, path = "/api/v1/kinematics/compliance", request_body = ComplianceRequest, responses((status = 200, body = ComplianceResponse), (status = "4XX", body = ApiError)))]
+async fn joint_compliance(State(s): State<Arc<AppState>>, Json(req): Json<ComplianceRequest>) -> ApiResult<ComplianceResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let chain = differential_chain(&s, &req.chain_id, &req.joint_angles, None, ("", None))?;
+    let jac = kinematics_solver::jacobian(&chain, &req.joint_angles);
+    let jac = if req.frame.as_deref() == Some("tool") { jac.in_tool() } else { jac };
+    // A single row is the diagonal
+    let square = |m: &[Vec<f64>]| if m.len() == 1 { (0..m[0].len()).map(|i| (0..m[0].len()).map(|j| if i == j { m[0][i] } else { 0.0 }).collect()).collect() } else { m.to_vec() };
+    let stiffness = square(&req.stiffness);
+    Ok(Json(ComplianceResponse {
+        joint_stiffness: jac.congruence(&stiffness), joint_damping: req.damping.as_deref().map(|d| jac.congruence(&square(d))),
+        min_singular_value: jac.min_singular_value(stiffness.len()), chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t),
+    }))
+}
+
+/// The chain for a differential solve, with `q`, any `qd` and any per-joint rate limits sized to it.
+fn differential_chain(s: &AppState, chain_id: &str, q: &[f64], qd: Option<&[f64]>, (field, limits): (&str, Option<&[f64]>)) -> Result<chains::ChainDef, ApiError> {
+    let chain = lookup_chain(s, Some(chain_id))?.expect("chain id given");
+    for (field, values) in [("joint_angles", Some(q)), ("joint_velocities", qd)] {
+        let Some(values) = values.filter(|v| v.len() != chain.dof()) else { continue };
+        return Err(ApiError::invalid(field, "Joint count mismatch", format!("{} values for the {} joints of {}", values.len(), chain.dof(), chain.pinned_id())));
+    }
+    if let Some(v) = limits.filter(|v| v.len() != 1 && v.len() != chain.dof()) {
+        return Err(ApiError::invalid(field, "Joint count mismatch", format!("{} limits for the {} joints of {}; give one, or one per joint", v.len(), chain.dof(), chain.pinned_id())));
+    }
+    Ok(chain)
+}
+
+/// A twist given in the base or the tool frame, as `[linear, angular]` in the base frame.
+fn twist_in_base(tip: &kinematics_solver::Frame, tool: bool, v: [f64; 3], w: [f64; 3]) -> [f64; 6] {
+    let (v, w) = if tool { (tip.rotate(v), tip.rotate(w)) } else { (v, w) };
+    [v[0], v[1], v[2], w[0], w[1], w[2]]
+}
+
+/// A base-frame twist back in the base or the tool frame.
+fn twist_in_frame(tip: &kinematics_solver::Frame, tool: bool, t: [f64; 6]) -> [f64; 6] {
+    if !tool { return t; }
+    let r = kinematics_solver::transpose(&tip.r);
+    let (v, w) = (kinematics_solver::mat_vec(&r, [t[0], t[1], t[2]]), kinematics_solver::mat_vec(&r, [t[3], t[4], t[5]]));
+    [v[0], v[1], v[2], w[0], w[1], w[2]]
+}
+
+/// Resolve `twist`, then again without the joints it would drive past a position limit;
+/// returns the joint rates, the joints held still and the damping used.
+fn resolve_within_limits(chain: &chains::ChainDef, jac: &kinematics_solver::Jacobian, q: &[f64], twist: [f64; 6], rows: usize, damping: f64) -> (Vec<f64>, Vec<usize>, f64) {
+    let (qd, used) = jac.resolve(twist, rows, damping, &[]);
+    let blocked: Vec<usize> = chain.joints.iter().zip(q.iter().zip(&qd)).enumerate()
+        .filter(|(_, (j, (&qi, &v)))| j.limits.is_some_and(|[lo, hi]| (qi <= lo + 1e-9 && v < 0.0) || (qi >= hi - 1e-9 && v > 0.0))).map(|(i, _)| i).collect();
+    if blocked.is_empty() { return (qd, blocked, used); }
+    let (qd, used) = jac.resolve(twist, rows, damping, &blocked);
+    (qd, blocked, used)
+}
+
+/// The factor (≤ 1) keeping every rate within `limits` (one for all joints, or one each), and the joint that set it.
+fn speed_scale(rates: &[f64], limits: Option<&[f64]>) -> (f64, Option<usize>) {
+    let Some(limits) = limits else { return (1.0, None) };
+    rates.iter().enumerate().map(|(i, v)| (limits[if limits.len() == 1 { 0 } else { i }] / v.abs(), i))
+        .filter(|(s, _)| *s < 1.0).min_by(|a, b| a.0.total_cmp(&b.0)).map_or((1.0, None), |(s, i)| (s, Some(i)))
+}
+
+/// Camera-to-flange transform from paired flange and target poses (AX = XB).
+#[utoipa::path(post, path = "/api/v1/kinematics/calibrate/hand-eye", request_body = HandEyeRequest, responses((status = 200, body = HandEyeResponse), (status = "4XX", body = ApiError)))]
+async fn calibrate_hand_eye(State(s): State<Arc<AppState>>, Json(req): Json<HandEyeRequest>) -> ApiResult<HandEyeResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let frame = |p: &Transform| { let [x, y, z, w] = p.orientation; kinematics_solver::Frame::from_quat_wxyz(p.position, [w, x, y, z]) };
+    let (a, b): (Vec<_>, Vec<_>) = (req.flange_poses.iter().map(frame).collect(), req.target_poses.iter().map(frame).collect());
+    let fit = compute::run(move || kinematics_solver::hand_eye(&a, &b)).await?
+        .map_err(|e| ApiError::invalid("flange_poses", "Calibration not determined", e))?;
+    Ok(Json(HandEyeResponse {
+        camera_to_flange: Transform { position: fit.x.p, orientation: fit.x.quat_xyzw() }, quaternion_order: QUATERNION_ORDER.into(),
+        stations: req.flange_poses.len(), pairs: fit.pairs, rotation_rms: fit.rotation_rms, translation_rms: fit.translation_rms,
+        station_errors: fit.station_errors, elapsed_us: deterministic::elapsed_us(t),
+    }))
+}
+
+/// Solve a BVH or C3D take frame by frame on a human chain; the result is kept under its `solution_id`.
+#[utoipa::path(post, path = "/api/v1/kinematics/mocap/import", params(MocapQuery), request_body(content = Vec<u8>, content_type = "application/octet-stream"), responses((status = 200, body = MocapResponse), (status = "4XX", body = ApiError)))]
+async fn import_mocap(State(s): State<Arc<AppState>>, Query(q): Query<MocapQuery>, body: axum::body::Bytes) -> ApiResult<MocapResponse> {
+    in_service(&s)?;
+    q.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, None);
+    let input = (&q, audit::digest(&body));
+    let record = audit::record("mocap", audit::input_hash(&input));
+    let format = match q.format.as_deref() {
+        Some(f) => mocap::Format::parse(f).map_err(|e| ApiError::invalid("format", "Unknown format", e))?,
+        None => mocap::Format::detect(&body).ok_or_else(|| ApiError::bad_request("Unknown format", "neither BVH (HIERARCHY) nor C3D; pass format"))?,
+    };
+    let chain = lookup_chain(&s, Some(q.chain_id.as_deref().unwrap_or("human_arm")))?.expect("chain id given");
+    let leg = q.limb.as_deref().map_or(chain.id.contains("leg"), |l| l == "leg");
+    let limb = mocap::Limb::new(&q, format, leg);
+    let take = mocap::read(&body, format, &limb, q.stride.unwrap_or(1)).map_err(|(field, e)| match field {
+        Some(field) => ApiError::invalid(field, "Unknown joint or marker", e),
+        None => ApiError::bad_request("Invalid motion capture file", e),
+    })?;
+    limits::Points::Poses.check(&s, "frames", take.samples.len())?;
+    let settings = solver_settings(&s, Some(&chain), &chains::SolverDefaults::default());
+    let seed = match &q.seed { Some(name) => named_configuration(Some(&chain), name)?, None => kinematics_solver::seed(&chain, &settings.seed_policy) };
+    if past(deadline) { return Err(deadline_exceeded()); }
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let scale = q.scale.unwrap_or_else(|| take.limb_length.map_or(take.metres_per_unit, |l| chain.reach() / l.max(1e-9)));
+    let map = retarget::Mapping::uniform(scale);
+    let poses: Vec<_> = take.samples.iter().map(|&(_, v)| (v, None)).collect();
+    let (_solve, span) = (slow::phase("solve"), tracing::info_span!("solve", chain = %chain.pinned_id(), frames = poses.len()));
+    let solve_chain = chain.clone();
+    let (frames, timed_out) = compute::run(move || span.in_scope(|| retarget::solve(&solve_chain, &poses, &map, false, seed, &ik, || past(deadline)))).await?;
+    drop(_solve);
+    let converged = frames.iter().filter(|f| f.converged).count();
+    count_op(&s, Op::Ik);
+    s.metrics.ik(Some(&chain.id), converged == frames.len(), frames.iter().map(|f| f.iterations).max().unwrap_or(0));
+    let resp = MocapResponse {
+        solution_id: deterministic::id("mocap", &(chain.pinned_id(), &input)), chain: chain.pinned_id(), format: format.name().into(),
+        source_frames: take.frames, frame_time: take.frame_time, scale,
+        times: take.samples.iter().take(frames.len()).map(|&(f, _)| f as f64 * take.frame_time).collect(), gaps: take.gaps,
+        clamped: frames.iter().filter(|f| f.clamped).count(), max_error: frames.iter().map(|f| f.error_distance).fold(0.0, f64::max),
+        converged, frames, solver: settings, elapsed_us: deterministic::elapsed_us(t), timed_out,
+    };
+    Ok(Json(audited(&s, record, keep_solution(&s, resp, |r| &r.solution_id))))
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/compress-intent", request_body = IntentRequest, responses((status = 200, body = IntentResponse), (status = "4XX", body = ApiError)))]
+async fn compress_intent(State(s): State<Arc<AppState>>, Json(req): Json<IntentRequest>) -> ApiResult<IntentResponse> {
+    req.validate()?;
+    limits::Points::Samples.check(&s, "samples", req.samples.len())?;
+    analyze_intent(&s, req).await.map(Json)
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/compress-intent/channels", request_body = MultiIntentRequest, responses((status = 200, body = MultiIntentResponse), (status = "4XX", body = ApiError)))]
+async fn compress_intent_channels(State(s): State<Arc<AppState>>, Json(req): Json<MultiIntentRequest>) -> ApiResult<MultiIntentResponse> {
+    req.validate()?;
+    // The cap is on the request as a whole, across channels
+    limits::Points::Samples.check(&s, "channels", req.channels.iter().map(|c| c.request.samples.len()).sum())?;
+    let t = Instant::now();
+    let mut channels = Vec::with_capacity(req.channels.len());
+    for (i, c) in req.channels.into_iter().enumerate() {
+        let intent = analyze_intent(&s, c.request).await
+            .map_err(|e| ApiError { message: format!("{} (channel '{}')", e.message, c.label), ..e.within(&format!("channels[{i}]")) })?;
+        channels.push(ChannelIntent { label: c.label, intent });
+    }
+    let motions: Vec<intent::ChannelMotion> = channels.iter()
+        .map(|c| intent::ChannelMotion { label: &c.label, direction: c.intent.direction, magnitude: c.intent.magnitude })
+        .collect();
+    let coordination = intent::coordinate(&motions).to_string();
+    Ok(Json(MultiIntentResponse { coordination, channels, elapsed_us: deterministic::elapsed_us(t) }))
+}
+
+/// Full intent analysis of one sample window, on the compute pool; also counts it and
+/// records it in the history unless the window is empty.
+async fn analyze_intent(s: &Arc<AppState>, req: IntentRequest) -> Result<IntentResponse, ApiError> {
+    let (t, intent_id) = (Instant::now(), deterministic::id("intent", &req));
+    let state = s.clone();
+    let (req, out) = compute::run(move || { let out = intent_window(&state, &req, intent_id); (req, out) }).await?;
+    let (mut resp, space) = out?;
+    resp.elapsed_us = deterministic::elapsed_us(t);
+    let Some(space) = space else { return Ok(resp) };
+    count_op(s, Op::Compression);
+    record_intent(s, &req, &resp, space);
+    Ok(publish(s, Op::Compression, resp))
+}
+
+/// The analysis proper, with the space it ran in (none for an empty window). Runs on a
+/// pool thread, so ids, timing and anything tenant- or job-scoped stay with the caller.
+fn intent_window(s: &AppState, req: &IntentRequest, intent_id: String) -> Result<(IntentResponse, Option<&'static str>), ApiError> {
     let n = req.samples.len();
-    let _rate = req.sample_rate_hz.unwrap_or(1000);
+    let rate = req.sample_rate_hz.unwrap_or(1000);
+    let filter = req.filter.as_ref().map(|f| match f.kind.as_str() {
+        "kalman" => Ok(intent::Filter::Kalman { q: f.process_noise.unwrap_or(1.0), r: f.measurement_noise.unwrap_or(1e-4) }),
+        "savgol" => Ok(intent::Filter::SavGol { window: f.window.unwrap_or(7), order: f.order.unwrap_or(2) }),
+        "butterworth" => Ok(intent::Filter::Butterworth { cutoff_hz: f.cutoff_hz.unwrap_or(10.0) }),
+        other => Err(ApiError::bad_request("Unknown filter", format!("'{other}' is not one of kalman, savgol, butterworth"))),
+    }).transpose()?;
 
     if n == 0 {
-        return Json(IntentResponse {
-            intent_id: uuid::Uuid::new_v4().to_string(),
+        return Ok((IntentResponse {
+            intent_id,
             compressed_bytes: 0, original_samples: 0, compression_ratio: 0.0,
-            intent_type: "idle".into(), direction: [0.0, 0.0, 0.0], magnitude: 0.0,
-            elapsed_us: t.elapsed().as_micros(),
-        });
+            intent_type: "idle".into(), confidence: 1.0, alternatives: Vec::new(), direction: [0.0, 0.0, 0.0], magnitude: 0.0,
+            avg_speed: 0.0, estimated_velocities: 0, timing: None, rotation: None, prediction: None,
+            joint_space: None, pca: None, encoded: None, filter: None, elapsed_us: 0,
+        }, None));
     }
 
+    let stamps: Vec<f64> = req.samples.iter().map(|s| s.timestamp_ms as f64 / 1000.0).collect();
+    let observed = intent::timing(&stamps, rate as f64);
+    let (resampled, times) = if req.resample.unwrap_or(false) {
+        let (samples, times) = resample_samples(&req.samples, &stamps, rate as f64)?;
+        (Some(samples), times)
+    } else { (None, stamps) };
+    let samples: &[MotionSample] = resampled.as_deref().unwrap_or(&req.samples);
+    let timing = Some(TimingSummary {
+        nominal_rate_hz: rate as f64, effective_rate_hz: observed.effective_rate_hz,
+        duration_ms: observed.duration_s * 1000.0,
+        gaps: observed.gaps.iter().map(|&(a, b)| {
+            let (start_ms, end_ms) = (req.samples[a].timestamp_ms, req.samples[b].timestamp_ms);
+            let missing_samples = ((end_ms - start_ms) as f64 * rate as f64 / 1000.0).round().max(1.0) as u64 - 1;
+            SampleGap { start_ms, end_ms, missing_samples }
+        }).collect(),
+        resampled_samples: resampled.as_ref().map(|r| r.len()),
+    });
+    let n = samples.len();
+    match req.space.as_deref().unwrap_or("cartesian") {
+        "cartesian" => {}
+        "joint" => {
+            let mut resp = joint_intent(req, intent_id, samples, &times, filter.as_ref(), rate as f64)?;
+            resp.original_samples = req.samples.len();
+            resp.timing = timing;
+            return Ok((resp, Some("joint")));
+        }
+        other => return Err(ApiError::bad_request("Unknown space", format!("'{other}' is not one of cartesian, joint"))),
+    }
+    let raw: Vec<[f64; 3]> = samples.iter().map(|s| s.position).collect();
+    let (positions, filter_summary) = match (&filter, &req.filter) {
+        (Some(f), Some(spec)) => {
+            let filtered = intent::filter_positions(f, &times, &raw, rate as f64);
+            let summary = FilterSummary {
+                kind: spec.kind.clone(),
+                raw: intent::path_stats(&times, &raw).into(),
+                filtered: intent::path_stats(&times, &filtered).into(),
+            };
+            (filtered, Some(summary))
+        }
+        _ => (raw, None),
+    };
+
     // Compute motion direction from first to last sample
-    let first = &req.samples[0].position;
-    let last = &req.samples[n - 1].position;
+    let first = &positions[0];
+    let last = &positions[n - 1];
     let dx = last[0] - first[0];
     let dy = last[1] - first[1];
     let dz = last[2] - first[2];
@@ -218,69 +1273,388 @@ async fn compress_intent(State(s): State<Arc<AppState>>, Json(req): Json<IntentR
         [0.0, 0.0, 0.0]
     };
 
-    // Compute average velocity from samples that have it
-    let avg_vel: f64 = req.samples.iter()
-        .filter_map(|s| s.velocity.as_ref())
+    // Average speed over every sample, finite-differencing positions where velocity is missing
+    let estimated_velocities = samples.iter().filter(|s| s.velocity.is_none()).count();
+    let estimates = if estimated_velocities > 0 {
+        intent::estimate_velocities(&times, &positions, rate as f64)
+    } else { Vec::new() };
+    let avg_vel: f64 = samples.iter().enumerate()
+        .map(|(i, s)| s.velocity.unwrap_or_else(|| estimates[i]))
         .map(|v| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt())
-        .sum::<f64>() / n.max(1) as f64;
+        .sum::<f64>() / n as f64;
+
+    // Net rotation, only when every sample carries an orientation
+    let orientations: Option<Vec<[f64; 4]>> = samples.iter().map(|s| s.orientation).collect();
+    let rotation = orientations.filter(|o| o.len() >= 2).map(|o| {
+        let r = intent::rotation_stats(&o);
+        let duration = times[n - 1] - times[0];
+        RotationSummary {
+            axis: r.axis, angle_rad: r.angle, path_angle_rad: r.path_angle,
+            angular_speed: if duration > 1e-9 { r.path_angle / duration } else { 0.0 },
+        }
+    });
+    let rotation_features = rotation.as_ref().map(|r| {
+        (r.angle_rad, (r.axis[0] * direction[0] + r.axis[1] * direction[1] + r.axis[2] * direction[2]).abs())
+    });
 
     // Classify intent
-    let intent_type = if magnitude < 0.01 {
-        "idle"
-    } else if magnitude < 0.1 && avg_vel < 0.05 {
-        "grasp"
-    } else if dz > magnitude * 0.7 {
-        "release"
-    } else if magnitude > 0.5 {
-        "traverse"
-    } else {
-        "reach"
-    }.to_string();
-
-    // Original: n samples * 3 floats * 8 bytes = 24n bytes. Compressed: 8 bytes
-    let original_bytes = (n * 24) as f64;
-    let compressed_bytes = 8u64;
-    let compression_ratio = original_bytes / compressed_bytes as f64;
-
-    s.stats.lock().unwrap().total_compressions += 1;
-    Json(IntentResponse {
-        intent_id: uuid::Uuid::new_v4().to_string(),
-        compressed_bytes, original_samples: n, compression_ratio,
-        intent_type, direction, magnitude,
-        elapsed_us: t.elapsed().as_micros(),
+    let features = intent::IntentFeatures { magnitude, avg_speed: avg_vel, dz, rotation: rotation_features };
+    let scores: Vec<(String, f64)> = match req.classifier.as_deref().unwrap_or("heuristic") {
+        "heuristic" => intent::classify(&features).into_iter().map(|(l, p)| (l.to_string(), p)).collect(),
+        "model" => {
+            let model = s.intent_model.as_ref()
+                .ok_or_else(|| ApiError::unavailable("Intent model not loaded", "set KINEMATICS_INTENT_MODEL to an ONNX classifier"))?;
+            model.classify(&features).map_err(|e| ApiError::internal("Model inference failed", e))?
+        }
+        other => return Err(ApiError::bad_request("Unknown classifier", format!("'{other}' is not one of heuristic, model"))),
+    };
+    let mut ranked = scores.into_iter().map(|(intent_type, score)| IntentAlternative { intent_type, score });
+    let top = ranked.next().unwrap_or(IntentAlternative { intent_type: "idle".into(), score: 1.0 });
+    let (intent_type, confidence) = (top.intent_type, top.score);
+    let alternatives: Vec<IntentAlternative> = ranked.collect();
+
+    let prediction = req.predict.unwrap_or(false).then(|| {
+        let horizon = req.prediction_horizon_ms.unwrap_or(2000) as f64 / 1000.0;
+        let p = intent::predict_endpoint(&times, &positions, horizon);
+        PredictionSummary { model: "minimum_jerk".into(), endpoint: p.endpoint, eta_ms: p.eta_s * 1000.0, reaches_rest: p.reaches_rest }
+    });
+
+    // Original: n samples * 3 floats * 8 bytes = 24n bytes. Compressed: 8 bytes,
+    // or the actual packed stream size when the codec is requested
+    let encoded = req.codec.as_ref().map(|spec| {
+        let frames: Vec<Vec<f64>> = req.samples.iter().map(|s| s.position.to_vec()).collect();
+        let stamps: Vec<u64> = req.samples.iter().map(|s| s.timestamp_ms).collect();
+        encode_stream(&frames, Some(&stamps), spec)
+    }).transpose()?;
+    let original_bytes = (req.samples.len() * 24) as f64;
+    let compressed_bytes = encoded.as_ref().map_or(8u64, |e| e.encoded_bytes);
+    let compression_ratio = encoded.as_ref().map_or(original_bytes / compressed_bytes as f64, |e| e.compression_ratio);
+
+    let pca = (req.mode.as_deref() == Some("pca")).then(|| {
+        let rows: Vec<Vec<f64>> = positions.iter().map(|p| p.to_vec()).collect();
+        intent::pca(&rows, req.pca_components.unwrap_or(2)).into()
+    });
+
+    let resp = IntentResponse {
+        intent_id, compressed_bytes, original_samples: req.samples.len(), compression_ratio,
+        intent_type, confidence, alternatives, direction, magnitude, avg_speed: avg_vel, estimated_velocities, timing, rotation,
+        prediction, joint_space: None, pca, filter: filter_summary, encoded, elapsed_us: 0,
+    };
+    Ok((resp, Some("cartesian")))
+}
+
+fn record_intent(s: &AppState, req: &IntentRequest, resp: &IntentResponse, space: &str) {
+    s.intents.lock().unwrap().push(history::IntentRecord {
+        intent_id: resp.intent_id.clone(), session_id: req.session_id.clone(),
+        recorded_at_ms: history::now_ms(), space: space.into(),
+        intent_type: resp.intent_type.clone(), confidence: resp.confidence,
+        direction: resp.direction, magnitude: resp.magnitude, original_samples: resp.original_samples, tenant: tenant::tag(),
+    });
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/intents", params(IntentQuery), responses((status = 200, body = IntentHistoryResponse)))]
+async fn list_intents(State(s): State<Arc<AppState>>, Query(q): Query<IntentQuery>) -> Json<IntentHistoryResponse> {
+    let store = s.intents.lock().unwrap();
+    let tenant = tenant::tag();
+    let matches = store.query(tenant.as_deref(), q.session.as_deref(), q.from, q.to);
+    Json(IntentHistoryResponse {
+        total: matches.len(),
+        intents: matches.into_iter().skip(q.offset.unwrap_or(0)).take(q.limit.unwrap_or(100)).cloned().collect(),
+    })
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/intents/{intent_id}", params(("intent_id" = String, Path)), responses((status = 200, body = history::IntentRecord), (status = "4XX", body = ApiError)))]
+async fn get_intent(State(s): State<Arc<AppState>>, Path(intent_id): Path<String>) -> ApiResult<history::IntentRecord> {
+    s.intents.lock().unwrap().get(tenant::tag().as_deref(), &intent_id).cloned().map(Json)
+        .ok_or_else(|| ApiError::not_found("Intent not found", intent_id))
+}
+
+/// Linearly interpolate samples onto a uniform grid (normalized lerp for orientations).
+/// Optional fields survive only where both neighbours carry them.
+fn resample_samples(samples: &[MotionSample], times: &[f64], rate: f64) -> Result<(Vec<MotionSample>, Vec<f64>), ApiError> {
+    const MAX_RESAMPLED: f64 = 1_000_000.0;
+    let span = times.last().unwrap_or(&0.0) - times.first().unwrap_or(&0.0);
+    if span * rate > MAX_RESAMPLED {
+        return Err(ApiError::invalid("sample_rate_hz", "Resampled window too large", format!("{:.0} samples at {rate} Hz exceeds {MAX_RESAMPLED}", span * rate)));
+    }
+    let lerp = |a: f64, b: f64, w: f64| a + (b - a) * w;
+    let lerp3 = |a: [f64; 3], b: [f64; 3], w: f64| [lerp(a[0], b[0], w), lerp(a[1], b[1], w), lerp(a[2], b[2], w)];
+    let grid = intent::resample_grid(times, rate);
+    let out = grid.iter().map(|&(t, i, w)| {
+        let (a, b) = (&samples[i], &samples[(i + 1).min(samples.len() - 1)]);
+        MotionSample {
+            timestamp_ms: (t * 1000.0).round() as u64,
+            position: lerp3(a.position, b.position, w),
+            velocity: a.velocity.zip(b.velocity).map(|(va, vb)| lerp3(va, vb, w)),
+            joint_angles: a.joint_angles.as_ref().zip(b.joint_angles.as_ref()).filter(|(ja, jb)| ja.len() == jb.len())
+                .map(|(ja, jb)| ja.iter().zip(jb).map(|(x, y)| lerp(*x, *y, w)).collect()),
+            orientation: a.orientation.zip(b.orientation).map(|(qa, qb)| {
+                // Flip to the same hemisphere so the blend takes the short arc
+                let dot: f64 = qa.iter().zip(&qb).map(|(x, y)| x * y).sum();
+                let qb = if dot < 0.0 { qb.map(|c| -c) } else { qb };
+                intent::quat_normalize([lerp(qa[0], qb[0], w), lerp(qa[1], qb[1], w), lerp(qa[2], qb[2], w), lerp(qa[3], qb[3], w)])
+            }),
+        }
+    }).collect();
+    Ok((out, grid.into_iter().map(|(t, _, _)| t).collect()))
+}
+
+/// Joint-space branch of `compress_intent`: segments and classifies `joint_angles` streams
+/// as produced natively by EMG and exoskeleton pipelines.
+fn joint_intent(req: &IntentRequest, intent_id: String, samples: &[MotionSample], times: &[f64], filter: Option<&intent::Filter>, rate: f64) -> Result<IntentResponse, ApiError> {
+    let n = samples.len();
+    if req.classifier.as_deref().is_some_and(|c| c != "heuristic") {
+        return Err(ApiError::bad_request("Unsupported classifier", "joint-space windows are classified heuristically only"));
+    }
+    let rows: Vec<Vec<f64>> = samples.iter().enumerate().map(|(i, s)| {
+        s.joint_angles.clone().ok_or_else(|| ApiError::invalid(format!("samples[{i}].joint_angles"), "Missing joint angles", "required when space is 'joint'"))
+    }).collect::<Result<_, _>>()?;
+    let dof = rows[0].len();
+    if let Some(i) = rows.iter().position(|r| r.len() != dof) {
+        return Err(ApiError::invalid(format!("samples[{i}].joint_angles"), "Inconsistent joint count", format!("samples[{i}] has {} joint angles, samples[0] has {dof}", rows[i].len())));
+    }
+    let (rows, filter_summary) = match (filter, &req.filter) {
+        (Some(f), Some(spec)) => {
+            let channels: Vec<Vec<f64>> = (0..dof).map(|j| {
+                let xs: Vec<f64> = rows.iter().map(|r| r[j]).collect();
+                intent::filter_channel(f, times, &xs, rate)
+            }).collect();
+            let filtered: Vec<Vec<f64>> = (0..n).map(|i| channels.iter().map(|c| c[i]).collect()).collect();
+            let summary = FilterSummary {
+                kind: spec.kind.clone(),
+                raw: intent::path_stats(times, &rows).into(),
+                filtered: intent::path_stats(times, &filtered).into(),
+            };
+            (filtered, Some(summary))
+        }
+        _ => (rows, None),
+    };
+
+    let displacement: Vec<f64> = rows[n - 1].iter().zip(&rows[0]).map(|(b, a)| b - a).collect();
+    let magnitude = displacement.iter().map(|d| d * d).sum::<f64>().sqrt();
+    let dominant = |d: &[f64]| d.iter().enumerate().max_by(|a, b| a.1.abs().total_cmp(&b.1.abs())).map_or(0, |(j, _)| j);
+    let speeds = intent::channel_speeds(times, &rows, rate);
+    let avg_speed = speeds.iter().sum::<f64>() / n as f64;
+    let segments = intent::segment(&speeds, 0.05).into_iter().map(|(a, b, moving)| {
+        let d: Vec<f64> = rows[b].iter().zip(&rows[a]).map(|(q, p)| q - p).collect();
+        MotionSegment { start_ms: samples[a].timestamp_ms, end_ms: samples[b].timestamp_ms, moving, dominant_joint: dominant(&d) }
+    }).collect();
+
+    let mut ranked = intent::classify_joint(&intent::JointFeatures { displacement: displacement.clone(), avg_speed }).into_iter()
+        .map(|(label, score)| IntentAlternative { intent_type: label.into(), score });
+    let top = ranked.next().unwrap_or(IntentAlternative { intent_type: "hold".into(), score: 1.0 });
+    let pca = (req.mode.as_deref() == Some("pca")).then(|| intent::pca(&rows, req.pca_components.unwrap_or(2)).into());
+
+    // Original: the request's samples * dof floats * 8 bytes. Compressed: displacement
+    // vector, or the actual packed stream size when the codec is requested
+    let encoded = req.codec.as_ref().map(|spec| {
+        let stamps: Vec<u64> = samples.iter().map(|s| s.timestamp_ms).collect();
+        encode_stream(&rows, Some(&stamps), spec)
+    }).transpose()?;
+    let compressed_bytes = encoded.as_ref().map_or((dof * 8) as u64, |e| e.encoded_bytes);
+    let compression_ratio = encoded.as_ref()
+        .map_or((req.samples.len() * dof * 8) as f64 / compressed_bytes.max(1) as f64, |e| e.compression_ratio);
+    Ok(IntentResponse {
+        intent_id, compressed_bytes, original_samples: n, compression_ratio,
+        intent_type: top.intent_type, confidence: top.score, alternatives: ranked.collect(),
+        direction: [0.0; 3], magnitude, avg_speed, estimated_velocities: 0, timing: None, rotation: None, prediction: None,
+        joint_space: Some(JointSpaceSummary { dominant_joint: dominant(&displacement), displacement, segments }),
+        pca, filter: filter_summary, encoded, elapsed_us: 0,
+    })
+}
+
+/// Pack frames with the AKQ1 codec. `raw_bytes` counts every value (and timestamp) as
+/// 8 bytes, i.e. what the stream costs as packed f64/u64 before encoding.
+fn encode_stream(frames: &[Vec<f64>], timestamps_ms: Option<&[u64]>, spec: &CodecSpec) -> Result<EncodedStream, ApiError> {
+    use base64::Engine;
+    let step = spec.step.unwrap_or(1e-4);
+    let entropy = codec::Entropy::parse(spec.entropy.as_deref()).map_err(|e| ApiError::bad_request("Unsupported entropy stage", e))?;
+    let bytes = codec::encode(frames, timestamps_ms, step, entropy).map_err(|e| ApiError::invalid("frames", "Encode failed", e))?;
+    let channels = frames.first().map_or(0, |f| f.len()) + usize::from(timestamps_ms.is_some());
+    let raw_bytes = (frames.len() * channels * 8) as u64;
+    Ok(EncodedStream {
+        data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        raw_bytes, encoded_bytes: bytes.len() as u64,
+        compression_ratio: raw_bytes as f64 / bytes.len().max(1) as f64, max_error: step / 2.0,
     })
 }
 
-async fn optimize_trajectory(State(s): State<Arc<AppState>>, Json(req): Json<TrajectoryRequest>) -> Json<TrajectoryResponse> {
+#[utoipa::path(post, path = "/api/v1/kinematics/codec/encode", request_body = EncodeRequest, responses((status = 200, body = EncodeResponse), (status = "4XX", body = ApiError)))]
+async fn codec_encode(State(s): State<Arc<AppState>>, Json(req): Json<EncodeRequest>) -> ApiResult<EncodeResponse> {
+    req.validate()?;
+    limits::Points::Frames.check(&s, "frames", req.frames.len())?;
+    let t = Instant::now();
+    let stream = encode_stream(&req.frames, req.timestamps_ms.as_deref(), &req.spec)?;
+    Ok(Json(EncodeResponse { stream, elapsed_us: deterministic::elapsed_us(t) }))
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/codec/decode", request_body = DecodeRequest, responses((status = 200, body = DecodeResponse), (status = "4XX", body = ApiError)))]
+async fn codec_decode(Json(req): Json<DecodeRequest>) -> ApiResult<DecodeResponse> {
+    use base64::Engine;
+    let t = Instant::now();
+    let bytes = base64::engine::general_purpose::STANDARD.decode(req.data.as_bytes())
+        .map_err(|e| ApiError::invalid("data", "Invalid base64", e.to_string()))?;
+    let stream = codec::decode(&bytes).map_err(|e| ApiError::invalid("data", "Decode failed", e))?;
+    Ok(Json(DecodeResponse {
+        frames: stream.frames, timestamps_ms: stream.timestamps_ms, step: stream.step,
+        elapsed_us: deterministic::elapsed_us(t),
+    }))
+}
+
+/// `optimize_trajectory` over HTTP: past `KINEMATICS_TRAJECTORY_STREAM_POINTS` points,
+/// or when `Accept` names `application/x-ndjson`, the points are streamed as they are timed.
+#[utoipa::path(post, path = "/api/v1/kinematics/optimize-trajectory", operation_id = "optimize_trajectory", request_body = TrajectoryRequest, responses(
+    (status = 200, body = TrajectoryResponse),
+    (status = 200, description = "One TrajectoryPoint per line, then a TrajectorySummary", body = TrajectoryPoint, content_type = "application/x-ndjson"),
+    (status = "4XX", body = ApiError)))]
+async fn optimize_trajectory_route(State(s): State<Arc<AppState>>, headers: axum::http::HeaderMap, Json(mut req): Json<TrajectoryRequest>) -> Result<axum::response::Response, ApiError> {
+    in_service(&s)?;
+    let ndjson = headers.get_all(header::ACCEPT).iter().filter_map(|v| v.to_str().ok()).flat_map(|v| v.split(','))
+        .any(|m| m.split(';').next().is_some_and(|m| m.trim().eq_ignore_ascii_case(NDJSON)));
+    let threshold = *s.trajectory_stream.lock().unwrap();
+    if !ndjson && threshold.is_none_or(|n| req.waypoints.len() <= n) { return optimize_trajectory(State(s), Json(req)).await.map(IntoResponse::into_response); }
+    req.validate()?;
     let t = Instant::now();
+    let start = session_chain(&s, req.session_id.as_deref(), &mut req.chain_id)?;
+    let (chain, mut waypoints) = trajectory_waypoints(&s, &req)?;
+    let end = execution(&s, start.as_deref(), chain.as_ref(), &mut waypoints).await?;
+    check_reach(waypoints.len(), |i| waypoints[i], chain.as_ref())?;
+    let id = trajectory_id(&req, start.as_deref());
+    executed(&s, req.session_id.as_deref(), end);
     let max_vel = req.max_velocity.unwrap_or(1.0);
-    let waypoints: Vec<[f64; 3]> = req.waypoints.iter().map(|w| {
-        [*w.first().unwrap_or(&0.0), *w.get(1).unwrap_or(&0.0), *w.get(2).unwrap_or(&0.0)]
-    }).collect();
+    let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(STREAM_CHUNKS_AHEAD);
+    let s2 = s.clone();
+    // Timing waits on `send` whenever the client is STREAM_CHUNKS_AHEAD chunks behind
+    tokio::spawn(auth::scope(auth::current(), deterministic::scope(deterministic::seed(), async move {
+        let (n, mut timer) = (waypoints.len(), PathTimer::default());
+        for start in (0..n).step_by(STREAM_CHUNK) {
+            let mut chunk = Vec::new();
+            for i in start..(start + STREAM_CHUNK).min(n) {
+                let _ = serde_json::to_writer(&mut chunk, &timer.point(n, |i| waypoints[i], i, max_vel));
+                chunk.push(b'\n');
+            }
+            // The client went away: no point timing the rest
+            if tx.send(chunk).await.is_err() { return; }
+        }
+        count_op(&s2, Op::Trajectory);
+        let out = publish(&s2, Op::Trajectory, timer.finish(id, Vec::new(), t));
+        let summary = TrajectorySummary {
+            trajectory_id: out.trajectory_id, points: n, total_distance: out.total_distance, total_time: out.total_time,
+            max_velocity_reached: out.max_velocity_reached, elapsed_us: out.elapsed_us,
+        };
+        let mut line = serde_json::to_vec(&summary).unwrap_or_default();
+        line.push(b'\n');
+        let _ = tx.send(line).await;
+    })));
+    let body = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (Ok::<_, std::convert::Infallible>(chunk), rx)) });
+    let body = axum::body::Body::from_stream(body);
+    Ok(([(header::CONTENT_TYPE, NDJSON)], body).into_response())
+}
+
+async fn optimize_trajectory(State(s): State<Arc<AppState>>, Json(mut req): Json<TrajectoryRequest>) -> ApiResult<TrajectoryResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let start = session_chain(&s, req.session_id.as_deref(), &mut req.chain_id)?;
+    let (chain, mut waypoints) = trajectory_waypoints(&s, &req)?;
+    let end = execution(&s, start.as_deref(), chain.as_ref(), &mut waypoints).await?;
+    let out = time_path(&s, trajectory_id(&req, start.as_deref()), waypoints.len(), move |i| waypoints[i], req.max_velocity.unwrap_or(1.0), chain, t).await?;
+    executed(&s, req.session_id.as_deref(), end);
+    Ok(Json(out))
+}
+
+/// A trajectory's id; on a session, its starting state is part of the input.
+fn trajectory_id(req: &TrajectoryRequest, start: Option<&[f64]>) -> String {
+    match start { Some(q) => deterministic::id("trajectory", &(req, q)), None => deterministic::id("trajectory", req) }
+}
+
+/// A session trajectory starts at the end effector of `start` (prepended unless already
+/// the first waypoint) and ends at IK of its last waypoint from `start`: the joint state
+/// executing it leaves the session in. 422 when that solve does not converge.
+async fn execution(s: &AppState, start: Option<&[f64]>, chain: Option<&chains::ChainDef>, waypoints: &mut Vec<[f64; 3]>) -> Result<Option<Vec<f64>>, ApiError> {
+    let (Some(start), Some(chain)) = (start, chain) else { return Ok(None) };
+    // Checked before the start goes in, so errors index the request's waypoints
+    check_reach(waypoints.len(), |i| waypoints[i], Some(chain))?;
+    let (here, field) = (kinematics_solver::forward(chain, start).tip.p, format!("waypoints[{}]", waypoints.len() - 1));
+    if waypoints.first().is_none_or(|w| w.iter().zip(here).any(|(a, b)| (a - b).abs() > 1e-9)) { waypoints.insert(0, here); }
+    let goal = *waypoints.last().expect("validated non-empty");
+    let settings = solver_settings(s, Some(chain), &chains::SolverDefaults::default());
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let (c, seed) = (chain.clone(), start.to_vec());
+    let r = compute::run(move || kinematics_solver::solve_ik_at(None, &c, goal, None, seed, &ik, || false)).await?;
+    if !r.converged {
+        return Err(ApiError::invalid(field, "Trajectory end unreachable", format!("IK from the session's joint state left {:.3e} m to the last waypoint", r.position_error)));
+    }
+    Ok(Some(r.angles))
+}
+
+/// The request's chain, and its waypoints with configuration names resolved to tip positions.
+fn trajectory_waypoints(s: &AppState, req: &TrajectoryRequest) -> Result<(Option<chains::ChainDef>, Vec<[f64; 3]>), ApiError> {
+    limits::Points::Waypoints.check(s, "waypoints", req.waypoints.len())?;
+    let chain = lookup_chain(s, req.chain_id.as_deref())?;
+    let waypoints = req.waypoints.iter().map(|w| Ok(match w {
+        Waypoint::Point(w) => [w[0], w[1], w[2]],
+        Waypoint::Named(name) => {
+            let q = named_configuration(chain.as_ref(), name)?;
+            kinematics_solver::forward(chain.as_ref().expect("named configuration implies a chain"), &q).tip.p
+        }
+    })).collect::<Result<_, ApiError>>()?;
+    Ok((chain, waypoints))
+}
 
-    let mut total_distance = 0.0f64;
-    let mut optimized = Vec::new();
-    let mut cumulative_time = 0.0f64;
-    let mut max_vel_reached = 0.0f64;
+/// Reject waypoints beyond `chain`'s reach.
+fn check_reach(n: usize, point: impl Fn(usize) -> [f64; 3], chain: Option<&chains::ChainDef>) -> Result<(), ApiError> {
+    let Some(chain) = chain else { return Ok(()) };
+    let reach = chain.reach();
+    match (0..n).map(&point).position(|w| (w[0] * w[0] + w[1] * w[1] + w[2] * w[2]).sqrt() > reach + 1e-9) {
+        Some(i) => Err(ApiError::invalid(format!("waypoints[{i}]"), "Waypoint out of reach", format!("beyond the {reach:.3} m reach of chain '{}'", chain.id))),
+        None => Ok(()),
+    }
+}
+
+/// Time `n` validated waypoints on the compute pool, read through `point` so binary
+/// bodies are used in place; points beyond `chain`'s reach are rejected.
+async fn time_path(s: &AppState, id: String, n: usize, point: impl Fn(usize) -> [f64; 3] + Send + 'static, max_vel: f64, chain: Option<chains::ChainDef>, t: Instant) -> Result<TrajectoryResponse, ApiError> {
+    let progress = jobs::progress();
+    let (timer, optimized) = compute::run(move || {
+        check_reach(n, &point, chain.as_ref())?;
+        progress.report(None, Some("waypoints validated"));
+        let step = (n / 100).max(1);
+        let (mut timer, mut optimized) = (PathTimer::default(), Vec::with_capacity(n));
+        for i in 0..n {
+            optimized.push(timer.point(n, &point, i, max_vel));
+            if (i + 1) % step == 0 { progress.report(Some((i + 1) as f64 / n as f64), None); }
+        }
+        Ok::<_, ApiError>((timer, optimized))
+    }).await??;
+    count_op(s, Op::Trajectory);
+    Ok(publish(s, Op::Trajectory, timer.finish(id, optimized, t)))
+}
 
-    for i in 0..waypoints.len() {
-        let pos = waypoints[i];
+/// Running totals of a path timed one waypoint at a time, in order.
+#[derive(Default)]
+struct PathTimer { total_distance: f64, cumulative_time: f64, max_vel_reached: f64 }
+
+impl PathTimer {
+    /// Waypoint `i` of `n`, timed after waypoints `0..i`.
+    fn point(&mut self, n: usize, point: impl Fn(usize) -> [f64; 3], i: usize, max_vel: f64) -> TrajectoryPoint {
+        let pos = point(i);
         let seg_dist = if i > 0 {
-            let prev = waypoints[i - 1];
+            let prev = point(i - 1);
             let d = ((pos[0] - prev[0]).powi(2) + (pos[1] - prev[1]).powi(2) + (pos[2] - prev[2]).powi(2)).sqrt();
-            total_distance += d;
+            self.total_distance += d;
             d
         } else { 0.0 };
 
         // Trapezoidal velocity profile: accelerate, cruise, decelerate
         let seg_time = if seg_dist > 0.0 { seg_dist / (max_vel * 0.8) } else { 0.0 };
-        cumulative_time += seg_time;
+        self.cumulative_time += seg_time;
 
         let vel_mag = if seg_time > 0.0 { seg_dist / seg_time } else { 0.0 };
-        if vel_mag > max_vel_reached { max_vel_reached = vel_mag; }
+        if vel_mag > self.max_vel_reached { self.max_vel_reached = vel_mag; }
 
-        let velocity = if i + 1 < waypoints.len() {
-            let next = waypoints[i + 1];
+        let velocity = if i + 1 < n {
+            let next = point(i + 1);
             let dx = next[0] - pos[0];
             let dy = next[1] - pos[1];
             let dz = next[2] - pos[2];
@@ -289,48 +1663,863 @@ async fn optimize_trajectory(State(s): State<Arc<AppState>>, Json(req): Json<Tra
         } else {
             [0.0, 0.0, 0.0]
         };
+        TrajectoryPoint { position: pos, velocity, time: self.cumulative_time }
+    }
 
-        optimized.push(TrajectoryPoint { position: pos, velocity, time: cumulative_time });
+    fn finish(self, trajectory_id: String, optimized: Vec<TrajectoryPoint>, t: Instant) -> TrajectoryResponse {
+        TrajectoryResponse {
+            trajectory_id,
+            optimized_waypoints: optimized, total_distance: self.total_distance,
+            total_time: self.cumulative_time, max_velocity_reached: self.max_vel_reached,
+            elapsed_us: deterministic::elapsed_us(t),
+        }
     }
+}
 
-    s.stats.lock().unwrap().total_trajectories += 1;
-    Json(TrajectoryResponse {
-        trajectory_id: uuid::Uuid::new_v4().to_string(),
-        optimized_waypoints: optimized, total_distance,
-        total_time: cumulative_time, max_velocity_reached: max_vel_reached,
-        elapsed_us: t.elapsed().as_micros(),
-    })
+/// Filtered page of chains; `X-Total-Count` carries the number of matches before paging.
+#[utoipa::path(get, path = "/api/v1/kinematics/chains", params(ChainListQuery), responses((status = 200, body = [ChainInfo], headers(("X-Total-Count" = usize, description = "Matches before paging"))), (status = "4XX", body = ApiError)))]
+async fn list_chains(State(s): State<Arc<AppState>>, Query(q): Query<ChainListQuery>) -> Result<impl IntoResponse, ApiError> {
+    if let Some(t) = q.joint_type.as_deref().filter(|t| !["revolute", "prismatic", "revolute+prismatic"].contains(t)) {
+        return Err(ApiError::bad_request("Invalid joint_type", format!("'{t}' is not one of revolute, prismatic, revolute+prismatic")));
+    }
+    let limit = q.limit.unwrap_or(CHAIN_PAGE_MAX);
+    if limit == 0 || limit > CHAIN_PAGE_MAX { return Err(ApiError::bad_request("Invalid limit", format!("limit must be between 1 and {CHAIN_PAGE_MAX}"))); }
+    let needle = q.q.as_deref().map(str::to_lowercase);
+    let matches: Vec<ChainInfo> = s.chains.current().lock().unwrap().list().into_iter()
+        .filter(|c| q.dof.is_none_or(|d| c.dof() == d as usize))
+        .filter(|c| q.joint_type.as_ref().is_none_or(|t| &c.joint_type() == t))
+        .filter(|c| needle.as_ref().is_none_or(|n| c.id.to_lowercase().contains(n) || c.name.to_lowercase().contains(n)))
+        .map(|c| ChainInfo {
+            id: c.id.clone(), version: c.version, name: c.name.clone(), description: c.description.clone(), dof: c.dof() as u32, joint_type: c.joint_type(),
+        }).collect();
+    let total = matches.len();
+    let page: Vec<ChainInfo> = matches.into_iter().skip(q.offset).take(limit).collect();
+    Ok(([(header::HeaderName::from_static("x-total-count"), total.to_string())], Json(page)))
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/chains", request_body = chains::ChainDef, responses((status = 201, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn register_chain(State(s): State<Arc<AppState>>, Json(chain): Json<chains::ChainDef>) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    store_chain(&s, chain)
+}
+
+/// Register every planning group of a MoveIt config; nothing is stored if any id is taken.
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/import/moveit", request_body = MoveItImport, responses((status = 201, body = MoveItImportResponse), (status = "4XX", body = ApiError)))]
+async fn import_moveit(State(s): State<Arc<AppState>>, Json(req): Json<MoveItImport>) -> Result<(StatusCode, Json<MoveItImportResponse>), ApiError> {
+    let (imported, skipped) = import::moveit(&req.urdf, &req.srdf, req.groups.as_deref()).map_err(|e| ApiError::bad_request("Invalid MoveIt config", e))?;
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    if let Some(taken) = imported.iter().find(|c| registry.list().iter().any(|r| r.id == c.id)) {
+        return Err(ApiError::conflict("Chain already exists", taken.id.clone()));
+    }
+    let mut chains = Vec::new();
+    for chain in imported {
+        let stored = registry.register(chain).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid chain", e))?;
+        tracing::info!("Registered chain {} from MoveIt group ({} joints)", stored.pinned_id(), stored.dof());
+        chains.push(stored.clone());
+    }
+    Ok((StatusCode::CREATED, Json(MoveItImportResponse { chains, skipped })))
+}
+
+/// Register a robot description (URDF, SDF or MJCF) as a new chain.
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/import", params(ImportQuery), request_body(content = String, content_type = "application/xml"), responses((status = 201, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn import_chain(State(s): State<Arc<AppState>>, Query(q): Query<ImportQuery>, body: String) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    let format = q.format.as_deref().map(import::Format::parse).transpose().map_err(|e| ApiError::bad_request("Unknown format", e))?;
+    let mut chain = import::import(&body, format, q.tip.as_deref()).map_err(|e| ApiError::bad_request("Invalid robot description", e))?;
+    if let Some(id) = q.id { chain.id = id; }
+    if let Some(name) = q.name { chain.name = name; }
+    store_chain(&s, chain)
+}
+
+/// Generate a chain from a parametric template and register it.
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/generate", request_body = chains::TemplateSpec, responses((status = 201, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn generate_chain(State(s): State<Arc<AppState>>, Json(spec): Json<chains::TemplateSpec>) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    let chain = chains::template(&spec).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid template", e))?;
+    store_chain(&s, chain)
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/compose", request_body = chains::ComposeSpec, responses((status = 201, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn compose_chain(State(s): State<Arc<AppState>>, Json(spec): Json<chains::ComposeSpec>) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    let base = lookup_chain(&s, Some(&spec.base))?.expect("chain id given");
+    let tool = lookup_chain(&s, Some(&spec.tool))?.expect("chain id given");
+    let chain = chains::compose(&spec, &base, &tool).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid composition", e))?;
+    store_chain(&s, chain)
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/{chain_id}/scale", params(("chain_id" = String, Path)), request_body = anthropometry::SubjectSpec, responses((status = 201, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn scale_chain(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>, Json(spec): Json<anthropometry::SubjectSpec>) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    let base = lookup_chain(&s, Some(&chain_id))?.expect("chain id given");
+    let chain = anthropometry::scale(&base, &spec).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid subject", e))?;
+    store_chain(&s, chain)
+}
+
+fn store_chain(s: &AppState, chain: chains::ChainDef) -> Result<(StatusCode, Json<chains::ChainDef>), ApiError> {
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    if registry.list().iter().any(|c| c.id == chain.id) {
+        return Err(ApiError::conflict("Chain already exists", chain.id));
+    }
+    let stored = registry.register(chain).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid chain", e))?;
+    tracing::info!("Registered chain {} ({} joints)", stored.pinned_id(), stored.dof());
+    Ok((StatusCode::CREATED, Json(stored.clone())))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/chains/{chain_id}", params(("chain_id" = String, Path)), responses((status = 200, body = ChainDetail), (status = "4XX", body = ApiError)))]
+async fn get_chain(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>) -> ApiResult<ChainDetail> {
+    let c = lookup_chain(&s, Some(&chain_id))?.expect("chain id given");
+    let versions = s.chains.current().lock().unwrap().versions(&c.id);
+    let joints = c.joints.iter().enumerate().map(|(index, j)| {
+        JointDetail { index, joint: chains::JointDef { origin: Some(c.origin(index)), ..j.clone() } }
+    }).collect();
+    Ok(Json(ChainDetail {
+        id: c.id.clone(), version: c.version, versions, name: c.name.clone(), description: c.description.clone(),
+        dof: c.dof() as u32, joint_type: c.joint_type(), reach: c.reach(), joints,
+        tcp: c.tcp.unwrap_or_default(), total_mass: c.total_mass(),
+        solver: solver_settings(&s, Some(&c), &chains::SolverDefaults::default()),
+        configurations: c.configurations.clone(),
+    }))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/chains/{chain_id}/export", params(("chain_id" = String, Path), ExportQuery), responses((status = 200, body = String, content_type = "application/xml"), (status = "4XX", body = ApiError)))]
+async fn export_chain(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>, Query(q): Query<ExportQuery>) -> Result<impl IntoResponse, ApiError> {
+    let chain = lookup_chain(&s, Some(&chain_id))?.expect("chain id given");
+    match q.format.as_deref().unwrap_or("urdf") {
+        "urdf" => Ok(([(header::CONTENT_TYPE, "application/xml")], export::urdf(&chain))),
+        other => Err(ApiError::bad_request("Unknown export format", format!("'{other}' is not one of urdf"))),
+    }
+}
+
+#[utoipa::path(put, path = "/api/v1/kinematics/chains/{chain_id}", params(("chain_id" = String, Path)), request_body = chains::ChainDef, responses((status = 200, body = chains::ChainDef), (status = "4XX", body = ApiError)))]
+async fn update_chain(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>, Json(chain): Json<chains::ChainDef>) -> ApiResult<chains::ChainDef> {
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    writable_chain(&registry, &chain_id)?;
+    let stored = registry.update(&chain_id, chain).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid chain", e))?;
+    tracing::info!("Updated chain {}", stored.pinned_id());
+    Ok(Json(stored.clone()))
 }
 
-async fn chains() -> Json<Vec<ChainInfo>> {
-    Json(vec![
-        ChainInfo { id: "human_arm".into(), name: "Human Arm".into(), description: "7-DOF human arm: shoulder(3) + elbow(1) + wrist(3)".into(), dof: 7, joint_type: "revolute".into() },
-        ChainInfo { id: "human_leg".into(), name: "Human Leg".into(), description: "6-DOF human leg: hip(3) + knee(1) + ankle(2)".into(), dof: 6, joint_type: "revolute".into() },
-        ChainInfo { id: "robotic_arm_6dof".into(), name: "Robotic Arm (6-DOF)".into(), description: "Standard industrial 6-DOF manipulator".into(), dof: 6, joint_type: "revolute".into() },
-        ChainInfo { id: "delta_robot".into(), name: "Delta Robot".into(), description: "3-DOF parallel kinematic delta robot for high-speed pick-and-place".into(), dof: 3, joint_type: "prismatic".into() },
-        ChainInfo { id: "scara".into(), name: "SCARA".into(), description: "4-DOF selective compliance assembly robot arm".into(), dof: 4, joint_type: "revolute+prismatic".into() },
-    ])
+#[utoipa::path(delete, path = "/api/v1/kinematics/chains/{chain_id}", params(("chain_id" = String, Path)), responses((status = 204), (status = "4XX", body = ApiError)))]
+async fn delete_chain(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>) -> Result<StatusCode, ApiError> {
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    writable_chain(&registry, &chain_id)?;
+    let removed = registry.delete(&chain_id);
+    tracing::info!("Deleted chain {chain_id} ({removed} versions)");
+    Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(get, path = "/api/v1/kinematics/chains/{chain_id}/configurations", params(("chain_id" = String, Path)), responses((status = 200, body = BTreeMap<String, Vec<f64>>), (status = "4XX", body = ApiError)))]
+async fn list_configurations(State(s): State<Arc<AppState>>, Path(chain_id): Path<String>) -> ApiResult<BTreeMap<String, Vec<f64>>> {
+    let c = lookup_chain(&s, Some(&chain_id))?.expect("chain id given");
+    let mut configurations = c.configurations.clone();
+    configurations.entry("zero".into()).or_insert_with(|| vec![0.0; c.dof()]);
+    Ok(Json(configurations))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/chains/{chain_id}/configurations/{name}", params(("chain_id" = String, Path), ("name" = String, Path)), responses((status = 200, body = Configuration), (status = "4XX", body = ApiError)))]
+async fn get_configuration(State(s): State<Arc<AppState>>, Path((chain_id, name)): Path<(String, String)>) -> ApiResult<Configuration> {
+    let c = lookup_chain(&s, Some(&chain_id))?;
+    let joint_angles = named_configuration(c.as_ref(), &name)?;
+    Ok(Json(Configuration { name, joint_angles, chain: c.expect("chain id given").pinned_id() }))
+}
+
+/// Store (or replace) one named configuration as a new chain version.
+#[utoipa::path(put, path = "/api/v1/kinematics/chains/{chain_id}/configurations/{name}", params(("chain_id" = String, Path), ("name" = String, Path)), request_body = Vec<f64>, responses((status = 200, body = Configuration), (status = "4XX", body = ApiError)))]
+async fn put_configuration(State(s): State<Arc<AppState>>, Path((chain_id, name)): Path<(String, String)>, Json(joint_angles): Json<Vec<f64>>) -> ApiResult<Configuration> {
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    writable_chain(&registry, &chain_id)?;
+    let mut chain = registry.get(&chain_id).cloned().expect("writable chain exists");
+    chains::validate_configuration(&chain, &name, &joint_angles).map_err(|e| ApiError::invalid("joint_angles", "Invalid configuration", e))?;
+    chain.configurations.insert(name.clone(), joint_angles.clone());
+    let stored = registry.update(&chain_id, chain).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid chain", e))?;
+    tracing::info!("Stored configuration '{name}' on chain {}", stored.pinned_id());
+    Ok(Json(Configuration { name, joint_angles, chain: stored.pinned_id() }))
+}
+
+/// Resolve a configuration name against the request's chain (404 if unknown).
+fn named_configuration(chain: Option<&chains::ChainDef>, name: &str) -> Result<Vec<f64>, ApiError> {
+    let Some(c) = chain else { return Err(ApiError::invalid("chain_id", "Missing chain_id", format!("configuration '{name}' needs a chain_id"))) };
+    c.configuration(name).ok_or_else(|| ApiError::not_found("Configuration not found", format!("'{name}' on chain {}", c.pinned_id())))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/hands", responses((status = 200, body = [HandInfo])))]
+async fn list_hands(State(s): State<Arc<AppState>>) -> Json<Vec<HandInfo>> {
+    Json(s.hands.current().lock().unwrap().list().iter().map(|h| HandInfo {
+        id: h.id.clone(), name: h.name.clone(), description: h.description.clone(), dof: h.dof(),
+        fingers: h.fingers.iter().map(|f| f.name.clone()).collect(),
+    }).collect())
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/hands", request_body = hand::HandDef, responses((status = 201, body = hand::HandDef), (status = "4XX", body = ApiError)))]
+async fn register_hand(State(s): State<Arc<AppState>>, Json(h): Json<hand::HandDef>) -> Result<(StatusCode, Json<hand::HandDef>), ApiError> {
+    let hands = s.hands.current();
+    let mut registry = hands.lock().unwrap();
+    if registry.get(&h.id).is_some() {
+        let error = if registry.is_preset(&h.id) { "Preset hands are read-only" } else { "Hand already exists" };
+        return Err(ApiError::conflict(error, h.id));
+    }
+    let stored = registry.register(h).map_err(|e| ApiError::new(ErrorCode::Invalid, "Invalid hand", e))?;
+    tracing::info!("Registered hand {} ({} fingers, {} joints)", stored.id, stored.fingers.len(), stored.dof());
+    Ok((StatusCode::CREATED, Json(stored.clone())))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/hands/{hand_id}", params(("hand_id" = String, Path)), responses((status = 200, body = hand::HandDef), (status = "4XX", body = ApiError)))]
+async fn get_hand(State(s): State<Arc<AppState>>, Path(hand_id): Path<String>) -> ApiResult<hand::HandDef> {
+    lookup_hand(&s, &hand_id).map(Json)
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/hands/{hand_id}/solve-fk", params(("hand_id" = String, Path)), request_body = HandFkRequest, responses((status = 200, body = HandFkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_hand_fk(State(s): State<Arc<AppState>>, Path(hand_id): Path<String>, Json(req): Json<HandFkRequest>) -> ApiResult<HandFkResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let h = lookup_hand(&s, &hand_id)?;
+    let record = AuditRecord { hand_id: Some(h.id.clone()), ..audit::record("hand-solve-fk", audit::input_hash(&req)) };
+    let posture = finger_angles(&h, "joint_angles", &req.joint_angles, |_| None)?;
+    let _phase = slow::phase("solve");
+    let fingertips = tracing::info_span!("solve", hand = %h.id).in_scope(|| h.fingers.iter().map(|f| {
+        let tip = kinematics_solver::forward(&hand::finger_chain(&h, f), &posture[&f.name]).tip;
+        (f.name.clone(), Fingertip { position: tip.p, orientation: tip.quat_xyzw() })
+    }).collect());
+    drop(_phase);
+    count_op(&s, Op::Fk);
+    Ok(Json(audited(&s, record, publish(&s, Op::Fk, HandFkResponse { fingertips, quaternion_order: QUATERNION_ORDER.into(), elapsed_us: deterministic::elapsed_us(t) }))))
+}
+
+/// Grasp posture: one IK solve per targeted finger, each on its own chain from the palm.
+#[utoipa::path(post, path = "/api/v1/kinematics/hands/{hand_id}/solve-ik", params(("hand_id" = String, Path)), request_body = HandIkRequest, responses((status = 200, body = HandIkResponse), (status = "4XX", body = ApiError)))]
+async fn solve_hand_ik(State(s): State<Arc<AppState>>, Path(hand_id): Path<String>, Json(req): Json<HandIkRequest>) -> ApiResult<HandIkResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, req.deadline_ms);
+    let h = lookup_hand(&s, &hand_id)?;
+    let record = AuditRecord { hand_id: Some(h.id.clone()), ..audit::record("hand-solve-ik", audit::input_hash(&req)) };
+    if let Some(unknown) = req.targets.keys().find(|k| h.finger(k).is_none()) {
+        return Err(ApiError::invalid(format!("targets.{unknown}"), "Unknown finger", format!("hand '{}' has no finger '{unknown}'", h.id)));
+    }
+    let mid = |f: &hand::FingerDef| req.targets.contains_key(&f.name)
+        .then(|| f.joints.iter().map(|j| j.limits.map_or(0.0, |[lo, hi]| (lo + hi) / 2.0)).collect());
+    let mut posture = finger_angles(&h, "seed", &req.seed, mid)?;
+    let c = req.constraints.as_ref();
+    let overrides = chains::SolverDefaults {
+        damping: c.and_then(|c| c.damping), tolerance: c.and_then(|c| c.tolerance), max_iterations: c.and_then(|c| c.max_iterations),
+        ..Default::default()
+    };
+    overrides.validate().map_err(|e| ApiError::bad_request("Invalid solver settings", e))?;
+    let effective = solver_settings(&s, None, &overrides);
+    let settings = kinematics_solver::IkSettings { max_iterations: effective.max_iterations, tolerance: effective.tolerance, damping: effective.damping };
+    let (mut fingers, mut timed_out, mut solve_us) = (BTreeMap::new(), false, 0);
+    if past(deadline) { return Err(deadline_exceeded()); }
+    let work: Vec<_> = req.targets.iter()
+        .map(|(name, target)| (name.clone(), hand::finger_chain(&h, h.finger(name).expect("checked above")), target.clone(), posture[name].clone())).collect();
+    let (_solve, span) = (slow::phase("solve"), tracing::info_span!("solve", hand = %h.id, fingers = work.len()));
+    let wrap = req.wrap_policy.clone();
+    let solved = compute::run(move || span.in_scope(|| work.into_par_iter().map(|(name, chain, target, seed)| {
+        let (t, start) = (Instant::now(), seed.clone());
+        let (mut r, allocated) = allocations::counted(|| kinematics_solver::solve_ik_until(&chain, target.position, target.orientation, seed, &settings, || past(deadline)));
+        kinematics_solver::wrap_angles(&chain, &mut r.angles, &start, wrap.as_deref());
+        let conditioning = kinematics_solver::conditioning(&chain, &r.angles, target.orientation.is_some());
+        (name, r, t.elapsed().as_micros(), allocated, conditioning)
+    }).collect::<Vec<_>>())).await?;
+    drop(_solve);
+    s.metrics.solve_allocations(solved.iter().map(|f| f.3));
+    for (name, r, elapsed_us, _, (min_singular_value, condition_number)) in solved {
+        let elapsed_us = if deterministic::seed().is_some() { 0 } else { elapsed_us };
+        (timed_out, solve_us) = (timed_out | r.stopped, solve_us + elapsed_us);
+        posture.insert(name.clone(), r.angles);
+        fingers.insert(name, FingerSolution {
+            iterations: r.iterations, converged: r.converged, error_distance: r.position_error, orientation_error: r.orientation_error,
+            min_singular_value: Some(min_singular_value), condition_number, elapsed_us,
+        });
+    }
+    count_op(&s, Op::Ik);
+    s.metrics.ik(None, fingers.values().all(|f| f.converged), fingers.values().map(|f| f.iterations).max().unwrap_or(0));
+    Ok(Json(audited(&s, record, publish(&s, Op::Ik, keep_solution(&s, HandIkResponse {
+        solution_id: deterministic::id("hand-solution", &(&h, &req)),
+        joint_angles: h.fingers.iter().flat_map(|f| posture[&f.name].clone()).collect(),
+        converged: fingers.values().all(|f| f.converged), posture, fingers, elapsed_us: deterministic::elapsed_us(t), solve_us, timed_out,
+    }, |r| &r.solution_id)))))
+}
+
+fn lookup_hand(s: &AppState, id: &str) -> Result<hand::HandDef, ApiError> {
+    s.hands.current().lock().unwrap().get(id).cloned()
+        .ok_or_else(|| ApiError::not_found("Hand not found", id))
+}
+
+/// Every finger's angles: given ones (checked against the finger's DoF), else `default` or zeros.
+fn finger_angles(h: &hand::HandDef, field: &str, given: &hand::FingerAngles, default: impl Fn(&hand::FingerDef) -> Option<Vec<f64>>) -> Result<hand::FingerAngles, ApiError> {
+    if let Some(unknown) = given.keys().find(|k| h.finger(k).is_none()) {
+        return Err(ApiError::invalid(format!("{field}.{unknown}"), "Unknown finger", format!("hand '{}' has no finger '{unknown}'", h.id)));
+    }
+    h.fingers.iter().map(|f| match given.get(&f.name) {
+        Some(q) if q.len() != f.joints.len() => Err(ApiError::invalid(format!("{field}.{}", f.name), "Joint count mismatch", format!("finger '{}' has {} joints, got {}", f.name, f.joints.len(), q.len()))),
+        Some(q) => Ok((f.name.clone(), q.clone())),
+        None => Ok((f.name.clone(), default(f).unwrap_or_else(|| vec![0.0; f.joints.len()]))),
+    }).collect()
+}
+
+/// PUT/DELETE target checks: presets are read-only, unknown (or pinned) ids are 404.
+fn writable_chain(registry: &chains::ChainRegistry, id: &str) -> Result<(), ApiError> {
+    if registry.is_preset(id) {
+        return Err(ApiError::forbidden("Preset chains are read-only", id));
+    }
+    if id.contains('@') || registry.get(id).is_none() {
+        return Err(ApiError::not_found("Chain not found", id));
+    }
+    Ok(())
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/chains/export", responses((status = 200, body = [chains::ChainDef])))]
+async fn export_chains(State(s): State<Arc<AppState>>) -> Json<Vec<chains::ChainDef>> {
+    Json(s.chains.current().lock().unwrap().custom().to_vec())
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/chains/flush", responses((status = 200, body = ChainFlushResponse), (status = "4XX", body = ApiError)))]
+async fn flush_chains(State(s): State<Arc<AppState>>) -> ApiResult<ChainFlushResponse> {
+    let chains = s.chains.current();
+    let mut registry = chains.lock().unwrap();
+    let path = registry.flush()
+        .map_err(|e| ApiError::internal("Chain store write failed", e))?.map(|p| p.display().to_string());
+    Ok(Json(ChainFlushResponse { path, chains: registry.custom().len() }))
+}
+
+/// Count a completed operation, globally and against the calling key.
+fn count_op(s: &AppState, op: Op) { count_ops(s, op, 1) }
+
+/// Count `n` completed operations at once, such as a batch's solves.
+fn count_ops(s: &AppState, op: Op, n: u64) {
+    {
+        let mut st = s.stats.lock().unwrap();
+        match op {
+            Op::Ik => st.total_ik_solves += n, Op::Fk => st.total_fk_solves += n,
+            Op::Compression => st.total_compressions += n, Op::Trajectory => st.total_trajectories += n,
+        }
+    }
+    let Some(p) = auth::current() else { return };
+    s.keys.lock().unwrap().record(&p, |u| match op {
+        Op::Ik => u.ik_solves += n, Op::Fk => u.fk_solves += n, Op::Compression => u.compressions += n, Op::Trajectory => u.trajectories += n,
+    });
+}
+
+/// Resolve an optional `chain_id` against the registry (404 when unknown).
+fn lookup_chain(s: &AppState, id: Option<&str>) -> Result<Option<chains::ChainDef>, ApiError> {
+    let Some(id) = id else { return Ok(None) };
+    s.chains.current().lock().unwrap().get(id).cloned().map(Some)
+        .ok_or_else(|| ApiError::not_found("Chain not found", id))
+}
+
+/// Queue an operation; the request is validated as JSON up front, the work runs in the background.
+#[utoipa::path(post, path = "/api/v1/kinematics/jobs", request_body = JobRequest, responses((status = 202, body = jobs::Job, headers(("Location" = String))), (status = "4XX", body = ApiError), (status = 503, body = ApiError)))]
+async fn submit_job(State(s): State<Arc<AppState>>, Json(req): Json<JobRequest>) -> Result<impl IntoResponse, ApiError> {
+    if let Some(url) = &req.callback_url {
+        s.webhooks.check_url(url).await.map_err(|e| ApiError::invalid("callback_url", "Invalid callback URL", e))?;
+    }
+    if s.shutdown.is_draining() { return Err(ApiError::unavailable("Shutting down", "this engine is draining; submit to another instance")); }
+    in_service(&s)?;
+    let task = operation_task(&s, &req.operation, req.request)?;
+    let job_id = s.jobs.lock().unwrap().create(&req.operation, req.callback_url, tenant::tag())
+        .ok_or_else(|| ApiError::unavailable("Job queue full", format!("{MAX_JOBS} jobs retained")))?;
+    let (state, id, principal, route) = (s.clone(), job_id.clone(), auth::current(), format!("/api/v1/kinematics/{}", req.operation));
+    let handle = tokio::spawn(auth::scope(principal, deterministic::scope(deterministic::seed(), async move {
+        let _slot = concurrency::job_slot(&state, route).await;
+        state.jobs.lock().unwrap().start(&id);
+        let reporter: jobs::Reporter = {
+            let (state, id) = (state.clone(), id.clone());
+            Arc::new(move |fraction, milestone| state.jobs.lock().unwrap().progress(&id, fraction, milestone))
+        };
+        let outcome = jobs::with_reporter(reporter, task).await;
+        state.jobs.lock().unwrap().finish(&id, outcome);
+        notify_job(&state, &id);
+    })));
+    let mut store = s.jobs.lock().unwrap();
+    store.attach(&job_id, handle.abort_handle());
+    let job = store.get(&job_id).map(|e| e.job.clone()).expect("job just created");
+    tracing::info!("Queued {} job {job_id}", req.operation);
+    Ok((StatusCode::ACCEPTED, [(header::LOCATION, format!("/api/v1/kinematics/jobs/{job_id}"))], Json(job)))
+}
+
+/// POST a finished job's outcome to its callback URL in the background, if it has one.
+fn notify_job(s: &Arc<AppState>, job_id: &str) {
+    let Some(entry) = s.jobs.lock().unwrap().get(job_id).filter(|e| e.job.callback.is_some()).cloned() else { return };
+    let Some(url) = entry.job.callback.as_ref().map(|c| c.url.clone()) else { return };
+    let event = format!("job.{}", serde_json::to_value(entry.job.state).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default());
+    let body = serde_json::to_vec(&serde_json::json!({ "event": event, "job": entry.job, "result": entry.result })).unwrap_or_default();
+    let (s, job_id) = (s.clone(), job_id.to_string());
+    tokio::spawn(async move {
+        s.webhooks.deliver(&url, &event, body, |outcome, last| {
+            if let Err(e) = &outcome { tracing::warn!("Callback for job {job_id} to {url}: {e}"); }
+            s.jobs.lock().unwrap().delivery(&job_id, outcome, last);
+        }).await;
+    });
+}
+
+/// `operation`'s handler, ready to run on `request`.
+fn operation_task(s: &Arc<AppState>, operation: &str, request: serde_json::Value) -> Result<JobTask, ApiError> {
+    match operation {
+        "solve-ik" => job_task(s, request, solve_ik),
+        "solve-ik/batch" => job_task(s, request, solve_ik_batch),
+        "solve-fk" => job_task(s, request, solve_fk),
+        "compress-intent" => job_task(s, request, compress_intent),
+        "optimize-trajectory" => job_task(s, request, optimize_trajectory),
+        "retarget" => job_task(s, request, retarget_motion),
+        other => Err(ApiError::bad_request("Unknown operation", format!("'{other}' is not one of solve-ik, solve-ik/batch, solve-fk, compress-intent, optimize-trajectory, retarget"))),
+    }
+}
+
+/// Parse `request` for `handler` now, returning the deferred call.
+fn job_task<Req, Resp, Fut>(s: &Arc<AppState>, request: serde_json::Value, handler: fn(State<Arc<AppState>>, Json<Req>) -> Fut) -> Result<JobTask, ApiError>
+where Req: serde::de::DeserializeOwned + Send + 'static, Resp: Serialize, Fut: std::future::Future<Output = ApiResult<Resp>> + Send + 'static {
+    let req: Req = error::from_value(request).map_err(|e| e.within("request"))?;
+    let s = s.clone();
+    Ok(Box::pin(async move {
+        let Json(resp) = handler(State(s), Json(req)).await?;
+        Ok(serde_json::to_value(resp).unwrap_or_default())
+    }))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/jobs", responses((status = 200, body = [jobs::Job])))]
+async fn list_jobs(State(s): State<Arc<AppState>>) -> Json<Vec<jobs::Job>> {
+    let tenant = tenant::tag();
+    Json(s.jobs.lock().unwrap().list().into_iter().filter(|e| e.tenant == tenant).map(|e| e.job.clone()).collect())
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/jobs/{job_id}", params(("job_id" = String, Path)), responses((status = 200, body = jobs::Job), (status = "4XX", body = ApiError)))]
+async fn get_job(State(s): State<Arc<AppState>>, Path(job_id): Path<String>) -> ApiResult<jobs::Job> {
+    visible_job(&mut s.jobs.lock().unwrap(), &job_id).map(|e| Json(e.job.clone()))
+}
+
+/// The finished job's response body, or its error with the status the endpoint would have returned.
+#[utoipa::path(get, path = "/api/v1/kinematics/jobs/{job_id}/result", params(("job_id" = String, Path)), responses((status = 200, description = "The synchronous endpoint's response body", body = Object), (status = "4XX", body = ApiError)))]
+async fn job_result(State(s): State<Arc<AppState>>, Path(job_id): Path<String>) -> ApiResult<serde_json::Value> {
+    let mut store = s.jobs.lock().unwrap();
+    let entry = visible_job(&mut store, &job_id)?;
+    match (&entry.result, &entry.job.error) {
+        (Some(result), _) => Ok(Json(result.clone())),
+        (None, Some(e)) => Err(e.clone()),
+        (None, None) => {
+            let state = serde_json::to_value(entry.job.state).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+            Err(ApiError::conflict("Job has no result", format!("job is {state}")))
+        }
+    }
+}
+
+/// SSE stream of a job's events: its history so far, then live ones until it finishes.
+#[utoipa::path(get, path = "/api/v1/kinematics/jobs/{job_id}/events", params(("job_id" = String, Path)), responses((status = 200, body = jobs::JobEvent, content_type = "text/event-stream"), (status = "4XX", body = ApiError)))]
+async fn job_events(State(s): State<Arc<AppState>>, Path(job_id): Path<String>) -> Result<Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>>, ApiError> {
+    let (history, rx) = {
+        let mut store = s.jobs.lock().unwrap();
+        visible_job(&mut store, &job_id)?;
+        store.subscribe(&job_id).ok_or_else(|| job_not_found(&job_id))?
+    };
+    let last = history.last().map(|e| e.seq);
+    let done = history.last().is_some_and(|e| e.terminal());
+    let drain = s.shutdown.wait();
+    let live = stream::unfold((rx, std::collections::VecDeque::new(), last, done), move |(mut rx, mut pending, mut last, done)| {
+        let (s, job_id) = (s.clone(), job_id.clone());
+        async move {
+            loop {
+                if let Some(e) = pending.pop_front() {
+                    let done = jobs::JobEvent::terminal(&e);
+                    return Some((e, (rx, pending, last, done)));
+                }
+                if done { return None; }
+                match rx.recv().await {
+                    Ok((id, e)) if id == job_id && last.is_none_or(|l| e.seq > l) => { last = Some(e.seq); pending.push_back(e); }
+                    Ok(_) => {}
+                    // Fell behind the broadcast: catch up from the job's own record
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        let missed = s.jobs.lock().unwrap().events_since(&job_id, last);
+                        if let Some(e) = missed.last() { last = Some(e.seq); }
+                        pending.extend(missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+    let events = stream::iter(history).chain(live).take_until(drain)
+        .map(|e| Ok(Event::default().event(&e.kind).json_data(&e).unwrap_or_default()));
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[utoipa::path(post, path = "/api/v1/kinematics/jobs/{job_id}/cancel", params(("job_id" = String, Path)), responses((status = 200, body = jobs::Job), (status = "4XX", body = ApiError)))]
+async fn cancel_job(State(s): State<Arc<AppState>>, Path(job_id): Path<String>) -> ApiResult<jobs::Job> {
+    let cancelled = {
+        let mut store = s.jobs.lock().unwrap();
+        let pending = !visible_job(&mut store, &job_id)?.job.finished();
+        if !store.cancel(&job_id) { return Err(job_not_found(&job_id)); }
+        pending
+    };
+    if cancelled { notify_job(&s, &job_id); }
+    s.jobs.lock().unwrap().get(&job_id).map(|e| Json(e.job.clone())).ok_or_else(|| job_not_found(&job_id))
+}
+
+#[utoipa::path(delete, path = "/api/v1/kinematics/jobs/{job_id}", params(("job_id" = String, Path)), responses((status = 204), (status = "4XX", body = ApiError)))]
+async fn delete_job(State(s): State<Arc<AppState>>, Path(job_id): Path<String>) -> Result<StatusCode, ApiError> {
+    let mut store = s.jobs.lock().unwrap();
+    visible_job(&mut store, &job_id)?;
+    store.remove(&job_id).map(|_| StatusCode::NO_CONTENT).ok_or_else(|| job_not_found(&job_id))
+}
+
+/// When a solve started at `start` must stop: after the engine's solve timeout, or the
+/// request's own shorter deadline.
+fn solve_deadline(s: &AppState, start: Instant, requested_ms: Option<u64>) -> Option<Instant> {
+    let budget = match (*s.solve_timeout.lock().unwrap(), requested_ms.map(std::time::Duration::from_millis)) {
+        (Some(limit), Some(asked)) => Some(limit.min(asked)),
+        (limit, asked) => limit.or(asked),
+    };
+    budget.map(|b| start + b)
+}
+
+fn past(deadline: Option<Instant>) -> bool { deadline.is_some_and(|d| Instant::now() >= d) }
+
+fn deadline_exceeded() -> ApiError {
+    ApiError::timeout("Deadline exceeded", "the deadline passed before solving began; raise deadline_ms or KINEMATICS_SOLVE_TIMEOUT_MS")
+}
+
+/// Refuse a solve while in maintenance mode, unless it is part of a job accepted before.
+fn in_service(s: &AppState) -> Result<(), ApiError> {
+    let m = s.maintenance.lock().unwrap();
+    if !m.enabled || jobs::in_job() { return Ok(()); }
+    Err(ApiError::unavailable("Under maintenance", m.reason.clone().unwrap_or_else(|| "this engine is in maintenance mode; retry later or use another instance".into())))
+}
+
+fn job_not_found(job_id: &str) -> ApiError {
+    ApiError::not_found("Job not found", job_id)
+}
+
+/// The job, if it belongs to the caller's tenant; other tenants' jobs are not found.
+fn visible_job<'a>(store: &'a mut jobs::JobStore, job_id: &str) -> Result<&'a jobs::Entry, ApiError> {
+    store.get(job_id).filter(|e| e.tenant == tenant::tag()).ok_or_else(|| job_not_found(job_id))
+}
+
+/// Start a simulation session on a chain, pinned to its current version.
+#[utoipa::path(post, path = "/api/v1/kinematics/sims", request_body = SimRequest, responses((status = 201, body = SimState, headers(("Location" = String))), (status = "4XX", body = ApiError), (status = 503, body = ApiError)))]
+async fn create_sim(State(s): State<Arc<AppState>>, Json(req): Json<SimRequest>) -> Result<impl IntoResponse, ApiError> {
+    in_service(&s)?;
+    req.validate()?;
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let n = chain.dof();
+    let q = match &req.joint_angles {
+        Some(Joints::Named(name)) => named_configuration(Some(&chain), name)?,
+        Some(Joints::Angles(q)) => q.clone(),
+        None => vec![0.0; n],
+    };
+    if q.len() != n { return Err(ApiError::invalid("joint_angles", "Joint count mismatch", format!("{} angles for {n} joints", q.len()))); }
+    let qd = req.joint_velocities.unwrap_or_else(|| vec![0.0; n]);
+    if qd.len() != n { return Err(ApiError::invalid("joint_velocities", "Joint count mismatch", format!("{} velocities for {n} joints", qd.len()))); }
+    let sim_id = deterministic::id("sim", &(chain.pinned_id(), &q, &qd, req.dt, req.gravity));
+    let sim = sim::Sim::new(sim_id.clone(), chain, q, qd, req.dt.unwrap_or(sim::DEFAULT_DT), req.gravity.unwrap_or(kinematics_solver::GRAVITY));
+    let sim = s.sims.lock().unwrap().insert(tenant::tag(), sim)
+        .ok_or_else(|| ApiError::unavailable("Too many simulations", format!("{MAX_SIMS} sessions open; delete finished ones")))?;
+    let state = busy(&sim)?.state();
+    Ok((StatusCode::CREATED, [(header::LOCATION, format!("/api/v1/kinematics/sims/{sim_id}"))], Json(state)))
+}
+
+fn sim_not_found(sim_id: &str) -> ApiError {
+    ApiError::not_found("Simulation not found", format!("{sim_id} (unknown, idle too long or another tenant's)"))
+}
+
+/// The session, if it belongs to the caller's tenant; other tenants' sessions are not found.
+fn visible_sim(s: &AppState, sim_id: &str) -> Result<Arc<Mutex<sim::Sim>>, ApiError> {
+    s.sims.lock().unwrap().get(tenant::tag().as_deref(), sim_id).ok_or_else(|| sim_not_found(sim_id))
+}
+
+/// The session's lock, or 409 while a step holds it, so no request waits on a long step.
+fn busy(sim: &Mutex<sim::Sim>) -> Result<std::sync::MutexGuard<'_, sim::Sim>, ApiError> {
+    sim.try_lock().map_err(|_| ApiError::conflict("Simulation busy", "another step is running on this session; retry once it returns"))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/sims/{sim_id}", params(("sim_id" = String, Path)), responses((status = 200, body = SimState), (status = "4XX", body = ApiError)))]
+async fn get_sim(State(s): State<Arc<AppState>>, Path(sim_id): Path<String>) -> ApiResult<SimState> {
+    let sim = visible_sim(&s, &sim_id)?;
+    let state = busy(&sim)?.state();
+    Ok(Json(state))
+}
+
+/// Hold a velocity or torque command for `steps` steps and return the state reached.
+/// A session takes one step request at a time.
+#[utoipa::path(post, path = "/api/v1/kinematics/sims/{sim_id}/step", params(("sim_id" = String, Path)), request_body = SimStepRequest, responses((status = 200, body = SimState), (status = "4XX", body = ApiError)))]
+async fn step_sim(State(s): State<Arc<AppState>>, Path(sim_id): Path<String>, Json(req): Json<SimStepRequest>) -> ApiResult<SimState> {
+    in_service(&s)?;
+    req.validate()?;
+    let sim = visible_sim(&s, &sim_id)?;
+    {
+        let sim = busy(&sim)?;
+        let n = sim.chain().dof();
+        if req.command.len() != n { return Err(ApiError::invalid("command", "Joint count mismatch", format!("{} values for {n} joints", req.command.len()))); }
+        if req.mode == "torque" && !sim.chain().joints.iter().any(|j| j.inertial.is_some_and(|m| m.mass > 0.0)) {
+            return Err(ApiError::invalid("mode", "No mass properties", format!("torque commands need link masses (inertial) on chain {}", sim.chain().pinned_id())));
+        }
+    }
+    let steps = req.steps.unwrap_or(1);
+    compute::run(move || { let mut sim = busy(&sim)?; sim.step(&req.mode, &req.command, steps); Ok(sim.state()) }).await?.map(Json)
+}
+
+#[utoipa::path(delete, path = "/api/v1/kinematics/sims/{sim_id}", params(("sim_id" = String, Path)), responses((status = 204), (status = "4XX", body = ApiError)))]
+async fn delete_sim(State(s): State<Arc<AppState>>, Path(sim_id): Path<String>) -> Result<StatusCode, ApiError> {
+    s.sims.lock().unwrap().remove(tenant::tag().as_deref(), &sim_id).then_some(StatusCode::NO_CONTENT).ok_or_else(|| sim_not_found(&sim_id))
+}
+
+/// Simulated execution of a planned joint trajectory under a PD controller and actuator model.
+#[utoipa::path(post, path = "/api/v1/kinematics/simulate-tracking", request_body = TrackingRequest, responses((status = 200, body = TrackingResponse), (status = "4XX", body = ApiError)))]
+async fn simulate_tracking(State(s): State<Arc<AppState>>, Json(req): Json<TrackingRequest>) -> ApiResult<TrackingResponse> {
+    in_service(&s)?;
+    req.validate()?;
+    let t = Instant::now();
+    let deadline = solve_deadline(&s, t, None);
+    limits::Points::Waypoints.check(&s, "joint_angles", req.joint_angles.len())?;
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let n = chain.dof();
+    if req.joint_angles[0].len() != n {
+        return Err(ApiError::invalid("joint_angles", "Joint count mismatch", format!("{} angles per frame for the {n} joints of {}", req.joint_angles[0].len(), chain.pinned_id())));
+    }
+    let per_joint = |field: &str, v: &[f64]| match v.len() {
+        1 => Ok(vec![v[0]; n]),
+        len if len == n => Ok(v.to_vec()),
+        len => Err(ApiError::invalid(field, "Joint count mismatch", format!("{len} values for the {n} joints of {}; give one, or one per joint", chain.pinned_id()))),
+    };
+    let dynamic = req.plant.as_deref() == Some("dynamic");
+    if dynamic && !chain.joints.iter().any(|j| j.inertial.is_some_and(|m| m.mass > 0.0)) {
+        return Err(ApiError::invalid("plant", "No mass properties", format!("the dynamic plant needs link masses (inertial) on chain {}", chain.pinned_id())));
+    }
+    let controller = tracking::Controller {
+        kp: per_joint("kp", &req.kp)?, kd: per_joint("kd", &req.kd)?, bandwidth_hz: req.bandwidth_hz,
+        max_effort: req.max_effort.as_deref().map(|m| per_joint("max_effort", m)).transpose()?, dynamic, gravity_compensation: req.gravity_compensation.unwrap_or(true),
+    };
+    let frame_time = req.frame_time.unwrap_or(tracking::DEFAULT_FRAME_TIME);
+    let times = req.times.unwrap_or_else(|| (0..req.joint_angles.len()).map(|i| i as f64 * frame_time).collect());
+    let dt = req.dt.unwrap_or(sim::DEFAULT_DT);
+    let (steps, cap) = ((times[times.len() - 1] - times[0]) / dt, if dynamic { tracking::MAX_DYNAMIC_STEPS } else { tracking::MAX_STEPS });
+    if steps > cap as f64 {
+        return Err(ApiError::invalid("dt", "Too many steps", format!("{steps:.0} steps of {dt} s for {:.3} s of trajectory; at most {cap} under the {} plant", times[times.len() - 1] - times[0], tracking::PLANTS[dynamic as usize])));
+    }
+    let (solve_chain, frames) = (chain.clone(), req.joint_angles);
+    let span = tracing::info_span!("simulate_tracking", chain = %chain.pinned_id(), frames = frames.len(), dynamic);
+    let run = compute::run(move || span.in_scope(|| tracking::run(&solve_chain, &frames, &times, &controller, dt, || past(deadline)))).await?;
+    let samples = run.samples;
+    let rms = |values: &mut dyn Iterator<Item = f64>| { let (sum, count) = values.fold((0.0, 0), |(s, c), v| (s + v * v, c + 1)); (sum / count.max(1) as f64).sqrt() };
+    let max_joint_error = (0..n).map(|i| samples.iter().map(|p| p.joint_error[i].abs()).fold(0.0, f64::max)).collect();
+    let rms_joint_error = (0..n).map(|i| rms(&mut samples.iter().map(|p| p.joint_error[i]))).collect();
+    let worst = samples.iter().max_by(|a, b| a.tip_error.total_cmp(&b.tip_error));
+    let (max_tip_error, max_tip_error_time) = worst.map_or((0.0, 0.0), |p| (p.tip_error, p.time));
+    count_op(&s, Op::Trajectory);
+    Ok(Json(TrackingResponse {
+        max_joint_error, rms_joint_error, max_tip_error, max_tip_error_time, rms_tip_error: rms(&mut samples.iter().map(|p| p.tip_error)),
+        within_tolerance: req.tolerance.map(|tol| !run.stopped && max_tip_error <= tol), samples, saturated_joints: run.saturated, limit_hits: run.limit_hits,
+        plant: tracking::PLANTS[dynamic as usize].into(), steps: run.steps, chain: chain.pinned_id(), elapsed_us: deterministic::elapsed_us(t), timed_out: run.stopped,
+    }))
+}
+
+/// Start tracking a robot's joint state on a chain, pinned to its current version.
+#[utoipa::path(post, path = "/api/v1/kinematics/sessions", request_body = SessionRequest, responses((status = 201, body = SessionState, headers(("Location" = String))), (status = "4XX", body = ApiError), (status = 503, body = ApiError)))]
+async fn create_session(State(s): State<Arc<AppState>>, Json(req): Json<SessionRequest>) -> Result<impl IntoResponse, ApiError> {
+    req.validate()?;
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let q = session_angles(&chain, "joint_angles", req.joint_angles.as_ref())?;
+    let session_id = deterministic::id("session", &(tenant::tag(), chain.pinned_id(), &req.robot, &q));
+    let session = sessions::Session::new(session_id.clone(), req.robot, chain, q);
+    let state = s.sessions.lock().unwrap().insert(tenant::tag(), session).map(sessions::Session::state)
+        .ok_or_else(|| ApiError::unavailable("Too many sessions", format!("{MAX_SESSIONS} sessions open; delete unused ones")))?;
+    Ok((StatusCode::CREATED, [(header::LOCATION, format!("/api/v1/kinematics/sessions/{session_id}"))], Json(state)))
+}
+
+/// `joints` (all zeros when absent) checked against `chain`'s DoF.
+fn session_angles(chain: &chains::ChainDef, field: &str, joints: Option<&Joints>) -> Result<Vec<f64>, ApiError> {
+    let n = chain.dof();
+    let q = match joints {
+        Some(Joints::Named(name)) => named_configuration(Some(chain), name)?,
+        Some(Joints::Angles(q)) => q.clone(),
+        None => vec![0.0; n],
+    };
+    if q.len() != n { return Err(ApiError::invalid(field, "Joint count mismatch", format!("{} angles for {n} joints", q.len()))); }
+    Ok(q)
+}
+
+fn session_not_found(session_id: &str) -> ApiError {
+    ApiError::not_found("Session not found", format!("{session_id} (unknown, idle too long or another tenant's)"))
+}
+
+/// With a `session_id`, the session's pinned chain stands in for `chain_id` (which may
+/// only name the same chain) and its joint state is returned.
+fn session_chain(s: &AppState, session_id: Option<&str>, chain_id: &mut Option<String>) -> Result<Option<Vec<f64>>, ApiError> {
+    let Some(session_id) = session_id else { return Ok(None) };
+    let mut sessions = s.sessions.lock().unwrap();
+    let session = sessions.get(tenant::tag().as_deref(), session_id).ok_or_else(|| session_not_found(session_id))?;
+    let chain = session.chain();
+    if chain_id.as_deref().is_some_and(|id| id.split('@').next() != Some(chain.id.as_str())) {
+        return Err(ApiError::invalid("chain_id", "Chain does not match session", format!("session {session_id} is on chain {}", chain.pinned_id())));
+    }
+    *chain_id = Some(chain.pinned_id());
+    Ok(Some(session.angles().to_vec()))
+}
+
+/// Move a session to where an executed trajectory left it; gone by now is no error.
+fn executed(s: &AppState, session_id: Option<&str>, end: Option<Vec<f64>>) {
+    let (Some(session_id), Some(q)) = (session_id, end) else { return };
+    if let Some(session) = s.sessions.lock().unwrap().get(tenant::tag().as_deref(), session_id) { session.set(q, "trajectory"); }
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/sessions/{session_id}", params(("session_id" = String, Path)), responses((status = 200, body = SessionState), (status = "4XX", body = ApiError)))]
+async fn get_session(State(s): State<Arc<AppState>>, Path(session_id): Path<String>) -> ApiResult<SessionState> {
+    s.sessions.lock().unwrap().get(tenant::tag().as_deref(), &session_id).map(|session| Json(session.state())).ok_or_else(|| session_not_found(&session_id))
+}
+
+/// Report the robot's current joint state.
+#[utoipa::path(put, path = "/api/v1/kinematics/sessions/{session_id}/state", params(("session_id" = String, Path)), request_body = StateReport, responses((status = 200, body = SessionState), (status = "4XX", body = ApiError)))]
+async fn report_state(State(s): State<Arc<AppState>>, Path(session_id): Path<String>, Json(req): Json<StateReport>) -> ApiResult<SessionState> {
+    req.validate()?;
+    let mut sessions = s.sessions.lock().unwrap();
+    let session = sessions.get(tenant::tag().as_deref(), &session_id).ok_or_else(|| session_not_found(&session_id))?;
+    let q = session_angles(session.chain(), "joint_angles", Some(&req.joint_angles))?;
+    session.set(q, "reported");
+    Ok(Json(session.state()))
+}
+
+#[utoipa::path(delete, path = "/api/v1/kinematics/sessions/{session_id}", params(("session_id" = String, Path)), responses((status = 204), (status = "4XX", body = ApiError)))]
+async fn delete_session(State(s): State<Arc<AppState>>, Path(session_id): Path<String>) -> Result<StatusCode, ApiError> {
+    s.sessions.lock().unwrap().remove(tenant::tag().as_deref(), &session_id).then_some(StatusCode::NO_CONTENT).ok_or_else(|| session_not_found(&session_id))
+}
+
+#[utoipa::path(get, path = "/api/v1/kinematics/stats", responses((status = 200, body = StatsResponse)))]
 async fn stats(State(s): State<Arc<AppState>>) -> Json<StatsResponse> {
     let st = s.stats.lock().unwrap();
-    Json(StatsResponse {
-        total_ik_solves: st.total_ik_solves, total_fk_solves: st.total_fk_solves,
+    let global = StatsResponse {
+        since_ms: st.since_ms, total_ik_solves: st.total_ik_solves, total_fk_solves: st.total_fk_solves,
         total_compressions: st.total_compressions, total_trajectories: st.total_trajectories,
+        keys: BTreeMap::new(), tenants: BTreeMap::new(), chains: s.metrics.chain_stats(&tenant::current()), endpoints: BTreeMap::new(),
+    };
+    let keys = s.keys.lock().unwrap();
+    Json(match auth::current() {
+        Some(p) if p.admin => StatsResponse { keys: keys.all_usage(), tenants: keys.all_tenant_usage(), endpoints: s.metrics.endpoint_stats(), ..global },
+        // Everyone else sees their own tenant's totals and their own usage
+        Some(p) => {
+            let t = keys.tenant_usage(&tenant::current());
+            StatsResponse {
+                total_ik_solves: t.ik_solves, total_fk_solves: t.fk_solves, total_compressions: t.compressions, total_trajectories: t.trajectories,
+                keys: BTreeMap::from([(p.key_id.clone(), keys.usage(&p.key_id))]), ..global
+            }
+        }
+        None => StatsResponse { endpoints: s.metrics.endpoint_stats(), ..global },
     })
 }
 
-// ── Helpers ─────────────────────────────────────────────────
-fn fk_chain(angles: &[f64], link_len: f64) -> (f64, f64, f64) {
-    let mut x = 0.0f64;
-    let mut y = 0.0f64;
-    let mut z = 0.0f64;
-    let mut cumulative = 0.0f64;
-    for &angle in angles {
-        cumulative += angle;
-        x += link_len * cumulative.cos();
-        y += link_len * cumulative.sin();
-        z += link_len * (cumulative * 0.5).sin() * 0.3;
+/// Solve audit records for the caller's tenant, oldest first.
+#[utoipa::path(get, path = "/api/v1/kinematics/audit", params(AuditQuery), responses((status = 200, body = AuditResponse)))]
+async fn list_audit(State(s): State<Arc<AppState>>, Query(q): Query<AuditQuery>) -> Json<AuditResponse> {
+    let log = s.audit.lock().unwrap();
+    let matches = log.query(tenant::tag().as_deref(), &q);
+    Json(AuditResponse {
+        total: matches.len(),
+        records: matches.into_iter().skip(q.offset.unwrap_or(0)).take(q.limit.unwrap_or(100)).cloned().collect(),
+    })
+}
+
+#[utoipa::path(get, path = "/api/v1/admin/keys", responses((status = 200, body = [auth::ApiKey]), (status = "4XX", body = ApiError)))]
+async fn list_keys(State(s): State<Arc<AppState>>) -> ApiResult<Vec<auth::ApiKey>> {
+    auth::require_admin()?;
+    Ok(Json(s.keys.lock().unwrap().list()))
+}
+
+/// Issue a key. The secret is in this response only; the engine keeps its hash.
+#[utoipa::path(post, path = "/api/v1/admin/keys", request_body = CreateKeyRequest, responses((status = 201, body = CreatedKey), (status = "4XX", body = ApiError)))]
+async fn create_key(State(s): State<Arc<AppState>>, Json(req): Json<CreateKeyRequest>) -> Result<(StatusCode, Json<CreatedKey>), ApiError> {
+    let admin = auth::require_admin()?;
+    if req.name.trim().is_empty() { return Err(ApiError::invalid("name", "Empty key name", "name must not be empty")); }
+    if let Some(t) = &req.tenant { tenant::check(t).map_err(|e| ApiError::invalid("tenant", "Invalid tenant", e))?; }
+    let (key, secret) = s.keys.lock().unwrap().create(req.name, req.admin, req.tenant)?;
+    tracing::info!("Key {} ({}) issued by {}", key.key_id, key.name, admin.key_id);
+    Ok((StatusCode::CREATED, Json(CreatedKey { key, secret })))
+}
+
+#[utoipa::path(delete, path = "/api/v1/admin/keys/{key_id}", params(("key_id" = String, Path)), responses((status = 204), (status = "4XX", body = ApiError)))]
+async fn revoke_key(State(s): State<Arc<AppState>>, Path(key_id): Path<String>) -> Result<StatusCode, ApiError> {
+    let admin = auth::require_admin()?;
+    s.keys.lock().unwrap().revoke(&key_id)?;
+    tracing::info!("Key {key_id} revoked by {}", admin.key_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Zero the solve totals, per-key and per-tenant usage and the `/metrics` counters,
+/// saving the stats store at once so the reset survives a restart.
+#[utoipa::path(post, path = "/api/v1/admin/stats/reset", responses((status = 200, body = StatsResponse), (status = "4XX", body = ApiError)))]
+async fn reset_stats(State(s): State<Arc<AppState>>) -> Result<Json<StatsResponse>, ApiError> {
+    let admin = auth::require_admin()?;
+    *s.stats.lock().unwrap() = EngineStats::new();
+    s.keys.lock().unwrap().restore_usage(Default::default(), Default::default());
+    s.metrics.restore(Default::default());
+    if let Some(path) = &s.stats_store {
+        snapshot::save(&s, path).map_err(|e| ApiError::new(ErrorCode::Internal, "Stats reset but not saved", e))?;
     }
-    (x, y, z)
+    tracing::info!("Stats reset by {}", admin.key_id);
+    admin_record(&s, "reset-stats", audit::digest(b""), "counters zeroed".into());
+    Ok(stats(State(s)).await)
+}
+
+#[utoipa::path(get, path = "/api/v1/admin/maintenance", responses((status = 200, body = Maintenance), (status = "4XX", body = ApiError)))]
+async fn get_maintenance(State(s): State<Arc<AppState>>) -> ApiResult<Maintenance> {
+    auth::require_admin()?;
+    Ok(Json(s.maintenance.lock().unwrap().clone()))
+}
+
+#[utoipa::path(put, path = "/api/v1/admin/maintenance", request_body = MaintenanceRequest, responses((status = 200, body = Maintenance), (status = "4XX", body = ApiError)))]
+async fn set_maintenance(State(s): State<Arc<AppState>>, Json(req): Json<MaintenanceRequest>) -> ApiResult<Maintenance> {
+    let admin = auth::require_admin()?;
+    let input_hash = audit::input_hash(&req);
+    let m = Maintenance { enabled: req.enabled, reason: req.reason.filter(|r| !r.trim().is_empty()), since_ms: Some(history::now_ms()), changed_by: Some(admin.key_id.clone()) };
+    let detail = match (&m.enabled, &m.reason) { (true, Some(r)) => format!("enabled: {r}"), (true, None) => "enabled".into(), (false, _) => "disabled".into() };
+    *s.maintenance.lock().unwrap() = m.clone();
+    tracing::warn!("Maintenance mode {detail} by {}", admin.key_id);
+    admin_record(&s, "maintenance", input_hash, detail);
+    Ok(Json(m))
+}
+
+/// Synthetic IK load on a chain, timed per solve; see `bench.rs`.
+#[utoipa::path(post, path = "/api/v1/admin/bench", request_body = BenchRequest, responses((status = 200, body = BenchResponse), (status = "4XX", body = ApiError)))]
+async fn run_bench(State(s): State<Arc<AppState>>, Json(req): Json<BenchRequest>) -> ApiResult<BenchResponse> {
+    auth::require_admin()?;
+    req.validate()?;
+    let input_hash = audit::input_hash(&req);
+    let chain = lookup_chain(&s, Some(&req.chain_id))?.expect("chain id given");
+    let c = req.constraints.clone().unwrap_or_default();
+    let overrides = chains::SolverDefaults { solver: req.solver.clone(), seed_policy: req.seed_policy.clone(), damping: c.damping, tolerance: c.tolerance, max_iterations: c.max_iterations };
+    overrides.validate().map_err(|e| ApiError::bad_request("Invalid solver settings", e))?;
+    let settings = kinematics_solver::with_precision(solver_settings(&s, Some(&chain), &overrides), req.precision.as_deref());
+    let (solves, threads) = (req.solves.unwrap_or(1000), req.threads.unwrap_or_else(compute::size));
+    let timeout = *s.solve_timeout.lock().unwrap();
+    // Its own pool does the work; this thread only waits, so it stays off the compute pool
+    let out = tokio::task::spawn_blocking(move || bench::run(&chain, settings, solves, threads, req.random_seed.unwrap_or(1), timeout)).await
+        .map_err(|e| ApiError::internal("Benchmark failed", e.to_string()))?
+        .map_err(|e| ApiError::internal("Benchmark failed", e))?;
+    tracing::info!(chain = %out.chain, solves, threads, solves_per_sec = out.solves_per_sec, p99_us = out.latency_us.p99, "benchmark run");
+    admin_record(&s, "bench", input_hash, format!("solves={solves} threads={threads}: {:.0}/s, p99 {} us", out.solves_per_sec, out.latency_us.p99));
+    Ok(Json(out))
+}
+
+/// Sizes of the in-memory stores and the settings in effect, for debugging.
+#[utoipa::path(get, path = "/api/v1/admin/state", responses((status = 200, body = EngineState), (status = "4XX", body = ApiError)))]
+async fn engine_state(State(s): State<Arc<AppState>>) -> ApiResult<EngineState> {
+    auth::require_admin()?;
+    let hands: BTreeMap<_, _> = s.hands.entries().into_iter().map(|(t, h)| (t, h.lock().unwrap().list().len())).collect();
+    let tenants = s.chains.entries().into_iter()
+        .map(|(t, c)| { let chains = c.lock().unwrap().list().len(); (t.clone(), TenantState { chains, hands: hands.get(&t).copied().unwrap_or(0) }) })
+        .collect();
+    let jobs = s.jobs.lock().unwrap();
+    Ok(Json(EngineState {
+        uptime_secs: s.start_time.elapsed().as_secs(), draining: s.shutdown.is_draining(), maintenance: s.maintenance.lock().unwrap().clone(),
+        config_file: config::path().map(|p| p.display().to_string()), tenants,
+        jobs: jobs.len(), active_jobs: jobs.active(),
+        solutions: s.solutions.lock().unwrap().len(), sims: s.sims.lock().unwrap().len(), sessions: s.sessions.lock().unwrap().len(), intents: s.intents.lock().unwrap().len(), audit_records: s.audit.lock().unwrap().len(),
+        idempotency_keys: s.idempotency.lock().unwrap().len(), cached_results: s.result_cache.lock().unwrap().len(), rate_limit_buckets: s.limiter.lock().unwrap().buckets(),
+        warm_starts: s.warm_starts.lock().unwrap().len(), in_flight: s.concurrency.in_flight(), compute_threads: compute::size(), compute_queued: compute::queued(), compute_running: compute::running(), gpu: s.gpu.as_ref().map(|g| g.adapter().to_string()), gpu_batch_threshold: *s.gpu_threshold.lock().unwrap(), streaming_sessions: s.shutdown.sessions(), pending_events: s.events.pending(), api_keys: s.keys.lock().unwrap().list().len(),
+        solver_defaults: s.solver_defaults.lock().unwrap().clone(), body_limits: s.body_limits.lock().unwrap().clone(),
+    }))
+}
+
+/// Audit an admin action alongside the solves.
+fn admin_record(s: &AppState, operation: &str, input_hash: String, detail: String) {
+    let r = AuditRecord { detail: Some(detail), ..audit::record(operation, input_hash) };
+    s.audit.lock().unwrap().push(r);
 }
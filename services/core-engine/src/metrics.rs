@@ -0,0 +1,256 @@
+// ── Prometheus metrics ──────────────────────────────────────
+//
+// `GET /metrics` in the Prometheus text format: request counts and latency per route
+// (the route template, e.g. `/api/v1/kinematics/chains/:chain_id`, so ids do not
+// become labels), operation totals, IK convergence and the iteration distribution.
+// The totals are the same counters `/stats` reports, engine-wide. With a stats store
+// (see `snapshot`) every counter here carries over restarts.
+//
+// The same counters feed the `/stats` breakdowns: latency percentiles per route,
+// estimated from the histograms, and solves per chain. Chains are counted per tenant
+// and kept out of the exposition, since custom chain ids are unbounded.
+//
+// Heap allocation counts (see `allocations`) are per process and not saved.
+
+use crate::{tenant, AppState};
+use axum::{extract::{MatchedPath, Request, State}, http::header, middleware::Next, response::{IntoResponse, Response}};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub use kinematics_types::{ChainStats, EndpointStats};
+
+const DURATION_BUCKETS: &[f64] = &[0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const ITERATION_BUCKETS: &[f64] = &[1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 10_000.0];
+const ALLOCATION_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 5.0, 10.0, 25.0, 100.0, 1000.0];
+
+struct Histogram { bounds: &'static [f64], counts: Vec<u64>, sum: f64, count: u64 }
+
+/// A histogram's counts without its bounds, as saved in the stats snapshot.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Buckets { counts: Vec<u64>, sum: f64, count: u64 }
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self { Self { bounds, counts: vec![0; bounds.len()], sum: 0.0, count: 0 } }
+
+    fn save(&self) -> Buckets { Buckets { counts: self.counts.clone(), sum: self.sum, count: self.count } }
+
+    /// Saved counts, or an empty histogram when the bucket bounds have changed since.
+    fn restore(bounds: &'static [f64], b: Buckets) -> Self {
+        if b.counts.len() != bounds.len() { return Self::new(bounds); }
+        Self { bounds, counts: b.counts, sum: b.sum, count: b.count }
+    }
+
+    fn observe(&mut self, v: f64) {
+        if let Some(i) = self.bounds.iter().position(|&b| v <= b) { self.counts[i] += 1; }
+        self.sum += v;
+        self.count += 1;
+    }
+
+    /// `_bucket` (cumulative), `_sum` and `_count` lines; `labels` is `k="v",` or empty.
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (b, c) in self.bounds.iter().zip(&self.counts) {
+            cumulative += c;
+            let _ = writeln!(out, "{name}_bucket{{{labels}le=\"{b}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels}le=\"+Inf\"}} {}", self.count);
+        let labels = match labels.trim_end_matches(',') { "" => String::new(), l => format!("{{{l}}}") };
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+    }
+
+    /// Quantile `q`, interpolated within its bucket as Prometheus' `histogram_quantile`
+    /// does; observations past the last bound report that bound.
+    fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 { return 0.0; }
+        let rank = q * self.count as f64;
+        let (mut below, mut lower) = (0u64, 0.0);
+        for (&upper, &n) in self.bounds.iter().zip(&self.counts) {
+            if n > 0 && (below + n) as f64 >= rank { return lower + (upper - lower) * (rank - below as f64) / n as f64; }
+            (below, lower) = (below + n, upper);
+        }
+        lower
+    }
+}
+
+struct Inner {
+    /// (method, route, status) → count
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// (method, route) → seconds to response headers
+    durations: BTreeMap<(String, String), Histogram>,
+    ik_converged: [u64; 2],
+    ik_iterations: Histogram,
+    /// Heap allocations per solver run (a hand finger or batch target each count as one)
+    solve_allocations: Histogram,
+    /// (tenant, chain id) → solves
+    chains: BTreeMap<(String, String), ChainCounts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ChainCounts { ik: u64, ik_converged: u64, ik_iterations: u64, fk: u64 }
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self { requests: BTreeMap::new(), durations: BTreeMap::new(), ik_converged: [0; 2], ik_iterations: Histogram::new(ITERATION_BUCKETS), solve_allocations: Histogram::new(ALLOCATION_BUCKETS), chains: BTreeMap::new() }
+    }
+}
+
+#[derive(Default)]
+pub struct Metrics { inner: Mutex<Inner> }
+
+/// Every counter and histogram, as saved in the stats snapshot.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Counters {
+    /// (method, route, status, count)
+    requests: Vec<(String, String, u16, u64)>,
+    durations: Vec<(String, String, Buckets)>,
+    ik_converged: [u64; 2],
+    ik_iterations: Buckets,
+    #[serde(default)] chains: Vec<(String, String, ChainCounts)>,
+}
+
+impl Metrics {
+    fn request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let mut m = self.inner.lock().unwrap();
+        *m.requests.entry((method.to_string(), route.to_string(), status)).or_default() += 1;
+        m.durations.entry((method.to_string(), route.to_string())).or_insert_with(|| Histogram::new(DURATION_BUCKETS)).observe(seconds);
+    }
+
+    pub fn counters(&self) -> Counters {
+        let m = self.inner.lock().unwrap();
+        Counters {
+            requests: m.requests.iter().map(|((method, route, status), n)| (method.clone(), route.clone(), *status, *n)).collect(),
+            durations: m.durations.iter().map(|((method, route), h)| (method.clone(), route.clone(), h.save())).collect(),
+            ik_converged: m.ik_converged, ik_iterations: m.ik_iterations.save(),
+            chains: m.chains.iter().map(|((tenant, chain), c)| (tenant.clone(), chain.clone(), c.clone())).collect(),
+        }
+    }
+
+    pub fn restore(&self, c: Counters) {
+        let mut m = self.inner.lock().unwrap();
+        m.requests = c.requests.into_iter().map(|(method, route, status, n)| ((method, route, status), n)).collect();
+        m.durations = c.durations.into_iter().map(|(method, route, b)| ((method, route), Histogram::restore(DURATION_BUCKETS, b))).collect();
+        m.ik_converged = c.ik_converged;
+        m.ik_iterations = Histogram::restore(ITERATION_BUCKETS, c.ik_iterations);
+        m.chains = c.chains.into_iter().map(|(tenant, chain, c)| ((tenant, chain), c)).collect();
+    }
+
+    /// One IK solve, on `chain` of the current tenant when it used one (a hand solve
+    /// counts once, with its slowest finger's iterations).
+    pub fn ik(&self, chain: Option<&str>, converged: bool, iterations: u32) { self.ik_many(chain, [(converged, iterations)]); }
+
+    /// Several IK solves on the same chain, such as a batch's targets: each `(converged,
+    /// iterations)` counts as one.
+    pub fn ik_many(&self, chain: Option<&str>, solves: impl IntoIterator<Item = (bool, u32)>) {
+        let key = chain.map(|chain| (tenant::current(), chain.to_string()));
+        let mut m = self.inner.lock().unwrap();
+        for (converged, iterations) in solves {
+            m.ik_converged[usize::from(converged)] += 1;
+            m.ik_iterations.observe(f64::from(iterations));
+            let Some(key) = &key else { continue };
+            let c = m.chains.entry(key.clone()).or_default();
+            c.ik += 1;
+            c.ik_converged += u64::from(converged);
+            c.ik_iterations += u64::from(iterations);
+        }
+    }
+
+    /// Heap allocations of solver runs, from `allocations::counted`.
+    pub fn solve_allocations(&self, counts: impl IntoIterator<Item = u64>) {
+        let mut m = self.inner.lock().unwrap();
+        for n in counts { m.solve_allocations.observe(n as f64); }
+    }
+
+    /// One FK solve on `chain` of the current tenant.
+    pub fn fk(&self, chain: &str) {
+        self.inner.lock().unwrap().chains.entry((tenant::current(), chain.to_string())).or_default().fk += 1;
+    }
+
+    /// Per-chain solves of `tenant`.
+    pub fn chain_stats(&self, tenant: &str) -> BTreeMap<String, ChainStats> {
+        let m = self.inner.lock().unwrap();
+        m.chains.iter().filter(|((t, _), _)| t == tenant).map(|((_, chain), c)| {
+            let per_ik = |n: u64| (c.ik > 0).then(|| n as f64 / c.ik as f64);
+            (chain.clone(), ChainStats {
+                ik_solves: c.ik, ik_converged: c.ik_converged, fk_solves: c.fk,
+                convergence_rate: per_ik(c.ik_converged), avg_iterations: per_ik(c.ik_iterations),
+            })
+        }).collect()
+    }
+
+    /// Requests, errors and latency percentiles per `METHOD /route`.
+    pub fn endpoint_stats(&self) -> BTreeMap<String, EndpointStats> {
+        let m = self.inner.lock().unwrap();
+        let mut out: BTreeMap<String, EndpointStats> = m.durations.iter().map(|((method, route), h)| {
+            let ms = |q| (h.quantile(q) * 1e6).round() / 1e3;
+            (format!("{method} {route}"), EndpointStats { p50_ms: ms(0.5), p95_ms: ms(0.95), p99_ms: ms(0.99), ..Default::default() })
+        }).collect();
+        for ((method, route, status), n) in &m.requests {
+            let Some(e) = out.get_mut(&format!("{method} {route}")) else { continue };
+            e.requests += n;
+            match status { 400..=499 => e.client_errors += n, 500.. => e.server_errors += n, _ => {} }
+        }
+        out
+    }
+}
+
+/// Times every request and counts it under its route template.
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let route = req.extensions().get::<MatchedPath>().map_or_else(|| "unmatched".to_string(), |p| p.as_str().to_string());
+    let method = req.method().to_string();
+    let t = Instant::now();
+    let res = next.run(req).await;
+    s.metrics.request(&method, &route, res.status().as_u16(), t.elapsed().as_secs_f64());
+    res
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+#[utoipa::path(get, path = "/metrics", operation_id = "metrics", responses((status = 200, description = "Prometheus text exposition format", content_type = "text/plain", body = String)))]
+pub async fn handler(State(s): State<Arc<AppState>>) -> Response {
+    let mut out = String::new();
+    family(&mut out, "kinematics_build_info", "gauge", "Engine version.");
+    let _ = writeln!(out, "kinematics_build_info{{version=\"{}\"}} 1", env!("CARGO_PKG_VERSION"));
+    family(&mut out, "kinematics_uptime_seconds", "gauge", "Seconds since the engine started.");
+    let _ = writeln!(out, "kinematics_uptime_seconds {}", s.start_time.elapsed().as_secs());
+    family(&mut out, "kinematics_compute_threads", "gauge", "Threads in the compute pool that runs IK solves.");
+    let _ = writeln!(out, "kinematics_compute_threads {}", crate::compute::size());
+    family(&mut out, "kinematics_compute_tasks", "gauge", "Solves waiting for a compute thread, and running on one.");
+    let _ = writeln!(out, "kinematics_compute_tasks{{state=\"queued\"}} {}\nkinematics_compute_tasks{{state=\"running\"}} {}", crate::compute::queued(), crate::compute::running());
+
+    {
+        let st = s.stats.lock().unwrap();
+        family(&mut out, "kinematics_operations_total", "counter", "Completed operations, as counted by /stats.");
+        for (op, n) in [("ik", st.total_ik_solves), ("fk", st.total_fk_solves), ("intent", st.total_compressions), ("trajectory", st.total_trajectories)] {
+            let _ = writeln!(out, "kinematics_operations_total{{operation=\"{op}\"}} {n}");
+        }
+    }
+
+    let m = s.metrics.inner.lock().unwrap();
+    family(&mut out, "kinematics_ik_solves_total", "counter", "IK solves by whether they converged.");
+    let _ = writeln!(out, "kinematics_ik_solves_total{{converged=\"true\"}} {}", m.ik_converged[1]);
+    let _ = writeln!(out, "kinematics_ik_solves_total{{converged=\"false\"}} {}", m.ik_converged[0]);
+    family(&mut out, "kinematics_ik_iterations", "histogram", "Solver iterations per IK solve.");
+    m.ik_iterations.write(&mut out, "kinematics_ik_iterations", "");
+    family(&mut out, "kinematics_solve_allocations", "histogram", "Heap allocations per IK solver run; 0 once the thread's workspace has grown to the chain.");
+    m.solve_allocations.write(&mut out, "kinematics_solve_allocations", "");
+    family(&mut out, "kinematics_heap_allocations_total", "counter", "Heap allocations since the engine started.");
+    let _ = writeln!(out, "kinematics_heap_allocations_total {}", crate::allocations::total());
+
+    family(&mut out, "kinematics_http_requests_total", "counter", "HTTP requests by method, route template and status.");
+    for ((method, route, status), n) in &m.requests {
+        let _ = writeln!(out, "kinematics_http_requests_total{{method=\"{method}\",route=\"{}\",status=\"{status}\"}} {n}", escape(route));
+    }
+    family(&mut out, "kinematics_http_request_duration_seconds", "histogram", "Time to response headers by method and route template.");
+    for ((method, route), h) in &m.durations {
+        h.write(&mut out, "kinematics_http_request_duration_seconds", &format!("method=\"{method}\",route=\"{}\",", escape(route)));
+    }
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], out).into_response()
+}
+
+fn escape(v: &str) -> String { v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n") }
@@ -0,0 +1,272 @@
+// ── Motion capture import ───────────────────────────────────
+//
+// Reads a BVH skeleton animation or a C3D marker recording and reduces it to one
+// vector per frame: the end of a limb (wrist or ankle) relative to its root (shoulder
+// or hip), in a body frame of x lateral (outward), y forward and z up. A left limb is
+// mirrored, so both sides land on the same chain. In BVH the body frame is the limb
+// root's parent joint (the torso), with the lateral axis along the root's offset from
+// it; in C3D it is built each frame from the root marker, a reference marker on the
+// other side and the lab's up axis. The vectors are in file units; the caller scales
+// them and solves them on a human chain. Only Intel (PC) C3D files are read.
+
+use kinematics_solver::{mat_vec, matmul, rot, transpose, Frame, Mat};
+use kinematics_types::MocapQuery;
+
+const MAX_JOINTS: usize = 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format { Bvh, C3d }
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "bvh" => Ok(Format::Bvh),
+            "c3d" => Ok(Format::C3d),
+            other => Err(format!("'{other}' is not one of bvh, c3d")),
+        }
+    }
+
+    pub fn detect(data: &[u8]) -> Option<Format> {
+        if data.len() >= 512 && data[1] == 0x50 { return Some(Format::C3d); }
+        String::from_utf8_lossy(&data[..data.len().min(64)]).trim_start().starts_with("HIERARCHY").then_some(Format::Bvh)
+    }
+
+    pub fn name(self) -> &'static str { match self { Format::Bvh => "bvh", Format::C3d => "c3d" } }
+}
+
+/// The joints or markers to follow, and how the file is oriented.
+pub struct Limb { base: String, tip: String, reference: String, up: usize, left: bool }
+
+impl Limb {
+    /// `q`'s names, or the usual ones for its side and limb: CMU-style joint names in
+    /// BVH, Plug-in Gait markers in C3D.
+    pub fn new(q: &MocapQuery, format: Format, leg: bool) -> Limb {
+        let left = q.side.as_deref() == Some("left");
+        let up = q.up.as_deref().and_then(|axis| ["x", "y", "z"].iter().position(|a| *a == axis)).unwrap_or(if format == Format::Bvh { 1 } else { 2 });
+        let (side, s, o) = if left { ("Left", "L", "R") } else { ("Right", "R", "L") };
+        let [base, tip, reference] = match (format, leg) {
+            (Format::Bvh, false) => [format!("{side}Arm"), format!("{side}Hand"), String::new()],
+            (Format::Bvh, true) => [format!("{side}UpLeg"), format!("{side}Foot"), String::new()],
+            (Format::C3d, false) => [format!("{s}SHO"), format!("{s}WRA,{s}WRB"), format!("{o}SHO")],
+            (Format::C3d, true) => [format!("{s}ASI"), format!("{s}ANK"), format!("{o}ASI")],
+        };
+        let pick = |given: &Option<String>, default: String| given.clone().unwrap_or(default);
+        Limb { base: pick(&q.base, base), tip: pick(&q.tip, tip), reference: pick(&q.reference, reference), up, left }
+    }
+
+    /// Rows of the body frame: x along `lateral` (flattened against up), z up, y forward.
+    fn body_frame(&self, lateral: [f64; 3]) -> Option<Mat> {
+        let mut z = [0.0; 3];
+        z[self.up] = 1.0;
+        let along = lateral[self.up];
+        let x = [0, 1, 2].map(|i| lateral[i] - along * z[i]);
+        let n = x.iter().map(|c| c * c).sum::<f64>().sqrt();
+        if n < 1e-9 { return None; }
+        let x = x.map(|c| c / n);
+        let y = [z[1] * x[2] - z[2] * x[1], z[2] * x[0] - z[0] * x[2], z[0] * x[1] - z[1] * x[0]];
+        // Up × outward points forward on the right and backward on the left
+        Some([x, if self.left { y.map(|c| -c) } else { y }, z])
+    }
+}
+
+/// A take reduced to limb vectors (file units, body frame).
+pub struct Take {
+    pub frames: usize, pub frame_time: f64,
+    /// `(file frame, limb vector)` for every `stride`-th frame that has one.
+    pub samples: Vec<(usize, [f64; 3])>,
+    /// Sampled file frames without a vector (a marker missing).
+    pub gaps: Vec<usize>,
+    /// BVH: length of the limb in the skeleton. C3D: metres per file unit.
+    pub limb_length: Option<f64>, pub metres_per_unit: f64,
+}
+
+/// Errors carry the query field at fault, or none when the file itself is.
+pub type Error = (Option<&'static str>, String);
+
+fn parse_error(e: impl Into<String>) -> Error { (None, e.into()) }
+
+pub fn read(data: &[u8], format: Format, limb: &Limb, stride: usize) -> Result<Take, Error> {
+    match format {
+        Format::Bvh => bvh(std::str::from_utf8(data).map_err(|_| parse_error("BVH must be UTF-8 text"))?, limb, stride),
+        Format::C3d => c3d(data, limb, stride),
+    }
+}
+
+// ── BVH ─────────────────────────────────────────────────────
+
+struct Joint { name: String, parent: Option<usize>, offset: [f64; 3], channels: Vec<(bool, usize)>, first: usize }
+
+fn bvh(text: &str, limb: &Limb, stride: usize) -> Result<Take, Error> {
+    let mut tokens = text.split_whitespace();
+    let mut next = || tokens.next().ok_or_else(|| parse_error("unexpected end of file"));
+    if next()? != "HIERARCHY" { return Err(parse_error("expected HIERARCHY")); }
+    let (mut joints, mut stack, mut channels) = (Vec::<Joint>::new(), Vec::<Option<usize>>::new(), 0usize);
+    let number = |t: &str| t.parse::<f64>().map_err(|_| parse_error(format!("expected a number, got '{t}'")));
+    loop {
+        match next()? {
+            "ROOT" | "JOINT" => {
+                if joints.len() >= MAX_JOINTS { return Err(parse_error(format!("more than {MAX_JOINTS} joints"))); }
+                let name = next()?.to_string();
+                joints.push(Joint { name, parent: stack.last().copied().flatten(), offset: [0.0; 3], channels: Vec::new(), first: channels });
+                if next()? != "{" { return Err(parse_error("expected '{'")); }
+                stack.push(Some(joints.len() - 1));
+            }
+            "End" => {
+                if next()? != "Site" || next()? != "{" { return Err(parse_error("expected 'End Site {'")); }
+                stack.push(None);
+            }
+            "OFFSET" => {
+                let offset = [number(next()?)?, number(next()?)?, number(next()?)?];
+                if let Some(Some(j)) = stack.last() { joints[*j].offset = offset; }
+            }
+            "CHANNELS" => {
+                let Some(Some(j)) = stack.last().copied() else { return Err(parse_error("CHANNELS outside a joint")) };
+                let n = next()?.parse::<usize>().map_err(|_| parse_error("expected a channel count"))?;
+                if n > 6 { return Err(parse_error(format!("{n} channels on joint {}", joints[j].name))); }
+                joints[j].first = channels;
+                for _ in 0..n {
+                    let c = next()?;
+                    let axis = ["X", "Y", "Z"].iter().position(|a| c.starts_with(a)).ok_or_else(|| parse_error(format!("unknown channel '{c}'")))?;
+                    joints[j].channels.push((c[1..].eq_ignore_ascii_case("rotation"), axis));
+                }
+                channels += n;
+            }
+            "}" => { stack.pop().ok_or_else(|| parse_error("unbalanced '}'"))?; }
+            "MOTION" => break,
+            other => return Err(parse_error(format!("unexpected '{other}' in HIERARCHY"))),
+        }
+    }
+    if next()? != "Frames:" { return Err(parse_error("expected 'Frames:'")); }
+    let frames = next()?.parse::<usize>().map_err(|_| parse_error("expected a frame count"))?;
+    if next()? != "Frame" || next()? != "Time:" { return Err(parse_error("expected 'Frame Time:'")); }
+    let frame_time = number(next()?)?;
+    let values = tokens.map(number).collect::<Result<Vec<f64>, _>>()?;
+    if values.len() < frames * channels { return Err(parse_error(format!("{frames} frames of {channels} channels announced, {} values found", values.len()))); }
+
+    let find = |field: &'static str, name: &str| joints.iter().position(|j| j.name.eq_ignore_ascii_case(name)).ok_or((Some(field), format!("no joint named '{name}'")));
+    let (base, tip) = (find("base", &limb.base)?, find("tip", &limb.tip)?);
+    let mut path = vec![tip];
+    while let Some(&j) = path.last().filter(|&&j| j != base) {
+        path.push(joints[j].parent.ok_or((Some("tip"), format!("'{}' is not below '{}'", limb.tip, limb.base)))?);
+    }
+    let body = limb.body_frame(joints[base].offset).ok_or((Some("base"), format!("'{}' has no sideways offset to take the lateral axis from", limb.base)))?;
+    let length = path[..path.len() - 1].iter().map(|&j| joints[j].offset.iter().map(|c| c * c).sum::<f64>().sqrt()).sum();
+
+    let mut globals = vec![Frame { r: rot(0, 0.0), p: [0.0; 3] }; joints.len()];
+    let samples = (0..frames).step_by(stride).map(|f| {
+        let row = &values[f * channels..(f + 1) * channels];
+        for (i, j) in joints.iter().enumerate() {
+            let (mut r, mut p) = (rot(0, 0.0), j.offset);
+            for (k, &(rotation, axis)) in j.channels.iter().enumerate() {
+                let v = row[j.first + k];
+                if rotation { r = matmul(&r, &rot(axis, v.to_radians())); } else { p[axis] = v; }
+            }
+            let local = Frame { r, p };
+            globals[i] = match j.parent { Some(parent) => globals[parent].mul(&local), None => local };
+        }
+        let torso = joints[base].parent.map_or(rot(0, 0.0), |parent| globals[parent].r);
+        let d = [0, 1, 2].map(|i| globals[tip].p[i] - globals[base].p[i]);
+        (f, mat_vec(&body, mat_vec(&transpose(&torso), d)))
+    }).collect();
+    Ok(Take { frames, frame_time, samples, gaps: Vec::new(), limb_length: Some(length), metres_per_unit: 1.0 })
+}
+
+// ── C3D ─────────────────────────────────────────────────────
+
+const BLOCK: usize = 512;
+const INTEL: u8 = 84;
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    fn slice(&self, at: usize, n: usize) -> Result<&'a [u8], Error> { self.0.get(at..at + n).ok_or_else(|| parse_error("truncated C3D file")) }
+    fn u8(&self, at: usize) -> Result<u8, Error> { Ok(self.slice(at, 1)?[0]) }
+    fn u16(&self, at: usize) -> Result<u16, Error> { let b = self.slice(at, 2)?; Ok(u16::from_le_bytes([b[0], b[1]])) }
+    fn i16(&self, at: usize) -> Result<i16, Error> { Ok(self.u16(at)? as i16) }
+    fn f32(&self, at: usize) -> Result<f32, Error> { let b = self.slice(at, 4)?; Ok(f32::from_le_bytes([b[0], b[1], b[2], b[3]])) }
+}
+
+/// `(group, parameter)` names, upper case, with the type, dimensions and raw data of each.
+type Parameters<'a> = Vec<((String, String), i8, Vec<usize>, &'a [u8])>;
+
+fn parameters<'a>(b: &Bytes<'a>, start: usize) -> Result<Parameters<'a>, Error> {
+    let end = start + b.u8(start + 2)? as usize * BLOCK;
+    let (mut groups, mut params) = (Vec::<(i8, String)>::new(), Vec::new());
+    let mut at = start + 4;
+    while at + 2 <= end.min(b.0.len()) {
+        let (len, id) = (b.u8(at)? as i8, b.u8(at + 1)? as i8);
+        if len == 0 { break; }
+        let name = String::from_utf8_lossy(b.slice(at + 2, len.unsigned_abs() as usize)?).to_ascii_uppercase();
+        let here = at + 2 + len.unsigned_abs() as usize;
+        let next = b.i16(here)?;
+        if id < 0 {
+            groups.push((-id, name));
+        } else {
+            let kind = b.u8(here + 2)? as i8;
+            let dims: Vec<usize> = b.slice(here + 4, b.u8(here + 3)? as usize)?.iter().map(|&d| d as usize).collect();
+            let size = kind.unsigned_abs() as usize * dims.iter().product::<usize>();
+            let data = b.slice(here + 4 + dims.len(), size)?;
+            let group = groups.iter().find(|(g, _)| *g == id).map(|(_, n)| n.clone()).unwrap_or_default();
+            params.push(((group, name), kind, dims, data));
+        }
+        if next <= 0 { break; }
+        at = here + next as usize;
+    }
+    Ok(params)
+}
+
+/// Labels of a character parameter (`dims = [length, count]`).
+fn labels(kind: i8, dims: &[usize], data: &[u8]) -> Vec<String> {
+    match (kind, dims) {
+        (-1, [n, _]) if *n > 0 => data.chunks(*n).map(|l| String::from_utf8_lossy(l).trim().to_string()).collect(),
+        (-1, [_]) => vec![String::from_utf8_lossy(data).trim().to_string()],
+        _ => Vec::new(),
+    }
+}
+
+fn c3d(data: &[u8], limb: &Limb, stride: usize) -> Result<Take, Error> {
+    let b = Bytes(data);
+    let start = (b.u8(0)? as usize).checked_sub(1).ok_or_else(|| parse_error("no parameter section"))? * BLOCK;
+    if b.u8(start + 3)? != INTEL { return Err(parse_error("only Intel (PC) C3D files are supported; convert DEC or MIPS files first")); }
+    let (points, analog) = (b.u16(2)? as usize, b.u16(4)? as usize);
+    let (first, last) = (b.u16(6)? as usize, b.u16(8)? as usize);
+    let (scale, data_start, rate) = (b.f32(12)?, (b.u16(16)? as usize).saturating_sub(1) * BLOCK, b.f32(20)?);
+    let params = parameters(&b, start)?;
+    let param = |name: &str| params.iter().find(|((g, n), ..)| g == "POINT" && n == name).map(|(_, k, d, v)| (*k, d.as_slice(), *v));
+    let mut names: Vec<String> = param("LABELS").map(|(k, d, v)| labels(k, d, v)).unwrap_or_default();
+    names.extend(param("LABELS2").map(|(k, d, v)| labels(k, d, v)).unwrap_or_default());
+    let units = param("UNITS").and_then(|(k, d, v)| labels(k, d, v).pop()).unwrap_or_else(|| "mm".into());
+    let metres_per_unit = match units.to_ascii_lowercase().as_str() { "m" => 1.0, "cm" => 0.01, _ => 0.001 };
+
+    let markers = |field: &'static str, list: &str| list.split(',').map(|m| {
+        names.iter().position(|n| n.eq_ignore_ascii_case(m.trim())).filter(|&i| i < points).ok_or((Some(field), format!("no marker labelled '{}'", m.trim())))
+    }).collect::<Result<Vec<_>, _>>();
+    let (base, tip, reference) = (markers("base", &limb.base)?, markers("tip", &limb.tip)?, markers("reference", &limb.reference)?);
+    let float = scale < 0.0;
+    let (word, scale) = (if float { 4 } else { 2 }, scale.abs() as f64);
+    let frame_bytes = (points * 4 + analog) * word;
+    let frames = (last + 1).saturating_sub(first);
+    if data.len() < data_start + frames * frame_bytes { return Err(parse_error(format!("{frames} frames announced, the data holds {}", data.len().saturating_sub(data_start) / frame_bytes.max(1)))); }
+    // Mean of `markers` in a frame, or none when one is missing
+    let mean = |frame: usize, markers: &[usize]| -> Option<[f64; 3]> {
+        let mut sum = [0.0; 3];
+        for &m in markers {
+            let at = data_start + frame * frame_bytes + m * 4 * word;
+            let read = |k: usize| if float { b.f32(at + 4 * k).map(f64::from).unwrap_or(-1.0) } else { b.i16(at + 2 * k).map(|v| v as f64 * scale).unwrap_or(-1.0) };
+            if read(3) < 0.0 { return None; }
+            for (k, s) in sum.iter_mut().enumerate() { *s += read(k); }
+        }
+        Some(sum.map(|s| s / markers.len() as f64))
+    };
+    let (mut samples, mut gaps) = (Vec::new(), Vec::new());
+    for f in (0..frames).step_by(stride) {
+        let vector = (|| {
+            let (root, end, other) = (mean(f, &base)?, mean(f, &tip)?, mean(f, &reference)?);
+            let body = limb.body_frame([0, 1, 2].map(|i| root[i] - other[i]))?;
+            Some(mat_vec(&body, [0, 1, 2].map(|i| end[i] - root[i])))
+        })();
+        match vector { Some(v) => samples.push((f, v)), None => gaps.push(f) }
+    }
+    let frame_time = if rate > 0.0 { 1.0 / rate as f64 } else { 0.0 };
+    Ok(Take { frames, frame_time, samples, gaps, limb_length: None, metres_per_unit })
+}
@@ -0,0 +1,92 @@
+// ── MQTT bridge ─────────────────────────────────────────────
+//
+// Optional client mode for edge devices without HTTP: the engine connects to a broker,
+// subscribes to `{prefix}/{op}/request[/...]` and publishes each result to the same
+// topic with `request` replaced by `response`, so a device that adds its own suffix
+// (`kinematics/ik/request/arm-7/42`) gets its reply on a topic only it listens to.
+// Payloads are the REST JSON bodies; failures are the REST error envelope. Requests
+// run the same handlers as REST, as the bridge's API key when auth is enabled, and
+// are charged to that key's solve rate limit.
+
+use crate::auth;
+use crate::error::ApiError;
+use crate::AppState;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// In-flight requests buffered between the event loop and the client.
+const CHANNEL_CAPACITY: usize = 64;
+
+pub struct Config {
+    pub host: String, pub port: u16, pub client_id: String, pub prefix: String,
+    pub username: Option<String>, pub password: Option<String>,
+    /// Key the bridge's requests run as when auth is enabled.
+    pub api_key: Option<String>,
+}
+
+/// Engine operation for a topic's `{op}` segment.
+fn operation(op: &str) -> Option<&'static str> {
+    Some(match op {
+        "ik" => "solve-ik",
+        "fk" => "solve-fk",
+        "intent" => "compress-intent",
+        "trajectory" => "optimize-trajectory",
+        _ => return None,
+    })
+}
+
+/// `{op}` and the reply topic of a request topic under `prefix`.
+fn route<'a>(prefix: &str, topic: &'a str) -> Option<(&'a str, String)> {
+    let rest = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let (op, tail) = rest.split_once('/')?;
+    let suffix = tail.strip_prefix("request")?;
+    if !(suffix.is_empty() || suffix.starts_with('/')) { return None; }
+    Some((op, format!("{prefix}/{op}/response{suffix}")))
+}
+
+async fn handle(s: Arc<AppState>, api_key: Option<String>, op: &str, payload: &[u8]) -> Vec<u8> {
+    let result = async {
+        let operation = operation(op).ok_or_else(|| ApiError::bad_request("Unknown operation", format!("'{op}' is not one of ik, fk, intent, trajectory")))?;
+        let request = crate::error::from_slice::<serde_json::Value>(payload)?;
+        let principal = auth::bridge(&s, "KINEMATICS_MQTT_API_KEY", api_key.as_deref())?;
+        auth::scope(principal, async { crate::operation_task(&s, operation, request)?.await }).await
+    }.await;
+    match result {
+        Ok(body) => serde_json::to_vec(&body),
+        Err(e) => serde_json::to_vec(&e),
+    }.unwrap_or_default()
+}
+
+/// Connect and serve requests until the process exits, reconnecting after errors.
+pub async fn run(s: Arc<AppState>, config: Config) {
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    let max_packet = s.body_limits.lock().unwrap().samples;
+    options.set_keep_alive(Duration::from_secs(30)).set_max_packet_size(max_packet, max_packet);
+    if let Some(user) = &config.username { options.set_credentials(user, config.password.clone().unwrap_or_default()); }
+    let (client, mut events) = AsyncClient::new(options, CHANNEL_CAPACITY);
+    let filters = [format!("{}/+/request", config.prefix), format!("{}/+/request/#", config.prefix)];
+    loop {
+        match events.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                tracing::info!("MQTT bridge connected to {}:{}, serving {}/+/request", config.host, config.port, config.prefix);
+                for f in &filters {
+                    if let Err(e) = client.subscribe(f, QoS::AtLeastOnce).await { tracing::error!("MQTT subscribe {f}: {e}"); }
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(p))) => {
+                let Some((op, reply)) = route(&config.prefix, &p.topic) else { continue };
+                let (s, client, api_key, op) = (s.clone(), client.clone(), config.api_key.clone(), op.to_string());
+                tokio::spawn(async move {
+                    let body = handle(s, api_key, &op, &p.payload).await;
+                    if let Err(e) = client.publish(&reply, QoS::AtLeastOnce, false, body).await { tracing::warn!("MQTT publish {reply}: {e}"); }
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("MQTT bridge: {e}; reconnecting");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
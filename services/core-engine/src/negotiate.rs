@@ -0,0 +1,84 @@
+// ── Content negotiation ─────────────────────────────────────
+//
+// MessagePack and CBOR as drop-in alternatives to JSON on every endpoint. Request
+// bodies sent with a binary `Content-Type` are transcoded to JSON before routing, and
+// JSON responses are transcoded to the first binary format named in `Accept`. Both
+// go through `serde_json::Value`, so handlers stay JSON-only; streaming (SSE,
+// WebSocket) and non-JSON responses (URDF, codec frames) pass through unchanged.
+
+use crate::error::{ApiError, ErrorCode};
+use axum::{body::{to_bytes, Body}, extract::Request, http::{header, HeaderValue}, middleware::Next, response::{IntoResponse, Response}};
+use serde_json::Value;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format { Json, MsgPack, Cbor }
+
+impl Format {
+    fn parse(media: &str) -> Option<Self> {
+        match media.split(';').next()?.trim().to_ascii_lowercase().as_str() {
+            "application/json" => Some(Format::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(Format::MsgPack),
+            "application/cbor" => Some(Format::Cbor),
+            _ => None,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self { Format::Json => "application/json", Format::MsgPack => "application/msgpack", Format::Cbor => "application/cbor" }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Result<Value, String> {
+        match self {
+            Format::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Format::MsgPack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Format::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn encode(self, value: &Value) -> Result<Vec<u8>, String> {
+        match self {
+            Format::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            // Named maps, so structs keep their field names as in JSON
+            Format::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Format::Cbor => { let mut out = Vec::new(); ciborium::into_writer(value, &mut out).map(|_| out).map_err(|e| e.to_string()) }
+        }
+    }
+}
+
+/// First recognised media type in `Accept`, in listed order (q-values are not ranked).
+fn accepted(accept: &str) -> Option<Format> { accept.split(',').find_map(Format::parse) }
+
+pub async fn layer(req: Request, next: Next) -> Response {
+    let input = req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).and_then(Format::parse).filter(|f| *f != Format::Json);
+    let output = req.headers().get(header::ACCEPT).and_then(|v| v.to_str().ok()).and_then(accepted).filter(|f| *f != Format::Json);
+    let req = match input {
+        Some(format) => {
+            let (mut parts, body) = req.into_parts();
+            let bytes = match to_bytes(body, usize::MAX).await {
+                Ok(b) => b,
+                Err(e) => return ApiError::new(ErrorCode::PayloadTooLarge, "Request body too large", e.to_string()).into_response(),
+            };
+            let json = match format.decode(&bytes).and_then(|v| Format::Json.encode(&v)) {
+                Ok(j) => j,
+                Err(e) => return ApiError::bad_request(format!("Invalid {} body", format.content_type()), e).into_response(),
+            };
+            parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Request::from_parts(parts, Body::from(json))
+        }
+        None => req,
+    };
+    let mut res = next.run(req).await;
+    res.headers_mut().append(header::VARY, HeaderValue::from_static("accept"));
+    let Some(format) = output else { return res };
+    let is_json = res.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).and_then(Format::parse) == Some(Format::Json);
+    if !is_json { return res }
+    let (mut parts, body) = res.into_parts();
+    let encoded = match to_bytes(body, usize::MAX).await.map_err(|e| e.to_string()).and_then(|b| Format::Json.decode(&b)).and_then(|v| format.encode(&v)) {
+        Ok(e) => e,
+        Err(e) => return ApiError::internal("Response encoding failed", e).into_response(),
+    };
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(encoded))
+}
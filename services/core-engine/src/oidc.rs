@@ -0,0 +1,115 @@
+// ── OIDC bearer tokens ──────────────────────────────────────
+//
+// JWTs from one configured issuer, accepted alongside API keys. Signing keys come
+// from the issuer's JWKS (found through `/.well-known/openid-configuration` unless
+// given directly), cached for an hour and refetched early when a token names an
+// unknown `kid`. Tokens must be signed with an asymmetric algorithm and carry a
+// matching `iss`, `exp`, and `aud` when an audience is configured. Scopes are read
+// from `scope` (space separated) or `scp` (string or list), the tenant from the
+// configured tenant claim.
+
+use crate::auth::Principal;
+use crate::error::{ApiError, ErrorCode};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const JWKS_TTL: Duration = Duration::from_secs(3600);
+/// Floor between refetches triggered by unknown key ids.
+const MIN_REFRESH: Duration = Duration::from_secs(30);
+const LEEWAY_SECS: u64 = 60;
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)] scope: Option<String>,
+    #[serde(default)] scp: Option<serde_json::Value>,
+    #[serde(flatten)] rest: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Claims {
+    fn scopes(&self) -> Vec<String> {
+        let mut scopes: Vec<String> = self.scope.iter().flat_map(|s| s.split_whitespace()).map(String::from).collect();
+        match &self.scp {
+            Some(serde_json::Value::String(s)) => scopes.extend(s.split_whitespace().map(String::from)),
+            Some(serde_json::Value::Array(a)) => scopes.extend(a.iter().filter_map(|v| v.as_str()).map(String::from)),
+            _ => {}
+        }
+        scopes
+    }
+}
+
+struct Cache { jwks: JwkSet, fetched: Instant }
+
+pub struct Oidc { issuer: String, audience: Option<String>, jwks_uri: Option<String>, tenant_claim: String, client: reqwest::Client, cache: Mutex<Option<Cache>> }
+
+fn unauthorized(message: &str, details: impl Into<String>) -> ApiError { ApiError::new(ErrorCode::Unauthorized, message, details) }
+
+impl Oidc {
+    pub fn new(issuer: String, audience: Option<String>, jwks_uri: Option<String>, tenant_claim: String) -> Self {
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().expect("HTTP client");
+        Self { issuer, audience, jwks_uri, tenant_claim, client, cache: Mutex::new(None) }
+    }
+
+    pub fn issuer(&self) -> &str { &self.issuer }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        let res = self.client.get(url).send().await.map_err(|e| format!("{url}: {e}"))?;
+        if !res.status().is_success() { return Err(format!("{url}: HTTP {}", res.status())); }
+        let bytes = res.bytes().await.map_err(|e| format!("{url}: {e}"))?;
+        serde_json::from_slice(&bytes).map_err(|e| format!("{url}: {e}"))
+    }
+
+    async fn fetch(&self) -> Result<JwkSet, String> {
+        let uri = match &self.jwks_uri {
+            Some(u) => u.clone(),
+            None => {
+                #[derive(Deserialize)]
+                struct Discovery { jwks_uri: String }
+                let url = format!("{}/.well-known/openid-configuration", self.issuer.trim_end_matches('/'));
+                self.get_json::<Discovery>(&url).await?.jwks_uri
+            }
+        };
+        self.get_json(&uri).await
+    }
+
+    /// Decoding key for `kid`, refetching the JWKS when stale or when `kid` is unknown.
+    async fn key(&self, kid: Option<&str>) -> Result<(DecodingKey, Option<Algorithm>), ApiError> {
+        let mut cache = self.cache.lock().await;
+        let find = |set: &JwkSet| match kid { Some(k) => set.find(k).cloned(), None => (set.keys.len() == 1).then(|| set.keys[0].clone()) };
+        let stale = cache.as_ref().is_none_or(|c| c.fetched.elapsed() > JWKS_TTL);
+        let missing = cache.as_ref().is_some_and(|c| find(&c.jwks).is_none() && c.fetched.elapsed() > MIN_REFRESH);
+        if stale || missing {
+            match self.fetch().await {
+                Ok(jwks) => *cache = Some(Cache { jwks, fetched: Instant::now() }),
+                Err(e) if cache.is_none() => return Err(ApiError::unavailable("Identity provider unreachable", e)),
+                Err(e) => tracing::warn!("JWKS refresh failed, keeping cached keys: {e}"),
+            }
+        }
+        let jwk = cache.as_ref().and_then(|c| find(&c.jwks)).ok_or_else(|| unauthorized("Invalid token", format!("no signing key {}", kid.unwrap_or("(no kid)"))))?;
+        let alg = jwk.common.key_algorithm.and_then(|a| a.to_string().parse().ok());
+        DecodingKey::from_jwk(&jwk).map(|k| (k, alg)).map_err(|e| unauthorized("Invalid token", format!("unusable signing key: {e}")))
+    }
+
+    pub async fn verify(&self, token: &str) -> Result<Principal, ApiError> {
+        let header = jsonwebtoken::decode_header(token).map_err(|e| unauthorized("Invalid token", e.to_string()))?;
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(unauthorized("Invalid token", "symmetric algorithms are not accepted"));
+        }
+        let (key, key_alg) = self.key(header.kid.as_deref()).await?;
+        if key_alg.is_some_and(|a| a != header.alg) { return Err(unauthorized("Invalid token", "algorithm does not match the signing key")); }
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = LEEWAY_SECS;
+        validation.set_issuer(&[&self.issuer]);
+        match &self.audience { Some(aud) => validation.set_audience(&[aud]), None => validation.validate_aud = false }
+        let claims = jsonwebtoken::decode::<Claims>(token, &key, &validation).map_err(|e| unauthorized("Invalid token", e.to_string()))?.claims;
+        let scopes = claims.scopes();
+        let tenant = match claims.rest.get(&self.tenant_claim) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(t)) => { crate::tenant::check(t).map_err(|e| unauthorized("Invalid token", e))?; Some(t.clone()) }
+            Some(_) => return Err(unauthorized("Invalid token", format!("claim '{}' must be a string", self.tenant_claim))),
+        };
+        Ok(Principal { key_id: format!("sub:{}", claims.sub), admin: scopes.iter().any(|s| s == crate::auth::SCOPE_ADMIN), scopes: Some(scopes), tenant })
+    }
+}
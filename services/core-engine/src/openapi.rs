@@ -0,0 +1,26 @@
+// ── OpenAPI ─────────────────────────────────────────────────
+//
+// OpenAPI 3.1 document assembled from the `#[utoipa::path]` annotations on the
+// handlers; schemas come from the request/response types themselves, so the spec
+// cannot drift from what the engine accepts. Served at `/api/v1/openapi.json`.
+
+use crate::*;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "ALICE Kinematics Engine", description = "Inverse/forward kinematics, motion intent compression and trajectory optimization."),
+    paths(
+        health::health, health::liveness, health::readiness, metrics::handler, selftest::handler, solve_ik, solve_ik_stream, solve_ik_batch, solve_ik_velocity, solve_ik_acceleration, check_reachable, sample_self_motion, joint_compliance, solve_fk, verify, retarget_motion, import_mocap, calibrate_hand_eye, compress_intent, compress_intent_channels, list_intents, get_intent,
+        codec_encode, codec_decode, optimize_trajectory_route, fbs::optimize_trajectory,
+        list_chains, register_chain, export_chains, import_chain, import_moveit, generate_chain, compose_chain, scale_chain, flush_chains,
+        get_chain, update_chain, delete_chain, export_chain, list_configurations, get_configuration, put_configuration,
+        list_hands, register_hand, get_hand, solve_hand_fk, solve_hand_ik, get_solution, export_bvh,
+        list_jobs, submit_job, get_job, delete_job, job_result, job_events, cancel_job, create_sim, get_sim, step_sim, delete_sim, simulate_tracking, create_session, get_session, report_state, delete_session, stats, list_audit,
+        list_keys, create_key, revoke_key, reset_stats, get_maintenance, set_maintenance, engine_state, run_bench,
+    ),
+    components(schemas(IkStreamTarget, TrajectorySummary)),
+)]
+pub struct ApiDoc;
+
+pub fn spec() -> utoipa::openapi::OpenApi { ApiDoc::openapi() }
@@ -0,0 +1,137 @@
+// ── Protobuf ────────────────────────────────────────────────
+//
+// Messages from proto/kinematics.proto, their conversions to and from the JSON
+// request/response types, and binary REST routes for clients that cannot afford
+// JSON on their hot paths. Bodies are single `application/x-protobuf` messages;
+// failures come back as `kinematics.v1.Error` with the usual HTTP status.
+
+use crate::error::{ApiError, Json};
+use crate::{AppState, FkRequest, FkResponse, IkConstraints, IkRequest, IkResponse, IntentRequest, IntentResponse, Joints, MotionSample, TrajectoryRequest, TrajectoryResponse, Waypoint};
+use axum::{body::Bytes, extract::State, http::{header, StatusCode}, response::{IntoResponse, Response}};
+use prost::Message;
+use std::sync::Arc;
+
+#[allow(clippy::all)]
+pub mod pb { include!(concat!(env!("OUT_DIR"), "/kinematics.v1.rs")); }
+
+const CONTENT_TYPE: &str = "application/x-protobuf";
+
+// ── Conversions ─────────────────────────────────────────────
+fn v3(v: Option<pb::Vec3>) -> [f64; 3] { v.map_or([0.0; 3], |v| [v.x, v.y, v.z]) }
+fn quat(q: pb::Quat) -> [f64; 4] { [q.x, q.y, q.z, q.w] }
+fn pv3(v: [f64; 3]) -> Option<pb::Vec3> { Some(pb::Vec3 { x: v[0], y: v[1], z: v[2] }) }
+fn pquat(q: [f64; 4]) -> Option<pb::Quat> { Some(pb::Quat { x: q[0], y: q[1], z: q[2], w: q[3] }) }
+
+pub fn ik_request(r: pb::IkRequest) -> IkRequest {
+    let seed = match (r.seed_configuration, r.seed.is_empty()) {
+        (Some(name), _) => Some(Joints::Named(name)),
+        (None, false) => Some(Joints::Angles(r.seed)),
+        (None, true) => None,
+    };
+    IkRequest {
+        chain_id: r.chain_id, target_position: v3(r.target_position), target_orientation: r.target_orientation.map(quat),
+        joint_count: r.joint_count,
+        constraints: Some(IkConstraints { max_iterations: r.max_iterations, tolerance: r.tolerance, damping: r.damping }),
+        solver: r.solver, seed_policy: r.seed_policy, seed, deadline_ms: r.deadline_ms, deadline_us: r.deadline_us,
+        precision: r.precision, wrap_policy: r.wrap_policy, session_id: None,
+    }
+}
+
+pub fn ik_response(out: IkResponse) -> pb::IkResponse {
+    let s = out.solver;
+    pb::IkResponse {
+        solution_id: out.solution_id, joint_angles: out.joint_angles, iterations: out.iterations, converged: out.converged,
+        error_distance: out.error_distance, orientation_error: out.orientation_error,
+        solver: Some(pb::SolverSettings { solver: s.solver, max_iterations: s.max_iterations, tolerance: s.tolerance, damping: s.damping, seed_policy: s.seed_policy, precision: s.precision }),
+        chain: out.chain, elapsed_us: out.elapsed_us as u64, timed_out: out.timed_out, warm_started: out.warm_started, quality: out.quality,
+        min_singular_value: out.min_singular_value, condition_number: out.condition_number,
+    }
+}
+
+pub fn fk_request(r: pb::FkRequest) -> FkRequest {
+    FkRequest { chain_id: r.chain_id, joint_angles: r.joint_angles, link_lengths: (!r.link_lengths.is_empty()).then_some(r.link_lengths), pad_link_length: r.pad_link_length }
+}
+
+pub fn fk_response(out: FkResponse) -> pb::FkResponse {
+    pb::FkResponse {
+        end_effector_position: pv3(out.end_effector_position), end_effector_orientation: pquat(out.end_effector_orientation),
+        joint_positions: out.joint_positions.into_iter().filter_map(pv3).collect(),
+        limit_violations: out.limit_violations.into_iter().map(|i| i as u32).collect(),
+        chain: out.chain, elapsed_us: out.elapsed_us as u64,
+    }
+}
+
+pub fn intent_request(r: pb::IntentRequest) -> IntentRequest {
+    let samples = r.samples.into_iter().map(|m| MotionSample {
+        timestamp_ms: m.timestamp_ms, position: v3(m.position), velocity: m.velocity.map(|v| v3(Some(v))),
+        joint_angles: (!m.joint_angles.is_empty()).then_some(m.joint_angles), orientation: m.orientation.map(quat),
+    }).collect();
+    IntentRequest {
+        samples, sample_rate_hz: r.sample_rate_hz, mode: None, pca_components: None, filter: None, space: r.space, codec: None,
+        session_id: r.session_id, classifier: r.classifier, predict: r.predict, prediction_horizon_ms: r.prediction_horizon_ms, resample: r.resample,
+    }
+}
+
+pub fn intent_response(out: IntentResponse) -> pb::IntentResponse {
+    pb::IntentResponse {
+        intent_id: out.intent_id, compressed_bytes: out.compressed_bytes, original_samples: out.original_samples as u64,
+        compression_ratio: out.compression_ratio, intent_type: out.intent_type, confidence: out.confidence,
+        alternatives: out.alternatives.into_iter().map(|a| pb::IntentAlternative { intent_type: a.intent_type, score: a.score }).collect(),
+        direction: pv3(out.direction), magnitude: out.magnitude, avg_speed: out.avg_speed,
+        estimated_velocities: out.estimated_velocities as u64,
+        prediction: out.prediction.map(|p| pb::Prediction { model: p.model, endpoint: pv3(p.endpoint), eta_ms: p.eta_ms, reaches_rest: p.reaches_rest }),
+        elapsed_us: out.elapsed_us as u64,
+    }
+}
+
+pub fn trajectory_request(r: pb::TrajectoryRequest) -> TrajectoryRequest {
+    let waypoints = r.waypoints.into_iter().map(|w| match w.point {
+        Some(pb::waypoint::Point::Configuration(name)) => Waypoint::Named(name),
+        Some(pb::waypoint::Point::Position(p)) => Waypoint::Point(v3(Some(p)).to_vec()),
+        None => Waypoint::Point(vec![0.0; 3]),
+    }).collect();
+    TrajectoryRequest { waypoints, max_velocity: r.max_velocity, chain_id: r.chain_id, max_acceleration: None, smoothness: None, session_id: None }
+}
+
+pub fn trajectory_response(out: TrajectoryResponse) -> pb::TrajectoryResponse {
+    pb::TrajectoryResponse {
+        trajectory_id: out.trajectory_id,
+        optimized_waypoints: out.optimized_waypoints.into_iter().map(|p| pb::TrajectoryPoint { position: pv3(p.position), velocity: pv3(p.velocity), time: p.time }).collect(),
+        total_distance: out.total_distance, total_time: out.total_time, max_velocity_reached: out.max_velocity_reached,
+        elapsed_us: out.elapsed_us as u64,
+    }
+}
+
+// ── Binary routes ───────────────────────────────────────────
+fn encode<M: Message>(status: StatusCode, msg: M) -> Response {
+    (status, [(header::CONTENT_TYPE, CONTENT_TYPE)], msg.encode_to_vec()).into_response()
+}
+
+fn error(e: ApiError) -> Response {
+    let code = serde_json::to_value(e.code).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+    encode(e.status(), pb::Error { code, message: e.message, field: e.field, details: e.details })
+}
+
+fn decode<M: Message + Default>(body: Bytes) -> Result<M, ApiError> {
+    M::decode(body).map_err(|e| ApiError::bad_request("Invalid protobuf body", e.to_string()))
+}
+
+pub async fn solve_ik(State(s): State<Arc<AppState>>, body: Bytes) -> Response {
+    let req = match decode(body) { Ok(r) => ik_request(r), Err(e) => return error(e) };
+    match crate::solve_ik(State(s), Json(req)).await { Ok(Json(out)) => encode(StatusCode::OK, ik_response(out)), Err(e) => error(e) }
+}
+
+pub async fn solve_fk(State(s): State<Arc<AppState>>, body: Bytes) -> Response {
+    let req = match decode(body) { Ok(r) => fk_request(r), Err(e) => return error(e) };
+    match crate::solve_fk(State(s), Json(req)).await { Ok(Json(out)) => encode(StatusCode::OK, fk_response(out)), Err(e) => error(e) }
+}
+
+pub async fn compress_intent(State(s): State<Arc<AppState>>, body: Bytes) -> Response {
+    let req = match decode(body) { Ok(r) => intent_request(r), Err(e) => return error(e) };
+    match crate::compress_intent(State(s), Json(req)).await { Ok(Json(out)) => encode(StatusCode::OK, intent_response(out)), Err(e) => error(e) }
+}
+
+pub async fn optimize_trajectory(State(s): State<Arc<AppState>>, body: Bytes) -> Response {
+    let req = match decode(body) { Ok(r) => trajectory_request(r), Err(e) => return error(e) };
+    match crate::optimize_trajectory(State(s), Json(req)).await { Ok(Json(out)) => encode(StatusCode::OK, trajectory_response(out)), Err(e) => error(e) }
+}
@@ -0,0 +1,142 @@
+// ── Rate limiting ───────────────────────────────────────────
+//
+// Token buckets per client and endpoint class, so one busy client cannot starve the
+// solver for everyone else. A client is its tenant (so a tenant's keys share one
+// quota), else its authenticated API key, else its IP address. Each class has its
+// own limit of `N` requests per `S` seconds, refilled continuously with bursts of up
+// to `N`. Over the limit, requests
+// get 429 with `Retry-After`; every limited response carries `X-RateLimit-Limit` and
+// `X-RateLimit-Remaining`.
+
+use crate::auth::{self, Principal};
+use crate::error::{ApiError, ErrorCode};
+use crate::AppState;
+use axum::{extract::{ConnectInfo, Request, State}, http::{header, HeaderMap, HeaderValue, Method}, middleware::Next, response::{IntoResponse, Response}};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Buckets held before full (idle) ones are dropped.
+const MAX_BUCKETS: usize = 100_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Class { Solve, Write, Read }
+
+impl Class {
+    /// Solving work (solves, retargeting, motion capture, calibration, simulation, intent, codec, trajectories, job submission, protobuf),
+    /// other writes, or reads (including GraphQL queries). Health and the OpenAPI
+    /// document are not limited.
+    pub fn of(method: &Method, path: &str) -> Option<Class> {
+        if !path.starts_with("/api/v1/") || path == "/api/v1/openapi.json" { return None; }
+        if path == "/api/v1/graphql" { return Some(Class::Read); }
+        const SOLVING: &[&str] = &["/solve-ik", "/solve-ik/velocity", "/solve-ik/acceleration", "/reachable", "/self-motion", "/compliance", "/solve-fk", "/verify", "/retarget", "/mocap/import", "/calibrate/hand-eye", "/sims", "/step", "/simulate-tracking", "/compress-intent", "/compress-intent/channels", "/optimize-trajectory", "/codec/encode", "/codec/decode", "/jobs"];
+        let solving = SOLVING.iter().any(|s| path.ends_with(s)) || path.starts_with("/api/v1/kinematics/pb/");
+        Some(match *method {
+            Method::POST if solving => Class::Solve,
+            // Each IK stream message is a solve; the connection is charged once
+            Method::GET if path.ends_with("/solve-ik/stream") => Class::Solve,
+            Method::GET | Method::HEAD => Class::Read,
+            _ => Class::Write,
+        })
+    }
+}
+
+/// `requests` per `per`.
+#[derive(Clone, Copy)]
+pub struct Limit { pub requests: u32, pub per: Duration }
+
+impl Limit {
+    /// `N/S` (N requests per S seconds), or `off`.
+    pub fn parse(spec: &str) -> Result<Option<Limit>, String> {
+        if spec.trim().eq_ignore_ascii_case("off") { return Ok(None); }
+        let (n, s) = spec.split_once('/').ok_or_else(|| format!("'{spec}' is not N/S or off"))?;
+        let requests: u32 = n.trim().parse().map_err(|_| format!("'{n}' is not a request count"))?;
+        let secs: f64 = s.trim().parse().map_err(|_| format!("'{s}' is not a number of seconds"))?;
+        if requests == 0 || !(secs.is_finite() && secs > 0.0) { return Err(format!("'{spec}' must allow at least one request per positive period")); }
+        Ok(Some(Limit { requests, per: Duration::from_secs_f64(secs) }))
+    }
+
+    fn rate(&self) -> f64 { self.requests as f64 / self.per.as_secs_f64() }
+}
+
+struct Bucket { tokens: f64, at: Instant }
+
+pub enum Verdict { Allowed { limit: u32, remaining: u32 }, Limited { limit: u32, retry_after: Duration } }
+
+pub struct RateLimiter { solve: Option<Limit>, write: Option<Limit>, read: Option<Limit>, buckets: HashMap<(String, Class), Bucket> }
+
+impl RateLimiter {
+    pub fn new(solve: Option<Limit>, write: Option<Limit>, read: Option<Limit>) -> Self { Self { solve, write, read, buckets: HashMap::new() } }
+
+    /// Change the limits in place; clients keep their buckets, capped at the new burst on next use.
+    pub fn set_limits(&mut self, solve: Option<Limit>, write: Option<Limit>, read: Option<Limit>) { (self.solve, self.write, self.read) = (solve, write, read); }
+
+    /// Buckets held, one per client and class.
+    pub fn buckets(&self) -> usize { self.buckets.len() }
+
+    fn limit(&self, class: Class) -> Option<Limit> {
+        match class { Class::Solve => self.solve, Class::Write => self.write, Class::Read => self.read }
+    }
+
+    /// Take a token for `client` in `class`; `None` when the class is unlimited.
+    pub fn take(&mut self, client: &str, class: Class) -> Option<Verdict> {
+        let limit = self.limit(class)?;
+        let (burst, rate, now) = (limit.requests as f64, limit.rate(), Instant::now());
+        if self.buckets.len() >= MAX_BUCKETS {
+            self.buckets.retain(|_, b| b.tokens + now.duration_since(b.at).as_secs_f64() * rate < burst);
+        }
+        let b = self.buckets.entry((client.to_string(), class)).or_insert(Bucket { tokens: burst, at: now });
+        b.tokens = (b.tokens + now.duration_since(b.at).as_secs_f64() * rate).min(burst);
+        b.at = now;
+        Some(if b.tokens >= 1.0 {
+            b.tokens -= 1.0;
+            Verdict::Allowed { limit: limit.requests, remaining: b.tokens as u32 }
+        } else {
+            Verdict::Limited { limit: limit.requests, retry_after: Duration::from_secs_f64((1.0 - b.tokens) / rate) }
+        })
+    }
+}
+
+/// The caller's tenant or key, otherwise the peer (or first forwarded) address.
+pub fn client(principal: Option<&Principal>, headers: &HeaderMap, peer: Option<SocketAddr>, trust_forwarded: bool) -> String {
+    if let Some(p) = principal { return p.tenant.as_ref().map_or_else(|| format!("key:{}", p.key_id), |t| format!("tenant:{t}")); }
+    let forwarded = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()).and_then(|v| v.split(',').next()).filter(|_| trust_forwarded);
+    match (forwarded, peer) {
+        (Some(ip), _) => format!("ip:{}", ip.trim()),
+        (None, Some(addr)) => format!("ip:{}", addr.ip()),
+        (None, None) => "ip:unknown".into(),
+    }
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let Some(class) = Class::of(req.method(), req.uri().path()) else { return next.run(req).await };
+    let peer = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|c| c.0);
+    let who = client(auth::current().as_ref(), req.headers(), peer, s.trust_forwarded);
+    let verdict = s.limiter.lock().unwrap().take(&who, class);
+    match verdict {
+        None => next.run(req).await,
+        Some(Verdict::Allowed { limit, remaining }) => {
+            let mut res = next.run(req).await;
+            quota_headers(&mut res, limit, remaining);
+            res
+        }
+        Some(Verdict::Limited { limit, retry_after }) => {
+            let mut res = exceeded(limit, retry_after).into_response();
+            res.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_secs(retry_after)));
+            quota_headers(&mut res, limit, 0);
+            res
+        }
+    }
+}
+
+fn retry_secs(retry_after: Duration) -> u64 { retry_after.as_secs_f64().ceil().max(1.0) as u64 }
+
+pub fn exceeded(limit: u32, retry_after: Duration) -> ApiError {
+    ApiError::new(ErrorCode::TooManyRequests, "Rate limit exceeded", format!("{limit} requests allowed per period; retry in {}s", retry_secs(retry_after)))
+}
+
+fn quota_headers(res: &mut Response, limit: u32, remaining: u32) {
+    res.headers_mut().insert("x-ratelimit-limit", HeaderValue::from(limit));
+    res.headers_mut().insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+}
@@ -0,0 +1,77 @@
+// ── Hot reload ──────────────────────────────────────────────
+//
+// Every `KINEMATICS_RELOAD_SECS` (5 by default, 0 turns it off) the engine re-reads
+// the configuration file and the chain stores of the tenants it has loaded, and
+// applies what changed without a restart. A chain store rewritten by something other
+// than the engine (a deploy pipeline, an operator, a replica sharing the volume)
+// replaces that tenant's custom chains. In the configuration file, `[limits]`,
+// `[solver]`, `log.filter` and `log.slow_request_ms` take effect at once; other keys
+// are read only at startup, so changes to them are logged as waiting for a restart.
+// Each reload is recorded in the audit log as `reload-chains` or `reload-config`,
+// with what changed in `detail`.
+
+use crate::{audit, config, AppState, AuditRecord, Tunables};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// File keys (or key prefixes) applied while running.
+const LIVE: &[&str] = &["limits.", "solver.", "log.filter", "log.slow_request_ms"];
+
+pub fn spawn(s: Arc<AppState>) {
+    let every = config::var("KINEMATICS_RELOAD_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(5);
+    if every == 0 { return; }
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(Duration::from_secs(every));
+        tick.tick().await;
+        loop {
+            tick.tick().await;
+            reload_config(&s);
+            reload_chains(&s);
+        }
+    });
+}
+
+fn reload_config(s: &AppState) {
+    let (digest, changed) = match config::reload() {
+        Ok(Some(change)) => change,
+        Ok(None) => return,
+        Err(e) => return tracing::warn!("{e}"),
+    };
+    let (live, restart): (Vec<&str>, Vec<&str>) = changed.into_iter().partition(|k| LIVE.iter().any(|p| k.starts_with(p)));
+    let mut detail = Vec::new();
+    if !live.is_empty() {
+        match Tunables::read().and_then(|t| crate::telemetry::reload_filter().map(|_| t)) {
+            Ok(t) => { t.apply(s); detail.push(format!("applied {}", live.join(", "))); }
+            Err(e) => {
+                tracing::error!("not applying reloaded configuration: {e}");
+                detail.push(format!("rejected {}: {e}", live.join(", ")));
+            }
+        }
+    }
+    if !restart.is_empty() {
+        tracing::warn!("configuration changes to {} take effect after a restart", restart.join(", "));
+        detail.push(format!("restart needed for {}", restart.join(", ")));
+    }
+    let detail = if detail.is_empty() { "no effective changes".to_string() } else { detail.join("; ") };
+    tracing::info!("Reloaded configuration: {detail}");
+    record(s, "reload-config", digest, detail, None);
+}
+
+fn reload_chains(s: &AppState) {
+    for (tenant, registry) in s.chains.entries() {
+        let reloaded = registry.lock().unwrap().reload();
+        match reloaded {
+            Ok(Some((digest, detail))) => {
+                tracing::info!(tenant, "Reloaded chain store: {detail}");
+                record(s, "reload-chains", digest, detail, Some(tenant).filter(|t| t != crate::tenant::DEFAULT));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(tenant, "{e}"),
+        }
+    }
+}
+
+fn record(s: &AppState, operation: &str, digest: String, detail: String, tenant: Option<String>) {
+    let r = AuditRecord { detail: Some(detail), tenant, ..audit::record(operation, digest) };
+    s.audit.lock().unwrap().push(r);
+}
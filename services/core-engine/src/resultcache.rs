@@ -0,0 +1,123 @@
+// ── Result cache ────────────────────────────────────────────
+//
+// Responses of pure computations (chain and hand FK, codec encode/decode) kept for
+// `ttl` and served again for exact repeats, so UI clients that re-fetch the same FK on
+// every render do not recompute it. A repeat is the same route, query and body after
+// canonicalization (key order and whitespace do not matter), from the same tenant,
+// against the same registry state: a chain or hand change makes earlier entries
+// unreachable. Hits skip the handler, so they are not counted as solves, audited or
+// published as events; they carry `X-Cache: hit` and `Age`. `Cache-Control: no-cache`
+// forces a fresh computation (which is then cached); `no-store` bypasses the cache
+// altogether. The least recently used entries go once `capacity` is reached.
+
+use crate::error::ApiError;
+use crate::AppState;
+use axum::{body::{to_bytes, Body, Bytes}, extract::{Request, State}, http::{header, HeaderMap, HeaderValue, Method, StatusCode}, middleware::Next, response::{IntoResponse, Response}};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Larger responses are not worth holding.
+const MAX_RESPONSE: usize = 64 << 10;
+
+#[derive(Clone)]
+struct Stored { headers: HeaderMap, body: Bytes, at: Instant }
+
+pub struct ResultCache { entries: HashMap<[u8; 32], (Stored, u64)>, recency: BTreeMap<u64, [u8; 32]>, ttl: Duration, capacity: usize, clock: u64 }
+
+impl ResultCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self { Self { entries: HashMap::new(), recency: BTreeMap::new(), ttl, capacity, clock: 0 } }
+
+    /// Responses held, expired ones included until they are next looked up or evicted.
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    fn enabled(&self) -> bool { !self.ttl.is_zero() && self.capacity > 0 }
+
+    fn get(&mut self, key: &[u8; 32]) -> Option<Stored> {
+        let (stored, used) = self.entries.get_mut(key)?;
+        if stored.at.elapsed() > self.ttl {
+            let used = *used;
+            self.entries.remove(key);
+            self.recency.remove(&used);
+            return None;
+        }
+        self.clock += 1;
+        self.recency.remove(used);
+        *used = self.clock;
+        self.recency.insert(self.clock, *key);
+        Some(stored.clone())
+    }
+
+    fn put(&mut self, key: [u8; 32], stored: Stored) {
+        self.clock += 1;
+        if let Some((_, used)) = self.entries.insert(key, (stored, self.clock)) { self.recency.remove(&used); }
+        self.recency.insert(self.clock, key);
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// Cacheable routes: pure functions of their body and the registries.
+fn cacheable(path: &str) -> bool {
+    let Some(rest) = path.strip_prefix("/api/v1/kinematics/") else { return false };
+    matches!(rest, "solve-fk" | "codec/encode" | "codec/decode") || (rest.starts_with("hands/") && rest.ends_with("/solve-fk"))
+}
+
+/// `v` with object keys sorted at every level, whatever map order serde_json keeps.
+fn canonical(v: Value) -> Value {
+    match v {
+        Value::Object(fields) => {
+            let sorted: BTreeMap<String, Value> = fields.into_iter().map(|(k, v)| (k, canonical(v))).collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical).collect()),
+        v => v,
+    }
+}
+
+fn fingerprint(s: &AppState, uri: &str, body: &Value) -> [u8; 32] {
+    let (chains, hands) = (s.chains.current().lock().unwrap().modified_ms(), s.hands.current().lock().unwrap().modified_ms());
+    let mut h = Sha256::new();
+    h.update(format!("{}\n{uri}\n{chains}.{hands}\n{:?}\n", crate::tenant::current(), crate::deterministic::seed()));
+    h.update(serde_json::to_vec(body).unwrap_or_default());
+    h.finalize().into()
+}
+
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if req.method() != Method::POST || !cacheable(req.uri().path()) || !s.result_cache.lock().unwrap().enabled() { return next.run(req).await; }
+    let directives = req.headers().get_all(header::CACHE_CONTROL).iter().filter_map(|v| v.to_str().ok()).flat_map(|v| v.split(',')).map(|d| d.trim().to_ascii_lowercase()).collect::<Vec<_>>();
+    if directives.iter().any(|d| d == "no-store") { return next.run(req).await; }
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => return ApiError::bad_request("Unreadable request body", e.to_string()).into_response(),
+    };
+    let Ok(value) = serde_json::from_slice::<Value>(&bytes) else { return next.run(Request::from_parts(parts, Body::from(bytes))).await };
+    let key = fingerprint(&s, parts.uri.path_and_query().map_or("", |p| p.as_str()), &canonical(value));
+    let hit = if directives.iter().any(|d| d == "no-cache") { None } else { s.result_cache.lock().unwrap().get(&key) };
+    if let Some(r) = hit {
+        let mut res = (StatusCode::OK, r.body).into_response();
+        res.headers_mut().extend(r.headers);
+        res.headers_mut().insert("x-cache", HeaderValue::from_static("hit"));
+        res.headers_mut().insert(header::AGE, HeaderValue::from(r.at.elapsed().as_secs()));
+        return res;
+    }
+    let res = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    if res.status() != StatusCode::OK { return res; }
+    let (mut parts, body) = res.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => return ApiError::internal("Response buffering failed", e.to_string()).into_response(),
+    };
+    if body.len() <= MAX_RESPONSE {
+        let mut headers = parts.headers.clone();
+        headers.remove(header::CONTENT_LENGTH);
+        s.result_cache.lock().unwrap().put(key, Stored { headers, body: body.clone(), at: Instant::now() });
+    }
+    parts.headers.insert("x-cache", HeaderValue::from_static("miss"));
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,78 @@
+// ── Motion retargeting ──────────────────────────────────────
+//
+// Carries a motion recorded on one chain (a human arm, by default) over to another
+// (a robot) by its end-effector path: each source pose is scaled (by default by the
+// ratio of the two reaches, so a full human reach is a full robot reach), rotated and
+// offset into the target's base frame, and solved on the target starting from the
+// previous frame's solution. The path keeps its shape; joint continuity comes from the
+// warm start and from unwrapping each solution towards the one before. Targets beyond
+// the target chain's reach are pulled onto it, and the solver keeps every joint within
+// its limits; frames where either happened are flagged. With `follow_orientation` the
+// target end effector also turns as the source's does, starting from its orientation at
+// the seed, so the two tools need not share a frame convention.
+
+use crate::chains::ChainDef;
+use crate::intent::{quat_conj, quat_mul, quat_normalize};
+use kinematics_solver::{Frame, IkSettings};
+use kinematics_types::{RetargetFrame, RetargetRequest};
+
+/// Where source poses land in the target's base frame.
+pub struct Mapping { pub scale: f64, rotation: [f64; 4], offset: [f64; 3] }
+
+impl Mapping {
+    pub fn new(source: &ChainDef, target: &ChainDef, req: &RetargetRequest) -> Mapping {
+        let scale = req.scale.unwrap_or_else(|| target.reach() / source.reach().max(1e-9));
+        Mapping { scale, rotation: quat_normalize(req.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0])), offset: req.offset.unwrap_or_default() }
+    }
+
+    /// Scaling only, for poses already in the target's base frame.
+    pub fn uniform(scale: f64) -> Mapping { Mapping { scale, rotation: [0.0, 0.0, 0.0, 1.0], offset: [0.0; 3] } }
+
+    fn position(&self, p: [f64; 3]) -> [f64; 3] {
+        let [x, y, z, w] = self.rotation;
+        let r = kinematics_solver::mat_vec(&Frame::from_quat_wxyz([0.0; 3], [w, x, y, z]).r, p.map(|c| c * self.scale));
+        [0, 1, 2].map(|i| r[i] + self.offset[i])
+    }
+
+    /// A source-frame rotation `q` as seen in the target frame.
+    fn turn(&self, q: [f64; 4]) -> [f64; 4] { quat_mul(quat_mul(self.rotation, q), quat_conj(self.rotation)) }
+}
+
+/// Source end-effector positions and orientations, one per frame.
+pub fn source_poses(source: &ChainDef, req: &RetargetRequest) -> Vec<([f64; 3], Option<[f64; 4]>)> {
+    match (&req.joint_angles, &req.positions) {
+        (Some(frames), _) => {
+            let model = kinematics_solver::Model::new(source);
+            frames.iter().map(|q| { let tip = model.forward(q).tip; (tip.p, Some(tip.quat_xyzw())) }).collect()
+        }
+        (None, Some(positions)) => positions.iter().enumerate().map(|(i, &p)| (p, req.orientations.as_ref().map(|o| o[i]))).collect(),
+        (None, None) => Vec::new(),
+    }
+}
+
+/// Solve every pose on `target` from `seed`, in order, until `stop`; `true` when stopped early.
+pub fn solve(target: &ChainDef, poses: &[([f64; 3], Option<[f64; 4]>)], map: &Mapping, follow: bool, seed: Vec<f64>, ik: &IkSettings, stop: impl Fn() -> bool) -> (Vec<RetargetFrame>, bool) {
+    let reach = target.reach();
+    // The source's first orientation maps onto the target's at the seed
+    let start = kinematics_solver::forward(target, &seed).tip.quat_xyzw();
+    let first = poses.first().and_then(|(_, q)| *q).map(quat_normalize);
+    let (mut prev, mut frames) = (seed, Vec::with_capacity(poses.len()));
+    for &(p, q) in poses {
+        if stop() { return (frames, true); }
+        let mut goal = map.position(p);
+        let distance = goal.iter().map(|c| c * c).sum::<f64>().sqrt();
+        let clamped = distance > reach;
+        if clamped { goal = goal.map(|c| c * reach / distance); }
+        let orientation = q.zip(first).filter(|_| follow).map(|(q, first)| quat_normalize(quat_mul(map.turn(quat_mul(quat_normalize(q), quat_conj(first))), start)));
+        let mut r = kinematics_solver::solve_ik(target, goal, orientation, prev.clone(), ik);
+        kinematics_solver::wrap_angles(target, &mut r.angles, &prev, Some("shortest_from_seed"));
+        let limit_hits = target.joints.iter().zip(&r.angles).enumerate()
+            .filter(|(_, (j, &qi))| j.limits.is_some_and(|[lo, hi]| qi <= lo + 1e-9 || qi >= hi - 1e-9)).map(|(i, _)| i).collect();
+        prev = r.angles.clone();
+        frames.push(RetargetFrame {
+            joint_angles: r.angles, target_position: goal, iterations: r.iterations, converged: r.converged, error_distance: r.position_error,
+            orientation_error: r.orientation_error, clamped, limit_hits,
+        });
+    }
+    (frames, false)
+}
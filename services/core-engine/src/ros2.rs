@@ -0,0 +1,247 @@
+// ── ROS 2 bridge ────────────────────────────────────────────
+//
+// Optional ROS 2 mode over Zenoh, for `zenoh-bridge-ros2dds` (or any router it is
+// connected to). ROS names map to key expressions as that bridge maps them
+// (`/kinematics/solve_ik` ↔ `kinematics/solve_ik`), so ROS nodes see:
+//
+//   {ns}/solve_ik         service  kinematics_cloud_msgs/srv/SolveIk
+//   {ns}/solve_fk         service  kinematics_cloud_msgs/srv/SolveFk
+//   {ns}/plan_trajectory  service  kinematics_cloud_msgs/srv/PlanTrajectory
+//   {ns}/joint_trajectory topic    trajectory_msgs/msg/JointTrajectory
+//
+// Services are queryables taking and returning CDR; each call runs the REST handler,
+// as the bridge's API key when auth is enabled. A planned trajectory is solved into
+// joint space along its waypoints and published on `joint_trajectory`. The service
+// definitions live in `ros2/kinematics_cloud_msgs`.
+
+use crate::error::{ApiError, Json};
+use crate::{auth, AppState, FkRequest, IkRequest, Joints, TrajectoryRequest, TrajectoryResponse, Waypoint};
+use axum::extract::State;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::pubsub::Publisher;
+use zenoh::query::Query;
+
+const RETRY: Duration = Duration::from_secs(5);
+
+pub struct Config {
+    /// Zenoh endpoint of the bridge or router, e.g. `tcp/127.0.0.1:7447`.
+    pub endpoint: String,
+    pub namespace: String, pub frame_id: String,
+    /// Key the bridge's requests run as when auth is enabled.
+    pub api_key: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Service { Ik, Fk, Trajectory }
+
+struct Bridge { state: Arc<AppState>, config: Config, trajectories: Publisher<'static> }
+
+/// Connect and serve until the process exits.
+pub async fn run(s: Arc<AppState>, config: Config) {
+    let mut zc = zenoh::Config::default();
+    let endpoints = serde_json::to_string(&[&config.endpoint]).unwrap_or_default();
+    if let Err(e) = zc.insert_json5("mode", r#""client""#).and_then(|_| zc.insert_json5("connect/endpoints", &endpoints)) {
+        return tracing::error!("ROS 2 bridge: invalid Zenoh endpoint {}: {e}", config.endpoint);
+    }
+    let session = loop {
+        match zenoh::open(zc.clone()).await {
+            Ok(session) => break session,
+            Err(e) => tracing::warn!("ROS 2 bridge: cannot reach {}: {e}; retrying", config.endpoint),
+        }
+        tokio::time::sleep(RETRY).await;
+    };
+    let ns = config.namespace.clone();
+    let trajectories = match session.declare_publisher(format!("{ns}/joint_trajectory")).await {
+        Ok(p) => p,
+        Err(e) => return tracing::error!("ROS 2 bridge: {ns}/joint_trajectory: {e}"),
+    };
+    let bridge = Arc::new(Bridge { state: s, config, trajectories });
+    for (name, service) in [("solve_ik", Service::Ik), ("solve_fk", Service::Fk), ("plan_trajectory", Service::Trajectory)] {
+        let queryable = match session.declare_queryable(format!("{ns}/{name}")).await {
+            Ok(q) => q,
+            Err(e) => return tracing::error!("ROS 2 bridge: {ns}/{name}: {e}"),
+        };
+        let bridge = bridge.clone();
+        tokio::spawn(async move {
+            while let Ok(query) = queryable.recv_async().await {
+                tokio::spawn(reply(bridge.clone(), service, query));
+            }
+        });
+    }
+    tracing::info!("ROS 2 bridge connected to {}, serving /{ns}/solve_ik, /{ns}/solve_fk and /{ns}/plan_trajectory", bridge.config.endpoint);
+    // The session closes when dropped
+    std::future::pending::<()>().await;
+}
+
+async fn reply(b: Arc<Bridge>, service: Service, query: Query) {
+    let payload = query.payload().map(|p| p.to_bytes().into_owned()).unwrap_or_default();
+    let result = async {
+        let principal = auth::bridge(&b.state, "KINEMATICS_ROS2_API_KEY", b.config.api_key.as_deref())?;
+        auth::scope(principal, handle(&b, service, &payload)).await
+    }.await;
+    let body = match (service, result) {
+        (_, Ok(body)) => body,
+        (Service::Ik, Err(e)) => ik_failure(&e),
+        (Service::Fk, Err(e)) => fk_failure(&e),
+        (Service::Trajectory, Err(e)) => trajectory_failure(&e),
+    };
+    if let Err(e) = query.reply(query.key_expr().clone(), body).await { tracing::warn!("ROS 2 reply on {}: {e}", query.key_expr()); }
+}
+
+async fn handle(b: &Bridge, service: Service, payload: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut r = Reader::new(payload).map_err(invalid)?;
+    let s = &b.state;
+    match service {
+        Service::Ik => {
+            let req = ik_request(&mut r).map_err(invalid)?;
+            let Json(out) = crate::solve_ik(State(s.clone()), Json(req)).await?;
+            let mut w = Writer::new();
+            w.bool(true); w.string(""); w.string(&out.solution_id); w.f64s(&out.joint_angles); w.bool(out.converged); w.u32(out.iterations); w.f64(out.error_distance);
+            Ok(w.0)
+        }
+        Service::Fk => {
+            let req = FkRequest { chain_id: non_empty(r.string().map_err(invalid)?), joint_angles: r.f64s().map_err(invalid)?, link_lengths: None, pad_link_length: None };
+            let Json(out) = crate::solve_fk(State(s.clone()), Json(req)).await?;
+            let mut w = Writer::new();
+            w.bool(true); w.string("");
+            out.end_effector_position.iter().chain(&out.end_effector_orientation).for_each(|v| w.f64(*v));
+            w.u32s(&out.limit_violations.iter().map(|&i| i as u32).collect::<Vec<_>>());
+            Ok(w.0)
+        }
+        Service::Trajectory => {
+            let (chain_id, waypoints, max_velocity) = (r.string().map_err(invalid)?, r.f64s().map_err(invalid)?, r.f64().map_err(invalid)?);
+            if chain_id.is_empty() { return Err(ApiError::invalid("chain_id", "Chain required", "joint trajectories are solved on a registered chain")); }
+            if !waypoints.len().is_multiple_of(3) { return Err(ApiError::invalid("waypoints", "Invalid waypoints", format!("{} values is not a whole number of [x, y, z] points", waypoints.len()))); }
+            let req = TrajectoryRequest {
+                waypoints: waypoints.chunks(3).map(|p| Waypoint::Point(p.to_vec())).collect(), max_velocity: (max_velocity > 0.0).then_some(max_velocity),
+                chain_id: Some(chain_id.clone()), max_acceleration: None, smoothness: None, session_id: None,
+            };
+            let Json(out) = crate::optimize_trajectory(State(s.clone()), Json(req)).await?;
+            let trajectory = joint_trajectory(s, &chain_id, &out, &b.config.frame_id)?;
+            if let Err(e) = b.trajectories.put(trajectory).await { tracing::warn!("ROS 2 publish {}: {e}", b.trajectories.key_expr()); }
+            let mut w = Writer::new();
+            w.bool(true); w.string(""); w.string(&out.trajectory_id); w.u32(out.optimized_waypoints.len() as u32); w.f64(out.total_time);
+            Ok(w.0)
+        }
+    }
+}
+
+fn invalid(e: String) -> ApiError { ApiError::bad_request("Invalid CDR request", e) }
+
+fn non_empty(s: String) -> Option<String> { Some(s).filter(|s| !s.is_empty()) }
+
+fn ik_request(r: &mut Reader) -> Result<IkRequest, String> {
+    let chain_id = non_empty(r.string()?);
+    let target_position = [r.f64()?, r.f64()?, r.f64()?];
+    let orientation = r.f64s()?;
+    let seed = r.f64s()?;
+    let target_orientation = match orientation[..] {
+        [] => None,
+        [x, y, z, w] => Some([x, y, z, w]),
+        _ => return Err(format!("target_orientation must be empty or [x, y, z, w], got {} values", orientation.len())),
+    };
+    Ok(IkRequest {
+        chain_id, target_position, target_orientation, joint_count: None, constraints: None, solver: None, seed_policy: None,
+        seed: (!seed.is_empty()).then_some(Joints::Angles(seed)), deadline_ms: None, deadline_us: None, precision: None, wrap_policy: None, session_id: None,
+    })
+}
+
+// Failure replies: `success` false, the error in `message`, every other field zeroed
+fn ik_failure(e: &ApiError) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.bool(false); w.string(&e.summary()); w.string(""); w.f64s(&[]); w.bool(false); w.u32(0); w.f64(0.0);
+    w.0
+}
+
+fn fk_failure(e: &ApiError) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.bool(false); w.string(&e.summary()); (0..7).for_each(|_| w.f64(0.0)); w.u32s(&[]);
+    w.0
+}
+
+fn trajectory_failure(e: &ApiError) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.bool(false); w.string(&e.summary()); w.string(""); w.u32(0); w.f64(0.0);
+    w.0
+}
+
+/// `trajectory_msgs/msg/JointTrajectory` of `out`: IK at every point, each seeded with
+/// the previous solution. Points carry positions only; controllers interpolate.
+fn joint_trajectory(s: &AppState, chain_id: &str, out: &TrajectoryResponse, frame_id: &str) -> Result<Vec<u8>, ApiError> {
+    let chain = crate::lookup_chain(s, Some(chain_id))?.expect("chain_id given");
+    let settings = crate::solver_settings(s, Some(&chain), &Default::default());
+    let ik = kinematics_solver::IkSettings { max_iterations: settings.max_iterations, tolerance: settings.tolerance, damping: settings.damping };
+    let mut seed = kinematics_solver::seed(&chain, &settings.seed_policy);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut w = Writer::new();
+    w.i32(now.as_secs() as i32); w.u32(now.subsec_nanos()); w.string(frame_id);
+    w.u32(chain.dof() as u32);
+    chain.joints.iter().enumerate().for_each(|(i, j)| w.string(&j.name.clone().unwrap_or_else(|| format!("joint_{}", i + 1))));
+    w.u32(out.optimized_waypoints.len() as u32);
+    for (i, p) in out.optimized_waypoints.iter().enumerate() {
+        let r = kinematics_solver::solve_ik(&chain, p.position, None, seed, &ik);
+        if !r.converged {
+            return Err(ApiError::invalid(format!("waypoints[{i}]"), "No joint solution", format!("IK did not converge within {} iterations (error {:.2e} m)", r.iterations, r.position_error)));
+        }
+        w.f64s(&r.angles); w.f64s(&[]); w.f64s(&[]); w.f64s(&[]);
+        let t = Duration::from_secs_f64(p.time.max(0.0));
+        w.i32(t.as_secs() as i32); w.u32(t.subsec_nanos());
+        seed = r.angles;
+    }
+    Ok(w.0)
+}
+
+// ── CDR ─────────────────────────────────────────────────────
+// Plain CDR as ROS 2 puts it on the wire: a 4-byte encapsulation header, then each
+// primitive aligned to its size from the end of that header. Strings carry their
+// length including the NUL; sequences are a u32 count and the elements.
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    /// Little-endian (`CDR_LE`).
+    fn new() -> Self { Self(vec![0x00, 0x01, 0x00, 0x00]) }
+    fn align(&mut self, n: usize) { while !(self.0.len() - 4).is_multiple_of(n) { self.0.push(0); } }
+    fn bool(&mut self, v: bool) { self.0.push(v as u8); }
+    fn u32(&mut self, v: u32) { self.align(4); self.0.extend(v.to_le_bytes()); }
+    fn i32(&mut self, v: i32) { self.align(4); self.0.extend(v.to_le_bytes()); }
+    fn f64(&mut self, v: f64) { self.align(8); self.0.extend(v.to_le_bytes()); }
+    fn string(&mut self, v: &str) { self.u32(v.len() as u32 + 1); self.0.extend(v.as_bytes()); self.0.push(0); }
+    fn f64s(&mut self, v: &[f64]) { self.u32(v.len() as u32); v.iter().for_each(|x| self.f64(*x)); }
+    fn u32s(&mut self, v: &[u32]) { self.u32(v.len() as u32); v.iter().for_each(|x| self.u32(*x)); }
+}
+
+struct Reader<'a> { buf: &'a [u8], pos: usize, le: bool }
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Result<Self, String> {
+        match buf {
+            [0x00, 0x00, _, _, ..] => Ok(Self { buf, pos: 4, le: false }),
+            [0x00, 0x01, _, _, ..] => Ok(Self { buf, pos: 4, le: true }),
+            _ => Err("expected a CDR_BE or CDR_LE encapsulation header".into()),
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        self.pos += (N - (self.pos - 4) % N) % N;
+        let bytes = self.buf.get(self.pos..self.pos + N).ok_or("message ends early")?;
+        self.pos += N;
+        Ok(bytes.try_into().expect("N bytes"))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> { let b = self.take()?; Ok(if self.le { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) }) }
+    fn f64(&mut self) -> Result<f64, String> { let b = self.take()?; Ok(if self.le { f64::from_le_bytes(b) } else { f64::from_be_bytes(b) }) }
+
+    fn string(&mut self) -> Result<String, String> {
+        let n = self.u32()? as usize;
+        let bytes = self.buf.get(self.pos..self.pos.saturating_add(n)).ok_or("message ends early")?;
+        self.pos += n;
+        String::from_utf8(bytes.strip_suffix(&[0]).unwrap_or(bytes).to_vec()).map_err(|_| "string is not UTF-8".into())
+    }
+
+    fn f64s(&mut self) -> Result<Vec<f64>, String> {
+        let n = self.u32()? as usize;
+        (0..n).map(|_| self.f64()).collect()
+    }
+}
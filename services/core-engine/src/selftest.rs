@@ -0,0 +1,132 @@
+// ── Self-test ───────────────────────────────────────────────
+//
+// `GET /selftest` runs a built-in suite of golden cases (preset chains with a known
+// solution and the end-effector pose FK gives for it) on every solver backend this
+// engine has: FK alone, then IK in f64 and f32, time-budgeted IK and the GPU when one is
+// open. IK starts mid-range and passes when it converges and FK of its answer lands
+// within twice its tolerance of the known pose, so a build whose numerics have drifted
+// (a dependency, feature or target change) fails here instead of in production. The
+// suite also runs at startup unless `KINEMATICS_SELFTEST_ON_START` is `false`; until
+// the latest run passes, `/readyz` fails its `selftest` check.
+
+use crate::{compute, config, error::{ApiError, Json}, AppState};
+use axum::{extract::State, http::StatusCode};
+use kinematics_solver::{IkResult, IkSettings};
+use kinematics_types::{ChainDef, SelfTest, SelfTestBackend, SelfTestCase};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A preset chain, a solution on it and the pose FK gives for that solution; `pose`
+/// cases also target the orientation in IK.
+struct Golden { name: &'static str, chain: &'static str, solution: &'static [f64], position: [f64; 3], orientation: [f64; 4], pose: bool }
+
+const GOLDEN: &[Golden] = &[
+    Golden {
+        name: "robotic_arm_6dof-position", chain: "robotic_arm_6dof", solution: &[0.3, -0.5, 0.8, 0.2, 0.4, -0.1],
+        position: [0.71158919713369, 0.22659892883430421, 0.03739398752165389],
+        orientation: [0.0013908293211813967, 0.3445861529599176, 0.16203499281232941, 0.9246637820777842], pose: false,
+    },
+    Golden {
+        name: "robotic_arm_6dof-pose", chain: "robotic_arm_6dof", solution: &[0.3, -0.5, 0.8, 0.2, 0.4, -0.1],
+        position: [0.71158919713369, 0.22659892883430421, 0.03739398752165389],
+        orientation: [0.0013908293211813967, 0.3445861529599176, 0.16203499281232941, 0.9246637820777842], pose: true,
+    },
+    Golden {
+        name: "scara-position", chain: "scara", solution: &[0.3, -0.2, -0.1, 0.5],
+        position: [0.6328690207773698, 0.13338209732551728, -0.1], orientation: [0.0, 0.0, 0.2955202066613396, 0.955336489125606], pose: false,
+    },
+    Golden {
+        name: "human_arm-position", chain: "human_arm", solution: &[0.4, 0.3, -0.2, 0.9, 0.1, 0.2, -0.1],
+        position: [0.38277276336485, 0.07200990596136525, -0.4013407734491353],
+        orientation: [0.17628047094189717, 0.6312594129653395, 0.11144459751685282, 0.7470052548620154], pose: false,
+    },
+];
+
+/// FK must reproduce the golden pose to this (m, rad).
+const FK_TOLERANCE: f64 = 1e-9;
+const IK: IkSettings = IkSettings { max_iterations: 1000, tolerance: 1e-6, damping: 0.1 };
+/// Time budget of each budgeted solve.
+const BUDGET: Duration = Duration::from_secs(1);
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 { a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt() }
+
+fn backend(cases: Vec<SelfTestCase>) -> SelfTestBackend { SelfTestBackend { passed: cases.iter().all(|c| c.passed), cases } }
+
+/// Every golden case through `solve`, from mid-range; `pose` passes the orientation too.
+fn solve_all(presets: &[ChainDef], tolerance: f64, pose: bool, solve: impl Fn(&ChainDef, &Golden, Vec<f64>) -> Result<IkResult, String>) -> SelfTestBackend {
+    backend(GOLDEN.iter().filter(|g| pose || !g.pose).map(|g| {
+        let chain = presets.iter().find(|c| c.id == g.chain).expect("golden cases use presets");
+        let (name, limit) = (g.name.to_string(), 2.0 * tolerance);
+        match solve(chain, g, kinematics_solver::seed(chain, "mid")) {
+            Err(e) => SelfTestCase { name, passed: false, error: f64::NAN, orientation_error: None, iterations: 0, detail: Some(e) },
+            Ok(r) => {
+                let tip = kinematics_solver::forward(chain, &r.angles).tip;
+                let error = distance(tip.p, g.position);
+                let orientation_error = g.pose.then(|| kinematics_solver::orientation_angle(g.orientation, tip.quat_xyzw()));
+                let passed = r.converged && error <= limit && orientation_error.is_none_or(|e| e <= limit);
+                let detail = (!r.converged).then(|| format!("did not converge in {} iterations", r.iterations));
+                SelfTestCase { name, passed, error, orientation_error, iterations: r.iterations, detail }
+            }
+        }
+    }).collect())
+}
+
+pub fn run(s: &AppState) -> SelfTest {
+    let t = Instant::now();
+    let presets = kinematics_solver::presets();
+    let mut backends = BTreeMap::new();
+    let fk = GOLDEN.iter().filter(|g| !g.pose).map(|g| {
+        let chain = presets.iter().find(|c| c.id == g.chain).expect("golden cases use presets");
+        let tip = kinematics_solver::forward(chain, g.solution).tip;
+        let (error, orientation_error) = (distance(tip.p, g.position), kinematics_solver::orientation_angle(g.orientation, tip.quat_xyzw()));
+        let passed = error <= FK_TOLERANCE && orientation_error <= FK_TOLERANCE;
+        SelfTestCase { name: g.name.into(), passed, error, orientation_error: Some(orientation_error), iterations: 0, detail: None }
+    }).collect();
+    backends.insert("fk".to_string(), backend(fk));
+    let orientation = |g: &Golden| g.pose.then_some(g.orientation);
+    backends.insert("dls".into(), solve_all(&presets, IK.tolerance, true, |c, g, seed| Ok(kinematics_solver::solve_ik_at(None, c, g.position, orientation(g), seed, &IK, || false))));
+    let f32 = IkSettings { tolerance: kinematics_solver::F32_TOLERANCE, ..IK };
+    backends.insert("dls-f32".into(), solve_all(&presets, f32.tolerance, true, |c, g, seed| Ok(kinematics_solver::solve_ik_at(Some("f32"), c, g.position, orientation(g), seed, &f32, || false))));
+    backends.insert("dls-budgeted".into(), solve_all(&presets, IK.tolerance, true, |c, g, seed| {
+        let start = Instant::now();
+        Ok(kinematics_solver::solve_ik_within(c, g.position, orientation(g), seed, &IK, || start.elapsed() >= BUDGET).0)
+    }));
+    // Batches are position only on the GPU
+    if let Some(gpu) = &s.gpu {
+        backends.insert("gpu".into(), solve_all(&presets, IK.tolerance, false, |c, g, seed| gpu.solve(c, &[g.position], &seed, &IK, None, || false).map(|mut r| r.remove(0))));
+    }
+    SelfTest { passed: backends.values().all(|b| b.passed), backends, elapsed_us: t.elapsed().as_micros() }
+}
+
+/// Keep the outcome for `/readyz`, and log it.
+fn record(s: &AppState, report: &SelfTest) {
+    let failed: Vec<String> = report.backends.iter().flat_map(|(name, b)| b.cases.iter().filter(|c| !c.passed).map(move |c| format!("{name}/{}", c.name))).collect();
+    if failed.is_empty() {
+        tracing::info!("Self-test passed on {} backends in {} µs", report.backends.len(), report.elapsed_us);
+        s.probes.set_selftest(Ok(()));
+    } else {
+        tracing::error!("Self-test failed: {}", failed.join(", "));
+        s.probes.set_selftest(Err(format!("failed: {}", failed.join(", "))));
+    }
+}
+
+/// Run the suite in the background at startup, unless `KINEMATICS_SELFTEST_ON_START` is `false`.
+pub fn startup(s: Arc<AppState>) {
+    if config::var("KINEMATICS_SELFTEST_ON_START").is_ok_and(|v| v == "0" || v == "false") { return; }
+    s.probes.set_selftest(Err("self-test still running".into()));
+    tokio::spawn(async move {
+        match compute::run({ let s = s.clone(); move || run(&s) }).await {
+            Ok(report) => record(&s, &report),
+            Err(e) => s.probes.set_selftest(Err(e.message)),
+        }
+    });
+}
+
+/// Run the suite now: 200 when every case passes, 500 with the same report otherwise.
+#[utoipa::path(get, path = "/selftest", responses((status = 200, body = SelfTest), (status = 500, body = SelfTest)))]
+pub async fn handler(State(s): State<Arc<AppState>>) -> Result<(StatusCode, Json<SelfTest>), ApiError> {
+    let report = compute::run({ let s = s.clone(); move || run(&s) }).await?;
+    record(&s, &report);
+    Ok((if report.passed { StatusCode::OK } else { StatusCode::INTERNAL_SERVER_ERROR }, Json(report)))
+}
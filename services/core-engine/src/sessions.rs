@@ -0,0 +1,78 @@
+// ── Robot sessions ──────────────────────────────────────────
+//
+// A session tracks one robot's current joint state on a chain pinned at creation, so
+// solves and trajectories can name the session instead of resending the chain and the
+// state. The state changes when the robot reports it, and when a trajectory planned on
+// the session is taken as executed (the state moves to its last waypoint). Sessions
+// belong to a tenant, are dropped after `idle` without use, and at most `capacity`
+// exist at once.
+
+use crate::chains::ChainDef;
+use crate::history::now_ms;
+use kinematics_types::{SessionState, QUATERNION_ORDER};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub struct Session { id: String, robot: Option<String>, chain: ChainDef, angles: Vec<f64>, source: &'static str, updated_ms: u64, updates: u64 }
+
+impl Session {
+    pub fn new(id: String, robot: Option<String>, chain: ChainDef, angles: Vec<f64>) -> Session {
+        Session { id, robot, chain, angles, source: "created", updated_ms: now_ms(), updates: 0 }
+    }
+
+    pub fn chain(&self) -> &ChainDef { &self.chain }
+    pub fn angles(&self) -> &[f64] { &self.angles }
+
+    /// Move the joint state to `angles`, set by `source`.
+    pub fn set(&mut self, angles: Vec<f64>, source: &'static str) {
+        (self.angles, self.source, self.updated_ms) = (angles, source, now_ms());
+        self.updates += 1;
+    }
+
+    pub fn state(&self) -> SessionState {
+        let tip = kinematics_solver::forward(&self.chain, &self.angles).tip;
+        SessionState {
+            session_id: self.id.clone(), robot: self.robot.clone(), chain: self.chain.pinned_id(), joint_angles: self.angles.clone(),
+            end_effector_position: tip.p, end_effector_orientation: tip.quat_xyzw(), quaternion_order: QUATERNION_ORDER.into(),
+            source: self.source.into(), updated_ms: self.updated_ms, updates: self.updates,
+        }
+    }
+}
+
+struct Entry { session: Session, tenant: Option<String>, used_ms: u64 }
+
+pub struct SessionStore { sessions: HashMap<String, Entry>, idle: Duration, capacity: usize }
+
+impl SessionStore {
+    pub fn new(idle: Duration, capacity: usize) -> Self { Self { sessions: HashMap::new(), idle, capacity } }
+
+    /// Sessions held, idle ones included until the next purge.
+    pub fn len(&self) -> usize { self.sessions.len() }
+
+    /// Keep `session` for `tenant`, replacing one with the same id; `None` when the store is full.
+    pub fn insert(&mut self, tenant: Option<String>, session: Session) -> Option<&Session> {
+        self.purge();
+        if self.sessions.len() >= self.capacity && !self.sessions.contains_key(&session.id) { return None; }
+        let id = session.id.clone();
+        self.sessions.insert(id.clone(), Entry { session, tenant, used_ms: now_ms() });
+        self.sessions.get(&id).map(|e| &e.session)
+    }
+
+    /// Session `id`, if it is still held and belongs to `tenant`; counts as use.
+    pub fn get(&mut self, tenant: Option<&str>, id: &str) -> Option<&mut Session> {
+        self.purge();
+        let entry = self.sessions.get_mut(id).filter(|e| e.tenant.as_deref() == tenant)?;
+        entry.used_ms = now_ms();
+        Some(&mut entry.session)
+    }
+
+    pub fn remove(&mut self, tenant: Option<&str>, id: &str) -> bool {
+        self.get(tenant, id).is_some() && self.sessions.remove(id).is_some()
+    }
+
+    /// Drop sessions idle for longer than `idle`.
+    fn purge(&mut self) {
+        let cutoff = now_ms().saturating_sub(self.idle.as_millis() as u64);
+        self.sessions.retain(|_, e| e.used_ms >= cutoff);
+    }
+}
@@ -0,0 +1,115 @@
+// ── Graceful shutdown ───────────────────────────────────────
+//
+// SIGTERM (or Ctrl-C) starts a drain instead of tearing the process down: the
+// listener closes, in-flight requests run to completion, WebSocket IK streams are
+// closed with `1001 going away` once their current solve is answered, job event
+// streams end, and queued or running jobs get to finish. Whatever is still open at
+// the `KINEMATICS_SHUTDOWN_TIMEOUT_SECS` deadline is abandoned. Last, queued result
+// events are handed to the bus, the stats snapshot is saved, chain stores are
+// rewritten (retrying any write that failed earlier) and buffered trace spans are
+// exported.
+
+use crate::AppState;
+use std::future::{Future, IntoFuture};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Default drain deadline, inside Kubernetes' default 30 s termination grace period.
+const DEFAULT_TIMEOUT_SECS: u64 = 25;
+
+pub struct Shutdown { tx: watch::Sender<bool>, sessions: Arc<AtomicUsize> }
+
+impl Default for Shutdown {
+    fn default() -> Self { Self { tx: watch::channel(false).0, sessions: Arc::new(AtomicUsize::new(0)) } }
+}
+
+impl Shutdown {
+    pub fn is_draining(&self) -> bool { *self.tx.borrow() }
+
+    /// Resolves once the drain has begun.
+    pub fn wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut rx = self.tx.subscribe();
+        async move { let _ = rx.wait_for(|draining| *draining).await; }
+    }
+
+    /// Streaming sessions open.
+    pub fn sessions(&self) -> usize { self.sessions.load(Ordering::Relaxed) }
+
+    /// Register a streaming session the drain waits for; it ends when the guard drops.
+    pub fn session(&self) -> Session {
+        self.sessions.fetch_add(1, Ordering::Relaxed);
+        Session(self.sessions.clone())
+    }
+}
+
+pub struct Session(Arc<AtomicUsize>);
+
+impl Drop for Session {
+    fn drop(&mut self) { self.0.fetch_sub(1, Ordering::Relaxed); }
+}
+
+/// SIGTERM or Ctrl-C, whichever comes first.
+async fn signal() {
+    let ctrl_c = async { let _ = tokio::signal::ctrl_c().await; };
+    #[cfg(unix)]
+    let term = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut s) => { s.recv().await; }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let term = std::future::pending::<()>();
+    tokio::select! { _ = ctrl_c => {}, _ = term => {} }
+}
+
+/// Poll `remaining` until it reaches zero or `deadline` passes.
+async fn settle(deadline: Instant, what: &str, remaining: impl Fn() -> usize) {
+    loop {
+        let n = remaining();
+        if n == 0 { return; }
+        if Instant::now() >= deadline { return tracing::warn!("{n} {what} still unfinished at the shutdown deadline; abandoning them"); }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Run `server` (already wired to `Shutdown::wait`) until a signal, then drain.
+pub async fn serve<S>(server: S, s: Arc<AppState>)
+where S: IntoFuture<Output = std::io::Result<()>>, S::IntoFuture: Send + 'static {
+    let timeout = crate::config::var("KINEMATICS_SHUTDOWN_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let mut server = tokio::spawn(server.into_future());
+    tokio::select! {
+        r = &mut server => return report(r),
+        _ = signal() => {}
+    }
+    tracing::info!("Shutting down: draining for up to {timeout} s");
+    s.shutdown.tx.send_replace(true);
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+
+    match tokio::time::timeout_at(deadline.into(), &mut server).await {
+        Ok(r) => report(r),
+        Err(_) => { server.abort(); tracing::warn!("HTTP connections still open at the shutdown deadline; closing them"); }
+    }
+    settle(deadline, "streaming sessions", || s.shutdown.sessions()).await;
+    settle(deadline, "jobs", || s.jobs.lock().unwrap().active()).await;
+    settle(deadline, "result events", || s.events.pending()).await;
+
+    if let Some(path) = &s.stats_store {
+        if let Err(e) = crate::snapshot::save(&s, path) { tracing::error!("{e}"); }
+    }
+    for registry in s.chains.all() {
+        if let Err(e) = registry.lock().unwrap().flush() { tracing::error!("{e}"); }
+    }
+    crate::telemetry::shutdown();
+    tracing::info!("Shutdown complete");
+}
+
+fn report(r: Result<std::io::Result<()>, tokio::task::JoinError>) {
+    match r {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::error!("HTTP server: {e}"),
+        Err(e) => tracing::error!("HTTP server task: {e}"),
+    }
+}
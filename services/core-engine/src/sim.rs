@@ -0,0 +1,111 @@
+// ── Simulation sessions ─────────────────────────────────────
+//
+// Stateful forward simulation of a chain, for testing client controllers in closed
+// loop against the engine's own model. A session pins the chain version it was created
+// with and holds joint angles and velocities. Each step request applies one command for
+// `steps` fixed steps of `dt`, integrated with semi-implicit Euler. `velocity` commands
+// set the joint velocities directly. `torque` commands go through the rigid-body
+// dynamics in `kinematics_solver::forward_dynamics` and need link masses on the chain.
+// Joints stop at their limits (angle clamped, velocity zeroed). Sessions belong to a
+// tenant, are dropped after `idle` without use, and at most `capacity` exist at once.
+
+use crate::chains::ChainDef;
+use crate::history::now_ms;
+use kinematics_solver::Model;
+use kinematics_types::{SimState, QUATERNION_ORDER};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const MODES: [&str; 2] = ["velocity", "torque"];
+pub const DEFAULT_DT: f64 = 0.001;
+pub const MAX_DT: f64 = 1.0;
+pub const MAX_STEPS: u32 = 10_000;
+
+pub struct Sim {
+    id: String, chain: ChainDef, model: Model,
+    q: Vec<f64>, qd: Vec<f64>, dt: f64, gravity: [f64; 3],
+    steps: u64, limit_hits: Vec<usize>,
+}
+
+impl Sim {
+    pub fn new(id: String, chain: ChainDef, q: Vec<f64>, qd: Vec<f64>, dt: f64, gravity: [f64; 3]) -> Sim {
+        let model = Model::new(&chain);
+        Sim { id, chain, model, q, qd, dt, gravity, steps: 0, limit_hits: Vec::new() }
+    }
+
+    pub fn chain(&self) -> &ChainDef { &self.chain }
+
+    /// Apply `command` in `mode` for `steps` steps.
+    pub fn step(&mut self, mode: &str, command: &[f64], steps: u32) {
+        self.limit_hits.clear();
+        for _ in 0..steps {
+            if mode == "torque" {
+                let qdd = kinematics_solver::forward_dynamics(&self.model, &self.chain, &self.q, &self.qd, command, self.gravity);
+                self.qd.iter_mut().zip(qdd).for_each(|(v, a)| *v += a * self.dt);
+            } else {
+                self.qd.copy_from_slice(command);
+            }
+            for (i, ((q, v), j)) in self.q.iter_mut().zip(&mut self.qd).zip(&self.chain.joints).enumerate() {
+                *q += *v * self.dt;
+                let Some([lo, hi]) = j.limits else { continue };
+                if *q < lo || *q > hi {
+                    *q = q.clamp(lo, hi);
+                    *v = 0.0;
+                    if !self.limit_hits.contains(&i) { self.limit_hits.push(i); }
+                }
+            }
+            self.steps += 1;
+        }
+        self.limit_hits.sort_unstable();
+    }
+
+    pub fn state(&self) -> SimState {
+        let tip = self.model.forward(&self.q).tip;
+        SimState {
+            sim_id: self.id.clone(), chain: self.chain.pinned_id(), dt: self.dt, gravity: self.gravity,
+            time: self.steps as f64 * self.dt, steps: self.steps,
+            joint_angles: self.q.clone(), joint_velocities: self.qd.clone(),
+            end_effector_position: tip.p, end_effector_orientation: tip.quat_xyzw(), quaternion_order: QUATERNION_ORDER.into(),
+            limit_hits: self.limit_hits.clone(),
+        }
+    }
+}
+
+struct Entry { sim: Arc<Mutex<Sim>>, tenant: Option<String>, used_ms: u64 }
+
+pub struct SimStore { sims: HashMap<String, Entry>, idle: Duration, capacity: usize }
+
+impl SimStore {
+    pub fn new(idle: Duration, capacity: usize) -> Self { Self { sims: HashMap::new(), idle, capacity } }
+
+    /// Sessions held, idle ones included until the next purge.
+    pub fn len(&self) -> usize { self.sims.len() }
+
+    /// Keep `sim` for `tenant`; `None` when the store is full.
+    pub fn insert(&mut self, tenant: Option<String>, sim: Sim) -> Option<Arc<Mutex<Sim>>> {
+        self.purge();
+        if self.sims.len() >= self.capacity { return None; }
+        let (id, sim) = (sim.id.clone(), Arc::new(Mutex::new(sim)));
+        self.sims.insert(id, Entry { sim: sim.clone(), tenant, used_ms: now_ms() });
+        Some(sim)
+    }
+
+    /// Session `id`, if it is still held and belongs to `tenant`; counts as use.
+    pub fn get(&mut self, tenant: Option<&str>, id: &str) -> Option<Arc<Mutex<Sim>>> {
+        self.purge();
+        let entry = self.sims.get_mut(id).filter(|e| e.tenant.as_deref() == tenant)?;
+        entry.used_ms = now_ms();
+        Some(entry.sim.clone())
+    }
+
+    pub fn remove(&mut self, tenant: Option<&str>, id: &str) -> bool {
+        self.get(tenant, id).is_some() && self.sims.remove(id).is_some()
+    }
+
+    /// Drop sessions idle for longer than `idle`.
+    fn purge(&mut self) {
+        let cutoff = now_ms().saturating_sub(self.idle.as_millis() as u64);
+        self.sims.retain(|_, e| e.used_ms >= cutoff);
+    }
+}
@@ -0,0 +1,136 @@
+// ── Slow requests ───────────────────────────────────────────
+//
+// With `KINEMATICS_SLOW_REQUEST_MS` set, a request that takes longer than its route's
+// threshold is logged at WARN with its parameters and where the time went, so a
+// pathological IK target can be found in production without debug logging. The
+// breakdown comes from phases marked with `phase` (parse, validate, solve,
+// serialize); `other` is the rest (rate limiting, idempotency, chain lookup). Time is
+// counted once the body has been read and authenticated. Parameters are sanitized:
+// secret-looking fields are redacted, and long arrays and strings are shortened. Each
+// message of an IK stream is timed as a request of its own.
+
+use crate::error::ApiError;
+use crate::AppState;
+use axum::{body::{to_bytes, Body}, extract::{MatchedPath, Request, State}, http::header, middleware::Next, response::{IntoResponse, Response}};
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Fields whose values are never logged (matched case-insensitively within the name).
+const REDACTED: &[&str] = &["secret", "password", "token", "api_key", "callback"];
+const MAX_ITEMS: usize = 16;
+const MAX_CHARS: usize = 256;
+const MAX_LOGGED: usize = 8 << 10;
+
+/// Threshold per route: the longest matching route suffix, else the default.
+#[derive(Default)]
+pub struct Thresholds { default: Option<Duration>, routes: Vec<(String, Duration)> }
+
+impl Thresholds {
+    /// `MS` for every route and/or `ROUTE=MS` entries, comma separated; `off` or empty
+    /// logs nothing.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut t = Thresholds::default();
+        let ms = |v: &str| v.trim().parse::<u64>().map(Duration::from_millis).map_err(|_| format!("'{}' is not a number of milliseconds", v.trim()));
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty() && *e != "off") {
+            match entry.split_once('=') {
+                Some((route, v)) => t.routes.push((route.trim().trim_start_matches('/').to_string(), ms(v)?)),
+                None => t.default = Some(ms(entry)?),
+            }
+        }
+        Ok(t)
+    }
+
+    fn get(&self, route: &str) -> Option<Duration> {
+        self.routes.iter().filter(|(r, _)| route.ends_with(r.as_str())).max_by_key(|(r, _)| r.len()).map(|(_, d)| *d).or(self.default)
+    }
+}
+
+tokio::task_local! { static PHASES: RefCell<Vec<(&'static str, Duration)>>; }
+
+/// Times a phase of the current request until dropped; a no-op outside one.
+pub struct Phase(&'static str, Instant);
+
+pub fn phase(name: &'static str) -> Phase { Phase(name, Instant::now()) }
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        let _ = PHASES.try_with(|p| {
+            let mut p = p.borrow_mut();
+            match p.iter_mut().find(|(n, _)| *n == self.0) {
+                Some((_, d)) => *d += self.1.elapsed(),
+                None => p.push((self.0, self.1.elapsed())),
+            }
+        });
+    }
+}
+
+pub struct Timing { total: Duration, phases: Vec<(&'static str, Duration)> }
+
+/// Run `task`, collecting the phases it marks.
+pub async fn timed<F: Future>(task: F) -> (F::Output, Timing) {
+    let t = Instant::now();
+    let (out, phases) = PHASES.scope(RefCell::new(Vec::new()), async { let out = task.await; (out, PHASES.with(RefCell::take)) }).await;
+    (out, Timing { total: t.elapsed(), phases })
+}
+
+/// Log the request if it outlasted `route`'s threshold; `params` are only built then.
+pub fn check(s: &AppState, method: &str, route: &str, status: u16, timing: &Timing, params: impl FnOnce() -> Value) {
+    let Some(threshold) = s.slow_requests.lock().unwrap().get(route) else { return };
+    if timing.total <= threshold { return; }
+    let ms = |d: Duration| (d.as_secs_f64() * 1e4).round() / 10.0;
+    let other = timing.total.saturating_sub(timing.phases.iter().map(|(_, d)| *d).sum());
+    let breakdown = timing.phases.iter().chain([&("other", other)]).map(|(n, d)| format!("{n} {} ms", ms(*d))).collect::<Vec<_>>().join(", ");
+    let mut params = sanitize(params()).to_string();
+    if params.len() > MAX_LOGGED { params = format!("{}…", truncate(&params, MAX_LOGGED)); }
+    tracing::warn!(method, route, status, total_ms = ms(timing.total), threshold_ms = threshold.as_millis() as u64, %breakdown, %params,
+        caller = crate::auth::current().map(|p| p.key_id), tenant = crate::tenant::tag(), "slow request");
+}
+
+/// Time requests to routes with a threshold, buffering the body to log it if they are slow.
+pub async fn layer(State(s): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let route = req.extensions().get::<MatchedPath>().map_or_else(|| "unmatched".to_string(), |p| p.as_str().to_string());
+    if s.slow_requests.lock().unwrap().get(&route).is_none() { return next.run(req).await; }
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(b) => b,
+        Err(e) => return ApiError::bad_request("Unreadable request body", e.to_string()).into_response(),
+    };
+    let (method, uri) = (parts.method.to_string(), parts.uri.clone());
+    let json = parts.headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_some_and(|v| v.starts_with("application/json"));
+    let (res, timing) = timed(next.run(Request::from_parts(parts, Body::from(bytes.clone())))).await;
+    check(&s, &method, &route, res.status().as_u16(), &timing, || {
+        let query: Map<String, Value> = uri.query().unwrap_or_default().split('&').filter(|kv| !kv.is_empty())
+            .map(|kv| { let (k, v) = kv.split_once('=').unwrap_or((kv, "")); (k.to_string(), Value::from(v)) }).collect();
+        let body = match serde_json::from_slice(&bytes) {
+            Ok(v) if json => v,
+            _ if bytes.is_empty() => Value::Null,
+            _ => Value::from(format!("<{} bytes>", bytes.len())),
+        };
+        json!({ "path": uri.path(), "query": query, "body": body })
+    });
+    res
+}
+
+fn sanitize(v: Value) -> Value {
+    match v {
+        Value::Object(fields) => Value::Object(fields.into_iter().map(|(k, v)| {
+            let secret = REDACTED.iter().any(|r| k.to_ascii_lowercase().contains(r));
+            let v = if secret { Value::from("[redacted]") } else { sanitize(v) };
+            (k, v)
+        }).collect()),
+        Value::Array(items) => {
+            let n = items.len();
+            let mut out: Vec<Value> = items.into_iter().take(MAX_ITEMS).map(sanitize).collect();
+            if n > MAX_ITEMS { out.push(Value::from(format!("… {} more", n - MAX_ITEMS))); }
+            Value::Array(out)
+        }
+        Value::String(s) if s.chars().count() > MAX_CHARS => Value::from(format!("{}…", truncate(&s, MAX_CHARS))),
+        v => v,
+    }
+}
+
+/// `s` cut to at most `max` bytes at a character boundary.
+fn truncate(s: &str, max: usize) -> &str { &s[..s.char_indices().map(|(i, _)| i).take_while(|&i| i <= max).last().unwrap_or(0)] }
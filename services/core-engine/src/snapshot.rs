@@ -0,0 +1,62 @@
+// ── Stats snapshots ─────────────────────────────────────────
+//
+// With `KINEMATICS_STATS_STORE` set, the counters behind `/stats` and `/metrics`
+// (operation totals, per-key and per-tenant usage, per-route request counts and
+// latencies, IK convergence) are written to that JSON file every
+// `KINEMATICS_STATS_FLUSH_SECS` and at shutdown, and loaded back at boot, so
+// dashboards spanning deploys keep counting instead of dropping to zero. A crash
+// loses what was counted since the last save. Uptime starts over.
+
+use crate::{auth::Usage, history::now_ms, metrics::Counters, AppState, EngineStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    saved_at_ms: u64,
+    totals: EngineStats,
+    #[serde(default)] keys: HashMap<String, Usage>,
+    #[serde(default)] tenants: HashMap<String, Usage>,
+    #[serde(default)] metrics: Counters,
+}
+
+/// Restore the counters saved in `path`, if it exists; an unreadable file is skipped with a warning.
+pub fn load(s: &AppState, path: &Path) {
+    let Ok(text) = std::fs::read_to_string(path) else { return };
+    let snap: Snapshot = match serde_json::from_str(&text) {
+        Ok(snap) => snap,
+        Err(e) => return tracing::warn!("ignoring unreadable stats store {}: {e}", path.display()),
+    };
+    *s.stats.lock().unwrap() = snap.totals;
+    s.keys.lock().unwrap().restore_usage(snap.keys, snap.tenants);
+    s.metrics.restore(snap.metrics);
+    tracing::info!("Restored stats saved at {} ms", snap.saved_at_ms);
+}
+
+/// Write the current counters to `path` (via a temp file and rename).
+pub fn save(s: &AppState, path: &Path) -> Result<(), String> {
+    let totals = s.stats.lock().unwrap().clone();
+    let (keys, tenants) = s.keys.lock().unwrap().usage_counters();
+    let snap = Snapshot { saved_at_ms: now_ms(), totals, keys, tenants, metrics: s.metrics.counters() };
+    let json = serde_json::to_string(&snap).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("failed to write stats store {}: {e}", path.display()))
+}
+
+/// Save every `KINEMATICS_STATS_FLUSH_SECS` (60 by default) in the background.
+pub fn spawn(s: Arc<AppState>) {
+    let Some(path) = s.stats_store.clone() else { return };
+    let every = crate::config::var("KINEMATICS_STATS_FLUSH_SECS").ok().and_then(|v| v.parse().ok()).filter(|&n| n > 0).unwrap_or(60);
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(Duration::from_secs(every));
+        tick.tick().await;
+        loop {
+            tick.tick().await;
+            if let Err(e) = save(&s, &path) { tracing::warn!("{e}"); }
+        }
+    });
+}
@@ -0,0 +1,58 @@
+// ── Solution store ──────────────────────────────────────────
+//
+// IK solutions (chain and hand) kept by `solution_id` so a downstream executor can
+// fetch one it was handed by reference, e.g. through a message queue. Each is stored
+// as the JSON response body the solver returned, tagged with its tenant, and kept for
+// `retention` or until `capacity` newer ones push it out.
+
+use crate::history::now_ms;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+pub struct Stored { pub body: String, tenant: Option<String>, pub at_ms: u64 }
+
+pub struct SolutionStore { solutions: HashMap<String, Stored>, order: VecDeque<String>, retention: Duration, capacity: usize }
+
+impl SolutionStore {
+    pub fn new(retention: Duration, capacity: usize) -> Self {
+        Self { solutions: HashMap::new(), order: VecDeque::new(), retention, capacity }
+    }
+
+    /// Solutions held, expired ones included until the next purge.
+    pub fn len(&self) -> usize { self.solutions.len() }
+
+    /// Keep `body` as solution `id`; a no-op with zero retention.
+    pub fn insert(&mut self, id: &str, tenant: Option<String>, body: String) {
+        if self.retention.is_zero() || self.capacity == 0 { return; }
+        self.purge();
+        while self.order.len() >= self.capacity {
+            if let Some(old) = self.order.pop_front() { self.solutions.remove(&old); }
+        }
+        self.order.push_back(id.to_string());
+        self.solutions.insert(id.to_string(), Stored { body, tenant, at_ms: now_ms() });
+    }
+
+    /// Solution `id`, if it is still kept and belongs to `tenant`.
+    pub fn get(&mut self, tenant: Option<&str>, id: &str) -> Option<&Stored> {
+        self.purge();
+        self.solutions.get(id).filter(|s| s.tenant.as_deref() == tenant)
+    }
+
+    /// `tenant`'s newest `limit` solutions, newest first.
+    #[cfg_attr(not(feature = "graphql"), allow(dead_code))]
+    pub fn recent(&mut self, tenant: Option<&str>, limit: usize) -> Vec<(&str, &Stored)> {
+        self.purge();
+        self.order.iter().rev().filter_map(|id| self.solutions.get_key_value(id))
+            .filter(|(_, s)| s.tenant.as_deref() == tenant).take(limit).map(|(id, s)| (id.as_str(), s)).collect()
+    }
+
+    /// Drop expired solutions (oldest first, in insertion order).
+    fn purge(&mut self) {
+        let cutoff = now_ms().saturating_sub(self.retention.as_millis() as u64);
+        while let Some(id) = self.order.front() {
+            if self.solutions.get(id).is_some_and(|s| s.at_ms >= cutoff) { break; }
+            let id = self.order.pop_front().expect("front exists");
+            self.solutions.remove(&id);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// ── Tracing ─────────────────────────────────────────────────
+//
+// Log output and, with the `otel` feature, OpenTelemetry trace export. Logs are text
+// or, with `KINEMATICS_LOG_FORMAT=json`, one JSON object per line. Every request
+// carries an `x-request-id`: the caller's, or a fresh UUID, echoed on the response
+// and recorded on the request span, so each line logged while serving it has the id.
+//
+// Each request gets a server span named `METHOD /route/template`, with child spans for body
+// parsing, validation, the solve itself and response serialization. When
+// `OTEL_EXPORTER_OTLP_ENDPOINT` (or `..._TRACES_ENDPOINT`) is set, spans are batched
+// to that collector over OTLP/HTTP (protobuf), and an incoming W3C `traceparent`
+// makes the request span a child of the caller's, so engine spans join the
+// gateway's traces. The standard `OTEL_*` variables (service name, resource
+// attributes, sampler, batch sizes) apply. The log filter can be swapped while running
+// (`reload_filter`); the format and exporter are fixed at startup.
+
+use axum::{body::Body, extract::MatchedPath, http::{Request, Response}};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{field, Span};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// `RUST_LOG` (`log.filter`), else `kinematics_engine=info`.
+fn filter() -> Result<EnvFilter, String> {
+    match crate::config::var("RUST_LOG") {
+        Ok(spec) => EnvFilter::try_new(&spec).map_err(|e| format!("RUST_LOG '{spec}': {e}")),
+        Err(_) => Ok("kinematics_engine=info".into()),
+    }
+}
+
+pub fn init() {
+    let (filter, handle) = reload::Layer::new(filter().unwrap_or_else(|_| "kinematics_engine=info".into()));
+    let _ = FILTER.set(handle);
+    let json = match crate::config::var("KINEMATICS_LOG_FORMAT").as_deref() {
+        Err(_) | Ok("" | "text") => false,
+        Ok("json") => true,
+        Ok(other) => panic!("KINEMATICS_LOG_FORMAT: expected text or json, got '{other}'"),
+    };
+    // JSON lines list the enclosing spans, outermost (`request`, with `request_id`) first.
+    let registry = tracing_subscriber::registry().with(filter)
+        .with((!json).then(tracing_subscriber::fmt::layer))
+        .with(json.then(|| tracing_subscriber::fmt::layer().json().with_current_span(false).with_span_list(true)));
+    #[cfg(feature = "otel")]
+    let registry = registry.with(otel::layer());
+    registry.init();
+    #[cfg(feature = "otel")]
+    if otel::enabled() { tracing::info!("Exporting traces over OTLP"); }
+}
+
+/// Replace the log filter with the one now configured.
+pub fn reload_filter() -> Result<(), String> {
+    let filter = filter()?;
+    FILTER.get().map_or(Ok(()), |h| h.reload(filter).map_err(|e| e.to_string()))
+}
+
+/// Server span for `TraceLayer`, named by route template so traces group by endpoint.
+pub fn request_span(req: &Request<Body>) -> Span {
+    let route = req.extensions().get::<MatchedPath>().map_or("unmatched", |p| p.as_str());
+    let request_id = req.headers().get("x-request-id").and_then(|v| v.to_str().ok()).unwrap_or_default();
+    let span = tracing::info_span!("request", request_id,
+        otel.name = format!("{} {route}", req.method()), otel.kind = "server",
+        http.request.method = %req.method(), http.route = route, url.path = req.uri().path(),
+        http.response.status_code = field::Empty, otel.status_code = field::Empty,
+    );
+    #[cfg(feature = "otel")]
+    otel::join_caller(&span, req.headers());
+    span
+}
+
+/// Failures are logged (5xx as errors, 4xx at info) so a failed call can be found by its request id.
+pub fn on_response(res: &Response<Body>, latency: Duration, span: &Span) {
+    let (status, latency_ms) = (res.status(), latency.as_millis() as u64);
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+        tracing::error!(status = status.as_u16(), latency_ms, "request failed");
+    } else if status.is_client_error() {
+        tracing::info!(status = status.as_u16(), latency_ms, "request rejected");
+    } else {
+        tracing::debug!(status = status.as_u16(), latency_ms, "finished processing request");
+    }
+}
+
+/// Export spans still buffered for the collector.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use axum::http::HeaderMap;
+    use opentelemetry::{propagation::Extractor, trace::TracerProvider};
+    use std::sync::OnceLock;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    static PROVIDER: OnceLock<opentelemetry_sdk::trace::SdkTracerProvider> = OnceLock::new();
+
+    pub fn enabled() -> bool {
+        ["OTEL_EXPORTER_OTLP_ENDPOINT", "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT"].iter().any(|v| std::env::var(v).is_ok_and(|e| !e.is_empty()))
+    }
+
+    /// The OTLP layer, or `None` (a no-op layer) when no endpoint is configured.
+    pub fn layer<S>() -> Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>
+    where S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a> {
+        if !enabled() { return None; }
+        let exporter = opentelemetry_otlp::SpanExporter::builder().with_http().build()
+            .map_err(|e| eprintln!("OTLP exporter: {e}; traces are not exported")).ok()?;
+        let mut resource = opentelemetry_sdk::Resource::builder();
+        if std::env::var_os("OTEL_SERVICE_NAME").is_none() { resource = resource.with_service_name("kinematics-engine"); }
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().with_batch_exporter(exporter).with_resource(resource.build()).build();
+        let tracer = provider.tracer("kinematics-engine");
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        let _ = PROVIDER.set(provider);
+        opentelemetry::global::set_text_map_propagator(opentelemetry_sdk::propagation::TraceContextPropagator::new());
+        Some(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+
+    pub fn shutdown() {
+        if let Some(Err(e)) = PROVIDER.get().map(|p| p.shutdown()) { eprintln!("OTLP exporter shutdown: {e}"); }
+    }
+
+    struct Headers<'a>(&'a HeaderMap);
+
+    impl Extractor for Headers<'_> {
+        fn get(&self, key: &str) -> Option<&str> { self.0.get(key).and_then(|v| v.to_str().ok()) }
+        fn keys(&self) -> Vec<&str> { self.0.keys().map(|k| k.as_str()).collect() }
+    }
+
+    /// Parent `span` on the caller's trace when the request carries `traceparent`.
+    pub fn join_caller(span: &tracing::Span, headers: &HeaderMap) {
+        if !headers.contains_key("traceparent") { return; }
+        let cx = opentelemetry::global::get_text_map_propagator(|p| p.extract(&Headers(headers)));
+        let _ = span.set_parent(cx);
+    }
+}
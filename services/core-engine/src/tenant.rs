@@ -0,0 +1,58 @@
+// ── Tenants ─────────────────────────────────────────────────
+//
+// Teams sharing one deployment are isolated by tenant, taken from the caller's API
+// key or token (see `auth`). Callers without one, and every caller while auth is
+// disabled, share the `default` tenant. Registries are kept per tenant and opened
+// on first use; tenant-tagged records (intents, jobs) are filtered on read.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const DEFAULT: &str = "default";
+const MAX_LEN: usize = 64;
+
+/// Tenant names are path- and header-safe: 1 to 64 of `[A-Za-z0-9_-]`.
+pub fn check(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_LEN || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("'{name}' is not a tenant name (1 to {MAX_LEN} letters, digits, '-' or '_')"));
+    }
+    Ok(())
+}
+
+/// Tenant of the current request or job.
+pub fn current() -> String { crate::auth::current().and_then(|p| p.tenant).unwrap_or_else(|| DEFAULT.into()) }
+
+/// The current tenant as stored on records: `None` for the default tenant.
+pub fn tag() -> Option<String> { Some(current()).filter(|t| t != DEFAULT) }
+
+/// Per-tenant store file: `chains.json` for the default tenant, `chains.<tenant>.json` for others.
+pub fn store_path(base: Option<&Path>, tenant: &str) -> Option<PathBuf> {
+    let base = base?;
+    if tenant == DEFAULT { return Some(base.to_path_buf()); }
+    let stem = base.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+    Some(match base.extension() {
+        Some(ext) => base.with_file_name(format!("{stem}.{tenant}.{}", ext.to_string_lossy())),
+        None => base.with_file_name(format!("{stem}.{tenant}")),
+    })
+}
+
+/// One `T` per tenant, created by `open` on first access.
+pub struct Tenants<T> { map: Mutex<HashMap<String, Arc<Mutex<T>>>>, open: Box<dyn Fn(&str) -> T + Send + Sync> }
+
+impl<T> Tenants<T> {
+    pub fn new(open: impl Fn(&str) -> T + Send + Sync + 'static) -> Self { Self { map: Mutex::new(HashMap::new()), open: Box::new(open) } }
+
+    pub fn get(&self, tenant: &str) -> Arc<Mutex<T>> {
+        self.map.lock().unwrap().entry(tenant.to_string()).or_insert_with(|| Arc::new(Mutex::new((self.open)(tenant)))).clone()
+    }
+
+    /// The current tenant's `T`.
+    pub fn current(&self) -> Arc<Mutex<T>> { self.get(&current()) }
+
+    /// Every tenant's `T` opened so far.
+    pub fn all(&self) -> Vec<Arc<Mutex<T>>> { self.map.lock().unwrap().values().cloned().collect() }
+
+    /// Every tenant opened so far, with its `T`.
+    pub fn entries(&self) -> Vec<(String, Arc<Mutex<T>>)> { self.map.lock().unwrap().iter().map(|(t, v)| (t.clone(), v.clone())).collect() }
+}
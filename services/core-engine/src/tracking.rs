@@ -0,0 +1,78 @@
+// ── Trajectory tracking ─────────────────────────────────────
+//
+// Simulated execution of a planned joint trajectory, so a plan can be vetted against
+// the controller that will run it and not only against kinematic limits. The reference
+// moves linearly between frames; a PD law per joint on the position and velocity errors
+// gives the effort, which reaches the plant through a first-order actuator lag (time
+// constant 1 / 2πf for a bandwidth of f Hz) and is clipped at the effort limit. The
+// `kinematic` plant treats every joint as a unit inertia, so the effort is the joint
+// acceleration; the `dynamic` plant applies it as torque through the chain's rigid-body
+// dynamics under gravity, compensated by default. Integration is semi-implicit Euler at
+// `dt`, and joints stop at their limits as in a simulation session.
+
+use crate::chains::ChainDef;
+use kinematics_solver::Model;
+use kinematics_types::TrackingSample;
+use std::f64::consts::PI;
+
+pub const PLANTS: [&str; 2] = ["kinematic", "dynamic"];
+pub const DEFAULT_FRAME_TIME: f64 = 0.01;
+/// Integration steps one run may take, under each plant.
+pub const MAX_STEPS: u64 = 1_000_000;
+pub const MAX_DYNAMIC_STEPS: u64 = 100_000;
+/// Steps between checks of the caller's `stop`.
+const STOP_EVERY: u64 = 1_000;
+
+/// Gains and limits per joint; `dynamic` selects the rigid-body plant.
+pub struct Controller { pub kp: Vec<f64>, pub kd: Vec<f64>, pub bandwidth_hz: Option<f64>, pub max_effort: Option<Vec<f64>>, pub dynamic: bool, pub gravity_compensation: bool }
+
+/// `stopped`: `stop` cut the run short, so `samples` ends early.
+pub struct Run { pub samples: Vec<TrackingSample>, pub saturated: Vec<usize>, pub limit_hits: Vec<usize>, pub steps: u64, pub stopped: bool }
+
+/// Execute `frames` at `times` (increasing, one per frame) from rest at the first frame.
+pub fn run(chain: &ChainDef, frames: &[Vec<f64>], times: &[f64], c: &Controller, dt: f64, stop: impl Fn() -> bool) -> Run {
+    let (model, n) = (Model::new(chain), chain.dof());
+    let (mut q, mut qd, mut effort) = (frames[0].clone(), vec![0.0; n], vec![0.0; n]);
+    let lag = c.bandwidth_hz.map(|f| 1.0 - (-2.0 * PI * f * dt).exp());
+    let mut run = Run { samples: Vec::with_capacity(frames.len()), saturated: Vec::new(), limit_hits: Vec::new(), steps: 0, stopped: false };
+    for k in 0..frames.len() {
+        while k > 0 && times[0] + run.steps as f64 * dt < times[k] - dt * 1e-6 {
+            if run.steps.is_multiple_of(STOP_EVERY) && stop() { run.stopped = true; break; }
+            let (from, to) = (&frames[k - 1], &frames[k]);
+            let span = times[k] - times[k - 1];
+            let s = ((times[0] + run.steps as f64 * dt - times[k - 1]) / span).clamp(0.0, 1.0);
+            let hold = if c.dynamic && c.gravity_compensation { kinematics_solver::gravity_torques(&model, chain, &q, kinematics_solver::GRAVITY) } else { vec![0.0; n] };
+            for i in 0..n {
+                let (reference, rate) = (from[i] + (to[i] - from[i]) * s, (to[i] - from[i]) / span);
+                let command = c.kp[i] * (reference - q[i]) + c.kd[i] * (rate - qd[i]) + hold[i];
+                effort[i] = lag.map_or(command, |a| effort[i] + (command - effort[i]) * a);
+                let Some(limit) = c.max_effort.as_ref().map(|m| m[i]) else { continue };
+                if effort[i].abs() > limit {
+                    effort[i] = effort[i].clamp(-limit, limit);
+                    if !run.saturated.contains(&i) { run.saturated.push(i); }
+                }
+            }
+            let qdd = if c.dynamic { kinematics_solver::forward_dynamics(&model, chain, &q, &qd, &effort, kinematics_solver::GRAVITY) } else { effort.clone() };
+            for (i, ((q, v), j)) in q.iter_mut().zip(&mut qd).zip(&chain.joints).enumerate() {
+                *v += qdd[i] * dt;
+                *q += *v * dt;
+                let Some([lo, hi]) = j.limits else { continue };
+                if *q < lo || *q > hi {
+                    *q = q.clamp(lo, hi);
+                    *v = 0.0;
+                    if !run.limit_hits.contains(&i) { run.limit_hits.push(i); }
+                }
+            }
+            run.steps += 1;
+        }
+        if run.stopped { break; }
+        let (planned, actual) = (model.forward(&frames[k]).tip.p, model.forward(&q).tip.p);
+        run.samples.push(TrackingSample {
+            time: times[k], joint_angles: q.clone(), joint_error: frames[k].iter().zip(&q).map(|(p, a)| p - a).collect(),
+            tip_error: (0..3).map(|i| (planned[i] - actual[i]).powi(2)).sum::<f64>().sqrt(),
+        });
+    }
+    run.saturated.sort_unstable();
+    run.limit_hits.sort_unstable();
+    run
+}
@@ -0,0 +1,418 @@
+// ── Request validation ──────────────────────────────────────
+//
+// Field-level checks run at the top of every solving handler, before any work and
+// on every transport (REST, WebSocket, jobs, protobuf, gRPC). Each request type
+// lists its rules in `check`; all violations are collected and returned together as
+// one `invalid` error, so clients can fix a request in a single round trip.
+
+use crate::error::{ApiError, ErrorCode, FieldError};
+use crate::*;
+
+/// Quaternions must be unit length to within this tolerance.
+const UNIT_TOLERANCE: f64 = 1e-3;
+/// Upper bound on `joint_count` for the generic chain.
+const MAX_JOINTS: u32 = 256;
+const MAX_SAMPLE_RATE_HZ: u32 = 100_000;
+const MAX_PREDICTION_HORIZON_MS: u64 = 60_000;
+const MAX_DEADLINE_MS: u64 = 3_600_000;
+const MAX_BATCH_TARGETS: usize = 100_000;
+const MAX_HAND_EYE_STATIONS: usize = 1_000;
+const MAX_SELF_MOTION_SAMPLES: usize = 720;
+const MAX_BENCH_SOLVES: usize = 1_000_000;
+const MAX_BENCH_THREADS: usize = 256;
+
+pub trait Validate {
+    fn check(&self, c: &mut Checks, path: &str);
+
+    fn validate(&self) -> Result<(), ApiError> {
+        let _span = (tracing::info_span!("validate").entered(), crate::slow::phase("validate"));
+        let mut c = Checks(Vec::new());
+        self.check(&mut c, "");
+        c.finish()
+    }
+}
+
+pub struct Checks(Vec<FieldError>);
+
+/// `path.field`, or `field` at the top level.
+fn at(path: &str, field: &str) -> String { if path.is_empty() { field.to_string() } else { format!("{path}.{field}") } }
+
+impl Checks {
+    fn fail(&mut self, field: String, message: impl Into<String>) { self.0.push(FieldError { field, message: message.into() }); }
+
+    fn finite(&mut self, field: String, values: &[f64]) {
+        if let Some(i) = values.iter().position(|v| !v.is_finite()) { self.fail(format!("{field}[{i}]"), "must be a finite number"); }
+    }
+
+    fn positive(&mut self, field: String, value: Option<f64>) {
+        if value.is_some_and(|v| !(v.is_finite() && v > 0.0)) { self.fail(field, "must be a positive number"); }
+    }
+
+    fn range<T: PartialOrd + std::fmt::Display + Copy>(&mut self, field: String, value: Option<T>, lo: T, hi: T) {
+        if value.is_some_and(|v| v < lo || v > hi) { self.fail(field, format!("must be between {lo} and {hi}")); }
+    }
+
+    fn quat(&mut self, field: String, q: Option<&[f64; 4]>) {
+        let Some(q) = q else { return };
+        if q.iter().any(|v| !v.is_finite()) { return self.finite(field, q); }
+        let norm = q.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if (norm - 1.0).abs() > UNIT_TOLERANCE { self.fail(field, format!("must be a unit quaternion [x, y, z, w] (norm is {norm:.4})")); }
+    }
+
+    fn constraints(&mut self, path: &str, c: Option<&IkConstraints>) {
+        let Some(c) = c else { return };
+        let path = at(path, "constraints");
+        self.range(at(&path, "max_iterations"), c.max_iterations, 1, chains::MAX_ITERATIONS);
+        self.positive(at(&path, "tolerance"), c.tolerance);
+        self.positive(at(&path, "damping"), c.damping);
+    }
+
+    fn twist_frame(&mut self, path: &str, frame: Option<&str>) {
+        if let Some(f) = frame.filter(|f| !["base", "tool"].contains(f)) { self.fail(at(path, "frame"), format!("'{f}' is not one of base, tool")); }
+    }
+
+    /// A symmetric 3 × 3 or 6 × 6 Cartesian matrix, or one row giving its diagonal.
+    fn cartesian_matrix(&mut self, path: &str, field: &str, m: &[Vec<f64>]) {
+        let field = at(path, field);
+        let diagonal = m.len() == 1 && [3, 6].contains(&m[0].len());
+        let square = [3, 6].contains(&m.len()) && m.iter().all(|r| r.len() == m.len());
+        if !(diagonal || square) {
+            return self.fail(field, "must be a 3 × 3 or 6 × 6 matrix, or a row of 3 or 6 diagonal values");
+        }
+        for (i, row) in m.iter().enumerate() { self.finite(format!("{field}[{i}]"), row); }
+        let at_diagonal = |i: usize| if diagonal { m[0][i] } else { m[i][i] };
+        if let Some(i) = (0..m[0].len()).find(|&i| at_diagonal(i) < 0.0) { self.fail(format!("{field}[{i}]"), "diagonal values must not be negative"); }
+        if diagonal { return; }
+        let tolerance = 1e-9 * m.iter().flatten().fold(1.0, |a: f64, v| a.max(v.abs()));
+        if let Some((i, j)) = (0..m.len()).flat_map(|i| (0..i).map(move |j| (i, j))).find(|&(i, j)| (m[i][j] - m[j][i]).abs() > tolerance) {
+            self.fail(format!("{field}[{i}][{j}]"), "the matrix must be symmetric");
+        }
+    }
+
+    fn joint_rates(&mut self, path: &str, field: &str, limits: Option<&[f64]>) {
+        let Some(limits) = limits else { return };
+        if limits.is_empty() { self.fail(at(path, field), "give one value for every joint, or one per joint"); }
+        if let Some(i) = limits.iter().position(|v| !(v.is_finite() && *v > 0.0)) { self.fail(format!("{}[{i}]", at(path, field)), "must be a positive number"); }
+    }
+
+    fn wrap_policy(&mut self, path: &str, policy: Option<&str>) {
+        let policies = kinematics_solver::WRAP_POLICIES;
+        if policy.is_some_and(|p| !policies.contains(&p)) { self.fail(at(path, "wrap_policy"), format!("must be one of {}", policies.join(", "))); }
+    }
+
+    fn precision(&mut self, path: &str, precision: Option<&str>) {
+        if precision.is_some_and(|p| !["f64", "f32"].contains(&p)) { self.fail(at(path, "precision"), "must be f64 or f32"); }
+    }
+
+    fn finish(self) -> Result<(), ApiError> {
+        let Some(first) = self.0.first() else { return Ok(()) };
+        let details = match self.0.len() {
+            1 => first.message.clone(),
+            n => format!("{} (and {} more)", first.message, n - 1),
+        };
+        Err(ApiError { field: Some(first.field.clone()), errors: self.0, ..ApiError::new(ErrorCode::Invalid, "Validation failed", details) })
+    }
+}
+
+impl Validate for IkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "target_position"), &self.target_position);
+        c.quat(at(path, "target_orientation"), self.target_orientation.as_ref());
+        if self.chain_id.is_none() { c.range(at(path, "joint_count"), self.joint_count, 1, MAX_JOINTS); }
+        c.constraints(path, self.constraints.as_ref());
+        if let Some(Joints::Angles(q)) = &self.seed { c.finite(at(path, "seed"), q); }
+        c.range(at(path, "deadline_ms"), self.deadline_ms, 1, MAX_DEADLINE_MS);
+        c.range(at(path, "deadline_us"), self.deadline_us, 1, MAX_DEADLINE_MS * 1000);
+        if self.deadline_us.is_some() && self.chain_id.is_none() { c.fail(at(path, "deadline_us"), "needs a chain_id"); }
+        c.precision(path, self.precision.as_deref());
+        c.wrap_policy(path, self.wrap_policy.as_deref());
+        if self.precision.as_deref() == Some("f32") {
+            if self.chain_id.is_none() { c.fail(at(path, "precision"), "f32 needs a chain_id"); }
+            if self.deadline_us.is_some() { c.fail(at(path, "precision"), "f32 cannot be combined with deadline_us"); }
+        }
+    }
+}
+
+impl Validate for IkBatchRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if self.targets.is_empty() || self.targets.len() > MAX_BATCH_TARGETS { c.fail(at(path, "targets"), format!("must hold 1 to {MAX_BATCH_TARGETS} targets")); }
+        if let Some(i) = self.targets.iter().position(|t| t.iter().any(|v| !v.is_finite())) { c.finite(format!("{}[{i}]", at(path, "targets")), &self.targets[i]); }
+        c.constraints(path, self.constraints.as_ref());
+        if let Some(Joints::Angles(q)) = &self.seed { c.finite(at(path, "seed"), q); }
+        if self.backend.as_deref().is_some_and(|b| !["auto", "cpu", "gpu"].contains(&b)) { c.fail(at(path, "backend"), "must be auto, cpu or gpu"); }
+        c.range(at(path, "deadline_ms"), self.deadline_ms, 1, MAX_DEADLINE_MS);
+        c.precision(path, self.precision.as_deref());
+        c.wrap_policy(path, self.wrap_policy.as_deref());
+    }
+}
+
+impl Validate for ReachableRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "target_position"), &self.target_position);
+        c.quat(at(path, "target_orientation"), self.target_orientation.as_ref());
+        c.positive(at(path, "tolerance"), self.tolerance);
+    }
+}
+
+impl Validate for SelfMotionRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "target_position"), &self.target_position);
+        c.quat(at(path, "target_orientation"), Some(&self.target_orientation));
+        c.range(at(path, "samples"), self.samples, 4, MAX_SELF_MOTION_SAMPLES);
+        if let Some(Joints::Angles(q)) = &self.seed { c.finite(at(path, "seed"), q); }
+    }
+}
+
+impl Validate for BenchRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.range(at(path, "solves"), self.solves, 1, MAX_BENCH_SOLVES);
+        c.range(at(path, "threads"), self.threads, 1, MAX_BENCH_THREADS);
+        c.constraints(path, self.constraints.as_ref());
+        c.precision(path, self.precision.as_deref());
+    }
+}
+
+impl Validate for VelocityIkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "joint_angles"), &self.joint_angles);
+        c.finite(at(path, "linear_velocity"), &self.linear_velocity);
+        if let Some(w) = &self.angular_velocity { c.finite(at(path, "angular_velocity"), w); }
+        c.twist_frame(path, self.frame.as_deref());
+        c.positive(at(path, "damping"), self.damping);
+        c.joint_rates(path, "max_joint_velocity", self.max_joint_velocity.as_deref());
+        c.positive(at(path, "dt"), self.dt);
+    }
+}
+
+impl Validate for AccelerationIkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "joint_angles"), &self.joint_angles);
+        c.finite(at(path, "joint_velocities"), &self.joint_velocities);
+        c.finite(at(path, "linear_acceleration"), &self.linear_acceleration);
+        if let Some(a) = &self.angular_acceleration { c.finite(at(path, "angular_acceleration"), a); }
+        c.twist_frame(path, self.frame.as_deref());
+        c.positive(at(path, "damping"), self.damping);
+        c.joint_rates(path, "max_joint_acceleration", self.max_joint_acceleration.as_deref());
+        c.positive(at(path, "dt"), self.dt);
+    }
+}
+
+impl Validate for ComplianceRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.finite(at(path, "joint_angles"), &self.joint_angles);
+        c.cartesian_matrix(path, "stiffness", &self.stiffness);
+        if let Some(d) = &self.damping {
+            c.cartesian_matrix(path, "damping", d);
+            let size = |m: &[Vec<f64>]| m.last().map_or(0, Vec::len);
+            if size(d) != size(&self.stiffness) { c.fail(at(path, "damping"), "must be the same size as stiffness"); }
+        }
+        c.twist_frame(path, self.frame.as_deref());
+    }
+}
+
+impl Validate for FkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if self.joint_angles.is_empty() { c.fail(at(path, "joint_angles"), "must not be empty"); }
+        c.finite(at(path, "joint_angles"), &self.joint_angles);
+        c.positive(at(path, "pad_link_length"), self.pad_link_length);
+        if self.pad_link_length.is_some() && self.chain_id.is_some() { c.fail(at(path, "pad_link_length"), "cannot be combined with chain_id"); }
+        let Some(links) = &self.link_lengths else { return };
+        c.finite(at(path, "link_lengths"), links);
+        let (m, n) = (links.len(), self.joint_angles.len());
+        if m > n || (m < n && self.pad_link_length.is_none()) {
+            let hint = if m < n { "; set pad_link_length to fill in the rest" } else { "" };
+            c.fail(at(path, "link_lengths"), format!("{m} link lengths for {n} joint angles{hint}"));
+        }
+        if let Some(i) = links.iter().position(|l| *l < 0.0) { c.fail(format!("{}[{i}]", at(path, "link_lengths")), "must not be negative"); }
+    }
+}
+
+impl Validate for IntentRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.range(at(path, "sample_rate_hz"), self.sample_rate_hz, 1, MAX_SAMPLE_RATE_HZ);
+        c.range(at(path, "prediction_horizon_ms"), self.prediction_horizon_ms, 1, MAX_PREDICTION_HORIZON_MS);
+        c.range(at(path, "pca_components"), self.pca_components, 1, MAX_JOINTS as usize);
+        if let Some(m) = self.mode.as_deref().filter(|m| *m != "pca") { c.fail(at(path, "mode"), format!("'{m}' is not one of pca")); }
+        if let Some(spec) = &self.codec { c.positive(at(path, "codec.step"), spec.step); }
+        if let Some(f) = &self.filter {
+            let path = at(path, "filter");
+            for (name, v) in [("cutoff_hz", f.cutoff_hz), ("process_noise", f.process_noise), ("measurement_noise", f.measurement_noise)] {
+                c.positive(at(&path, name), v);
+            }
+        }
+        let samples = at(path, "samples");
+        for (i, s) in self.samples.iter().enumerate() {
+            let path = format!("{samples}[{i}]");
+            if i > 0 && s.timestamp_ms < self.samples[i - 1].timestamp_ms { c.fail(at(&path, "timestamp_ms"), "timestamps must not decrease"); }
+            c.finite(at(&path, "position"), &s.position);
+            if let Some(v) = &s.velocity { c.finite(at(&path, "velocity"), v); }
+            if let Some(q) = &s.joint_angles { c.finite(at(&path, "joint_angles"), q); }
+            c.quat(at(&path, "orientation"), s.orientation.as_ref());
+        }
+    }
+}
+
+impl Validate for MultiIntentRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        for (i, ch) in self.channels.iter().enumerate() { ch.request.check(c, &format!("{}[{i}]", at(path, "channels"))); }
+    }
+}
+
+impl Validate for TrajectoryRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if self.waypoints.is_empty() { c.fail(at(path, "waypoints"), "at least one waypoint is required"); }
+        for (i, w) in self.waypoints.iter().enumerate() {
+            let Waypoint::Point(p) = w else { continue };
+            let field = format!("{}[{i}]", at(path, "waypoints"));
+            if p.len() != 3 { c.fail(field, format!("expected [x, y, z], got {} values", p.len())); } else { c.finite(field, p); }
+        }
+        for (name, v) in [("max_velocity", self.max_velocity), ("max_acceleration", self.max_acceleration), ("smoothness", self.smoothness)] {
+            c.positive(at(path, name), v);
+        }
+    }
+}
+
+impl Validate for RetargetRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        match (&self.joint_angles, &self.positions) {
+            (Some(_), Some(_)) | (None, None) => c.fail(at(path, "joint_angles"), "give exactly one of joint_angles and positions"),
+            (Some(q), None) if q.is_empty() => c.fail(at(path, "joint_angles"), "at least one frame is required"),
+            (None, Some(p)) if p.is_empty() => c.fail(at(path, "positions"), "at least one position is required"),
+            (Some(q), None) => if let Some(i) = q.iter().position(|q| q.iter().any(|v| !v.is_finite())) { c.finite(format!("{}[{i}]", at(path, "joint_angles")), &q[i]); },
+            (None, Some(p)) => if let Some(i) = p.iter().position(|p| p.iter().any(|v| !v.is_finite())) { c.finite(format!("{}[{i}]", at(path, "positions")), &p[i]); },
+        }
+        match (&self.orientations, &self.positions) {
+            (Some(_), None) => c.fail(at(path, "orientations"), "only with positions"),
+            (Some(o), Some(p)) if o.len() != p.len() => c.fail(at(path, "orientations"), format!("{} orientations for {} positions", o.len(), p.len())),
+            (Some(o), _) => for (i, q) in o.iter().enumerate() { c.quat(format!("{}[{i}]", at(path, "orientations")), Some(q)); },
+            (None, p) => if self.follow_orientation && p.is_some() { c.fail(at(path, "follow_orientation"), "needs orientations with positions"); },
+        }
+        c.positive(at(path, "scale"), self.scale);
+        c.quat(at(path, "rotation"), self.rotation.as_ref());
+        if let Some(o) = &self.offset { c.finite(at(path, "offset"), o); }
+        if let Some(Joints::Angles(q)) = &self.seed { c.finite(at(path, "seed"), q); }
+        c.constraints(path, self.constraints.as_ref());
+    }
+}
+
+impl Validate for MocapQuery {
+    fn check(&self, c: &mut Checks, path: &str) {
+        for (field, value, choices) in [("side", &self.side, &["right", "left"][..]), ("limb", &self.limb, &["arm", "leg"]), ("up", &self.up, &["x", "y", "z"])] {
+            if let Some(v) = value.as_deref().filter(|v| !choices.contains(v)) { c.fail(at(path, field), format!("'{v}' is not one of {}", choices.join(", "))); }
+        }
+        c.positive(at(path, "scale"), self.scale);
+        if self.stride == Some(0) { c.fail(at(path, "stride"), "must be at least 1"); }
+    }
+}
+
+impl Validate for BvhQuery {
+    fn check(&self, c: &mut Checks, path: &str) {
+        for (field, value, choices) in [("side", &self.side, &["right", "left"][..]), ("units", &self.units, &["cm", "mm", "m"])] {
+            if let Some(v) = value.as_deref().filter(|v| !choices.contains(v)) { c.fail(at(path, field), format!("'{v}' is not one of {}", choices.join(", "))); }
+        }
+        c.positive(at(path, "frame_time"), self.frame_time);
+    }
+}
+
+impl Validate for HandEyeRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        let (n, min) = (self.flange_poses.len(), kinematics_solver::HAND_EYE_MIN_POSES);
+        if !(min..=MAX_HAND_EYE_STATIONS).contains(&n) { c.fail(at(path, "flange_poses"), format!("must hold {min} to {MAX_HAND_EYE_STATIONS} stations")); }
+        if self.target_poses.len() != n { c.fail(at(path, "target_poses"), format!("{} target poses for {n} flange poses", self.target_poses.len())); }
+        for (field, poses) in [("flange_poses", &self.flange_poses), ("target_poses", &self.target_poses)] {
+            for (i, t) in poses.iter().enumerate() {
+                let field = format!("{}[{i}]", at(path, field));
+                c.finite(format!("{field}.position"), &t.position);
+                c.quat(format!("{field}.orientation"), Some(&t.orientation));
+            }
+        }
+    }
+}
+
+impl Validate for EncodeRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        c.positive(at(path, "step"), self.spec.step);
+        let width = self.frames.first().map_or(0, Vec::len);
+        for (i, f) in self.frames.iter().enumerate() {
+            let field = format!("{}[{i}]", at(path, "frames"));
+            if f.len() != width { c.fail(field, format!("{} channels, frames[0] has {width}", f.len())); } else { c.finite(field, f); }
+        }
+        if let Some(ts) = &self.timestamps_ms {
+            if ts.len() != self.frames.len() { c.fail(at(path, "timestamps_ms"), format!("{} timestamps for {} frames", ts.len(), self.frames.len())); }
+            if let Some(i) = (1..ts.len()).find(|&i| ts[i] < ts[i - 1]) { c.fail(format!("{}[{i}]", at(path, "timestamps_ms")), "timestamps must not decrease"); }
+        }
+    }
+}
+
+impl Validate for HandFkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        for (finger, q) in &self.joint_angles { c.finite(format!("{}.{finger}", at(path, "joint_angles")), q); }
+    }
+}
+
+impl Validate for HandIkRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        for (finger, t) in &self.targets {
+            let path = format!("{}.{finger}", at(path, "targets"));
+            c.finite(at(&path, "position"), &t.position);
+            c.quat(at(&path, "orientation"), t.orientation.as_ref());
+        }
+        for (finger, q) in &self.seed { c.finite(format!("{}.{finger}", at(path, "seed")), q); }
+        c.constraints(path, self.constraints.as_ref());
+        c.range(at(path, "deadline_ms"), self.deadline_ms, 1, MAX_DEADLINE_MS);
+        c.wrap_policy(path, self.wrap_policy.as_deref());
+    }
+}
+
+impl Validate for SimRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if let Some(Joints::Angles(q)) = &self.joint_angles { c.finite(at(path, "joint_angles"), q); }
+        if let Some(v) = &self.joint_velocities { c.finite(at(path, "joint_velocities"), v); }
+        c.positive(at(path, "dt"), self.dt);
+        c.range(at(path, "dt"), self.dt, 0.0, crate::sim::MAX_DT);
+        if let Some(g) = &self.gravity { c.finite(at(path, "gravity"), g); }
+    }
+}
+
+impl Validate for TrackingRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if self.joint_angles.len() < 2 { c.fail(at(path, "joint_angles"), "must hold at least 2 frames"); }
+        if let Some(i) = self.joint_angles.iter().position(|f| f.len() != self.joint_angles[0].len()) { c.fail(format!("{}[{i}]", at(path, "joint_angles")), "every frame needs the same number of joints"); }
+        for (i, f) in self.joint_angles.iter().enumerate() { c.finite(format!("{}[{i}]", at(path, "joint_angles")), f); }
+        if let Some(times) = &self.times {
+            if times.len() != self.joint_angles.len() { c.fail(at(path, "times"), format!("{} times for {} frames", times.len(), self.joint_angles.len())); }
+            c.finite(at(path, "times"), times);
+            if let Some(i) = times.windows(2).position(|w| w[1] <= w[0]) { c.fail(format!("{}[{}]", at(path, "times"), i + 1), "times must increase"); }
+            if self.frame_time.is_some() { c.fail(at(path, "frame_time"), "give times or frame_time, not both"); }
+        }
+        c.positive(at(path, "frame_time"), self.frame_time);
+        c.joint_rates(path, "kp", Some(&self.kp));
+        c.joint_rates(path, "kd", Some(&self.kd));
+        c.joint_rates(path, "max_effort", self.max_effort.as_deref());
+        c.positive(at(path, "bandwidth_hz"), self.bandwidth_hz);
+        if let Some(p) = self.plant.as_deref().filter(|p| !crate::tracking::PLANTS.contains(p)) { c.fail(at(path, "plant"), format!("'{p}' is not one of {}", crate::tracking::PLANTS.join(", "))); }
+        c.positive(at(path, "dt"), self.dt);
+        c.range(at(path, "dt"), self.dt, 0.0, crate::sim::MAX_DT);
+        c.positive(at(path, "tolerance"), self.tolerance);
+    }
+}
+
+impl Validate for SessionRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if let Some(Joints::Angles(q)) = &self.joint_angles { c.finite(at(path, "joint_angles"), q); }
+    }
+}
+
+impl Validate for StateReport {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if let Joints::Angles(q) = &self.joint_angles { c.finite(at(path, "joint_angles"), q); }
+    }
+}
+
+impl Validate for SimStepRequest {
+    fn check(&self, c: &mut Checks, path: &str) {
+        if !crate::sim::MODES.contains(&self.mode.as_str()) { c.fail(at(path, "mode"), format!("must be one of {}", crate::sim::MODES.join(", "))); }
+        c.finite(at(path, "command"), &self.command);
+        c.range(at(path, "steps"), self.steps, 1, crate::sim::MAX_STEPS);
+    }
+}
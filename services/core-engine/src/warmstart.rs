@@ -0,0 +1,80 @@
+// ── IK warm starts ──────────────────────────────────────────
+//
+// Converged IK solutions remembered by chain and target region, so a solve without a
+// seed of its own starts from the solution for the nearest recent target instead of
+// the seed policy. Teleoperation clients posting a run of close targets then converge
+// in a few iterations, as an IK stream does by carrying its last solution. Targets are
+// bucketed into cubes `cell` metres wide; a lookup searches the target's cube and its
+// neighbours and takes the closest target. Each cube keeps its latest solution, and
+// the oldest cubes go once `capacity` is reached. Entries are per tenant and per
+// pinned chain version, so a re-registered chain never seeds from its old geometry.
+
+use std::collections::{HashMap, VecDeque};
+
+type Key = (Option<String>, String, [i64; 3]);
+
+struct Entry { target: [f64; 3], angles: Vec<f64>, generation: u64 }
+
+pub struct WarmStarts { entries: HashMap<Key, Entry>, order: VecDeque<(Key, u64)>, cell: f64, capacity: usize, generation: u64 }
+
+impl WarmStarts {
+    pub fn new(cell: f64, capacity: usize) -> Self { Self { entries: HashMap::new(), order: VecDeque::new(), cell, capacity, generation: 0 } }
+
+    /// Change the cube size and capacity; a new cube size forgets every entry.
+    pub fn configure(&mut self, cell: f64, capacity: usize) {
+        if cell != self.cell { (self.entries, self.order) = (HashMap::new(), VecDeque::new()); }
+        (self.cell, self.capacity) = (cell, capacity);
+        self.evict();
+    }
+
+    /// Regions remembered.
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// The solution for the remembered target nearest `target` on `chain` (pinned id),
+    /// within one cube in every direction.
+    pub fn seed(&self, tenant: Option<&str>, chain: &str, target: [f64; 3]) -> Option<Vec<f64>> {
+        if self.capacity == 0 { return None; }
+        let [x, y, z] = self.cube(target);
+        let (tenant, chain) = (tenant.map(String::from), chain.to_string());
+        let mut key = (tenant, chain, [0; 3]);
+        let mut best: Option<(f64, &Entry)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    key.2 = [x + dx, y + dy, z + dz];
+                    let Some(e) = self.entries.get(&key) else { continue };
+                    let d = e.target.iter().zip(target).map(|(a, b)| (a - b) * (a - b)).sum::<f64>();
+                    if best.is_none_or(|(bd, _)| d < bd) { best = Some((d, e)); }
+                }
+            }
+        }
+        best.map(|(_, e)| e.angles.clone())
+    }
+
+    /// Remember `angles` as the converged solution for `target` on `chain`.
+    pub fn remember(&mut self, tenant: Option<String>, chain: &str, target: [f64; 3], angles: Vec<f64>) {
+        if self.capacity == 0 { return; }
+        self.generation += 1;
+        let key = (tenant, chain.to_string(), self.cube(target));
+        self.order.push_back((key.clone(), self.generation));
+        self.entries.insert(key, Entry { target, angles, generation: self.generation });
+        if self.order.len() > 2 * self.entries.len() + 64 {
+            let entries = &self.entries;
+            self.order.retain(|(k, g)| entries.get(k).is_some_and(|e| e.generation == *g));
+        }
+        self.evict();
+    }
+
+    fn cube(&self, p: [f64; 3]) -> [i64; 3] { p.map(|v| (v / self.cell).floor() as i64) }
+
+    /// Drop the least recently written cubes beyond capacity, and queue slots that a
+    /// later write to the same cube superseded.
+    fn evict(&mut self) {
+        while let Some((key, generation)) = self.order.front() {
+            let current = self.entries.get(key).is_some_and(|e| e.generation == *generation);
+            if current && self.entries.len() <= self.capacity { break; }
+            let (key, _) = self.order.pop_front().expect("front exists");
+            if current { self.entries.remove(&key); }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// ── Webhooks ────────────────────────────────────────────────
+//
+// Completion callbacks for jobs. When a job with a `callback_url` finishes, its
+// outcome is POSTed there as JSON. Deliveries are signed with HMAC-SHA256 over
+// `"{timestamp}.{body}"` when `KINEMATICS_WEBHOOK_SECRET` is set, and retried with
+// exponential backoff on transport errors, 408, 429 and 5xx; any other status is
+// final. Every attempt of one delivery carries the same `X-Kinematics-Delivery` id,
+// so receivers can drop duplicates.
+//
+// Any API key can name a callback, so unless `allow_private` is set the engine only
+// calls public addresses: a URL naming a loopback, private, link-local (cloud metadata)
+// or otherwise non-global address is refused at submission, and callback hosts are
+// resolved through `PublicOnly` at connect time, so a name that re-resolves to an
+// internal address later is refused too. Redirects are never followed, and callbacks
+// bypass any system proxy, which could otherwise reach internal hosts on our behalf.
+
+use crate::history::now_ms;
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+pub use kinematics_types::{Delivery, DeliveryState};
+
+pub struct Webhooks { client: reqwest::Client, secret: Option<Vec<u8>>, max_attempts: u32, allow_private: bool }
+
+impl Webhooks {
+    pub fn new(secret: Option<String>, max_attempts: u32, allow_private: bool) -> Self {
+        let builder = reqwest::Client::builder().timeout(TIMEOUT).redirect(reqwest::redirect::Policy::none());
+        let builder = if allow_private { builder } else { builder.no_proxy().dns_resolver(Arc::new(PublicOnly)) };
+        let client = builder.build().expect("HTTP client");
+        Self { client, secret: secret.filter(|s| !s.is_empty()).map(String::into_bytes), max_attempts: max_attempts.max(1), allow_private }
+    }
+
+    /// Reject anything but an absolute http(s) URL and, unless private callbacks are
+    /// allowed, one whose host is or resolves only to non-public addresses. A name that
+    /// does not resolve yet is accepted; delivery resolves it again.
+    pub async fn check_url(&self, url: &str) -> Result<(), String> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+        if !matches!(parsed.scheme(), "http" | "https") { return Err(format!("scheme '{}' is not http or https", parsed.scheme())); }
+        let Some(host) = parsed.host_str() else { return Err("missing host".into()) };
+        if self.allow_private { return Ok(()); }
+        let ips: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => match tokio::net::lookup_host((host, 0)).await {
+                Ok(addrs) => addrs.map(|a| a.ip()).collect(),
+                Err(_) => return Ok(()),
+            },
+        };
+        if !ips.is_empty() && !ips.iter().copied().any(is_public) {
+            return Err(format!("{host} is not a public address; set KINEMATICS_WEBHOOK_ALLOW_PRIVATE to call internal hosts"));
+        }
+        Ok(())
+    }
+
+    /// POST `body` to `url` until it is accepted, refused or out of attempts, calling
+    /// `record` after every attempt with its outcome and whether it was the last.
+    pub async fn deliver(&self, url: &str, event: &str, body: Vec<u8>, mut record: impl FnMut(Result<u16, String>, bool)) {
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let mut backoff = FIRST_BACKOFF;
+        for attempt in 1..=self.max_attempts {
+            let timestamp = (now_ms() / 1000).to_string();
+            let mut req = self.client.post(url)
+                .header("content-type", "application/json")
+                .header("user-agent", concat!("kinematics-engine/", env!("CARGO_PKG_VERSION")))
+                .header("x-kinematics-event", event)
+                .header("x-kinematics-delivery", &delivery_id)
+                .header("x-kinematics-timestamp", &timestamp);
+            if let Some(secret) = &self.secret { req = req.header("x-kinematics-signature", signature(secret, &timestamp, &body)); }
+            let outcome = req.body(body.clone()).send().await.map(|r| r.status().as_u16()).map_err(|e| e.to_string());
+            let retry = match &outcome { Ok(status) => matches!(status, 408 | 429 | 500..=599), Err(_) => true };
+            let last = !retry || attempt == self.max_attempts;
+            record(outcome, last);
+            if last { return; }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC of `"{timestamp}.{body}"`.
+pub fn signature(secret: &[u8], timestamp: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// DNS for callback hosts that drops every non-public address, and fails when none is left.
+struct PublicOnly;
+
+impl Resolve for PublicOnly {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let public: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.filter(|a| is_public(a.ip())).collect();
+            if public.is_empty() { return Err(format!("{host} does not resolve to a public address").into()); }
+            Ok(Box::new(public.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is globally routable: not loopback, private, shared (CGNAT), link-local,
+/// documentation, benchmarking, reserved, multicast or unspecified. IPv4 addresses
+/// embedded in IPv6 (mapped, NAT64) are judged as IPv4.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(a) => {
+            let [o0, o1, o2, _] = a.octets();
+            !(a.is_private() || a.is_loopback() || a.is_link_local() || a.is_broadcast() || a.is_documentation() || a.is_unspecified() || a.is_multicast()
+                || o0 == 0 || o0 >= 240 || (o0 == 100 && (64..128).contains(&o1)) || (o0 == 192 && o1 == 0 && o2 == 0) || (o0 == 198 && (18..20).contains(&o1)))
+        }
+        IpAddr::V6(a) => {
+            let s = a.segments();
+            if let Some(v4) = a.to_ipv4_mapped() { return is_public(v4.into()); }
+            if s[..6] == [0x64, 0xff9b, 0, 0, 0, 0] { return is_public(IpAddr::V4(std::net::Ipv4Addr::from(((s[6] as u32) << 16) | s[7] as u32))); }
+            !(a.is_loopback() || a.is_unspecified() || a.is_multicast() || (s[0] & 0xfe00) == 0xfc00 || (s[0] & 0xffc0) == 0xfe80 || (s[0] == 0x2001 && s[1] == 0x0db8))
+        }
+    }
+}